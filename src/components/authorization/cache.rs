@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use super::{AuthorizationStatus, IdToken, IdTokenInfo};
+
+#[derive(Debug, Clone, PartialEq)]
+struct CacheEntry {
+    info: IdTokenInfo,
+    expires_at: Option<DateTime<Utc>>,
+    last_used: DateTime<Utc>,
+}
+
+/// The Authorization Cache of a Charging Station.
+///
+/// Stores the status information of identifiers received in `Authorize`, `StartTransaction`
+/// and `TransactionEvent` responses. An entry expires at its expiry date or, when configured,
+/// after `AuthCacheLifeTime`, whichever comes first. When the cache is full, expired entries
+/// are dropped first, then entries that are not `Accepted`, then the least recently used.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuthorizationCache {
+    max_entries: Option<usize>,
+    life_time: Option<Duration>,
+    entries: HashMap<IdToken, CacheEntry>,
+}
+
+impl AuthorizationCache {
+    /// Creates an unbounded cache whose entries only expire at their expiry date.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the number of cached identifiers (`AuthCacheCtrlr.Storage` in 2.x).
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// Sets the maximum time an entry is kept (`AuthCacheCtrlr.LifeTime` in 2.x).
+    pub fn with_life_time(mut self, life_time: Duration) -> Self {
+        self.life_time = Some(life_time);
+        self
+    }
+
+    /// Number of cached identifiers, including entries that have expired but were not purged.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` when nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Stores or replaces the status information of `id_token`.
+    pub fn insert(&mut self, id_token: IdToken, info: IdTokenInfo, now: DateTime<Utc>) {
+        if self.max_entries == Some(0) {
+            return;
+        }
+
+        let expires_at = match (info.expiry_date, self.life_time.map(|life| now + life)) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        if !self.entries.contains_key(&id_token) {
+            self.make_room(now);
+        }
+
+        self.entries.insert(
+            id_token,
+            CacheEntry {
+                info,
+                expires_at,
+                last_used: now,
+            },
+        );
+    }
+
+    /// Returns the status information of `id_token` if it is cached and not expired.
+    ///
+    /// A successful lookup marks the entry as recently used.
+    pub fn get(&mut self, id_token: &IdToken, now: DateTime<Utc>) -> Option<&IdTokenInfo> {
+        let expired = self
+            .entries
+            .get(id_token)?
+            .expires_at
+            .is_some_and(|expiry| expiry <= now);
+        if expired {
+            self.entries.remove(id_token);
+            return None;
+        }
+        let entry = self.entries.get_mut(id_token)?;
+        entry.last_used = now;
+        Some(&entry.info)
+    }
+
+    /// Removes `id_token` from the cache.
+    pub fn remove(&mut self, id_token: &IdToken) -> Option<IdTokenInfo> {
+        self.entries.remove(id_token).map(|entry| entry.info)
+    }
+
+    /// Removes all expired entries.
+    pub fn purge_expired(&mut self, now: DateTime<Utc>) {
+        self.entries
+            .retain(|_, entry| entry.expires_at.is_none_or(|expiry| expiry > now));
+    }
+
+    /// Removes all entries, as requested by `ClearCache`.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn make_room(&mut self, now: DateTime<Utc>) {
+        let Some(max_entries) = self.max_entries else {
+            return;
+        };
+        if self.entries.len() < max_entries {
+            return;
+        }
+
+        self.purge_expired(now);
+        while self.entries.len() >= max_entries {
+            let victim = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| {
+                    (
+                        entry.info.status == AuthorizationStatus::Accepted,
+                        entry.last_used,
+                    )
+                })
+                .map(|(id_token, _)| id_token.clone());
+            match victim {
+                Some(id_token) => self.entries.remove(&id_token),
+                None => break,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(status: AuthorizationStatus) -> IdTokenInfo {
+        IdTokenInfo::new(status)
+    }
+
+    #[test]
+    fn entries_expire_at_expiry_date_or_life_time() {
+        let now = Utc::now();
+        let mut cache = AuthorizationCache::new().with_life_time(Duration::hours(1));
+
+        cache.insert(
+            IdToken::new("A"),
+            info(AuthorizationStatus::Accepted).with_expiry_date(now + Duration::minutes(10)),
+            now,
+        );
+        cache.insert(IdToken::new("B"), info(AuthorizationStatus::Accepted), now);
+
+        let later = now + Duration::minutes(30);
+        assert!(cache.get(&IdToken::new("A"), later).is_none());
+        assert!(cache.get(&IdToken::new("B"), later).is_some());
+
+        let much_later = now + Duration::hours(2);
+        assert!(cache.get(&IdToken::new("B"), much_later).is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn eviction_prefers_rejected_then_least_recently_used() {
+        let now = Utc::now();
+        let mut cache = AuthorizationCache::new().with_max_entries(2);
+
+        cache.insert(IdToken::new("A"), info(AuthorizationStatus::Accepted), now);
+        cache.insert(
+            IdToken::new("B"),
+            info(AuthorizationStatus::Blocked),
+            now + Duration::seconds(1),
+        );
+        cache.insert(
+            IdToken::new("C"),
+            info(AuthorizationStatus::Accepted),
+            now + Duration::seconds(2),
+        );
+        assert_eq!(cache.len(), 2);
+        assert!(cache.remove(&IdToken::new("B")).is_none());
+
+        cache.get(&IdToken::new("A"), now + Duration::seconds(3));
+        cache.insert(
+            IdToken::new("D"),
+            info(AuthorizationStatus::Accepted),
+            now + Duration::seconds(4),
+        );
+        assert!(cache.get(&IdToken::new("A"), now).is_some());
+        assert!(cache.get(&IdToken::new("C"), now).is_none());
+        assert!(cache.get(&IdToken::new("D"), now).is_some());
+    }
+
+    #[test]
+    fn clear_and_disabled_storage() {
+        let now = Utc::now();
+        let mut cache = AuthorizationCache::new();
+        cache.insert(IdToken::new("A"), info(AuthorizationStatus::Accepted), now);
+        cache.clear();
+        assert!(cache.is_empty());
+
+        let mut cache = AuthorizationCache::new().with_max_entries(0);
+        cache.insert(IdToken::new("A"), info(AuthorizationStatus::Accepted), now);
+        assert!(cache.is_empty());
+    }
+}
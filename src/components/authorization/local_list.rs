@@ -0,0 +1,305 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{IdToken, IdTokenInfo};
+
+/// Type of update in a `SendLocalList` request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateKind {
+    /// The entries are changes to apply to the current list.
+    Differential,
+    /// The entries replace the current list.
+    Full,
+}
+
+/// Version independent content of a `SendLocalList` request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalListUpdate {
+    /// Version number of the list after the update has been applied.
+    pub version_number: i32,
+    /// Full or differential update.
+    pub update_kind: UpdateKind,
+    /// Identifiers with their status. An entry without status information removes the
+    /// identifier in a differential update and is not allowed in a full update.
+    pub entries: Vec<(IdToken, Option<IdTokenInfo>)>,
+}
+
+/// Result of applying a [`LocalListUpdate`], convertible into the status of each version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalListStatus {
+    /// The list has been updated.
+    Accepted,
+    /// The update was invalid or would exceed the maximum list length. The list is unchanged.
+    Failed,
+    /// The version number of a differential update is not higher than the current version.
+    VersionMismatch,
+}
+
+/// The Local Authorization List of a Charging Station.
+///
+/// Updates are applied atomically: when an update is rejected the list keeps its previous
+/// entries and version.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LocalAuthorizationList {
+    version: i32,
+    max_length: Option<usize>,
+    entries: HashMap<IdToken, IdTokenInfo>,
+}
+
+impl LocalAuthorizationList {
+    /// Creates an empty list with version 0 and no maximum length.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the number of entries, as configured by `LocalAuthListMaxLength` (1.6) or
+    /// `LocalAuthListCtrlr.Entries` (2.x).
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Current version number, 0 when no list has been received yet.
+    pub fn version(&self) -> i32 {
+        self.version
+    }
+
+    /// Maximum number of entries, if limited.
+    pub fn max_length(&self) -> Option<usize> {
+        self.max_length
+    }
+
+    /// Number of entries in the list.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` when the list has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the status information stored for `id_token`.
+    pub fn get(&self, id_token: &IdToken) -> Option<&IdTokenInfo> {
+        self.entries.get(id_token)
+    }
+
+    /// Returns `true` when `id_token` is on the list.
+    pub fn contains(&self, id_token: &IdToken) -> bool {
+        self.entries.contains_key(id_token)
+    }
+
+    /// Iterates over all entries in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&IdToken, &IdTokenInfo)> {
+        self.entries.iter()
+    }
+
+    /// Applies a `SendLocalList` update.
+    ///
+    /// * A version number lower than 1 or duplicate identifiers fail the update.
+    /// * A full update fails when an entry has no status information.
+    /// * A differential update with a version number lower than or equal to the current
+    ///   version results in `VersionMismatch`. An empty differential update has no effect.
+    /// * An update that would exceed the maximum length fails.
+    pub fn apply(&mut self, update: LocalListUpdate) -> LocalListStatus {
+        if update.version_number < 1 {
+            return LocalListStatus::Failed;
+        }
+
+        let mut seen = HashSet::with_capacity(update.entries.len());
+        if !update
+            .entries
+            .iter()
+            .all(|(id_token, _)| seen.insert(id_token))
+        {
+            return LocalListStatus::Failed;
+        }
+
+        let entries = match update.update_kind {
+            UpdateKind::Full => {
+                let mut entries = HashMap::with_capacity(update.entries.len());
+                for (id_token, info) in update.entries {
+                    let Some(info) = info else {
+                        return LocalListStatus::Failed;
+                    };
+                    entries.insert(id_token, info);
+                }
+                entries
+            }
+            UpdateKind::Differential => {
+                if update.version_number <= self.version {
+                    return LocalListStatus::VersionMismatch;
+                }
+                if update.entries.is_empty() {
+                    return LocalListStatus::Accepted;
+                }
+                let mut entries = self.entries.clone();
+                for (id_token, info) in update.entries {
+                    match info {
+                        Some(info) => entries.insert(id_token, info),
+                        None => entries.remove(&id_token),
+                    };
+                }
+                entries
+            }
+        };
+
+        if self.max_length.is_some_and(|max| entries.len() > max) {
+            return LocalListStatus::Failed;
+        }
+
+        self.entries = entries;
+        self.version = update.version_number;
+        LocalListStatus::Accepted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::AuthorizationStatus;
+    use super::*;
+
+    fn accepted() -> Option<IdTokenInfo> {
+        Some(IdTokenInfo::new(AuthorizationStatus::Accepted))
+    }
+
+    fn update(
+        version_number: i32,
+        update_kind: UpdateKind,
+        entries: Vec<(&str, Option<IdTokenInfo>)>,
+    ) -> LocalListUpdate {
+        LocalListUpdate {
+            version_number,
+            update_kind,
+            entries: entries
+                .into_iter()
+                .map(|(id, info)| (IdToken::new(id), info))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn full_update_replaces_list() {
+        let mut list = LocalAuthorizationList::new();
+        assert_eq!(list.version(), 0);
+
+        let status = list.apply(update(
+            1,
+            UpdateKind::Full,
+            vec![("A", accepted()), ("B", accepted())],
+        ));
+        assert_eq!(status, LocalListStatus::Accepted);
+        assert_eq!(list.version(), 1);
+        assert_eq!(list.len(), 2);
+
+        // a full update may lower the version and clears the list when empty
+        let status = list.apply(update(1, UpdateKind::Full, vec![]));
+        assert_eq!(status, LocalListStatus::Accepted);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn full_update_requires_status_information() {
+        let mut list = LocalAuthorizationList::new();
+        let status = list.apply(update(1, UpdateKind::Full, vec![("A", None)]));
+        assert_eq!(status, LocalListStatus::Failed);
+        assert_eq!(list.version(), 0);
+    }
+
+    #[test]
+    fn differential_update_adds_updates_and_removes() {
+        let mut list = LocalAuthorizationList::new();
+        list.apply(update(
+            1,
+            UpdateKind::Full,
+            vec![("A", accepted()), ("B", accepted())],
+        ));
+
+        let blocked = Some(IdTokenInfo::new(AuthorizationStatus::Blocked));
+        let status = list.apply(update(
+            2,
+            UpdateKind::Differential,
+            vec![("a", blocked), ("B", None), ("C", accepted())],
+        ));
+        assert_eq!(status, LocalListStatus::Accepted);
+        assert_eq!(list.version(), 2);
+        assert_eq!(
+            list.get(&IdToken::new("A")).unwrap().status,
+            AuthorizationStatus::Blocked
+        );
+        assert!(!list.contains(&IdToken::new("B")));
+        assert!(list.contains(&IdToken::new("C")));
+    }
+
+    #[test]
+    fn differential_update_checks_version() {
+        let mut list = LocalAuthorizationList::new();
+        list.apply(update(5, UpdateKind::Full, vec![("A", accepted())]));
+
+        for version in [4, 5] {
+            let status = list.apply(update(
+                version,
+                UpdateKind::Differential,
+                vec![("B", accepted())],
+            ));
+            assert_eq!(status, LocalListStatus::VersionMismatch);
+        }
+        assert_eq!(list.len(), 1);
+
+        // an empty differential update has no effect
+        let status = list.apply(update(6, UpdateKind::Differential, vec![]));
+        assert_eq!(status, LocalListStatus::Accepted);
+        assert_eq!(list.version(), 5);
+    }
+
+    #[test]
+    fn invalid_updates_fail() {
+        let mut list = LocalAuthorizationList::new();
+        assert_eq!(
+            list.apply(update(0, UpdateKind::Full, vec![("A", accepted())])),
+            LocalListStatus::Failed
+        );
+        assert_eq!(
+            list.apply(update(
+                1,
+                UpdateKind::Full,
+                vec![("A", accepted()), ("a", accepted())]
+            )),
+            LocalListStatus::Failed
+        );
+    }
+
+    #[test]
+    fn max_length_is_enforced_atomically() {
+        let mut list = LocalAuthorizationList::new().with_max_length(2);
+        assert_eq!(
+            list.apply(update(
+                1,
+                UpdateKind::Full,
+                vec![("A", accepted()), ("B", accepted()), ("C", accepted())]
+            )),
+            LocalListStatus::Failed
+        );
+
+        list.apply(update(
+            1,
+            UpdateKind::Full,
+            vec![("A", accepted()), ("B", accepted())],
+        ));
+        assert_eq!(
+            list.apply(update(2, UpdateKind::Differential, vec![("C", accepted())])),
+            LocalListStatus::Failed
+        );
+        assert_eq!(list.version(), 1);
+
+        // removing and adding in one update stays within the limit
+        assert_eq!(
+            list.apply(update(
+                2,
+                UpdateKind::Differential,
+                vec![("A", None), ("C", accepted())]
+            )),
+            LocalListStatus::Accepted
+        );
+        assert_eq!(list.len(), 2);
+    }
+}
@@ -0,0 +1,278 @@
+use chrono::{DateTime, Utc};
+
+use super::{
+    AuthorizationCache, AuthorizationStatus, IdToken, IdTokenInfo, LocalAuthorizationList,
+    LocalListStatus, LocalListUpdate,
+};
+
+/// Configuration that decides how identifiers are authorized while offline.
+///
+/// The fields correspond to the 1.6 configuration keys and the 2.x `AuthCtrlr`,
+/// `AuthCacheCtrlr` and `LocalAuthListCtrlr` variables of the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OfflineConfig {
+    /// `LocalAuthorizeOffline`: use the local list and cache while offline.
+    pub local_authorize_offline: bool,
+    /// `AllowOfflineTxForUnknownId`: accept identifiers that are on neither the list nor the cache.
+    pub allow_offline_tx_for_unknown_id: bool,
+    /// `LocalAuthListEnabled` / `LocalAuthListCtrlr.Enabled`.
+    pub local_auth_list_enabled: bool,
+    /// `AuthorizationCacheEnabled` / `AuthCacheCtrlr.Enabled`.
+    pub auth_cache_enabled: bool,
+}
+
+impl Default for OfflineConfig {
+    fn default() -> Self {
+        Self {
+            local_authorize_offline: true,
+            allow_offline_tx_for_unknown_id: false,
+            local_auth_list_enabled: true,
+            auth_cache_enabled: true,
+        }
+    }
+}
+
+/// Where the outcome of an offline authorization came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorizationSource {
+    /// The identifier is on the Local Authorization List.
+    LocalList,
+    /// The identifier is in the Authorization Cache.
+    Cache,
+    /// The identifier is unknown, the outcome follows `AllowOfflineTxForUnknownId`.
+    Unknown,
+}
+
+/// Outcome of an offline authorization.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OfflineAuthorization {
+    /// Status of the identifier, `Expired` when its expiry date has passed.
+    pub status: AuthorizationStatus,
+    /// Where the status came from.
+    pub source: AuthorizationSource,
+    /// The group (parent) identifier, when known.
+    pub group_id: Option<IdToken>,
+}
+
+impl OfflineAuthorization {
+    /// Returns `true` when the identifier may start charging.
+    pub fn is_allowed(&self) -> bool {
+        self.status == AuthorizationStatus::Accepted
+    }
+}
+
+/// Combines the Local Authorization List and the Authorization Cache.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuthorizationManager {
+    config: OfflineConfig,
+    local_list: LocalAuthorizationList,
+    cache: AuthorizationCache,
+}
+
+impl AuthorizationManager {
+    /// Creates a manager with an empty list and cache.
+    pub fn new(config: OfflineConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Replaces the local list, e.g. to set its maximum length or restore a persisted list.
+    pub fn with_local_list(mut self, local_list: LocalAuthorizationList) -> Self {
+        self.local_list = local_list;
+        self
+    }
+
+    /// Replaces the cache, e.g. to set its capacity and life time.
+    pub fn with_cache(mut self, cache: AuthorizationCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// The offline configuration.
+    pub fn config(&self) -> &OfflineConfig {
+        &self.config
+    }
+
+    /// Updates the offline configuration.
+    pub fn set_config(&mut self, config: OfflineConfig) -> &mut Self {
+        self.config = config;
+        self
+    }
+
+    /// The Local Authorization List.
+    pub fn local_list(&self) -> &LocalAuthorizationList {
+        &self.local_list
+    }
+
+    /// The Authorization Cache.
+    pub fn cache(&self) -> &AuthorizationCache {
+        &self.cache
+    }
+
+    /// Mutable access to the Authorization Cache, e.g. to handle `ClearCache`.
+    pub fn cache_mut(&mut self) -> &mut AuthorizationCache {
+        &mut self.cache
+    }
+
+    /// Applies a `SendLocalList` update to the Local Authorization List.
+    pub fn apply_local_list(&mut self, update: impl Into<LocalListUpdate>) -> LocalListStatus {
+        self.local_list.apply(update.into())
+    }
+
+    /// Records status information received from the CSMS in the Authorization Cache.
+    ///
+    /// Identifiers on the Local Authorization List are not cached.
+    pub fn record(&mut self, id_token: IdToken, info: IdTokenInfo, now: DateTime<Utc>) {
+        if !self.config.auth_cache_enabled
+            || (self.config.local_auth_list_enabled && self.local_list.contains(&id_token))
+        {
+            return;
+        }
+        self.cache.insert(id_token, info, now);
+    }
+
+    /// Decides whether `id_token` is allowed to charge while offline.
+    ///
+    /// The Local Authorization List takes precedence over the Authorization Cache. Unknown
+    /// identifiers, and all identifiers when `LocalAuthorizeOffline` is disabled, are only
+    /// accepted when `AllowOfflineTxForUnknownId` is enabled.
+    pub fn authorize_offline(
+        &mut self,
+        id_token: &IdToken,
+        now: DateTime<Utc>,
+    ) -> OfflineAuthorization {
+        if self.config.local_authorize_offline {
+            if self.config.local_auth_list_enabled {
+                if let Some(info) = self.local_list.get(id_token) {
+                    return Self::known(info, AuthorizationSource::LocalList, now);
+                }
+            }
+            if self.config.auth_cache_enabled {
+                if let Some(info) = self.cache.get(id_token, now) {
+                    return Self::known(info, AuthorizationSource::Cache, now);
+                }
+            }
+        }
+
+        OfflineAuthorization {
+            status: match self.config.allow_offline_tx_for_unknown_id {
+                true => AuthorizationStatus::Accepted,
+                false => AuthorizationStatus::Unknown,
+            },
+            source: AuthorizationSource::Unknown,
+            group_id: None,
+        }
+    }
+
+    fn known(
+        info: &IdTokenInfo,
+        source: AuthorizationSource,
+        now: DateTime<Utc>,
+    ) -> OfflineAuthorization {
+        OfflineAuthorization {
+            status: info.effective_status(now),
+            source,
+            group_id: info.group_id.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::super::UpdateKind;
+    use super::*;
+
+    fn manager(config: OfflineConfig) -> AuthorizationManager {
+        let mut manager = AuthorizationManager::new(config);
+        let status = manager.apply_local_list(LocalListUpdate {
+            version_number: 1,
+            update_kind: UpdateKind::Full,
+            entries: vec![
+                (
+                    IdToken::new("LIST"),
+                    Some(
+                        IdTokenInfo::new(AuthorizationStatus::Accepted)
+                            .with_group_id(IdToken::new("GROUP")),
+                    ),
+                ),
+                (
+                    IdToken::new("BLOCKED"),
+                    Some(IdTokenInfo::new(AuthorizationStatus::Blocked)),
+                ),
+            ],
+        });
+        assert_eq!(status, LocalListStatus::Accepted);
+        manager
+    }
+
+    #[test]
+    fn local_list_takes_precedence_over_cache() {
+        let now = Utc::now();
+        let mut manager = manager(OfflineConfig::default());
+
+        // identifiers on the list are never cached
+        manager.record(
+            IdToken::new("BLOCKED"),
+            IdTokenInfo::new(AuthorizationStatus::Accepted),
+            now,
+        );
+        assert!(manager.cache().is_empty());
+
+        let result = manager.authorize_offline(&IdToken::new("blocked"), now);
+        assert_eq!(result.status, AuthorizationStatus::Blocked);
+        assert_eq!(result.source, AuthorizationSource::LocalList);
+
+        let result = manager.authorize_offline(&IdToken::new("LIST"), now);
+        assert!(result.is_allowed());
+        assert_eq!(result.group_id, Some(IdToken::new("GROUP")));
+    }
+
+    #[test]
+    fn cache_is_used_until_expiry() {
+        let now = Utc::now();
+        let mut manager = manager(OfflineConfig::default());
+        manager.record(
+            IdToken::new("CACHED"),
+            IdTokenInfo::new(AuthorizationStatus::Accepted)
+                .with_expiry_date(now + Duration::hours(1)),
+            now,
+        );
+
+        let result = manager.authorize_offline(&IdToken::new("CACHED"), now);
+        assert!(result.is_allowed());
+        assert_eq!(result.source, AuthorizationSource::Cache);
+
+        let result = manager.authorize_offline(&IdToken::new("CACHED"), now + Duration::hours(2));
+        assert!(!result.is_allowed());
+        assert_eq!(result.source, AuthorizationSource::Unknown);
+    }
+
+    #[test]
+    fn unknown_identifiers_follow_configuration() {
+        let now = Utc::now();
+        let mut manager = manager(OfflineConfig::default());
+        let result = manager.authorize_offline(&IdToken::new("OTHER"), now);
+        assert_eq!(result.status, AuthorizationStatus::Unknown);
+
+        manager.set_config(OfflineConfig {
+            allow_offline_tx_for_unknown_id: true,
+            ..OfflineConfig::default()
+        });
+        assert!(manager
+            .authorize_offline(&IdToken::new("OTHER"), now)
+            .is_allowed());
+
+        // without local authorization everything is unknown
+        manager.set_config(OfflineConfig {
+            local_authorize_offline: false,
+            ..OfflineConfig::default()
+        });
+        let result = manager.authorize_offline(&IdToken::new("LIST"), now);
+        assert!(!result.is_allowed());
+        assert_eq!(result.source, AuthorizationSource::Unknown);
+    }
+}
@@ -0,0 +1,189 @@
+//! # Authorization
+//!
+//! Local Authorization List and Authorization Cache handling shared by OCPP 1.6 and 2.x.
+//!
+//! The [`LocalAuthorizationList`] is maintained by the CSMS through `SendLocalList` and
+//! `GetLocalListVersion`, the [`AuthorizationCache`] is filled by the Charging Station from
+//! the `IdTagInfo`/`IdTokenInfoType` it receives in responses. [`AuthorizationManager`]
+//! combines both to answer whether an identifier is allowed to charge while offline.
+
+use std::hash::{Hash, Hasher};
+
+use chrono::{DateTime, Utc};
+
+mod cache;
+mod local_list;
+mod manager;
+
+#[cfg(feature = "v1_6")]
+mod v1_6;
+#[cfg(feature = "v2_0_1")]
+mod v2_0_1;
+#[cfg(feature = "v2_1")]
+mod v2_1;
+
+pub use cache::AuthorizationCache;
+pub use local_list::{LocalAuthorizationList, LocalListStatus, LocalListUpdate, UpdateKind};
+pub use manager::{AuthorizationManager, AuthorizationSource, OfflineAuthorization, OfflineConfig};
+
+/// Version independent identifier.
+///
+/// OCPP 1.6 `idTag`s only carry the identifier, OCPP 2.x `IdTokenType`s also carry the type
+/// of the identifier. Identifiers are case insensitive in all versions, so two tokens are
+/// equal when their identifiers match ignoring ASCII case and their types are equal.
+#[derive(Debug, Clone, Eq)]
+pub struct IdToken {
+    /// The identifier as received.
+    pub id: String,
+    /// The type of the identifier, `None` for OCPP 1.6 `idTag`s.
+    pub kind: Option<String>,
+}
+
+impl IdToken {
+    /// Creates an OCPP 1.6 style identifier without a type.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            kind: None,
+        }
+    }
+
+    /// Creates an OCPP 2.x style identifier with a type, e.g. `ISO14443`.
+    pub fn with_kind(id: impl Into<String>, kind: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            kind: Some(kind.into()),
+        }
+    }
+}
+
+impl PartialEq for IdToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.id.eq_ignore_ascii_case(&other.id) && self.kind == other.kind
+    }
+}
+
+impl Hash for IdToken {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.to_ascii_lowercase().hash(state);
+        self.kind.hash(state);
+    }
+}
+
+/// Version independent authorization status.
+///
+/// Superset of the OCPP 1.6 `AuthorizationStatus` and the OCPP 2.x `AuthorizationStatusEnumType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuthorizationStatus {
+    /// Identifier is allowed for charging.
+    Accepted,
+    /// Identifier has been blocked.
+    Blocked,
+    /// Identifier is already involved in another transaction.
+    ConcurrentTx,
+    /// Identifier has expired.
+    Expired,
+    /// Identifier is invalid.
+    Invalid,
+    /// Identifier is valid, but has no credit left.
+    NoCredit,
+    /// Identifier is valid, but not allowed for the type of EVSE.
+    NotAllowedTypeEVSE,
+    /// Identifier is valid, but not allowed at this location.
+    NotAtThisLocation,
+    /// Identifier is valid, but not allowed at this time.
+    NotAtThisTime,
+    /// Identifier is unknown.
+    Unknown,
+}
+
+/// Version independent status information about an identifier.
+///
+/// Built from an OCPP 1.6 `IdTagInfo` or an OCPP 2.x `IdTokenInfoType`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdTokenInfo {
+    /// Whether the identifier is allowed to charge.
+    pub status: AuthorizationStatus,
+    /// `expiryDate` (1.6) or `cacheExpiryDateTime` (2.x).
+    pub expiry_date: Option<DateTime<Utc>>,
+    /// `parentIdTag` (1.6) or `groupIdToken` (2.x).
+    pub group_id: Option<IdToken>,
+}
+
+impl IdTokenInfo {
+    /// Creates status information without expiry date and group.
+    pub fn new(status: AuthorizationStatus) -> Self {
+        Self {
+            status,
+            expiry_date: None,
+            group_id: None,
+        }
+    }
+
+    /// Sets the expiry date.
+    pub fn with_expiry_date(mut self, expiry_date: DateTime<Utc>) -> Self {
+        self.expiry_date = Some(expiry_date);
+        self
+    }
+
+    /// Sets the group (parent) identifier.
+    pub fn with_group_id(mut self, group_id: IdToken) -> Self {
+        self.group_id = Some(group_id);
+        self
+    }
+
+    /// Returns `true` when the expiry date lies at or before `now`.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expiry_date.is_some_and(|expiry| expiry <= now)
+    }
+
+    /// Returns the status taking the expiry date into account.
+    ///
+    /// An `Accepted` identifier whose expiry date has passed is reported as `Expired`.
+    pub fn effective_status(&self, now: DateTime<Utc>) -> AuthorizationStatus {
+        match self.status {
+            AuthorizationStatus::Accepted if self.is_expired(now) => AuthorizationStatus::Expired,
+            status => status,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn id_tokens_compare_case_insensitive() {
+        assert_eq!(IdToken::new("ABCdef"), IdToken::new("abcDEF"));
+        assert_eq!(
+            IdToken::with_kind("ABC", "ISO14443"),
+            IdToken::with_kind("abc", "ISO14443")
+        );
+        assert_ne!(
+            IdToken::with_kind("ABC", "ISO14443"),
+            IdToken::with_kind("ABC", "ISO15693")
+        );
+        assert_ne!(IdToken::new("ABC"), IdToken::with_kind("ABC", "ISO14443"));
+
+        let set: HashSet<_> = [IdToken::new("abc"), IdToken::new("ABC")].into();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn effective_status_honours_expiry() {
+        let now = Utc::now();
+        let info = IdTokenInfo::new(AuthorizationStatus::Accepted);
+        assert_eq!(info.effective_status(now), AuthorizationStatus::Accepted);
+
+        let info = info.with_expiry_date(now - Duration::seconds(1));
+        assert_eq!(info.effective_status(now), AuthorizationStatus::Expired);
+
+        let blocked = IdTokenInfo::new(AuthorizationStatus::Blocked)
+            .with_expiry_date(now - Duration::seconds(1));
+        assert_eq!(blocked.effective_status(now), AuthorizationStatus::Blocked);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::v1_6::{
+    messages::{
+        get_local_list_version::GetLocalListVersionResponse,
+        send_local_list::{SendLocalListRequest, SendLocalListResponse},
+    },
+    types::{self, IdTagInfo, UpdateStatus, UpdateType},
+};
+
+use super::{
+    AuthorizationStatus, IdToken, IdTokenInfo, LocalAuthorizationList, LocalListStatus,
+    LocalListUpdate, UpdateKind,
+};
+
+impl From<&types::AuthorizationStatus> for AuthorizationStatus {
+    fn from(status: &types::AuthorizationStatus) -> Self {
+        match status {
+            types::AuthorizationStatus::Accepted => Self::Accepted,
+            types::AuthorizationStatus::Blocked => Self::Blocked,
+            types::AuthorizationStatus::Expired => Self::Expired,
+            types::AuthorizationStatus::Invalid => Self::Invalid,
+            types::AuthorizationStatus::ConcurrentTx => Self::ConcurrentTx,
+        }
+    }
+}
+
+impl From<&IdTagInfo> for IdTokenInfo {
+    fn from(info: &IdTagInfo) -> Self {
+        Self {
+            status: (&info.status).into(),
+            expiry_date: info.expiry_date,
+            group_id: info.parent_id_tag.as_deref().map(IdToken::new),
+        }
+    }
+}
+
+impl From<&SendLocalListRequest> for LocalListUpdate {
+    fn from(request: &SendLocalListRequest) -> Self {
+        Self {
+            version_number: request.list_version,
+            update_kind: match request.update_type {
+                UpdateType::Differential => UpdateKind::Differential,
+                UpdateType::Full => UpdateKind::Full,
+            },
+            entries: request
+                .local_authorization_list
+                .iter()
+                .flatten()
+                .map(|data| {
+                    (
+                        IdToken::new(data.id_tag.as_str()),
+                        data.id_tag_info.as_ref().map(Into::into),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<LocalListStatus> for UpdateStatus {
+    fn from(status: LocalListStatus) -> Self {
+        match status {
+            LocalListStatus::Accepted => Self::Accepted,
+            LocalListStatus::Failed => Self::Failed,
+            LocalListStatus::VersionMismatch => Self::VersionMismatch,
+        }
+    }
+}
+
+impl From<LocalListStatus> for SendLocalListResponse {
+    fn from(status: LocalListStatus) -> Self {
+        Self {
+            status: status.into(),
+        }
+    }
+}
+
+impl From<&LocalAuthorizationList> for GetLocalListVersionResponse {
+    fn from(list: &LocalAuthorizationList) -> Self {
+        Self {
+            list_version: list.version(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::super::AuthorizationManager;
+    use super::*;
+    use crate::v1_6::types::AuthorizationData;
+
+    #[test]
+    fn send_local_list_round_trip() {
+        let now = Utc::now();
+        let mut manager = AuthorizationManager::default();
+        let request = SendLocalListRequest {
+            list_version: 3,
+            update_type: UpdateType::Full,
+            local_authorization_list: Some(vec![AuthorizationData {
+                id_tag: "TAG1".to_string(),
+                id_tag_info: Some(IdTagInfo {
+                    expiry_date: Some(now + Duration::days(1)),
+                    parent_id_tag: Some("PARENT".to_string()),
+                    status: types::AuthorizationStatus::Accepted,
+                }),
+            }]),
+        };
+
+        let response: SendLocalListResponse = manager.apply_local_list(&request).into();
+        assert_eq!(response.status, UpdateStatus::Accepted);
+
+        let version: GetLocalListVersionResponse = manager.local_list().into();
+        assert_eq!(version.list_version, 3);
+
+        let result = manager.authorize_offline(&IdToken::new("tag1"), now);
+        assert!(result.is_allowed());
+        assert_eq!(result.group_id, Some(IdToken::new("PARENT")));
+        assert!(!manager
+            .authorize_offline(&IdToken::new("TAG1"), now + Duration::days(2))
+            .is_allowed());
+    }
+
+    #[test]
+    fn send_local_list_version_mismatch() {
+        let mut manager = AuthorizationManager::default();
+        let full = SendLocalListRequest {
+            list_version: 3,
+            update_type: UpdateType::Full,
+            local_authorization_list: None,
+        };
+        manager.apply_local_list(&full);
+
+        let differential = SendLocalListRequest {
+            list_version: 2,
+            update_type: UpdateType::Differential,
+            local_authorization_list: Some(vec![AuthorizationData {
+                id_tag: "TAG1".to_string(),
+                id_tag_info: None,
+            }]),
+        };
+        let response: SendLocalListResponse = manager.apply_local_list(&differential).into();
+        assert_eq!(response.status, UpdateStatus::VersionMismatch);
+    }
+}
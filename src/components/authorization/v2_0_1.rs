@@ -0,0 +1,183 @@
+use crate::v2_0_1::{
+    datatypes::{id_token_info_type::IdTokenInfoType, id_token_type::IdTokenType},
+    enumerations::{
+        authorization_status_enum_type::AuthorizationStatusEnumType,
+        id_token_enum_type::IdTokenEnumType,
+        send_local_list_status_enum_type::SendLocalListStatusEnumType,
+        update_enum_type::UpdateEnumType,
+    },
+    messages::{
+        get_local_list_version::GetLocalListVersionResponse,
+        send_local_list::{SendLocalListRequest, SendLocalListResponse},
+    },
+};
+
+use super::{
+    AuthorizationStatus, IdToken, IdTokenInfo, LocalAuthorizationList, LocalListStatus,
+    LocalListUpdate, UpdateKind,
+};
+
+impl From<&IdTokenType> for IdToken {
+    fn from(id_token: &IdTokenType) -> Self {
+        let kind = match id_token.kind {
+            IdTokenEnumType::Central => "Central",
+            IdTokenEnumType::EMAID => "eMAID",
+            IdTokenEnumType::ISO14443 => "ISO14443",
+            IdTokenEnumType::ISO15693 => "ISO15693",
+            IdTokenEnumType::KeyCode => "KeyCode",
+            IdTokenEnumType::Local => "Local",
+            IdTokenEnumType::MacAddress => "MacAddress",
+            IdTokenEnumType::NoAuthorization => "NoAuthorization",
+        };
+        Self::with_kind(id_token.id_token.as_str(), kind)
+    }
+}
+
+impl From<&AuthorizationStatusEnumType> for AuthorizationStatus {
+    fn from(status: &AuthorizationStatusEnumType) -> Self {
+        match status {
+            AuthorizationStatusEnumType::Accepted => Self::Accepted,
+            AuthorizationStatusEnumType::Blocked => Self::Blocked,
+            AuthorizationStatusEnumType::ConcurrentTx => Self::ConcurrentTx,
+            AuthorizationStatusEnumType::Expired => Self::Expired,
+            AuthorizationStatusEnumType::Invalid => Self::Invalid,
+            AuthorizationStatusEnumType::NoCredit => Self::NoCredit,
+            AuthorizationStatusEnumType::NotAllowedTypeEVSE => Self::NotAllowedTypeEVSE,
+            AuthorizationStatusEnumType::NotAtThisLocation => Self::NotAtThisLocation,
+            AuthorizationStatusEnumType::NotAtThisTime => Self::NotAtThisTime,
+            AuthorizationStatusEnumType::Unknown => Self::Unknown,
+        }
+    }
+}
+
+impl From<&IdTokenInfoType> for IdTokenInfo {
+    fn from(info: &IdTokenInfoType) -> Self {
+        Self {
+            status: (&info.status).into(),
+            expiry_date: info.cache_expiry_date_time,
+            group_id: info.group_id_token.as_ref().map(Into::into),
+        }
+    }
+}
+
+impl From<&SendLocalListRequest> for LocalListUpdate {
+    fn from(request: &SendLocalListRequest) -> Self {
+        Self {
+            version_number: request.version_number,
+            update_kind: match request.update_type {
+                UpdateEnumType::Differential => UpdateKind::Differential,
+                UpdateEnumType::Full => UpdateKind::Full,
+            },
+            entries: request
+                .local_authorization_list
+                .iter()
+                .flatten()
+                .map(|data| {
+                    (
+                        (&data.id_token).into(),
+                        data.id_token_info.as_ref().map(Into::into),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<LocalListStatus> for SendLocalListStatusEnumType {
+    fn from(status: LocalListStatus) -> Self {
+        match status {
+            LocalListStatus::Accepted => Self::Accepted,
+            LocalListStatus::Failed => Self::Failed,
+            LocalListStatus::VersionMismatch => Self::VersionMismatch,
+        }
+    }
+}
+
+impl From<LocalListStatus> for SendLocalListResponse {
+    fn from(status: LocalListStatus) -> Self {
+        Self {
+            status: status.into(),
+            status_info: None,
+        }
+    }
+}
+
+impl From<&LocalAuthorizationList> for GetLocalListVersionResponse {
+    fn from(list: &LocalAuthorizationList) -> Self {
+        Self {
+            version_number: list.version(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::super::AuthorizationManager;
+    use super::*;
+    use crate::v2_0_1::datatypes::authorization_data::AuthorizationData;
+
+    fn id_token(id: &str) -> IdTokenType {
+        IdTokenType {
+            id_token: id.to_string(),
+            kind: IdTokenEnumType::ISO14443,
+            additional_info: None,
+        }
+    }
+
+    #[test]
+    fn send_local_list_and_offline_authorization() {
+        let now = Utc::now();
+        let mut manager = AuthorizationManager::default();
+        let request = SendLocalListRequest {
+            version_number: 1,
+            update_type: UpdateEnumType::Full,
+            local_authorization_list: Some(vec![AuthorizationData {
+                id_token: id_token("TOKEN1"),
+                id_token_info: Some(IdTokenInfoType {
+                    status: AuthorizationStatusEnumType::Accepted,
+                    cache_expiry_date_time: Some(now + Duration::days(1)),
+                    group_id_token: Some(id_token("GROUP")),
+                    ..Default::default()
+                }),
+            }]),
+        };
+
+        let response: SendLocalListResponse = manager.apply_local_list(&request).into();
+        assert_eq!(response.status, SendLocalListStatusEnumType::Accepted);
+        let version: GetLocalListVersionResponse = manager.local_list().into();
+        assert_eq!(version.version_number, 1);
+
+        let result = manager.authorize_offline(&(&id_token("token1")).into(), now);
+        assert!(result.is_allowed());
+        assert_eq!(
+            result.group_id,
+            Some(IdToken::with_kind("GROUP", "ISO14443"))
+        );
+
+        // the type is part of the identity of a token
+        let key_code = IdTokenType {
+            kind: IdTokenEnumType::KeyCode,
+            ..id_token("TOKEN1")
+        };
+        assert!(!manager
+            .authorize_offline(&(&key_code).into(), now)
+            .is_allowed());
+    }
+
+    #[test]
+    fn send_local_list_full_without_info_fails() {
+        let mut manager = AuthorizationManager::default();
+        let request = SendLocalListRequest {
+            version_number: 1,
+            update_type: UpdateEnumType::Full,
+            local_authorization_list: Some(vec![AuthorizationData {
+                id_token: id_token("TOKEN1"),
+                id_token_info: None,
+            }]),
+        };
+        let response: SendLocalListResponse = manager.apply_local_list(&request).into();
+        assert_eq!(response.status, SendLocalListStatusEnumType::Failed);
+    }
+}
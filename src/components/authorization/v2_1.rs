@@ -0,0 +1,163 @@
+use crate::v2_1::{
+    datatypes::{IdTokenInfoType, IdTokenType},
+    enumerations::{AuthorizationStatusEnumType, SendLocalListStatusEnumType},
+    messages::{
+        get_local_list_version::GetLocalListVersionResponse,
+        send_local_list::{SendLocalListRequest, SendLocalListResponse, UpdateEnumType},
+    },
+};
+
+use super::{
+    AuthorizationStatus, IdToken, IdTokenInfo, LocalAuthorizationList, LocalListStatus,
+    LocalListUpdate, UpdateKind,
+};
+
+impl From<&IdTokenType> for IdToken {
+    fn from(id_token: &IdTokenType) -> Self {
+        Self::with_kind(id_token.id_token.as_str(), id_token.type_.as_str())
+    }
+}
+
+impl From<&AuthorizationStatusEnumType> for AuthorizationStatus {
+    fn from(status: &AuthorizationStatusEnumType) -> Self {
+        match status {
+            AuthorizationStatusEnumType::Accepted => Self::Accepted,
+            AuthorizationStatusEnumType::Blocked => Self::Blocked,
+            AuthorizationStatusEnumType::ConcurrentTx => Self::ConcurrentTx,
+            AuthorizationStatusEnumType::Expired => Self::Expired,
+            AuthorizationStatusEnumType::Invalid => Self::Invalid,
+            AuthorizationStatusEnumType::NoCredit => Self::NoCredit,
+            AuthorizationStatusEnumType::NotAllowedTypeEVSE => Self::NotAllowedTypeEVSE,
+            AuthorizationStatusEnumType::NotAtThisLocation => Self::NotAtThisLocation,
+            AuthorizationStatusEnumType::NotAtThisTime => Self::NotAtThisTime,
+            AuthorizationStatusEnumType::Unknown => Self::Unknown,
+        }
+    }
+}
+
+impl From<&IdTokenInfoType> for IdTokenInfo {
+    fn from(info: &IdTokenInfoType) -> Self {
+        Self {
+            status: (&info.status).into(),
+            expiry_date: info.cache_expiry_date_time,
+            group_id: info.group_id_token.as_ref().map(Into::into),
+        }
+    }
+}
+
+impl From<&SendLocalListRequest> for LocalListUpdate {
+    fn from(request: &SendLocalListRequest) -> Self {
+        Self {
+            version_number: request.version_number,
+            update_kind: match request.update_type {
+                UpdateEnumType::Differential => UpdateKind::Differential,
+                UpdateEnumType::Full => UpdateKind::Full,
+            },
+            entries: request
+                .local_authorization_list
+                .iter()
+                .flatten()
+                .map(|data| ((&data.id_token).into(), Some((&data.id_token_info).into())))
+                .collect(),
+        }
+    }
+}
+
+impl From<LocalListStatus> for SendLocalListStatusEnumType {
+    fn from(status: LocalListStatus) -> Self {
+        match status {
+            LocalListStatus::Accepted => Self::Accepted,
+            LocalListStatus::Failed => Self::Failed,
+            LocalListStatus::VersionMismatch => Self::VersionMismatch,
+        }
+    }
+}
+
+impl From<LocalListStatus> for SendLocalListResponse {
+    fn from(status: LocalListStatus) -> Self {
+        Self {
+            custom_data: None,
+            status: status.into(),
+            status_info: None,
+        }
+    }
+}
+
+impl From<&LocalAuthorizationList> for GetLocalListVersionResponse {
+    fn from(list: &LocalAuthorizationList) -> Self {
+        Self {
+            version_number: list.version(),
+            custom_data: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::super::{AuthorizationManager, AuthorizationSource};
+    use super::*;
+    use crate::v2_1::datatypes::AuthorizationData;
+
+    fn id_token(id: &str) -> IdTokenType {
+        IdTokenType::new(id.to_string(), "ISO14443".to_string())
+    }
+
+    fn request(
+        version_number: i32,
+        update_type: UpdateEnumType,
+        list: Vec<AuthorizationData>,
+    ) -> SendLocalListRequest {
+        SendLocalListRequest {
+            custom_data: None,
+            local_authorization_list: Some(list),
+            version_number,
+            update_type,
+        }
+    }
+
+    #[test]
+    fn send_local_list_and_offline_authorization() {
+        let now = Utc::now();
+        let mut manager = AuthorizationManager::default();
+
+        let info = IdTokenInfoType::new(AuthorizationStatusEnumType::Accepted)
+            .with_cache_expiry_date_time(now + Duration::hours(1))
+            .with_group_id_token(id_token("GROUP"));
+        let full = request(
+            1,
+            UpdateEnumType::Full,
+            vec![AuthorizationData::new(id_token("TOKEN1"), info)],
+        );
+        let response: SendLocalListResponse = manager.apply_local_list(&full).into();
+        assert_eq!(response.status, SendLocalListStatusEnumType::Accepted);
+
+        let result = manager.authorize_offline(&(&id_token("token1")).into(), now);
+        assert!(result.is_allowed());
+        assert_eq!(result.source, AuthorizationSource::LocalList);
+        assert_eq!(
+            result.group_id,
+            Some(IdToken::with_kind("GROUP", "ISO14443"))
+        );
+
+        let result =
+            manager.authorize_offline(&(&id_token("TOKEN1")).into(), now + Duration::hours(2));
+        assert_eq!(result.status, AuthorizationStatus::Expired);
+
+        let blocked = IdTokenInfoType::new(AuthorizationStatusEnumType::Blocked);
+        let differential = request(
+            1,
+            UpdateEnumType::Differential,
+            vec![AuthorizationData::new(id_token("TOKEN1"), blocked)],
+        );
+        let response: SendLocalListResponse = manager.apply_local_list(&differential).into();
+        assert_eq!(
+            response.status,
+            SendLocalListStatusEnumType::VersionMismatch
+        );
+
+        let version: GetLocalListVersionResponse = manager.local_list().into();
+        assert_eq!(version.version_number, 1);
+    }
+}
@@ -0,0 +1,473 @@
+use super::{
+    AvailabilityError, AvailabilityTarget, ChangeAvailabilityStatus, ConnectorStatus,
+    CsmsOperation, OperationalStatus, StatusChange,
+};
+
+/// Operational status of one level, with the change scheduled until its transactions end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Availability {
+    operational: OperationalStatus,
+    scheduled: Option<OperationalStatus>,
+}
+
+impl Availability {
+    fn apply_scheduled(&mut self) {
+        if let Some(operational) = self.scheduled.take() {
+            self.operational = operational;
+        }
+    }
+}
+
+impl Default for Availability {
+    fn default() -> Self {
+        Self {
+            operational: OperationalStatus::Operative,
+            scheduled: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Connector {
+    id: i32,
+    status: ConnectorStatus,
+    availability: Availability,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Evse {
+    id: i32,
+    availability: Availability,
+    transaction: bool,
+    connectors: Vec<Connector>,
+}
+
+/// Status and availability of the connectors and EVSEs of a Charging Station.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AvailabilityManager {
+    availability: Availability,
+    evses: Vec<Evse>,
+}
+
+impl AvailabilityManager {
+    /// Creates an operative Charging Station with available connectors, `connectors[i]`
+    /// being the number of connectors of EVSE `i + 1`. Use one connector per EVSE for 1.6.
+    pub fn new(connectors: &[i32]) -> Self {
+        Self {
+            availability: Availability::default(),
+            evses: (1..)
+                .zip(connectors)
+                .map(|(id, &count)| Evse {
+                    id,
+                    availability: Availability::default(),
+                    transaction: false,
+                    connectors: (1..=count)
+                        .map(|id| Connector {
+                            id,
+                            status: ConnectorStatus::Available,
+                            availability: Availability::default(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Status of connector `connector_id` of EVSE `evse_id`.
+    pub fn status(&self, evse_id: i32, connector_id: i32) -> Option<ConnectorStatus> {
+        self.connector(evse_id, connector_id)
+            .map(|(_, connector)| connector.status)
+    }
+
+    /// Operational status of `target`, not taking the levels above it into account.
+    pub fn operational_status(&self, target: AvailabilityTarget) -> Option<OperationalStatus> {
+        self.availability(target)
+            .map(|availability| availability.operational)
+    }
+
+    /// Operational status `target` changes to when its transactions have ended.
+    pub fn scheduled(&self, target: AvailabilityTarget) -> Option<OperationalStatus> {
+        self.availability(target)
+            .and_then(|availability| availability.scheduled)
+    }
+
+    /// Whether a transaction is ongoing on EVSE `evse_id`.
+    pub fn has_transaction(&self, evse_id: i32) -> bool {
+        self.evses
+            .iter()
+            .any(|evse| evse.id == evse_id && evse.transaction)
+    }
+
+    /// Applies a `ChangeAvailability`. A change to `Inoperative` is `Scheduled` while a
+    /// transaction is ongoing on an EVSE of `target`.
+    pub fn change_availability(
+        &mut self,
+        target: AvailabilityTarget,
+        operational: OperationalStatus,
+    ) -> (ChangeAvailabilityStatus, Vec<StatusChange>) {
+        let busy = self
+            .evses
+            .iter()
+            .filter(|evse| match target {
+                AvailabilityTarget::Station => true,
+                AvailabilityTarget::Evse(id) | AvailabilityTarget::Connector(id, _) => {
+                    evse.id == id
+                }
+            })
+            .any(|evse| evse.transaction);
+        let Some(availability) = self.availability_mut(target) else {
+            return (ChangeAvailabilityStatus::Rejected, Vec::new());
+        };
+        match operational {
+            OperationalStatus::Inoperative if busy => {
+                availability.scheduled = Some(operational);
+                (ChangeAvailabilityStatus::Scheduled, Vec::new())
+            }
+            _ => {
+                availability.operational = operational;
+                availability.scheduled = None;
+                (ChangeAvailabilityStatus::Accepted, self.refresh())
+            }
+        }
+    }
+
+    /// Applies a status reported for a connector, e.g. in a `StatusNotification`, returning
+    /// the change when the status differs from the current one.
+    pub fn report(
+        &mut self,
+        evse_id: i32,
+        connector_id: i32,
+        status: ConnectorStatus,
+    ) -> Result<Option<StatusChange>, AvailabilityError> {
+        let operative = self.is_operative(evse_id, connector_id);
+        let connector = self
+            .evses
+            .iter_mut()
+            .filter(|evse| evse.id == evse_id)
+            .flat_map(|evse| evse.connectors.iter_mut())
+            .find(|connector| connector.id == connector_id)
+            .ok_or(AvailabilityError::UnknownConnector {
+                evse_id,
+                connector_id,
+            })?;
+        if !operative
+            && !matches!(
+                status,
+                ConnectorStatus::Unavailable | ConnectorStatus::Faulted
+            )
+        {
+            return Err(AvailabilityError::Inoperative(status));
+        }
+        if !connector.status.can_change_to(status) {
+            return Err(AvailabilityError::InvalidTransition {
+                from: connector.status,
+                to: status,
+            });
+        }
+        if connector.status == status {
+            return Ok(None);
+        }
+        connector.status = status;
+        Ok(Some(StatusChange {
+            evse_id,
+            connector_id,
+            status,
+        }))
+    }
+
+    /// Records that a transaction started on EVSE `evse_id`, returning whether it is known.
+    pub fn transaction_started(&mut self, evse_id: i32) -> bool {
+        self.evses
+            .iter_mut()
+            .find(|evse| evse.id == evse_id)
+            .map(|evse| evse.transaction = true)
+            .is_some()
+    }
+
+    /// Records that the transaction on EVSE `evse_id` ended and applies the changes
+    /// scheduled for it.
+    pub fn transaction_ended(&mut self, evse_id: i32) -> Vec<StatusChange> {
+        let Some(evse) = self.evses.iter_mut().find(|evse| evse.id == evse_id) else {
+            return Vec::new();
+        };
+        evse.transaction = false;
+        evse.availability.apply_scheduled();
+        for connector in &mut evse.connectors {
+            connector.availability.apply_scheduled();
+        }
+        if !self.evses.iter().any(|evse| evse.transaction) {
+            self.availability.apply_scheduled();
+        }
+        self.refresh()
+    }
+
+    /// Whether the CSMS may initiate `operation` on connector `connector_id` of EVSE
+    /// `evse_id`:
+    ///
+    /// - `RemoteStart` without transaction on an operative, `Available`, `Preparing`,
+    ///   `Occupied` or `Reserved` connector,
+    /// - `RemoteStop` with a transaction,
+    /// - `ReserveNow` without transaction on an operative, `Available` connector,
+    /// - `UnlockConnector` without transaction (a 1.6 Charge Point may instead stop the
+    ///   transaction first),
+    /// - `ChangeAvailability` always, see [`Self::change_availability`].
+    pub fn allows(&self, evse_id: i32, connector_id: i32, operation: CsmsOperation) -> bool {
+        let Some((evse, connector)) = self.connector(evse_id, connector_id) else {
+            return false;
+        };
+        let operative = self.is_operative(evse_id, connector_id);
+        match operation {
+            CsmsOperation::RemoteStart => {
+                operative
+                    && !evse.transaction
+                    && matches!(
+                        connector.status,
+                        ConnectorStatus::Available
+                            | ConnectorStatus::Preparing
+                            | ConnectorStatus::Occupied
+                            | ConnectorStatus::Reserved
+                    )
+            }
+            CsmsOperation::RemoteStop => evse.transaction,
+            CsmsOperation::ReserveNow => {
+                operative && !evse.transaction && connector.status == ConnectorStatus::Available
+            }
+            CsmsOperation::UnlockConnector => !evse.transaction,
+            CsmsOperation::ChangeAvailability => true,
+        }
+    }
+
+    fn connector(&self, evse_id: i32, connector_id: i32) -> Option<(&Evse, &Connector)> {
+        let evse = self.evses.iter().find(|evse| evse.id == evse_id)?;
+        let connector = evse.connectors.iter().find(|c| c.id == connector_id)?;
+        Some((evse, connector))
+    }
+
+    fn is_operative(&self, evse_id: i32, connector_id: i32) -> bool {
+        self.connector(evse_id, connector_id)
+            .is_some_and(|(evse, connector)| {
+                [self.availability, evse.availability, connector.availability]
+                    .iter()
+                    .all(|availability| availability.operational == OperationalStatus::Operative)
+            })
+    }
+
+    fn availability(&self, target: AvailabilityTarget) -> Option<&Availability> {
+        match target {
+            AvailabilityTarget::Station => Some(&self.availability),
+            AvailabilityTarget::Evse(evse_id) => self
+                .evses
+                .iter()
+                .find(|evse| evse.id == evse_id)
+                .map(|evse| &evse.availability),
+            AvailabilityTarget::Connector(evse_id, connector_id) => self
+                .connector(evse_id, connector_id)
+                .map(|(_, connector)| &connector.availability),
+        }
+    }
+
+    fn availability_mut(&mut self, target: AvailabilityTarget) -> Option<&mut Availability> {
+        match target {
+            AvailabilityTarget::Station => Some(&mut self.availability),
+            AvailabilityTarget::Evse(evse_id) => self
+                .evses
+                .iter_mut()
+                .find(|evse| evse.id == evse_id)
+                .map(|evse| &mut evse.availability),
+            AvailabilityTarget::Connector(evse_id, connector_id) => self
+                .evses
+                .iter_mut()
+                .filter(|evse| evse.id == evse_id)
+                .flat_map(|evse| evse.connectors.iter_mut())
+                .find(|connector| connector.id == connector_id)
+                .map(|connector| &mut connector.availability),
+        }
+    }
+
+    /// Makes the connectors of an inoperative level `Unavailable` and those of operative
+    /// levels that were `Unavailable` `Available` again, returning the changes.
+    fn refresh(&mut self) -> Vec<StatusChange> {
+        let mut changes = Vec::new();
+        let station = self.availability.operational;
+        for evse in &mut self.evses {
+            for connector in &mut evse.connectors {
+                let operative = [station, evse.availability.operational]
+                    .into_iter()
+                    .chain([connector.availability.operational])
+                    .all(|operational| operational == OperationalStatus::Operative);
+                let status = match (operative, connector.status) {
+                    (_, ConnectorStatus::Faulted) => ConnectorStatus::Faulted,
+                    (false, _) => ConnectorStatus::Unavailable,
+                    (true, ConnectorStatus::Unavailable) => ConnectorStatus::Available,
+                    (true, status) => status,
+                };
+                if status != connector.status {
+                    connector.status = status;
+                    changes.push(StatusChange {
+                        evse_id: evse.id,
+                        connector_id: connector.id,
+                        status,
+                    });
+                }
+            }
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(evse_id: i32, connector_id: i32, status: ConnectorStatus) -> StatusChange {
+        StatusChange {
+            evse_id,
+            connector_id,
+            status,
+        }
+    }
+
+    #[test]
+    fn change_availability_of_levels() {
+        let mut manager = AvailabilityManager::new(&[2, 1]);
+        let (status, changes) = manager
+            .change_availability(AvailabilityTarget::Evse(1), OperationalStatus::Inoperative);
+        assert_eq!(status, ChangeAvailabilityStatus::Accepted);
+        assert_eq!(
+            changes,
+            vec![
+                change(1, 1, ConnectorStatus::Unavailable),
+                change(1, 2, ConnectorStatus::Unavailable),
+            ]
+        );
+
+        // the connector stays unavailable while its EVSE is inoperative
+        let (status, changes) = manager.change_availability(
+            AvailabilityTarget::Connector(1, 2),
+            OperationalStatus::Inoperative,
+        );
+        assert_eq!(status, ChangeAvailabilityStatus::Accepted);
+        assert!(changes.is_empty());
+        let (_, changes) =
+            manager.change_availability(AvailabilityTarget::Evse(1), OperationalStatus::Operative);
+        assert_eq!(changes, vec![change(1, 1, ConnectorStatus::Available)]);
+
+        let (_, changes) = manager
+            .change_availability(AvailabilityTarget::Station, OperationalStatus::Inoperative);
+        assert_eq!(
+            changes,
+            vec![
+                change(1, 1, ConnectorStatus::Unavailable),
+                change(2, 1, ConnectorStatus::Unavailable),
+            ]
+        );
+        assert_eq!(
+            manager.operational_status(AvailabilityTarget::Connector(1, 2)),
+            Some(OperationalStatus::Inoperative)
+        );
+
+        let (status, _) =
+            manager.change_availability(AvailabilityTarget::Evse(3), OperationalStatus::Operative);
+        assert_eq!(status, ChangeAvailabilityStatus::Rejected);
+        let (status, _) = manager.change_availability(
+            AvailabilityTarget::Connector(2, 2),
+            OperationalStatus::Operative,
+        );
+        assert_eq!(status, ChangeAvailabilityStatus::Rejected);
+    }
+
+    #[test]
+    fn inoperative_is_scheduled_during_transactions() {
+        let mut manager = AvailabilityManager::new(&[1, 1]);
+        manager.report(1, 1, ConnectorStatus::Occupied).unwrap();
+        manager.transaction_started(1);
+
+        let (status, changes) = manager
+            .change_availability(AvailabilityTarget::Station, OperationalStatus::Inoperative);
+        assert_eq!(status, ChangeAvailabilityStatus::Scheduled);
+        assert!(changes.is_empty());
+        assert_eq!(
+            manager.scheduled(AvailabilityTarget::Station),
+            Some(OperationalStatus::Inoperative)
+        );
+        let (status, _) = manager
+            .change_availability(AvailabilityTarget::Evse(1), OperationalStatus::Inoperative);
+        assert_eq!(status, ChangeAvailabilityStatus::Scheduled);
+
+        manager.report(1, 1, ConnectorStatus::Available).unwrap();
+        assert_eq!(
+            manager.transaction_ended(1),
+            vec![
+                change(1, 1, ConnectorStatus::Unavailable),
+                change(2, 1, ConnectorStatus::Unavailable),
+            ]
+        );
+        assert_eq!(manager.scheduled(AvailabilityTarget::Station), None);
+
+        // a change back to operative cancels the scheduled change
+        manager.transaction_started(2);
+        let (status, _) = manager
+            .change_availability(AvailabilityTarget::Evse(2), OperationalStatus::Inoperative);
+        assert_eq!(status, ChangeAvailabilityStatus::Scheduled);
+        let (status, _) =
+            manager.change_availability(AvailabilityTarget::Evse(2), OperationalStatus::Operative);
+        assert_eq!(status, ChangeAvailabilityStatus::Accepted);
+        assert_eq!(manager.scheduled(AvailabilityTarget::Evse(2)), None);
+    }
+
+    #[test]
+    fn validates_reported_statuses() {
+        let mut manager = AvailabilityManager::new(&[1]);
+        assert_eq!(
+            manager.report(1, 1, ConnectorStatus::Preparing),
+            Ok(Some(change(1, 1, ConnectorStatus::Preparing)))
+        );
+        assert_eq!(manager.report(1, 1, ConnectorStatus::Preparing), Ok(None));
+        assert_eq!(
+            manager.report(1, 1, ConnectorStatus::Reserved),
+            Err(AvailabilityError::InvalidTransition {
+                from: ConnectorStatus::Preparing,
+                to: ConnectorStatus::Reserved,
+            })
+        );
+        assert_eq!(
+            manager.report(1, 2, ConnectorStatus::Available),
+            Err(AvailabilityError::UnknownConnector {
+                evse_id: 1,
+                connector_id: 2,
+            })
+        );
+
+        manager.report(1, 1, ConnectorStatus::Available).unwrap();
+        manager.change_availability(AvailabilityTarget::Evse(1), OperationalStatus::Inoperative);
+        assert_eq!(
+            manager.report(1, 1, ConnectorStatus::Available),
+            Err(AvailabilityError::Inoperative(ConnectorStatus::Available))
+        );
+        assert!(manager.report(1, 1, ConnectorStatus::Faulted).is_ok());
+    }
+
+    #[test]
+    fn allowed_operations() {
+        let mut manager = AvailabilityManager::new(&[1, 1]);
+        assert!(manager.allows(1, 1, CsmsOperation::RemoteStart));
+        assert!(manager.allows(1, 1, CsmsOperation::ReserveNow));
+        assert!(!manager.allows(1, 1, CsmsOperation::RemoteStop));
+        assert!(!manager.allows(3, 1, CsmsOperation::ChangeAvailability));
+
+        manager.report(1, 1, ConnectorStatus::Preparing).unwrap();
+        assert!(manager.allows(1, 1, CsmsOperation::RemoteStart));
+        assert!(!manager.allows(1, 1, CsmsOperation::ReserveNow));
+
+        manager.transaction_started(1);
+        manager.report(1, 1, ConnectorStatus::Charging).unwrap();
+        assert!(!manager.allows(1, 1, CsmsOperation::RemoteStart));
+        assert!(manager.allows(1, 1, CsmsOperation::RemoteStop));
+        assert!(!manager.allows(1, 1, CsmsOperation::UnlockConnector));
+        assert!(manager.allows(1, 1, CsmsOperation::ChangeAvailability));
+
+        manager.change_availability(AvailabilityTarget::Evse(2), OperationalStatus::Inoperative);
+        assert!(!manager.allows(2, 1, CsmsOperation::RemoteStart));
+        assert!(manager.allows(2, 1, CsmsOperation::UnlockConnector));
+    }
+}
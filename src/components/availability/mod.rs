@@ -0,0 +1,251 @@
+//! # Availability
+//!
+//! Status and availability of connectors and EVSEs, shared by OCPP 1.6 and 2.x.
+//!
+//! OCPP 2.x keeps an operational status (`Operative` or `Inoperative`) for the Charging
+//! Station, each EVSE and each connector; a connector is `Unavailable` when any of them is
+//! `Inoperative`. OCPP 1.6 has no EVSEs: each connector is modelled as an EVSE with a single
+//! connector and connector 0 addresses the Charge Point as a whole.
+//!
+//! [`AvailabilityManager`] applies `ChangeAvailability`, scheduling a change to `Inoperative`
+//! until the transactions it affects have ended, validates reported statuses against the
+//! transitions allowed by the specification, and tells which CSMS-initiated operations a
+//! connector allows. Every change of a connector status is returned as a [`StatusChange`]
+//! to report in a `StatusNotification`.
+
+use std::fmt;
+
+mod manager;
+
+#[cfg(feature = "v1_6")]
+pub mod v1_6;
+#[cfg(feature = "v2_0_1")]
+pub mod v2_0_1;
+#[cfg(feature = "v2_1")]
+pub mod v2_1;
+
+pub use manager::AvailabilityManager;
+
+/// Status of a connector.
+///
+/// Superset of the OCPP 1.6 `ChargePointStatus` and the OCPP 2.x `ConnectorStatusEnumType`.
+/// OCPP 2.x reports `Occupied` for each of the 1.6 states from `Preparing` to `Finishing`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectorStatus {
+    /// Free for a new user.
+    Available,
+    /// Occupied before a transaction has started, e.g. the EV is plugged in (1.6).
+    Preparing,
+    /// Energy is being transferred (1.6).
+    Charging,
+    /// The EV is not taking energy (1.6).
+    SuspendedEv,
+    /// The EVSE is not offering energy (1.6).
+    SuspendedEvse,
+    /// The transaction has stopped, the connector is not free yet (1.6).
+    Finishing,
+    /// Occupied, without detail (2.x).
+    Occupied,
+    /// Reserved for a user.
+    Reserved,
+    /// Not available for charging.
+    Unavailable,
+    /// In an error state.
+    Faulted,
+}
+
+impl ConnectorStatus {
+    /// Returns `true` for `Occupied` and the 1.6 states it stands for.
+    pub fn is_occupied(self) -> bool {
+        matches!(
+            self,
+            Self::Preparing
+                | Self::Charging
+                | Self::SuspendedEv
+                | Self::SuspendedEvse
+                | Self::Finishing
+                | Self::Occupied
+        )
+    }
+
+    /// Whether a connector may go from this status to `to`.
+    ///
+    /// Follows the transition table of OCPP 1.6. A transition from or to `Occupied` is checked
+    /// as in OCPP 2.x, taking the 1.6 states from `Preparing` to `Finishing` as `Occupied`.
+    pub fn can_change_to(self, to: Self) -> bool {
+        use ConnectorStatus::*;
+
+        if self == to {
+            return true;
+        }
+        if self == Occupied || to == Occupied {
+            return match (self, to) {
+                (from, to) if from.is_occupied() && to.is_occupied() => true,
+                (Available | Reserved | Unavailable | Faulted, to) => to.is_occupied(),
+                (from, Available | Unavailable | Faulted) => from.is_occupied(),
+                _ => false,
+            };
+        }
+        match self {
+            Available => !matches!(to, Finishing),
+            Preparing => !matches!(to, Reserved | Unavailable),
+            Charging | SuspendedEv | SuspendedEvse => !matches!(to, Preparing | Reserved),
+            Finishing => matches!(to, Available | Preparing | Unavailable | Faulted),
+            Reserved => matches!(to, Available | Preparing | Unavailable | Faulted),
+            Unavailable => !matches!(to, Finishing | Reserved),
+            Faulted => true,
+            Occupied => false,
+        }
+    }
+}
+
+/// Operational status of the Charging Station, an EVSE or a connector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperationalStatus {
+    /// Available for charging.
+    Operative,
+    /// Not available for charging.
+    Inoperative,
+}
+
+/// What a `ChangeAvailability` applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AvailabilityTarget {
+    /// The Charging Station as a whole, connector 0 in 1.6.
+    Station,
+    /// An EVSE, a connector in 1.6.
+    Evse(i32),
+    /// A connector of an EVSE (2.x).
+    Connector(i32, i32),
+}
+
+/// Result of a `ChangeAvailability`, `AvailabilityStatus` in 1.6 and
+/// `ChangeAvailabilityStatusEnumType` in 2.x.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeAvailabilityStatus {
+    /// The change has been made.
+    Accepted,
+    /// The target is unknown.
+    Rejected,
+    /// The change is made when the transactions of the target have ended.
+    Scheduled,
+}
+
+/// A connector changed status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusChange {
+    /// The EVSE, the connector in 1.6.
+    pub evse_id: i32,
+    /// The connector of the EVSE, 1 in 1.6.
+    pub connector_id: i32,
+    /// The new status.
+    pub status: ConnectorStatus,
+}
+
+/// Operation initiated by the CSMS on a connector, see [`AvailabilityManager::allows`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CsmsOperation {
+    /// `RemoteStartTransaction` (1.6), `RequestStartTransaction` (2.x).
+    RemoteStart,
+    /// `RemoteStopTransaction` (1.6), `RequestStopTransaction` (2.x).
+    RemoteStop,
+    /// `ReserveNow`.
+    ReserveNow,
+    /// `UnlockConnector`.
+    UnlockConnector,
+    /// `ChangeAvailability`.
+    ChangeAvailability,
+}
+
+/// Error of a reported status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AvailabilityError {
+    /// The EVSE or connector is unknown.
+    UnknownConnector {
+        /// The EVSE, the connector in 1.6.
+        evse_id: i32,
+        /// The connector of the EVSE.
+        connector_id: i32,
+    },
+    /// The status cannot follow the current status.
+    InvalidTransition {
+        /// The current status.
+        from: ConnectorStatus,
+        /// The reported status.
+        to: ConnectorStatus,
+    },
+    /// The connector is inoperative, so it can only be `Unavailable` or `Faulted`.
+    Inoperative(ConnectorStatus),
+}
+
+impl fmt::Display for AvailabilityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownConnector {
+                evse_id,
+                connector_id,
+            } => write!(f, "unknown connector {connector_id} of EVSE {evse_id}"),
+            Self::InvalidTransition { from, to } => {
+                write!(f, "connector cannot change from {from:?} to {to:?}")
+            }
+            Self::Inoperative(status) => write!(f, "inoperative connector cannot be {status:?}"),
+        }
+    }
+}
+
+impl std::error::Error for AvailabilityError {}
+
+#[cfg(test)]
+mod tests {
+    use super::ConnectorStatus::*;
+
+    #[test]
+    fn transitions_follow_the_ocpp_1_6_table() {
+        let all = [
+            Available,
+            Preparing,
+            Charging,
+            SuspendedEv,
+            SuspendedEvse,
+            Finishing,
+            Reserved,
+            Unavailable,
+            Faulted,
+        ];
+        // rows and columns in the order of `all`, as in the table of OCPP 1.6 section 4.9
+        let table = [
+            "-xxxx-xxx",
+            "x-xxxx--x",
+            "x-xxxx-xx",
+            "x-xxxx-xx",
+            "x-xxxx-xx",
+            "xx-----xx",
+            "xx-----xx",
+            "xxxxx---x",
+            "xxxxxxxx-",
+        ];
+        for (from, row) in all.iter().zip(table) {
+            for (to, allowed) in all.iter().zip(row.chars()) {
+                if from != to {
+                    assert_eq!(
+                        from.can_change_to(*to),
+                        allowed == 'x',
+                        "{from:?} to {to:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn occupied_transitions_follow_ocpp_2_x() {
+        assert!(Available.can_change_to(Occupied));
+        assert!(Reserved.can_change_to(Occupied));
+        assert!(Occupied.can_change_to(Unavailable));
+        assert!(Occupied.can_change_to(Faulted));
+        assert!(!Occupied.can_change_to(Reserved));
+        assert!(Charging.can_change_to(Occupied));
+        assert!(Occupied.can_change_to(Finishing));
+        assert!(!Unavailable.can_change_to(Reserved));
+    }
+}
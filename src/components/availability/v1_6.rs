@@ -0,0 +1,218 @@
+//! OCPP 1.6 messages for the [`AvailabilityManager`].
+//!
+//! In OCPP 1.6 connector N is EVSE N with a single connector, see [`AvailabilityTarget`].
+//! Connector 0 addresses the Charge Point as a whole.
+
+use chrono::{DateTime, Utc};
+
+use crate::v1_6::{
+    messages::{
+        change_availability::{ChangeAvailabilityRequest, ChangeAvailabilityResponse},
+        status_notification::StatusNotificationRequest,
+    },
+    types::{AvailabilityStatus, AvailabilityType, ChargePointErrorCode, ChargePointStatus},
+};
+
+use super::{
+    AvailabilityError, AvailabilityManager, AvailabilityTarget, ChangeAvailabilityStatus,
+    ConnectorStatus, OperationalStatus, StatusChange,
+};
+
+/// The target of `connector_id`, out of range connectors being unknown EVSEs.
+fn target(connector_id: u32) -> AvailabilityTarget {
+    match connector_id {
+        0 => AvailabilityTarget::Station,
+        connector_id => AvailabilityTarget::Evse(i32::try_from(connector_id).unwrap_or(i32::MAX)),
+    }
+}
+
+impl From<&ChargePointStatus> for ConnectorStatus {
+    fn from(status: &ChargePointStatus) -> Self {
+        match status {
+            ChargePointStatus::Available => Self::Available,
+            ChargePointStatus::Preparing => Self::Preparing,
+            ChargePointStatus::Charging => Self::Charging,
+            ChargePointStatus::SuspendedEVSE => Self::SuspendedEvse,
+            ChargePointStatus::SuspendedEV => Self::SuspendedEv,
+            ChargePointStatus::Finishing => Self::Finishing,
+            ChargePointStatus::Reserved => Self::Reserved,
+            ChargePointStatus::Unavailable => Self::Unavailable,
+            ChargePointStatus::Faulted => Self::Faulted,
+        }
+    }
+}
+
+impl From<ConnectorStatus> for ChargePointStatus {
+    fn from(status: ConnectorStatus) -> Self {
+        match status {
+            ConnectorStatus::Available => Self::Available,
+            ConnectorStatus::Preparing => Self::Preparing,
+            // only reported in 2.x, where a connector is mostly occupied while charging
+            ConnectorStatus::Charging | ConnectorStatus::Occupied => Self::Charging,
+            ConnectorStatus::SuspendedEv => Self::SuspendedEV,
+            ConnectorStatus::SuspendedEvse => Self::SuspendedEVSE,
+            ConnectorStatus::Finishing => Self::Finishing,
+            ConnectorStatus::Reserved => Self::Reserved,
+            ConnectorStatus::Unavailable => Self::Unavailable,
+            ConnectorStatus::Faulted => Self::Faulted,
+        }
+    }
+}
+
+impl From<&AvailabilityType> for OperationalStatus {
+    fn from(kind: &AvailabilityType) -> Self {
+        match kind {
+            AvailabilityType::Operative => Self::Operative,
+            AvailabilityType::Inoperative => Self::Inoperative,
+        }
+    }
+}
+
+impl From<ChangeAvailabilityStatus> for AvailabilityStatus {
+    fn from(status: ChangeAvailabilityStatus) -> Self {
+        match status {
+            ChangeAvailabilityStatus::Accepted => Self::Accepted,
+            ChangeAvailabilityStatus::Rejected => Self::Rejected,
+            ChangeAvailabilityStatus::Scheduled => Self::Scheduled,
+        }
+    }
+}
+
+/// `StatusNotificationRequest` reporting `change` at `now`.
+pub fn status_notification_request(
+    change: &StatusChange,
+    now: DateTime<Utc>,
+) -> StatusNotificationRequest {
+    StatusNotificationRequest {
+        connector_id: u32::try_from(change.evse_id).unwrap_or_default(),
+        error_code: match change.status {
+            ConnectorStatus::Faulted => ChargePointErrorCode::OtherError,
+            _ => ChargePointErrorCode::NoError,
+        },
+        status: change.status.into(),
+        timestamp: Some(now),
+        ..Default::default()
+    }
+}
+
+/// Handles a `ChangeAvailabilityRequest`, returning the `StatusNotificationRequest`s of the
+/// connectors whose status changed.
+pub fn change_availability(
+    manager: &mut AvailabilityManager,
+    request: &ChangeAvailabilityRequest,
+    now: DateTime<Utc>,
+) -> (ChangeAvailabilityResponse, Vec<StatusNotificationRequest>) {
+    let (status, changes) =
+        manager.change_availability(target(request.connector_id), (&request.kind).into());
+    let notifications = changes
+        .iter()
+        .map(|change| status_notification_request(change, now))
+        .collect();
+    (
+        ChangeAvailabilityResponse {
+            status: status.into(),
+        },
+        notifications,
+    )
+}
+
+/// Validates and applies a reported `StatusNotificationRequest`.
+///
+/// Connector 0 may only report `Available`, `Unavailable` or `Faulted`; its status is not
+/// kept, so it never changes a connector.
+pub fn status_notification(
+    manager: &mut AvailabilityManager,
+    request: &StatusNotificationRequest,
+) -> Result<Option<StatusChange>, AvailabilityError> {
+    let status = ConnectorStatus::from(&request.status);
+    match target(request.connector_id) {
+        AvailabilityTarget::Station => match status {
+            ConnectorStatus::Available
+            | ConnectorStatus::Unavailable
+            | ConnectorStatus::Faulted => Ok(None),
+            _ => Err(AvailabilityError::InvalidTransition {
+                from: ConnectorStatus::Available,
+                to: status,
+            }),
+        },
+        AvailabilityTarget::Evse(evse_id) | AvailabilityTarget::Connector(evse_id, _) => {
+            manager.report(evse_id, 1, status)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn change_availability_and_report() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut manager = AvailabilityManager::new(&[1, 1]);
+        manager.transaction_started(2);
+
+        let request = ChangeAvailabilityRequest {
+            connector_id: 0,
+            kind: AvailabilityType::Inoperative,
+        };
+        let (response, notifications) = change_availability(&mut manager, &request, now);
+        assert_eq!(response.status, AvailabilityStatus::Scheduled);
+        assert!(notifications.is_empty());
+
+        let request = ChangeAvailabilityRequest {
+            connector_id: 1,
+            kind: AvailabilityType::Inoperative,
+        };
+        let (response, notifications) = change_availability(&mut manager, &request, now);
+        assert_eq!(response.status, AvailabilityStatus::Accepted);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].connector_id, 1);
+        assert_eq!(notifications[0].status, ChargePointStatus::Unavailable);
+        assert_eq!(notifications[0].timestamp, Some(now));
+
+        let request = ChangeAvailabilityRequest {
+            connector_id: 3,
+            kind: AvailabilityType::Operative,
+        };
+        let (response, _) = change_availability(&mut manager, &request, now);
+        assert_eq!(response.status, AvailabilityStatus::Rejected);
+
+        let request = StatusNotificationRequest {
+            connector_id: 2,
+            status: ChargePointStatus::Charging,
+            ..Default::default()
+        };
+        assert_eq!(
+            status_notification(&mut manager, &request),
+            Ok(Some(StatusChange {
+                evse_id: 2,
+                connector_id: 1,
+                status: ConnectorStatus::Charging,
+            }))
+        );
+        let request = StatusNotificationRequest {
+            connector_id: 1,
+            status: ChargePointStatus::Preparing,
+            ..Default::default()
+        };
+        assert_eq!(
+            status_notification(&mut manager, &request),
+            Err(AvailabilityError::Inoperative(ConnectorStatus::Preparing))
+        );
+        let request = StatusNotificationRequest {
+            connector_id: 0,
+            status: ChargePointStatus::Charging,
+            ..Default::default()
+        };
+        assert!(status_notification(&mut manager, &request).is_err());
+
+        let changes = manager.transaction_ended(2);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(
+            status_notification_request(&changes[0], now).status,
+            ChargePointStatus::Unavailable
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! OCPP 2.0.1 messages for the [`AvailabilityManager`].
+
+use chrono::{DateTime, Utc};
+
+use crate::v2_0_1::{
+    datatypes::evse_type::EVSEType,
+    enumerations::{
+        change_availability_status_enum_type::ChangeAvailabilityStatusEnumType,
+        connector_status_enum_type::ConnectorStatusEnumType,
+        operational_status_enum_type::OperationalStatusEnumType,
+    },
+    messages::{
+        change_availability::{ChangeAvailabilityRequest, ChangeAvailabilityResponse},
+        status_notification::StatusNotificationRequest,
+    },
+};
+
+use super::{
+    AvailabilityError, AvailabilityManager, AvailabilityTarget, ChangeAvailabilityStatus,
+    ConnectorStatus, OperationalStatus, StatusChange,
+};
+
+impl From<Option<&EVSEType>> for AvailabilityTarget {
+    fn from(evse: Option<&EVSEType>) -> Self {
+        match evse {
+            None => Self::Station,
+            Some(EVSEType {
+                id,
+                connector_id: None,
+            }) => Self::Evse(*id),
+            Some(EVSEType {
+                id,
+                connector_id: Some(connector_id),
+            }) => Self::Connector(*id, *connector_id),
+        }
+    }
+}
+
+impl From<&ConnectorStatusEnumType> for ConnectorStatus {
+    fn from(status: &ConnectorStatusEnumType) -> Self {
+        match status {
+            ConnectorStatusEnumType::Available => Self::Available,
+            ConnectorStatusEnumType::Occupied => Self::Occupied,
+            ConnectorStatusEnumType::Reserved => Self::Reserved,
+            ConnectorStatusEnumType::Unavailable => Self::Unavailable,
+            ConnectorStatusEnumType::Faulted => Self::Faulted,
+        }
+    }
+}
+
+impl From<ConnectorStatus> for ConnectorStatusEnumType {
+    fn from(status: ConnectorStatus) -> Self {
+        match status {
+            ConnectorStatus::Available => Self::Available,
+            ConnectorStatus::Reserved => Self::Reserved,
+            ConnectorStatus::Unavailable => Self::Unavailable,
+            ConnectorStatus::Faulted => Self::Faulted,
+            _ => Self::Occupied,
+        }
+    }
+}
+
+impl From<&OperationalStatusEnumType> for OperationalStatus {
+    fn from(status: &OperationalStatusEnumType) -> Self {
+        match status {
+            OperationalStatusEnumType::Operative => Self::Operative,
+            OperationalStatusEnumType::Inoperative => Self::Inoperative,
+        }
+    }
+}
+
+impl From<ChangeAvailabilityStatus> for ChangeAvailabilityStatusEnumType {
+    fn from(status: ChangeAvailabilityStatus) -> Self {
+        match status {
+            ChangeAvailabilityStatus::Accepted => Self::Accepted,
+            ChangeAvailabilityStatus::Rejected => Self::Rejected,
+            ChangeAvailabilityStatus::Scheduled => Self::Scheduled,
+        }
+    }
+}
+
+/// `StatusNotificationRequest` reporting `change` at `now`.
+pub fn status_notification_request(
+    change: &StatusChange,
+    now: DateTime<Utc>,
+) -> StatusNotificationRequest {
+    StatusNotificationRequest {
+        timestamp: now,
+        connector_status: change.status.into(),
+        evse_id: change.evse_id,
+        connector_id: change.connector_id,
+    }
+}
+
+/// Handles a `ChangeAvailabilityRequest`, returning the `StatusNotificationRequest`s of the
+/// connectors whose status changed.
+pub fn change_availability(
+    manager: &mut AvailabilityManager,
+    request: &ChangeAvailabilityRequest,
+    now: DateTime<Utc>,
+) -> (ChangeAvailabilityResponse, Vec<StatusNotificationRequest>) {
+    let (status, changes) = manager.change_availability(
+        request.evse.as_ref().into(),
+        (&request.operational_status).into(),
+    );
+    let notifications = changes
+        .iter()
+        .map(|change| status_notification_request(change, now))
+        .collect();
+    (
+        ChangeAvailabilityResponse {
+            status: status.into(),
+            status_info: None,
+        },
+        notifications,
+    )
+}
+
+/// Validates and applies a reported `StatusNotificationRequest`.
+pub fn status_notification(
+    manager: &mut AvailabilityManager,
+    request: &StatusNotificationRequest,
+) -> Result<Option<StatusChange>, AvailabilityError> {
+    manager.report(
+        request.evse_id,
+        request.connector_id,
+        (&request.connector_status).into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn change_availability_and_report() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut manager = AvailabilityManager::new(&[2]);
+
+        let request = StatusNotificationRequest {
+            timestamp: now,
+            connector_status: ConnectorStatusEnumType::Occupied,
+            evse_id: 1,
+            connector_id: 2,
+        };
+        assert!(status_notification(&mut manager, &request)
+            .unwrap()
+            .is_some());
+        manager.transaction_started(1);
+
+        let request = ChangeAvailabilityRequest {
+            operational_status: OperationalStatusEnumType::Inoperative,
+            evse: Some(EVSEType {
+                id: 1,
+                connector_id: Some(1),
+            }),
+        };
+        let (response, notifications) = change_availability(&mut manager, &request, now);
+        assert_eq!(response.status, ChangeAvailabilityStatusEnumType::Scheduled);
+        assert!(notifications.is_empty());
+
+        let request = StatusNotificationRequest {
+            timestamp: now,
+            connector_status: ConnectorStatusEnumType::Available,
+            evse_id: 1,
+            connector_id: 2,
+        };
+        assert!(status_notification(&mut manager, &request).is_ok());
+        let notifications: Vec<_> = manager
+            .transaction_ended(1)
+            .iter()
+            .map(|change| status_notification_request(change, now))
+            .collect();
+        assert_eq!(
+            notifications,
+            vec![StatusNotificationRequest {
+                timestamp: now,
+                connector_status: ConnectorStatusEnumType::Unavailable,
+                evse_id: 1,
+                connector_id: 1,
+            }]
+        );
+
+        let request = ChangeAvailabilityRequest {
+            operational_status: OperationalStatusEnumType::Operative,
+            evse: None,
+        };
+        let (response, notifications) = change_availability(&mut manager, &request, now);
+        assert_eq!(response.status, ChangeAvailabilityStatusEnumType::Accepted);
+        assert!(notifications.is_empty());
+
+        let request = StatusNotificationRequest {
+            timestamp: now,
+            connector_status: ConnectorStatusEnumType::Reserved,
+            evse_id: 1,
+            connector_id: 1,
+        };
+        assert_eq!(
+            status_notification(&mut manager, &request),
+            Err(AvailabilityError::Inoperative(ConnectorStatus::Reserved))
+        );
+    }
+}
@@ -0,0 +1,194 @@
+//! OCPP 2.1 messages for the [`AvailabilityManager`].
+
+use chrono::{DateTime, Utc};
+
+use crate::v2_1::{
+    enumerations::{
+        ChangeAvailabilityStatusEnumType, ConnectorStatusEnumType, OperationalStatusEnumType,
+    },
+    messages::{
+        change_availability::{ChangeAvailabilityRequest, ChangeAvailabilityResponse, EVSEType},
+        status_notification::StatusNotificationRequest,
+    },
+};
+
+use super::{
+    AvailabilityError, AvailabilityManager, AvailabilityTarget, ChangeAvailabilityStatus,
+    ConnectorStatus, OperationalStatus, StatusChange,
+};
+
+impl From<Option<&EVSEType>> for AvailabilityTarget {
+    fn from(evse: Option<&EVSEType>) -> Self {
+        match evse {
+            None => Self::Station,
+            Some(evse) => match evse.connector_id {
+                None => Self::Evse(evse.id),
+                Some(connector_id) => Self::Connector(evse.id, connector_id),
+            },
+        }
+    }
+}
+
+impl From<&ConnectorStatusEnumType> for ConnectorStatus {
+    fn from(status: &ConnectorStatusEnumType) -> Self {
+        match status {
+            ConnectorStatusEnumType::Available => Self::Available,
+            ConnectorStatusEnumType::Occupied => Self::Occupied,
+            ConnectorStatusEnumType::Reserved => Self::Reserved,
+            ConnectorStatusEnumType::Unavailable => Self::Unavailable,
+            ConnectorStatusEnumType::Faulted => Self::Faulted,
+        }
+    }
+}
+
+impl From<ConnectorStatus> for ConnectorStatusEnumType {
+    fn from(status: ConnectorStatus) -> Self {
+        match status {
+            ConnectorStatus::Available => Self::Available,
+            ConnectorStatus::Reserved => Self::Reserved,
+            ConnectorStatus::Unavailable => Self::Unavailable,
+            ConnectorStatus::Faulted => Self::Faulted,
+            _ => Self::Occupied,
+        }
+    }
+}
+
+impl From<&OperationalStatusEnumType> for OperationalStatus {
+    fn from(status: &OperationalStatusEnumType) -> Self {
+        match status {
+            OperationalStatusEnumType::Operative => Self::Operative,
+            OperationalStatusEnumType::Inoperative => Self::Inoperative,
+        }
+    }
+}
+
+impl From<ChangeAvailabilityStatus> for ChangeAvailabilityStatusEnumType {
+    fn from(status: ChangeAvailabilityStatus) -> Self {
+        match status {
+            ChangeAvailabilityStatus::Accepted => Self::Accepted,
+            ChangeAvailabilityStatus::Rejected => Self::Rejected,
+            ChangeAvailabilityStatus::Scheduled => Self::Scheduled,
+        }
+    }
+}
+
+/// `StatusNotificationRequest` reporting `change` at `now`.
+pub fn status_notification_request(
+    change: &StatusChange,
+    now: DateTime<Utc>,
+) -> StatusNotificationRequest {
+    StatusNotificationRequest {
+        custom_data: None,
+        timestamp: now,
+        connector_status: change.status.into(),
+        evse_id: change.evse_id,
+        connector_id: change.connector_id,
+    }
+}
+
+/// Handles a `ChangeAvailabilityRequest`, returning the `StatusNotificationRequest`s of the
+/// connectors whose status changed.
+pub fn change_availability(
+    manager: &mut AvailabilityManager,
+    request: &ChangeAvailabilityRequest,
+    now: DateTime<Utc>,
+) -> (ChangeAvailabilityResponse, Vec<StatusNotificationRequest>) {
+    let (status, changes) = manager.change_availability(
+        request.evse.as_ref().into(),
+        (&request.operational_status).into(),
+    );
+    let notifications = changes
+        .iter()
+        .map(|change| status_notification_request(change, now))
+        .collect();
+    (
+        ChangeAvailabilityResponse {
+            status: status.into(),
+            status_info: None,
+            custom_data: None,
+        },
+        notifications,
+    )
+}
+
+/// Validates and applies a reported `StatusNotificationRequest`.
+pub fn status_notification(
+    manager: &mut AvailabilityManager,
+    request: &StatusNotificationRequest,
+) -> Result<Option<StatusChange>, AvailabilityError> {
+    manager.report(
+        request.evse_id,
+        request.connector_id,
+        (&request.connector_status).into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::components::availability::CsmsOperation;
+
+    #[test]
+    fn change_availability_and_report() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut manager = AvailabilityManager::new(&[1, 1]);
+
+        let request = ChangeAvailabilityRequest {
+            evse: Some(EVSEType {
+                id: 2,
+                connector_id: None,
+                custom_data: None,
+            }),
+            operational_status: OperationalStatusEnumType::Inoperative,
+            custom_data: None,
+        };
+        let (response, notifications) = change_availability(&mut manager, &request, now);
+        assert_eq!(response.status, ChangeAvailabilityStatusEnumType::Accepted);
+        assert_eq!(
+            notifications,
+            vec![StatusNotificationRequest {
+                custom_data: None,
+                timestamp: now,
+                connector_status: ConnectorStatusEnumType::Unavailable,
+                evse_id: 2,
+                connector_id: 1,
+            }]
+        );
+        assert!(!manager.allows(2, 1, CsmsOperation::ReserveNow));
+
+        let request = StatusNotificationRequest {
+            custom_data: None,
+            timestamp: now,
+            connector_status: ConnectorStatusEnumType::Occupied,
+            evse_id: 1,
+            connector_id: 1,
+        };
+        assert_eq!(
+            status_notification(&mut manager, &request),
+            Ok(Some(StatusChange {
+                evse_id: 1,
+                connector_id: 1,
+                status: ConnectorStatus::Occupied,
+            }))
+        );
+        let request = StatusNotificationRequest {
+            connector_status: ConnectorStatusEnumType::Reserved,
+            ..request
+        };
+        assert!(status_notification(&mut manager, &request).is_err());
+
+        let request = ChangeAvailabilityRequest {
+            evse: Some(EVSEType {
+                id: 1,
+                connector_id: Some(2),
+                custom_data: None,
+            }),
+            operational_status: OperationalStatusEnumType::Operative,
+            custom_data: None,
+        };
+        let (response, _) = change_availability(&mut manager, &request, now);
+        assert_eq!(response.status, ChangeAvailabilityStatusEnumType::Rejected);
+    }
+}
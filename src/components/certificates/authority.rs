@@ -0,0 +1,235 @@
+use std::{str::FromStr, time::SystemTime};
+
+use chrono::{DateTime, Utc};
+use p256::ecdsa::{DerSignature, SigningKey};
+use rand_core::{OsRng, RngCore};
+use x509_cert::{
+    builder::{Builder, CertificateBuilder, Profile, RequestBuilder},
+    der::{pem::LineEnding, DecodePem, Encode, EncodePem},
+    name::Name,
+    request::CertReq,
+    serial_number::SerialNumber,
+    spki::SubjectPublicKeyInfoOwned,
+    time::{Time, Validity},
+    Certificate,
+};
+
+use super::{verify::verify_signature, CertificateError};
+
+/// Certificate authority with a P-256 key that issues certificates, for example to sign
+/// the certificate signing requests of Charging Stations on the CSMS side, or to set up a
+/// local public key infrastructure in tests.
+#[derive(Debug, Clone)]
+pub struct CertificateAuthority {
+    certificate: Certificate,
+    key: SigningKey,
+    chain: Vec<Certificate>,
+}
+
+impl CertificateAuthority {
+    /// Creates a self-signed root certificate authority with a new key.
+    ///
+    /// `subject` is a distinguished name such as `CN=CSMS Root,O=Example`.
+    pub fn new_root(
+        subject: &str,
+        not_before: DateTime<Utc>,
+        not_after: DateTime<Utc>,
+    ) -> Result<Self, CertificateError> {
+        let key = SigningKey::random(&mut OsRng);
+        let subject = parse_name(subject)?;
+        let public_key = public_key_info(&key)?;
+        let builder = CertificateBuilder::new(
+            Profile::Root,
+            random_serial_number()?,
+            validity(not_before, not_after)?,
+            subject,
+            public_key,
+            &key,
+        )
+        .map_err(signing_error)?;
+        let certificate = builder.build::<DerSignature>().map_err(signing_error)?;
+        Ok(Self {
+            certificate,
+            key,
+            chain: Vec::new(),
+        })
+    }
+
+    /// Uses an existing certificate authority certificate and its key.
+    ///
+    /// `chain` holds the certificates of the authorities between this certificate
+    /// authority and the root, which are sent along with issued certificates.
+    pub fn from_key(certificate: Certificate, key: SigningKey, chain: Vec<Certificate>) -> Self {
+        Self {
+            certificate,
+            key,
+            chain,
+        }
+    }
+
+    /// Certificate of the certificate authority.
+    pub fn certificate(&self) -> &Certificate {
+        &self.certificate
+    }
+
+    /// Creates a subordinate certificate authority with a new key.
+    pub fn new_sub_ca(
+        &self,
+        subject: &str,
+        not_before: DateTime<Utc>,
+        not_after: DateTime<Utc>,
+    ) -> Result<Self, CertificateError> {
+        let key = SigningKey::random(&mut OsRng);
+        let profile = Profile::SubCA {
+            issuer: self.certificate.tbs_certificate.subject.clone(),
+            path_len_constraint: None,
+        };
+        let certificate = self.issue(
+            profile,
+            parse_name(subject)?,
+            public_key_info(&key)?,
+            not_before,
+            not_after,
+        )?;
+        Ok(Self {
+            certificate,
+            key,
+            chain: self.issued_chain(),
+        })
+    }
+
+    /// Issues an end entity certificate for `public_key` and returns it followed by the
+    /// certificates of the issuing authorities up to, but not including, the root.
+    pub fn issue_leaf(
+        &self,
+        subject: &str,
+        public_key: SubjectPublicKeyInfoOwned,
+        not_before: DateTime<Utc>,
+        not_after: DateTime<Utc>,
+    ) -> Result<Vec<Certificate>, CertificateError> {
+        let profile = Profile::Leaf {
+            issuer: self.certificate.tbs_certificate.subject.clone(),
+            enable_key_agreement: true,
+            enable_key_encipherment: false,
+        };
+        let leaf = self.issue(
+            profile,
+            parse_name(subject)?,
+            public_key,
+            not_before,
+            not_after,
+        )?;
+        Ok(std::iter::once(leaf).chain(self.issued_chain()).collect())
+    }
+
+    /// Signs a PEM encoded certificate signing request, such as the `csr` of a
+    /// `SignCertificateRequest`, and returns the chain as [`Self::issue_leaf`].
+    ///
+    /// The subject of the certificate is the subject of the request.
+    pub fn sign_request(
+        &self,
+        csr: &str,
+        not_before: DateTime<Utc>,
+        not_after: DateTime<Utc>,
+    ) -> Result<Vec<Certificate>, CertificateError> {
+        let request = CertReq::from_pem(csr.trim())?;
+        verify_signature(
+            &request.info.public_key,
+            &request.algorithm,
+            &request.info.to_der()?,
+            request.signature.raw_bytes(),
+        )?;
+        let profile = Profile::Leaf {
+            issuer: self.certificate.tbs_certificate.subject.clone(),
+            enable_key_agreement: true,
+            enable_key_encipherment: false,
+        };
+        let leaf = self.issue(
+            profile,
+            request.info.subject,
+            request.info.public_key,
+            not_before,
+            not_after,
+        )?;
+        Ok(std::iter::once(leaf).chain(self.issued_chain()).collect())
+    }
+
+    fn issue(
+        &self,
+        profile: Profile,
+        subject: Name,
+        public_key: SubjectPublicKeyInfoOwned,
+        not_before: DateTime<Utc>,
+        not_after: DateTime<Utc>,
+    ) -> Result<Certificate, CertificateError> {
+        CertificateBuilder::new(
+            profile,
+            random_serial_number()?,
+            validity(not_before, not_after)?,
+            subject,
+            public_key,
+            &self.key,
+        )
+        .map_err(signing_error)?
+        .build::<DerSignature>()
+        .map_err(signing_error)
+    }
+
+    /// Certificates sent along with certificates issued by this authority.
+    fn issued_chain(&self) -> Vec<Certificate> {
+        let self_signed =
+            self.certificate.tbs_certificate.issuer == self.certificate.tbs_certificate.subject;
+        match self_signed {
+            true => Vec::new(),
+            false => std::iter::once(self.certificate.clone())
+                .chain(self.chain.iter().cloned())
+                .collect(),
+        }
+    }
+}
+
+/// Generates a P-256 key and a PEM encoded certificate signing request for `subject`.
+pub(crate) fn certificate_signing_request(
+    subject: &str,
+) -> Result<(SigningKey, String), CertificateError> {
+    let key = SigningKey::random(&mut OsRng);
+    let request = RequestBuilder::new(parse_name(subject)?, &key)
+        .map_err(signing_error)?
+        .build::<DerSignature>()
+        .map_err(signing_error)?;
+    Ok((key, request.to_pem(LineEnding::LF)?))
+}
+
+/// Public key info of a P-256 key.
+pub(crate) fn public_key_info(
+    key: &SigningKey,
+) -> Result<SubjectPublicKeyInfoOwned, CertificateError> {
+    SubjectPublicKeyInfoOwned::from_key(*key.verifying_key()).map_err(signing_error)
+}
+
+fn parse_name(name: &str) -> Result<Name, CertificateError> {
+    Name::from_str(name).map_err(|error| CertificateError::Encoding(error.to_string()))
+}
+
+fn validity(
+    not_before: DateTime<Utc>,
+    not_after: DateTime<Utc>,
+) -> Result<Validity, CertificateError> {
+    let time = |time: DateTime<Utc>| Time::try_from(SystemTime::from(time));
+    Ok(Validity {
+        not_before: time(not_before)?,
+        not_after: time(not_after)?,
+    })
+}
+
+fn random_serial_number() -> Result<SerialNumber, CertificateError> {
+    let mut bytes = [0; 16];
+    OsRng.fill_bytes(&mut bytes);
+    // positive and without leading zero byte
+    bytes[0] = (bytes[0] & 0x7f) | 0x40;
+    Ok(SerialNumber::new(&bytes)?)
+}
+
+fn signing_error(error: impl std::fmt::Display) -> CertificateError {
+    CertificateError::Signing(error.to_string())
+}
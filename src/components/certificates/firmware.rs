@@ -0,0 +1,220 @@
+use std::fmt;
+
+use base64ct::{Base64, Encoding};
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+
+use super::{
+    verify::verify_prehash, Certificate, CertificateError, CertificateKind, CertificateStore,
+    HashAlgorithm,
+};
+
+/// Reason why a signed firmware update is rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FirmwareVerificationError {
+    /// The signing certificate is missing, malformed or does not lead to an installed
+    /// manufacturer root certificate, reported as `InvalidCertificate`.
+    InvalidCertificate(CertificateError),
+    /// The signature is missing, malformed or does not match the firmware image,
+    /// reported as `InvalidSignature`.
+    InvalidSignature(CertificateError),
+}
+
+impl fmt::Display for FirmwareVerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidCertificate(error) => write!(f, "invalid signing certificate: {error}"),
+            Self::InvalidSignature(error) => write!(f, "invalid firmware signature: {error}"),
+        }
+    }
+}
+
+impl FirmwareVerificationError {
+    /// Reason code reported in the `StatusInfoType` of a rejected update.
+    pub fn reason_code(&self) -> &'static str {
+        match self {
+            Self::InvalidCertificate(error) => error.reason_code(),
+            Self::InvalidSignature(_) => "InvalidSignature",
+        }
+    }
+}
+
+impl std::error::Error for FirmwareVerificationError {}
+
+/// Verifies a signed firmware image while it is downloaded.
+///
+/// The signing certificate is verified against the installed manufacturer root
+/// certificates when the update is requested, so that it can be answered with
+/// `InvalidCertificate`. The signature is verified over the SHA-256 hash of the image
+/// once it has been downloaded, with the key of the signing certificate.
+///
+/// The same applies to the `signingCertificate` and `signature` of a 1.6
+/// `SignedUpdateFirmware.req`.
+#[derive(Debug, Clone)]
+pub struct FirmwareVerifier {
+    certificate: Certificate,
+    signature: Vec<u8>,
+    hasher: Sha256,
+}
+
+impl FirmwareVerifier {
+    /// Verifies the PEM encoded `signing_certificate`, which may be followed by the
+    /// certificates of its issuing sub CAs, and decodes the Base64 encoded `signature`.
+    pub fn new(
+        store: &CertificateStore,
+        signing_certificate: &str,
+        signature: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Self, FirmwareVerificationError> {
+        let certificate = store
+            .verify_certificate(CertificateKind::ManufacturerRoot, signing_certificate, now)
+            .map_err(FirmwareVerificationError::InvalidCertificate)?;
+        let signature = Base64::decode_vec(signature.trim()).map_err(|error| {
+            FirmwareVerificationError::InvalidSignature(CertificateError::Encoding(
+                error.to_string(),
+            ))
+        })?;
+        Ok(Self {
+            certificate,
+            signature,
+            hasher: Sha256::new(),
+        })
+    }
+
+    /// Signing certificate of the firmware.
+    pub fn certificate(&self) -> &Certificate {
+        &self.certificate
+    }
+
+    /// Adds the next part of the downloaded image.
+    pub fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    /// Verifies the signature over all parts of the image added so far.
+    pub fn verify(self) -> Result<(), FirmwareVerificationError> {
+        verify_prehash(
+            &self.certificate.tbs_certificate.subject_public_key_info,
+            HashAlgorithm::Sha256,
+            &self.hasher.finalize(),
+            &self.signature,
+        )
+        .map_err(FirmwareVerificationError::InvalidSignature)
+    }
+}
+
+/// Verifies a complete signed firmware image, see [`FirmwareVerifier`].
+pub fn verify_firmware(
+    store: &CertificateStore,
+    signing_certificate: &str,
+    signature: &str,
+    image: &[u8],
+    now: DateTime<Utc>,
+) -> Result<(), FirmwareVerificationError> {
+    let mut verifier = FirmwareVerifier::new(store, signing_certificate, signature, now)?;
+    verifier.update(image);
+    verifier.verify()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use chrono::{Duration, TimeZone};
+    use p256::ecdsa::{signature::hazmat::PrehashSigner, DerSignature, SigningKey};
+    use rand_core::OsRng;
+
+    use super::*;
+    use crate::components::certificates::{
+        authority::public_key_info, to_pem_chain, CertificateAuthority,
+    };
+
+    pub(crate) const IMAGE: &[u8] = b"firmware image 1.2.3";
+
+    /// Store with a manufacturer root, and the PEM encoded signing certificate chain and
+    /// Base64 encoded signature of [`IMAGE`].
+    pub(crate) fn signed_firmware(now: DateTime<Utc>) -> (CertificateStore, String, String) {
+        let not_after = now + Duration::days(365);
+        let root = CertificateAuthority::new_root("CN=Manufacturer Root", now, not_after).unwrap();
+        let sub_ca = root.new_sub_ca("CN=Firmware CA", now, not_after).unwrap();
+        let key = SigningKey::random(&mut OsRng);
+        let chain = sub_ca
+            .issue_leaf(
+                "CN=Firmware Signing",
+                public_key_info(&key).unwrap(),
+                now,
+                not_after,
+            )
+            .unwrap();
+        let signature: DerSignature = key.sign_prehash(&Sha256::digest(IMAGE)).unwrap();
+
+        let mut store = CertificateStore::new();
+        let root_pem = to_pem_chain(std::slice::from_ref(root.certificate())).unwrap();
+        store
+            .install_root(CertificateKind::ManufacturerRoot, &root_pem, now)
+            .unwrap();
+        (
+            store,
+            to_pem_chain(&chain).unwrap(),
+            Base64::encode_string(signature.as_bytes()),
+        )
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn verifies_signed_firmware() {
+        let (store, certificate, signature) = signed_firmware(now());
+        assert_eq!(
+            verify_firmware(&store, &certificate, &signature, IMAGE, now()),
+            Ok(())
+        );
+
+        let mut verifier = FirmwareVerifier::new(&store, &certificate, &signature, now()).unwrap();
+        for part in IMAGE.chunks(3) {
+            verifier.update(part);
+        }
+        assert_eq!(verifier.verify(), Ok(()));
+
+        assert_eq!(
+            verify_firmware(&store, &certificate, &signature, b"tampered image", now()),
+            Err(FirmwareVerificationError::InvalidSignature(
+                CertificateError::InvalidSignature
+            ))
+        );
+        assert!(matches!(
+            verify_firmware(&store, &certificate, "not base64!", IMAGE, now()),
+            Err(FirmwareVerificationError::InvalidSignature(_))
+        ));
+    }
+
+    #[test]
+    fn signing_certificate_must_lead_to_manufacturer_root() {
+        let (store, certificate, signature) = signed_firmware(now());
+        let (other_store, _, _) = signed_firmware(now());
+        assert_eq!(
+            verify_firmware(&other_store, &certificate, &signature, IMAGE, now()),
+            Err(FirmwareVerificationError::InvalidCertificate(
+                CertificateError::UntrustedRoot
+            ))
+        );
+        assert_eq!(
+            verify_firmware(
+                &store,
+                &certificate,
+                &signature,
+                IMAGE,
+                now() + Duration::days(400)
+            ),
+            Err(FirmwareVerificationError::InvalidCertificate(
+                CertificateError::OutsideValidityPeriod
+            ))
+        );
+        assert_eq!(
+            verify_firmware(&store, "", &signature, IMAGE, now()),
+            Err(FirmwareVerificationError::InvalidCertificate(
+                CertificateError::EmptyChain
+            ))
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use x509_cert::{
+    der::{oid::db::rfc5280::ID_AD_OCSP, Encode},
+    ext::pkix::{name::GeneralName, AuthorityInfoAccessSyntax},
+    Certificate,
+};
+
+use super::{CertificateError, CertificateHash, HashAlgorithm, OcspRequest};
+
+/// Computes the hash data of `certificate`, which is issued by `issuer`.
+///
+/// The issuer of a self-signed root certificate is the certificate itself.
+pub fn certificate_hash(
+    certificate: &Certificate,
+    issuer: &Certificate,
+    hash_algorithm: HashAlgorithm,
+) -> Result<CertificateHash, CertificateError> {
+    let tbs = &certificate.tbs_certificate;
+    let issuer_key = &issuer
+        .tbs_certificate
+        .subject_public_key_info
+        .subject_public_key;
+    Ok(CertificateHash {
+        hash_algorithm,
+        issuer_name_hash: hex(&digest(hash_algorithm, &tbs.issuer.to_der()?)),
+        issuer_key_hash: hex(&digest(hash_algorithm, issuer_key.raw_bytes())),
+        serial_number: serial_number(tbs.serial_number.as_bytes()),
+    })
+}
+
+/// Computes the hash data of every certificate of `chain`, in the same order.
+///
+/// The issuer of each certificate is looked up in the chain by its subject, so the chain
+/// must end with a self-signed root or with a certificate whose issuer is part of it.
+pub fn chain_hashes(
+    chain: &[Certificate],
+    hash_algorithm: HashAlgorithm,
+) -> Result<Vec<CertificateHash>, CertificateError> {
+    chain
+        .iter()
+        .map(|certificate| {
+            let issuer = chain
+                .iter()
+                .find(|issuer| issuer.tbs_certificate.subject == certificate.tbs_certificate.issuer)
+                .ok_or(CertificateError::MissingIssuer)?;
+            certificate_hash(certificate, issuer, hash_algorithm)
+        })
+        .collect()
+}
+
+/// Computes the data of an OCSP request for `certificate`, which is issued by `issuer`.
+///
+/// The responder is the first OCSP URL in the authority information access extension of
+/// the certificate.
+pub fn ocsp_request(
+    certificate: &Certificate,
+    issuer: &Certificate,
+    hash_algorithm: HashAlgorithm,
+) -> Result<OcspRequest, CertificateError> {
+    let access = certificate
+        .tbs_certificate
+        .get::<AuthorityInfoAccessSyntax>()?
+        .map(|(_, access)| access.0)
+        .unwrap_or_default();
+    let responder_url = access
+        .into_iter()
+        .filter(|description| description.access_method == ID_AD_OCSP)
+        .find_map(|description| match description.access_location {
+            GeneralName::UniformResourceIdentifier(uri) => Some(uri.to_string()),
+            _ => None,
+        })
+        .ok_or(CertificateError::NoOcspResponder)?;
+    Ok(OcspRequest {
+        certificate_hash: certificate_hash(certificate, issuer, hash_algorithm)?,
+        responder_url,
+    })
+}
+
+pub(crate) fn digest(hash_algorithm: HashAlgorithm, data: &[u8]) -> Vec<u8> {
+    match hash_algorithm {
+        HashAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+        HashAlgorithm::Sha384 => Sha384::digest(data).to_vec(),
+        HashAlgorithm::Sha512 => Sha512::digest(data).to_vec(),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Hexadecimal serial number without leading zeroes, "0" for a zero serial number.
+fn serial_number(bytes: &[u8]) -> String {
+    let serial = hex(bytes);
+    match serial.trim_start_matches('0') {
+        "" => "0".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::certificates::{
+        parse_pem_chain,
+        tests::{LEAF, ROOT, SUB_CA},
+    };
+
+    fn certificate(pem: &str) -> Certificate {
+        parse_pem_chain(pem).unwrap().remove(0)
+    }
+
+    // expected values from `openssl ocsp -issuer <issuer> -cert <certificate> -req_text`
+    #[test]
+    fn hash_data_of_certificate() {
+        let hash = certificate_hash(
+            &certificate(LEAF),
+            &certificate(SUB_CA),
+            HashAlgorithm::Sha256,
+        )
+        .unwrap();
+        assert_eq!(
+            hash.issuer_name_hash,
+            "37aca09255e238a9a84604e89a443811f1181a3715522e6d47fa0124c55a3f88"
+        );
+        assert_eq!(
+            hash.issuer_key_hash,
+            "bbc3f7259463f2d0814111610da9c1127a387604d4878abc0c43f6cdf438fd2d"
+        );
+        // encoded with a leading zero byte
+        assert_eq!(hash.serial_number, "f1e2d3c4b5a69788");
+
+        let hash = certificate_hash(
+            &certificate(LEAF),
+            &certificate(SUB_CA),
+            HashAlgorithm::Sha384,
+        )
+        .unwrap();
+        assert!(hash
+            .issuer_name_hash
+            .starts_with("c03ec17ec23ce73fbf8c64fd1174b4af"));
+        assert_eq!(hash.issuer_key_hash.len(), 96);
+
+        let hash = certificate_hash(
+            &certificate(LEAF),
+            &certificate(SUB_CA),
+            HashAlgorithm::Sha512,
+        )
+        .unwrap();
+        assert!(hash
+            .issuer_key_hash
+            .starts_with("6b2c18fcfb1104b1d6af95adfaecded5"));
+        assert_eq!(hash.issuer_name_hash.len(), 128);
+    }
+
+    #[test]
+    fn hash_data_of_chain() {
+        let chain = parse_pem_chain(&format!("{LEAF}{SUB_CA}{ROOT}")).unwrap();
+        let hashes = chain_hashes(&chain, HashAlgorithm::Sha256).unwrap();
+        assert_eq!(hashes.len(), 3);
+        assert_eq!(hashes[1].serial_number, "a1b2c");
+        // the root is its own issuer
+        assert_eq!(hashes[2].issuer_name_hash, hashes[1].issuer_name_hash);
+        assert_eq!(
+            hashes[2].issuer_key_hash,
+            "59c30fc6eee42e5537c1f22d6792a1f6964808ada59760492d774a52ee9aff37"
+        );
+        assert_eq!(hashes[2].serial_number, "1");
+
+        assert_eq!(
+            chain_hashes(&chain[..2], HashAlgorithm::Sha256),
+            Err(CertificateError::MissingIssuer)
+        );
+    }
+
+    #[test]
+    fn ocsp_request_data() {
+        let request = ocsp_request(
+            &certificate(LEAF),
+            &certificate(SUB_CA),
+            HashAlgorithm::Sha256,
+        )
+        .unwrap();
+        assert_eq!(request.responder_url, "http://ocsp.example.com");
+        assert_eq!(request.certificate_hash.serial_number, "f1e2d3c4b5a69788");
+
+        assert_eq!(
+            ocsp_request(
+                &certificate(SUB_CA),
+                &certificate(ROOT),
+                HashAlgorithm::Sha256
+            ),
+            Err(CertificateError::NoOcspResponder)
+        );
+    }
+}
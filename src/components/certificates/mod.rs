@@ -0,0 +1,234 @@
+//! # Certificates
+//!
+//! X.509 certificate utilities of the OCPP security profiles, available with the `certs`
+//! feature.
+//!
+//! Certificates are identified in `GetInstalledCertificateIds`, `DeleteCertificate`,
+//! `GetCertificateStatus` and `Authorize` by the hashes of their issuer name and issuer
+//! public key and by their serial number, as in an OCSP `CertID` (RFC 6960).
+//! [`certificate_hash`] computes these from a certificate and its issuer,
+//! [`chain_hashes`] from a chain and [`ocsp_request`] adds the OCSP responder of the
+//! certificate. The version submodules convert the results into `CertificateHashDataType`,
+//! `CertificateHashDataChainType` and `OCSPRequestDataType`.
+//!
+//! The [`CertificateStore`] manages the certificates of a Charging Station: it generates the
+//! certificate signing requests of `SignCertificate`, verifies the chains received in
+//! `CertificateSigned`, installs roots with `InstallCertificate` and answers
+//! `GetInstalledCertificateIds` and `DeleteCertificate`. A [`CertificateAuthority`] signs
+//! the requests on the CSMS side, or sets up a local public key infrastructure in tests.
+//! The version submodules handle the corresponding requests.
+//!
+//! A [`FirmwareVerifier`] checks signed firmware updates: the signing certificate against
+//! the installed manufacturer roots and the signature over the downloaded image.
+
+use std::fmt;
+
+use x509_cert::der::{pem::LineEnding, EncodePem};
+
+pub use p256::ecdsa::SigningKey;
+pub use x509_cert::Certificate;
+
+mod authority;
+mod firmware;
+mod hash;
+mod store;
+mod verify;
+
+pub use authority::CertificateAuthority;
+pub use firmware::{verify_firmware, FirmwareVerificationError, FirmwareVerifier};
+pub use hash::{certificate_hash, chain_hashes, ocsp_request};
+pub use store::{CertificateKind, CertificateStore, DeleteStatus, InstalledCertificate};
+pub use verify::{
+    check_certificate_authority, check_validity, verify_chain, verify_issued_by, verify_prehash,
+    verify_signature,
+};
+
+#[cfg(feature = "v2_0_1")]
+pub mod v2_0_1;
+#[cfg(feature = "v2_1")]
+pub mod v2_1;
+
+/// Hash algorithm of certificate hash data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    /// SHA-256
+    #[default]
+    Sha256,
+    /// SHA-384
+    Sha384,
+    /// SHA-512
+    Sha512,
+}
+
+/// Identification of a certificate by its issuer and serial number.
+///
+/// Hashes are lowercase hexadecimal and the serial number is hexadecimal without leading
+/// zeroes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateHash {
+    /// Algorithm of the hashes.
+    pub hash_algorithm: HashAlgorithm,
+    /// Hash of the DER encoded issuer distinguished name.
+    pub issuer_name_hash: String,
+    /// Hash of the public key of the issuer.
+    pub issuer_key_hash: String,
+    /// Serial number of the certificate.
+    pub serial_number: String,
+}
+
+impl CertificateHash {
+    /// Whether both identify the same certificate, ignoring the case of the hexadecimal
+    /// values and leading zeroes of the serial number.
+    pub fn matches(&self, other: &CertificateHash) -> bool {
+        let serial = |serial: &str| serial.trim_start_matches('0').to_ascii_lowercase();
+        self.hash_algorithm == other.hash_algorithm
+            && self
+                .issuer_name_hash
+                .eq_ignore_ascii_case(&other.issuer_name_hash)
+            && self
+                .issuer_key_hash
+                .eq_ignore_ascii_case(&other.issuer_key_hash)
+            && serial(&self.serial_number) == serial(&other.serial_number)
+    }
+}
+
+/// Data to request the revocation status of a certificate from its OCSP responder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OcspRequest {
+    /// Identification of the certificate.
+    pub certificate_hash: CertificateHash,
+    /// URL of the OCSP responder from the authority information access of the certificate.
+    pub responder_url: String,
+}
+
+/// Reason why a certificate cannot be processed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CertificateError {
+    /// The PEM or DER encoding of a certificate is malformed.
+    Encoding(String),
+    /// A certificate chain without certificates.
+    EmptyChain,
+    /// The issuer of a certificate is not part of the chain.
+    MissingIssuer,
+    /// The certificate does not name an OCSP responder.
+    NoOcspResponder,
+    /// The signature or key algorithm is not supported.
+    UnsupportedAlgorithm,
+    /// A signature does not match.
+    InvalidSignature,
+    /// A certificate is not valid at the time of verification.
+    OutsideValidityPeriod,
+    /// An issuer or root certificate is not a certificate authority.
+    NotCertificateAuthority,
+    /// A chain does not lead to an installed root certificate.
+    UntrustedRoot,
+    /// A signed certificate was received without pending certificate signing request.
+    NoPendingRequest,
+    /// A signed certificate does not hold the key of the certificate signing request.
+    KeyMismatch,
+    /// No more root certificates can be installed.
+    StoreFull,
+    /// The certificate kind does not fit the operation.
+    InvalidUse,
+    /// Generating a key, request or certificate failed.
+    Signing(String),
+}
+
+impl fmt::Display for CertificateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Encoding(error) => write!(f, "malformed certificate: {error}"),
+            Self::EmptyChain => write!(f, "empty certificate chain"),
+            Self::MissingIssuer => write!(f, "issuer certificate not found"),
+            Self::NoOcspResponder => write!(f, "certificate without OCSP responder"),
+            Self::UnsupportedAlgorithm => write!(f, "unsupported signature algorithm"),
+            Self::InvalidSignature => write!(f, "invalid signature"),
+            Self::OutsideValidityPeriod => write!(f, "certificate outside its validity period"),
+            Self::NotCertificateAuthority => write!(f, "issuer is not a certificate authority"),
+            Self::UntrustedRoot => write!(f, "chain does not lead to an installed root"),
+            Self::NoPendingRequest => write!(f, "no pending certificate signing request"),
+            Self::KeyMismatch => write!(f, "certificate does not match the signing request"),
+            Self::StoreFull => write!(f, "no more certificates can be installed"),
+            Self::InvalidUse => write!(f, "certificate type not allowed"),
+            Self::Signing(error) => write!(f, "signing failed: {error}"),
+        }
+    }
+}
+
+impl CertificateError {
+    /// Reason code reported in the `StatusInfoType` of a rejected certificate.
+    pub fn reason_code(&self) -> &'static str {
+        match self {
+            Self::OutsideValidityPeriod => "CertificateExpired",
+            Self::StoreFull => "OutOfStorage",
+            Self::NoPendingRequest | Self::KeyMismatch => "InvalidCSR",
+            _ => "InvalidCertificate",
+        }
+    }
+}
+
+impl std::error::Error for CertificateError {}
+
+impl From<x509_cert::der::Error> for CertificateError {
+    fn from(error: x509_cert::der::Error) -> Self {
+        Self::Encoding(error.to_string())
+    }
+}
+
+/// Parses one or more concatenated PEM certificates, such as a certificate chain.
+pub fn parse_pem_chain(pem: &str) -> Result<Vec<Certificate>, CertificateError> {
+    if pem.trim().is_empty() {
+        return Err(CertificateError::EmptyChain);
+    }
+    Ok(Certificate::load_pem_chain(pem.trim().as_bytes())?)
+}
+
+/// Encodes certificates as concatenated PEM, such as the `certificateChain` of a
+/// `CertificateSignedRequest`.
+pub fn to_pem_chain(chain: &[Certificate]) -> Result<String, CertificateError> {
+    chain
+        .iter()
+        .map(|certificate| Ok(certificate.to_pem(LineEnding::LF)?))
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) const ROOT: &str = include_str!("../../tests/certificates/root.pem");
+    pub(crate) const SUB_CA: &str = include_str!("../../tests/certificates/sub_ca.pem");
+    pub(crate) const LEAF: &str = include_str!("../../tests/certificates/leaf.pem");
+
+    #[test]
+    fn parses_chains() {
+        let chain = parse_pem_chain(&format!("{LEAF}{SUB_CA}")).unwrap();
+        assert_eq!(chain.len(), 2);
+        assert_eq!(parse_pem_chain("\n"), Err(CertificateError::EmptyChain));
+        assert!(matches!(
+            parse_pem_chain("not a certificate"),
+            Err(CertificateError::Encoding(_))
+        ));
+    }
+
+    #[test]
+    fn matching_ignores_case_and_leading_zeroes() {
+        let hash = CertificateHash {
+            hash_algorithm: HashAlgorithm::Sha256,
+            issuer_name_hash: "ab01".to_string(),
+            issuer_key_hash: "cd02".to_string(),
+            serial_number: "a1b2c".to_string(),
+        };
+        let other = CertificateHash {
+            issuer_name_hash: "AB01".to_string(),
+            serial_number: "0A1B2C".to_string(),
+            ..hash.clone()
+        };
+        assert!(hash.matches(&other));
+        let other = CertificateHash {
+            hash_algorithm: HashAlgorithm::Sha384,
+            ..hash.clone()
+        };
+        assert!(!hash.matches(&other));
+    }
+}
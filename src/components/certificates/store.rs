@@ -0,0 +1,540 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use p256::ecdsa::SigningKey;
+
+use super::{
+    authority::{certificate_signing_request, public_key_info},
+    certificate_hash, parse_pem_chain,
+    verify::{check_certificate_authority, check_validity, verify_chain, verify_issued_by},
+    Certificate, CertificateError, CertificateHash, HashAlgorithm,
+};
+
+/// Use of an installed certificate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CertificateKind {
+    /// Root certificate of the V2G public key infrastructure.
+    V2GRoot,
+    /// Root certificate of a mobility operator.
+    MORoot,
+    /// Root certificate of the CSMS, used to verify the CSMS server certificate.
+    CSMSRoot,
+    /// Root certificate of the manufacturer, used to verify firmware signing certificates.
+    ManufacturerRoot,
+    /// Root certificate of a vehicle manufacturer.
+    OEMRoot,
+    /// Charging Station certificate chain, used for the connection to the CSMS.
+    ChargingStation,
+    /// ISO 15118-2 V2G Charging Station certificate chain.
+    V2G,
+    /// ISO 15118-20 V2G Charging Station certificate chain.
+    V2G20,
+}
+
+impl CertificateKind {
+    /// Whether the kind is a root certificate installed with `InstallCertificate`.
+    pub fn is_root(self) -> bool {
+        !self.is_signed()
+    }
+
+    /// Whether the kind is a certificate chain signed by a CSR from the Charging Station.
+    pub fn is_signed(self) -> bool {
+        matches!(self, Self::ChargingStation | Self::V2G | Self::V2G20)
+    }
+
+    /// Kind of root a signed certificate chain must lead to.
+    fn trust_anchor(self) -> Self {
+        match self {
+            Self::ChargingStation => Self::CSMSRoot,
+            Self::V2G | Self::V2G20 => Self::V2GRoot,
+            root => root,
+        }
+    }
+}
+
+/// A certificate, or certificate chain starting with the leaf, in a [`CertificateStore`].
+#[derive(Debug, Clone)]
+pub struct InstalledCertificate {
+    /// Use of the certificate.
+    pub kind: CertificateKind,
+    /// The certificate followed by the certificates of its issuing sub CAs.
+    pub chain: Vec<Certificate>,
+    /// Private key of a signed certificate.
+    pub key: Option<SigningKey>,
+}
+
+/// Result of deleting a certificate, as in `DeleteCertificateStatusEnumType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeleteStatus {
+    /// The certificate has been deleted.
+    Accepted,
+    /// The certificate may not be deleted.
+    Failed,
+    /// No installed certificate matches.
+    NotFound,
+}
+
+/// Certificates and keys of a Charging Station.
+///
+/// The store generates keys and certificate signing requests, verifies the signed
+/// certificate chains against the installed roots, and answers the certificate management
+/// requests of the CSMS. It does not persist anything: the installed certificates and their
+/// keys can be read with [`Self::certificates`] and restored with [`Self::insert`].
+#[derive(Debug, Clone, Default)]
+pub struct CertificateStore {
+    installed: Vec<InstalledCertificate>,
+    pending: HashMap<CertificateKind, SigningKey>,
+    max_entries: Option<usize>,
+}
+
+impl CertificateStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the number of installed root certificates, as `CertificateEntries` of the
+    /// `SecurityCtrlr`.
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// Installed certificates.
+    pub fn certificates(&self) -> &[InstalledCertificate] {
+        &self.installed
+    }
+
+    /// Installed certificates of a kind.
+    pub fn certificates_of(
+        &self,
+        kind: CertificateKind,
+    ) -> impl Iterator<Item = &InstalledCertificate> {
+        self.installed
+            .iter()
+            .filter(move |installed| installed.kind == kind)
+    }
+
+    /// Adds a certificate without verification, for example when restoring a persisted
+    /// store. A signed certificate chain replaces the installed one of the same kind.
+    pub fn insert(&mut self, certificate: InstalledCertificate) {
+        if certificate.kind.is_signed() {
+            self.installed
+                .retain(|installed| installed.kind != certificate.kind);
+        }
+        self.installed.push(certificate);
+    }
+
+    /// Generates a new key for a signed certificate kind and returns a PEM encoded
+    /// certificate signing request for `subject`, such as
+    /// `CN=<serial number>,O=<organization name>`.
+    ///
+    /// The key is kept until the signed certificate chain is received.
+    pub fn certificate_signing_request(
+        &mut self,
+        kind: CertificateKind,
+        subject: &str,
+    ) -> Result<String, CertificateError> {
+        if !kind.is_signed() {
+            return Err(CertificateError::InvalidUse);
+        }
+        let (key, csr) = certificate_signing_request(subject)?;
+        self.pending.insert(kind, key);
+        Ok(csr)
+    }
+
+    /// Verifies and installs a signed certificate chain received in a
+    /// `CertificateSignedRequest`.
+    ///
+    /// The leaf must hold the key of the pending certificate signing request and the chain
+    /// must lead to an installed root of the matching kind: a CSMS root for a Charging
+    /// Station certificate and a V2G root for a V2G certificate.
+    pub fn certificate_signed(
+        &mut self,
+        kind: CertificateKind,
+        certificate_chain: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), CertificateError> {
+        if !kind.is_signed() {
+            return Err(CertificateError::InvalidUse);
+        }
+        let key = self
+            .pending
+            .get(&kind)
+            .ok_or(CertificateError::NoPendingRequest)?;
+        let mut chain = parse_pem_chain(certificate_chain)?;
+        if chain[0].tbs_certificate.subject_public_key_info != public_key_info(key)? {
+            return Err(CertificateError::KeyMismatch);
+        }
+        let roots = self.roots(kind.trust_anchor());
+        let root = verify_chain(&chain, &roots, now)?.clone();
+        chain.retain(|certificate| *certificate != root);
+        let key = self.pending.remove(&kind);
+        self.insert(InstalledCertificate { kind, chain, key });
+        Ok(())
+    }
+
+    /// Verifies and installs a root certificate received in an
+    /// `InstallCertificateRequest`.
+    ///
+    /// The certificate must be a valid, self-signed certificate authority. Installing a
+    /// certificate that is already installed succeeds without using an entry.
+    pub fn install_root(
+        &mut self,
+        kind: CertificateKind,
+        certificate: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), CertificateError> {
+        if !kind.is_root() {
+            return Err(CertificateError::InvalidUse);
+        }
+        let chain = parse_pem_chain(certificate)?;
+        let [root] = chain.as_slice() else {
+            return Err(CertificateError::Encoding(
+                "expected a single certificate".to_string(),
+            ));
+        };
+        check_validity(root, now)?;
+        check_certificate_authority(root)?;
+        verify_issued_by(root, root)?;
+        let installed = self
+            .certificates_of(kind)
+            .any(|installed| installed.chain == chain);
+        if installed {
+            return Ok(());
+        }
+        let roots = self
+            .installed
+            .iter()
+            .filter(|installed| installed.kind.is_root())
+            .count();
+        if self.max_entries.is_some_and(|max| roots >= max) {
+            return Err(CertificateError::StoreFull);
+        }
+        self.insert(InstalledCertificate {
+            kind,
+            chain,
+            key: None,
+        });
+        Ok(())
+    }
+
+    /// Verifies a PEM encoded certificate, which may be followed by the certificates of its
+    /// issuing sub CAs, against the installed roots of `kind` and returns the certificate.
+    pub fn verify_certificate(
+        &self,
+        kind: CertificateKind,
+        certificate: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Certificate, CertificateError> {
+        let mut chain = parse_pem_chain(certificate)?;
+        verify_chain(&chain, &self.roots(kind), now)?;
+        Ok(chain.swap_remove(0))
+    }
+
+    /// Hash data of the installed certificates of `kinds`, of all kinds reported in
+    /// `GetInstalledCertificateIds` when empty. The Charging Station certificate is never
+    /// reported.
+    ///
+    /// Each entry holds the hashes of the certificate followed by its sub CAs.
+    pub fn installed_hashes(
+        &self,
+        kinds: &[CertificateKind],
+        hash_algorithm: HashAlgorithm,
+    ) -> Result<Vec<(CertificateKind, Vec<CertificateHash>)>, CertificateError> {
+        self.installed
+            .iter()
+            .filter(|installed| installed.kind != CertificateKind::ChargingStation)
+            .filter(|installed| kinds.is_empty() || kinds.contains(&installed.kind))
+            .map(|installed| {
+                let hashes = installed
+                    .chain
+                    .iter()
+                    .map(|certificate| self.hash(certificate, &installed.chain, hash_algorithm))
+                    .collect::<Result<_, _>>()?;
+                Ok((installed.kind, hashes))
+            })
+            .collect()
+    }
+
+    /// Deletes the installed certificate identified by `hash`, as requested by
+    /// `DeleteCertificateRequest`.
+    ///
+    /// The Charging Station certificate and the last CSMS root certificate may not be
+    /// deleted. Deleting the leaf of a V2G certificate chain deletes the chain.
+    pub fn delete(&mut self, hash: &CertificateHash) -> DeleteStatus {
+        let found = self.installed.iter().position(|installed| {
+            installed.chain.first().is_some_and(|certificate| {
+                self.hash(certificate, &installed.chain, hash.hash_algorithm)
+                    .is_ok_and(|installed| installed.matches(hash))
+            })
+        });
+        let Some(index) = found else {
+            return DeleteStatus::NotFound;
+        };
+        let kind = self.installed[index].kind;
+        let last_csms_root =
+            kind == CertificateKind::CSMSRoot && self.certificates_of(kind).count() == 1;
+        if kind == CertificateKind::ChargingStation || last_csms_root {
+            return DeleteStatus::Failed;
+        }
+        self.installed.remove(index);
+        DeleteStatus::Accepted
+    }
+
+    /// Private key of the installed signed certificate of `kind`.
+    pub fn private_key(&self, kind: CertificateKind) -> Option<&SigningKey> {
+        self.certificates_of(kind)
+            .find_map(|installed| installed.key.as_ref())
+    }
+
+    fn roots(&self, kind: CertificateKind) -> Vec<Certificate> {
+        self.certificates_of(kind)
+            .flat_map(|installed| installed.chain.iter().cloned())
+            .collect()
+    }
+
+    /// Hash of a certificate whose issuer is in `chain` or is an installed root.
+    fn hash(
+        &self,
+        certificate: &Certificate,
+        chain: &[Certificate],
+        hash_algorithm: HashAlgorithm,
+    ) -> Result<CertificateHash, CertificateError> {
+        let roots = self
+            .installed
+            .iter()
+            .filter(|installed| installed.kind.is_root())
+            .flat_map(|installed| installed.chain.iter());
+        let issuer = chain
+            .iter()
+            .chain(roots)
+            .find(|issuer| issuer.tbs_certificate.subject == certificate.tbs_certificate.issuer)
+            .ok_or(CertificateError::MissingIssuer)?;
+        certificate_hash(certificate, issuer, hash_algorithm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use x509_cert::der::{pem::LineEnding, EncodePem};
+
+    use super::*;
+    use crate::components::certificates::CertificateAuthority;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn root(subject: &str) -> CertificateAuthority {
+        let not_after = now() + Duration::days(3650);
+        CertificateAuthority::new_root(subject, now() - Duration::days(1), not_after).unwrap()
+    }
+
+    fn pem(certificates: &[Certificate]) -> String {
+        certificates
+            .iter()
+            .map(|certificate| certificate.to_pem(LineEnding::LF).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn installs_root_certificates() {
+        let csms = root("CN=CSMS Root,O=Example");
+        let mut store = CertificateStore::new().with_max_entries(1);
+        let csms_pem = pem(&[csms.certificate().clone()]);
+
+        store
+            .install_root(CertificateKind::CSMSRoot, &csms_pem, now())
+            .unwrap();
+        // reinstalling does not use an entry
+        store
+            .install_root(CertificateKind::CSMSRoot, &csms_pem, now())
+            .unwrap();
+        let v2g = pem(&[root("CN=V2G Root").certificate().clone()]);
+        assert_eq!(
+            store.install_root(CertificateKind::V2GRoot, &v2g, now()),
+            Err(CertificateError::StoreFull)
+        );
+        assert_eq!(
+            store.install_root(
+                CertificateKind::CSMSRoot,
+                &csms_pem,
+                now() + Duration::days(4000)
+            ),
+            Err(CertificateError::OutsideValidityPeriod)
+        );
+        assert_eq!(
+            store.install_root(CertificateKind::ChargingStation, &csms_pem, now()),
+            Err(CertificateError::InvalidUse)
+        );
+
+        // a leaf is not a certificate authority
+        let key = SigningKey::random(&mut rand_core::OsRng);
+        let leaf = csms
+            .issue_leaf(
+                "CN=CS001",
+                public_key_info(&key).unwrap(),
+                now(),
+                now() + Duration::days(1),
+            )
+            .unwrap();
+        assert_eq!(
+            CertificateStore::new().install_root(CertificateKind::MORoot, &pem(&leaf), now()),
+            Err(CertificateError::NotCertificateAuthority)
+        );
+    }
+
+    #[test]
+    fn signs_and_verifies_certificate_chains() {
+        let csms = root("CN=CSMS Root");
+        let sub_ca = csms
+            .new_sub_ca(
+                "CN=CSMS Sub CA",
+                now() - Duration::days(1),
+                now() + Duration::days(365),
+            )
+            .unwrap();
+        let mut store = CertificateStore::new();
+        store
+            .install_root(
+                CertificateKind::CSMSRoot,
+                &pem(&[csms.certificate().clone()]),
+                now(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            store.certificate_signed(CertificateKind::ChargingStation, "", now()),
+            Err(CertificateError::NoPendingRequest)
+        );
+        let csr = store
+            .certificate_signing_request(CertificateKind::ChargingStation, "CN=CS001,O=Example")
+            .unwrap();
+        let chain = sub_ca
+            .sign_request(&csr, now(), now() + Duration::days(365))
+            .unwrap();
+        assert_eq!(chain.len(), 2);
+
+        // a chain for another key
+        let (_, other_csr) = certificate_signing_request("CN=CS001,O=Example").unwrap();
+        let other = sub_ca
+            .sign_request(&other_csr, now(), now() + Duration::days(365))
+            .unwrap();
+        assert_eq!(
+            store.certificate_signed(CertificateKind::ChargingStation, &pem(&other), now()),
+            Err(CertificateError::KeyMismatch)
+        );
+        // the sub CA is missing
+        assert_eq!(
+            store.certificate_signed(CertificateKind::ChargingStation, &pem(&chain[..1]), now()),
+            Err(CertificateError::UntrustedRoot)
+        );
+
+        store
+            .certificate_signed(CertificateKind::ChargingStation, &pem(&chain), now())
+            .unwrap();
+        let installed = store
+            .certificates_of(CertificateKind::ChargingStation)
+            .next()
+            .unwrap();
+        assert_eq!(installed.chain, chain);
+        assert!(store
+            .private_key(CertificateKind::ChargingStation)
+            .is_some());
+        assert_eq!(
+            store.certificate_signed(CertificateKind::ChargingStation, &pem(&chain), now()),
+            Err(CertificateError::NoPendingRequest)
+        );
+    }
+
+    #[test]
+    fn chain_must_lead_to_root_of_matching_kind() {
+        let csms = root("CN=CSMS Root");
+        let v2g = root("CN=V2G Root");
+        let mut store = CertificateStore::new();
+        for (kind, root) in [
+            (CertificateKind::CSMSRoot, &csms),
+            (CertificateKind::V2GRoot, &v2g),
+        ] {
+            store
+                .install_root(kind, &pem(&[root.certificate().clone()]), now())
+                .unwrap();
+        }
+        let csr = store
+            .certificate_signing_request(CertificateKind::V2G, "CN=CS001")
+            .unwrap();
+        let chain = csms
+            .sign_request(&csr, now(), now() + Duration::days(1))
+            .unwrap();
+        assert_eq!(
+            store.certificate_signed(CertificateKind::V2G, &pem(&chain), now()),
+            Err(CertificateError::UntrustedRoot)
+        );
+        // a root sent along with the chain is accepted
+        let mut chain = v2g
+            .sign_request(&csr, now(), now() + Duration::days(1))
+            .unwrap();
+        chain.push(v2g.certificate().clone());
+        store
+            .certificate_signed(CertificateKind::V2G, &pem(&chain), now())
+            .unwrap();
+        assert_eq!(
+            store
+                .certificates_of(CertificateKind::V2G)
+                .next()
+                .unwrap()
+                .chain
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn reports_and_deletes_certificates() {
+        let csms = root("CN=CSMS Root");
+        let mo = root("CN=MO Root");
+        let mut store = CertificateStore::new();
+        for (kind, root) in [
+            (CertificateKind::CSMSRoot, &csms),
+            (CertificateKind::MORoot, &mo),
+        ] {
+            store
+                .install_root(kind, &pem(&[root.certificate().clone()]), now())
+                .unwrap();
+        }
+        let csr = store
+            .certificate_signing_request(CertificateKind::ChargingStation, "CN=CS001")
+            .unwrap();
+        let chain = csms
+            .sign_request(&csr, now(), now() + Duration::days(1))
+            .unwrap();
+        store
+            .certificate_signed(CertificateKind::ChargingStation, &pem(&chain), now())
+            .unwrap();
+
+        let all = store.installed_hashes(&[], HashAlgorithm::Sha256).unwrap();
+        assert_eq!(all.len(), 2);
+        let mo_roots = store
+            .installed_hashes(&[CertificateKind::MORoot], HashAlgorithm::Sha256)
+            .unwrap();
+        assert_eq!(mo_roots.len(), 1);
+        assert!(store
+            .installed_hashes(&[CertificateKind::V2G], HashAlgorithm::Sha256)
+            .unwrap()
+            .is_empty());
+
+        let leaf = certificate_hash(&chain[0], csms.certificate(), HashAlgorithm::Sha384).unwrap();
+        assert_eq!(store.delete(&leaf), DeleteStatus::Failed);
+        let csms_root = certificate_hash(
+            csms.certificate(),
+            csms.certificate(),
+            HashAlgorithm::Sha512,
+        )
+        .unwrap();
+        assert_eq!(store.delete(&csms_root), DeleteStatus::Failed);
+        assert_eq!(store.delete(&mo_roots[0].1[0]), DeleteStatus::Accepted);
+        assert_eq!(store.delete(&mo_roots[0].1[0]), DeleteStatus::NotFound);
+    }
+}
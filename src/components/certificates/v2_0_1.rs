@@ -0,0 +1,533 @@
+//! OCPP 2.0.1 certificate hash data, certificate management messages and signed firmware.
+
+use chrono::{DateTime, Utc};
+
+use crate::v2_0_1::{
+    datatypes::{
+        certificate_hash_data_chain_type::CertificateHashDataChainType,
+        certificate_hash_data_type::CertificateHashDataType, firmware_type::FirmwareType,
+        ocsp_request_data_type::OCSPRequestDataType, status_info_type::StatusInfoType,
+    },
+    enumerations::{
+        certificate_signed_status_enum_type::CertificateSignedStatusEnumType,
+        certificate_signing_use_enum_type::CertificateSigningUseEnumType,
+        delete_certificate_status_enum_type::DeleteCertificateStatusEnumType,
+        firmware_status_enum_type::FirmwareStatusEnumType,
+        get_certificate_id_use_enum_type::GetCertificateIdUseEnumType,
+        get_installed_certificate_status_enum_type::GetInstalledCertificateStatusEnumType,
+        hash_algorithm_enum_type::HashAlgorithmEnumType,
+        install_certificate_status_enum_type::InstallCertificateStatusEnumType,
+        install_certificate_use_enum_type::InstallCertificateUseEnumType,
+        update_firmware_status_enum_type::UpdateFirmwareStatusEnumType,
+    },
+    messages::{
+        certificate_signed::{CertificateSignedRequest, CertificateSignedResponse},
+        delete_certificate::{DeleteCertificateRequest, DeleteCertificateResponse},
+        get_installed_certificate_ids::{
+            GetInstalledCertificateIdsRequest, GetInstalledCertificateIdsResponse,
+        },
+        install_certificate::{InstallCertificateRequest, InstallCertificateResponse},
+        sign_certificate::SignCertificateRequest,
+        update_firmware::UpdateFirmwareResponse,
+    },
+};
+
+use super::{
+    chain_hashes, to_pem_chain, Certificate, CertificateAuthority, CertificateError,
+    CertificateHash, CertificateKind, CertificateStore, DeleteStatus, FirmwareVerificationError,
+    FirmwareVerifier, HashAlgorithm, OcspRequest,
+};
+
+impl From<HashAlgorithm> for HashAlgorithmEnumType {
+    fn from(hash_algorithm: HashAlgorithm) -> Self {
+        match hash_algorithm {
+            HashAlgorithm::Sha256 => Self::SHA256,
+            HashAlgorithm::Sha384 => Self::SHA384,
+            HashAlgorithm::Sha512 => Self::SHA512,
+        }
+    }
+}
+
+impl From<&HashAlgorithmEnumType> for HashAlgorithm {
+    fn from(hash_algorithm: &HashAlgorithmEnumType) -> Self {
+        match hash_algorithm {
+            HashAlgorithmEnumType::SHA256 => Self::Sha256,
+            HashAlgorithmEnumType::SHA384 => Self::Sha384,
+            HashAlgorithmEnumType::SHA512 => Self::Sha512,
+        }
+    }
+}
+
+impl From<CertificateHash> for CertificateHashDataType {
+    fn from(hash: CertificateHash) -> Self {
+        Self {
+            hash_algorithm: hash.hash_algorithm.into(),
+            issuer_name_hash: hash.issuer_name_hash,
+            issuer_key_hash: hash.issuer_key_hash,
+            serial_number: hash.serial_number,
+        }
+    }
+}
+
+impl From<&CertificateHashDataType> for CertificateHash {
+    fn from(data: &CertificateHashDataType) -> Self {
+        Self {
+            hash_algorithm: (&data.hash_algorithm).into(),
+            issuer_name_hash: data.issuer_name_hash.clone(),
+            issuer_key_hash: data.issuer_key_hash.clone(),
+            serial_number: data.serial_number.clone(),
+        }
+    }
+}
+
+impl From<OcspRequest> for OCSPRequestDataType {
+    fn from(request: OcspRequest) -> Self {
+        let hash = request.certificate_hash;
+        Self {
+            hash_algorithm: hash.hash_algorithm.into(),
+            issuer_name_hash: hash.issuer_name_hash,
+            issuer_key_hash: hash.issuer_key_hash,
+            serial_number: hash.serial_number,
+            responder_url: request.responder_url,
+        }
+    }
+}
+
+impl From<&InstallCertificateUseEnumType> for CertificateKind {
+    fn from(certificate_type: &InstallCertificateUseEnumType) -> Self {
+        match certificate_type {
+            InstallCertificateUseEnumType::V2GRootCertificate => Self::V2GRoot,
+            InstallCertificateUseEnumType::MORootCertificate => Self::MORoot,
+            InstallCertificateUseEnumType::CSMSRootCertificate => Self::CSMSRoot,
+            InstallCertificateUseEnumType::ManufacturerRootCertificate => Self::ManufacturerRoot,
+        }
+    }
+}
+
+impl From<&CertificateSigningUseEnumType> for CertificateKind {
+    fn from(certificate_type: &CertificateSigningUseEnumType) -> Self {
+        match certificate_type {
+            CertificateSigningUseEnumType::ChargingStationCertificate => Self::ChargingStation,
+            CertificateSigningUseEnumType::V2GCertificate => Self::V2G,
+        }
+    }
+}
+
+impl From<DeleteStatus> for DeleteCertificateStatusEnumType {
+    fn from(status: DeleteStatus) -> Self {
+        match status {
+            DeleteStatus::Accepted => Self::Accepted,
+            DeleteStatus::Failed => Self::Failed,
+            DeleteStatus::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl From<&CertificateError> for StatusInfoType {
+    fn from(error: &CertificateError) -> Self {
+        Self {
+            reason_code: error.reason_code().to_string(),
+            additional_info: Some(error.to_string()),
+        }
+    }
+}
+
+impl From<&FirmwareVerificationError> for StatusInfoType {
+    fn from(error: &FirmwareVerificationError) -> Self {
+        Self {
+            reason_code: error.reason_code().to_string(),
+            additional_info: Some(error.to_string()),
+        }
+    }
+}
+
+/// Response to an `UpdateFirmwareRequest` whose signing certificate or signature is invalid.
+impl From<&FirmwareVerificationError> for UpdateFirmwareResponse {
+    fn from(error: &FirmwareVerificationError) -> Self {
+        let status = match error {
+            FirmwareVerificationError::InvalidCertificate(_) => {
+                UpdateFirmwareStatusEnumType::InvalidCertificate
+            }
+            FirmwareVerificationError::InvalidSignature(_) => {
+                UpdateFirmwareStatusEnumType::Rejected
+            }
+        };
+        UpdateFirmwareResponse {
+            status,
+            status_info: Some(error.into()),
+        }
+    }
+}
+
+/// Certificate type an installed certificate is reported as, if any.
+fn certificate_id_use(kind: CertificateKind) -> Option<GetCertificateIdUseEnumType> {
+    match kind {
+        CertificateKind::V2GRoot => Some(GetCertificateIdUseEnumType::V2GRootCertificate),
+        CertificateKind::MORoot => Some(GetCertificateIdUseEnumType::MORootCertificate),
+        CertificateKind::CSMSRoot => Some(GetCertificateIdUseEnumType::CSMSRootCertificate),
+        CertificateKind::ManufacturerRoot => {
+            Some(GetCertificateIdUseEnumType::ManufacturerRootCertificate)
+        }
+        CertificateKind::V2G => Some(GetCertificateIdUseEnumType::V2GCertificateChain),
+        CertificateKind::OEMRoot | CertificateKind::ChargingStation | CertificateKind::V2G20 => {
+            None
+        }
+    }
+}
+
+/// Builds the hash data of an installed certificate chain. The first certificate of
+/// `chain` is the certificate itself, the others become its child certificates.
+pub fn certificate_hash_data_chain(
+    certificate_type: GetCertificateIdUseEnumType,
+    chain: &[Certificate],
+    hash_algorithm: HashAlgorithm,
+) -> Result<CertificateHashDataChainType, CertificateError> {
+    hash_data_chain(certificate_type, chain_hashes(chain, hash_algorithm)?)
+}
+
+fn hash_data_chain(
+    certificate_type: GetCertificateIdUseEnumType,
+    hashes: Vec<CertificateHash>,
+) -> Result<CertificateHashDataChainType, CertificateError> {
+    let mut hashes = hashes.into_iter().map(CertificateHashDataType::from);
+    let certificate_hash_data = hashes.next().ok_or(CertificateError::EmptyChain)?;
+    let children: Vec<_> = hashes.collect();
+    Ok(CertificateHashDataChainType {
+        certificate_type,
+        certificate_hash_data,
+        child_certificate_hash_data: (!children.is_empty()).then_some(children),
+    })
+}
+
+/// Generates a key and builds the `SignCertificateRequest` for a certificate of
+/// `certificate_type`, a Charging Station certificate when `None`.
+pub fn sign_certificate_request(
+    store: &mut CertificateStore,
+    certificate_type: Option<CertificateSigningUseEnumType>,
+    subject: &str,
+) -> Result<SignCertificateRequest, CertificateError> {
+    let kind = certificate_type
+        .as_ref()
+        .map_or(CertificateKind::ChargingStation, CertificateKind::from);
+    Ok(SignCertificateRequest {
+        csr: store.certificate_signing_request(kind, subject)?,
+        certificate_type,
+    })
+}
+
+/// Signs the certificate signing request of a `SignCertificateRequest` on the CSMS side
+/// and builds the `CertificateSignedRequest` that returns the chain.
+pub fn certificate_signed_request(
+    authority: &CertificateAuthority,
+    request: &SignCertificateRequest,
+    not_before: DateTime<Utc>,
+    not_after: DateTime<Utc>,
+) -> Result<CertificateSignedRequest, CertificateError> {
+    let chain = authority.sign_request(&request.csr, not_before, not_after)?;
+    Ok(CertificateSignedRequest {
+        certificate_chain: to_pem_chain(&chain)?,
+        certificate_type: request.certificate_type.clone(),
+    })
+}
+
+/// Handles a `CertificateSignedRequest`.
+pub fn certificate_signed(
+    store: &mut CertificateStore,
+    request: &CertificateSignedRequest,
+    now: DateTime<Utc>,
+) -> CertificateSignedResponse {
+    let kind = request
+        .certificate_type
+        .as_ref()
+        .map_or(CertificateKind::ChargingStation, CertificateKind::from);
+    match store.certificate_signed(kind, &request.certificate_chain, now) {
+        Ok(()) => CertificateSignedResponse {
+            status: CertificateSignedStatusEnumType::Accepted,
+            status_info: None,
+        },
+        Err(error) => CertificateSignedResponse {
+            status: CertificateSignedStatusEnumType::Rejected,
+            status_info: Some((&error).into()),
+        },
+    }
+}
+
+/// Handles an `InstallCertificateRequest`.
+pub fn install_certificate(
+    store: &mut CertificateStore,
+    request: &InstallCertificateRequest,
+    now: DateTime<Utc>,
+) -> InstallCertificateResponse {
+    let kind = (&request.certificate_type).into();
+    match store.install_root(kind, &request.certificate, now) {
+        Ok(()) => InstallCertificateResponse {
+            status: InstallCertificateStatusEnumType::Accepted,
+            status_info: None,
+        },
+        Err(error) => InstallCertificateResponse {
+            status: match error {
+                CertificateError::StoreFull => InstallCertificateStatusEnumType::Failed,
+                _ => InstallCertificateStatusEnumType::Rejected,
+            },
+            status_info: Some((&error).into()),
+        },
+    }
+}
+
+/// Handles a `GetInstalledCertificateIdsRequest`, reporting SHA-256 hash data.
+pub fn get_installed_certificate_ids(
+    store: &CertificateStore,
+    request: &GetInstalledCertificateIdsRequest,
+) -> GetInstalledCertificateIdsResponse {
+    let requested = |certificate_type: &GetCertificateIdUseEnumType| {
+        request
+            .certificate_type
+            .as_ref()
+            .is_none_or(|types| types.is_empty() || types.contains(certificate_type))
+    };
+    let chains = store
+        .installed_hashes(&[], HashAlgorithm::Sha256)
+        .and_then(|installed| {
+            installed
+                .into_iter()
+                .filter_map(|(kind, hashes)| Some((certificate_id_use(kind)?, hashes)))
+                .filter(|(certificate_type, _)| requested(certificate_type))
+                .map(|(certificate_type, hashes)| hash_data_chain(certificate_type, hashes))
+                .collect::<Result<Vec<_>, _>>()
+        });
+    match chains {
+        Ok(chains) if !chains.is_empty() => GetInstalledCertificateIdsResponse {
+            status: GetInstalledCertificateStatusEnumType::Accepted,
+            certificate_hash_data_chain: Some(chains),
+            status_info: None,
+        },
+        Ok(_) => GetInstalledCertificateIdsResponse {
+            status: GetInstalledCertificateStatusEnumType::NotFound,
+            certificate_hash_data_chain: None,
+            status_info: None,
+        },
+        Err(error) => GetInstalledCertificateIdsResponse {
+            status: GetInstalledCertificateStatusEnumType::NotFound,
+            certificate_hash_data_chain: None,
+            status_info: Some((&error).into()),
+        },
+    }
+}
+
+/// Handles a `DeleteCertificateRequest`.
+pub fn delete_certificate(
+    store: &mut CertificateStore,
+    request: &DeleteCertificateRequest,
+) -> DeleteCertificateResponse {
+    DeleteCertificateResponse {
+        status: store
+            .delete(&(&request.certificate_hash_data).into())
+            .into(),
+        status_info: None,
+    }
+}
+
+/// Verifies the signing certificate of the firmware of an `UpdateFirmwareRequest` against
+/// the installed manufacturer root certificates and returns the verifier of the image.
+///
+/// A failure is answered with an `UpdateFirmwareResponse` built from the error.
+pub fn firmware_verifier(
+    store: &CertificateStore,
+    firmware: &FirmwareType,
+    now: DateTime<Utc>,
+) -> Result<FirmwareVerifier, FirmwareVerificationError> {
+    let signing_certificate = firmware.signing_certificate.as_deref().unwrap_or_default();
+    let signature =
+        firmware
+            .signature
+            .as_deref()
+            .ok_or(FirmwareVerificationError::InvalidSignature(
+                CertificateError::InvalidSignature,
+            ))?;
+    FirmwareVerifier::new(store, signing_certificate, signature, now)
+}
+
+/// Status reported in a `FirmwareStatusNotificationRequest` once the signature of the
+/// downloaded image has been verified.
+pub fn firmware_status(
+    verification: &Result<(), FirmwareVerificationError>,
+) -> FirmwareStatusEnumType {
+    match verification {
+        Ok(()) => FirmwareStatusEnumType::SignatureVerified,
+        Err(_) => FirmwareStatusEnumType::InvalidSignature,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+    use crate::components::certificates::{
+        firmware::tests::{signed_firmware, IMAGE},
+        parse_pem_chain,
+        tests::{LEAF, ROOT, SUB_CA},
+    };
+
+    #[test]
+    fn chain_of_installed_certificate() {
+        let chain = parse_pem_chain(&format!("{LEAF}{SUB_CA}{ROOT}")).unwrap();
+        let data = certificate_hash_data_chain(
+            GetCertificateIdUseEnumType::V2GCertificateChain,
+            &chain,
+            HashAlgorithm::Sha256,
+        )
+        .unwrap();
+        assert_eq!(data.certificate_hash_data.serial_number, "f1e2d3c4b5a69788");
+        assert_eq!(data.child_certificate_hash_data.unwrap().len(), 2);
+
+        let root = parse_pem_chain(ROOT).unwrap();
+        let data = certificate_hash_data_chain(
+            GetCertificateIdUseEnumType::CSMSRootCertificate,
+            &root,
+            HashAlgorithm::Sha384,
+        )
+        .unwrap();
+        assert_eq!(
+            data.certificate_hash_data.hash_algorithm,
+            HashAlgorithmEnumType::SHA384
+        );
+        assert_eq!(data.child_certificate_hash_data, None);
+        assert_eq!(
+            certificate_hash_data_chain(
+                GetCertificateIdUseEnumType::CSMSRootCertificate,
+                &[],
+                HashAlgorithm::Sha256,
+            ),
+            Err(CertificateError::EmptyChain)
+        );
+    }
+
+    #[test]
+    fn certificate_management_messages() {
+        let now = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        let root = CertificateAuthority::new_root(
+            "CN=CSMS Root",
+            now - Duration::days(1),
+            now + Duration::days(3650),
+        )
+        .unwrap();
+        let root_pem = to_pem_chain(std::slice::from_ref(root.certificate())).unwrap();
+        let mut store = CertificateStore::new().with_max_entries(1);
+
+        let install = InstallCertificateRequest {
+            certificate_type: InstallCertificateUseEnumType::CSMSRootCertificate,
+            certificate: root_pem.clone(),
+        };
+        let response = install_certificate(&mut store, &install, now);
+        assert_eq!(response.status, InstallCertificateStatusEnumType::Accepted);
+        let response = install_certificate(
+            &mut store,
+            &InstallCertificateRequest {
+                certificate_type: InstallCertificateUseEnumType::V2GRootCertificate,
+                certificate: root_pem.clone(),
+            },
+            now,
+        );
+        assert_eq!(response.status, InstallCertificateStatusEnumType::Failed);
+        let response = install_certificate(
+            &mut store,
+            &InstallCertificateRequest {
+                certificate_type: InstallCertificateUseEnumType::MORootCertificate,
+                certificate: LEAF.to_string(),
+            },
+            now,
+        );
+        assert_eq!(response.status, InstallCertificateStatusEnumType::Rejected);
+        assert_eq!(
+            response.status_info.unwrap().reason_code,
+            "InvalidCertificate"
+        );
+
+        let sign = sign_certificate_request(&mut store, None, "CN=CS001,O=rust-ocpp").unwrap();
+        let signed =
+            certificate_signed_request(&root, &sign, now, now + Duration::days(365)).unwrap();
+        let response = certificate_signed(&mut store, &signed, now);
+        assert_eq!(response.status, CertificateSignedStatusEnumType::Accepted);
+        // the key of the request has been used
+        let response = certificate_signed(&mut store, &signed, now);
+        assert_eq!(response.status, CertificateSignedStatusEnumType::Rejected);
+        assert_eq!(response.status_info.unwrap().reason_code, "InvalidCSR");
+
+        let response = get_installed_certificate_ids(
+            &store,
+            &GetInstalledCertificateIdsRequest {
+                certificate_type: None,
+            },
+        );
+        assert_eq!(
+            response.status,
+            GetInstalledCertificateStatusEnumType::Accepted
+        );
+        let chains = response.certificate_hash_data_chain.unwrap();
+        assert_eq!(chains.len(), 1);
+        assert_eq!(
+            chains[0].certificate_type,
+            GetCertificateIdUseEnumType::CSMSRootCertificate
+        );
+        let response = get_installed_certificate_ids(
+            &store,
+            &GetInstalledCertificateIdsRequest {
+                certificate_type: Some(vec![GetCertificateIdUseEnumType::V2GCertificateChain]),
+            },
+        );
+        assert_eq!(
+            response.status,
+            GetInstalledCertificateStatusEnumType::NotFound
+        );
+
+        // the last CSMS root may not be deleted
+        let request = DeleteCertificateRequest {
+            certificate_hash_data: chains[0].certificate_hash_data.clone(),
+        };
+        let response = delete_certificate(&mut store, &request);
+        assert_eq!(response.status, DeleteCertificateStatusEnumType::Failed);
+    }
+
+    #[test]
+    fn signed_firmware_update() {
+        let now = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        let (store, certificate, signature) = signed_firmware(now);
+        let firmware = FirmwareType {
+            location: "https://example.com/firmware.bin".to_string(),
+            retrieve_date_time: now,
+            install_date_time: None,
+            signing_certificate: Some(certificate.clone()),
+            signature: Some(signature),
+        };
+        let mut verifier = firmware_verifier(&store, &firmware, now).unwrap();
+        verifier.update(IMAGE);
+        assert_eq!(
+            firmware_status(&verifier.verify()),
+            FirmwareStatusEnumType::SignatureVerified
+        );
+        let mut verifier = firmware_verifier(&store, &firmware, now).unwrap();
+        verifier.update(b"tampered image");
+        assert_eq!(
+            firmware_status(&verifier.verify()),
+            FirmwareStatusEnumType::InvalidSignature
+        );
+
+        let unsigned = FirmwareType {
+            signing_certificate: None,
+            ..firmware.clone()
+        };
+        let error = firmware_verifier(&store, &unsigned, now).unwrap_err();
+        let response = UpdateFirmwareResponse::from(&error);
+        assert_eq!(
+            response.status,
+            UpdateFirmwareStatusEnumType::InvalidCertificate
+        );
+        let (other_store, _, _) = signed_firmware(now);
+        let error = firmware_verifier(&other_store, &firmware, now).unwrap_err();
+        assert_eq!(
+            error,
+            FirmwareVerificationError::InvalidCertificate(CertificateError::UntrustedRoot)
+        );
+    }
+}
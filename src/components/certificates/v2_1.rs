@@ -0,0 +1,508 @@
+//! OCPP 2.1 certificate hash data, certificate management messages and signed firmware.
+
+use chrono::{DateTime, Utc};
+
+use crate::v2_1::{
+    datatypes::{
+        CertificateHashDataChainType, CertificateHashDataType, FirmwareType, OCSPRequestDataType,
+        StatusInfoType,
+    },
+    enumerations::{
+        CertificateSignedStatusEnumType, CertificateSigningUseEnumType,
+        DeleteCertificateStatusEnumType, FirmwareStatusEnumType, GetCertificateIdUseEnumType,
+        GetInstalledCertificateStatusEnumType, HashAlgorithmEnumType,
+        InstallCertificateStatusEnumType, InstallCertificateUseEnumType,
+        UpdateFirmwareStatusEnumType,
+    },
+    messages::{
+        certificate_signed::{CertificateSignedRequest, CertificateSignedResponse},
+        delete_certificate::{DeleteCertificateRequest, DeleteCertificateResponse},
+        get_installed_certificate_ids::{
+            GetInstalledCertificateIdsRequest, GetInstalledCertificateIdsResponse,
+        },
+        install_certificate::{InstallCertificateRequest, InstallCertificateResponse},
+        sign_certificate::{self, SignCertificateRequest},
+        update_firmware::UpdateFirmwareResponse,
+    },
+};
+
+use super::{
+    chain_hashes, to_pem_chain, Certificate, CertificateAuthority, CertificateError,
+    CertificateHash, CertificateKind, CertificateStore, DeleteStatus, FirmwareVerificationError,
+    FirmwareVerifier, HashAlgorithm, OcspRequest,
+};
+
+impl From<HashAlgorithm> for HashAlgorithmEnumType {
+    fn from(hash_algorithm: HashAlgorithm) -> Self {
+        match hash_algorithm {
+            HashAlgorithm::Sha256 => Self::SHA256,
+            HashAlgorithm::Sha384 => Self::SHA384,
+            HashAlgorithm::Sha512 => Self::SHA512,
+        }
+    }
+}
+
+impl From<&HashAlgorithmEnumType> for HashAlgorithm {
+    fn from(hash_algorithm: &HashAlgorithmEnumType) -> Self {
+        match hash_algorithm {
+            HashAlgorithmEnumType::SHA256 => Self::Sha256,
+            HashAlgorithmEnumType::SHA384 => Self::Sha384,
+            HashAlgorithmEnumType::SHA512 => Self::Sha512,
+        }
+    }
+}
+
+impl From<CertificateHash> for CertificateHashDataType {
+    fn from(hash: CertificateHash) -> Self {
+        CertificateHashDataType::new(
+            hash.hash_algorithm.into(),
+            hash.issuer_name_hash,
+            hash.issuer_key_hash,
+            hash.serial_number,
+        )
+    }
+}
+
+impl From<&CertificateHashDataType> for CertificateHash {
+    fn from(data: &CertificateHashDataType) -> Self {
+        Self {
+            hash_algorithm: (&data.hash_algorithm).into(),
+            issuer_name_hash: data.issuer_name_hash.clone(),
+            issuer_key_hash: data.issuer_key_hash.clone(),
+            serial_number: data.serial_number.clone(),
+        }
+    }
+}
+
+impl From<OcspRequest> for OCSPRequestDataType {
+    fn from(request: OcspRequest) -> Self {
+        let hash = request.certificate_hash;
+        OCSPRequestDataType::new(
+            hash.hash_algorithm.into(),
+            hash.issuer_name_hash,
+            hash.issuer_key_hash,
+            hash.serial_number,
+            request.responder_url,
+        )
+    }
+}
+
+impl From<&InstallCertificateUseEnumType> for CertificateKind {
+    fn from(certificate_type: &InstallCertificateUseEnumType) -> Self {
+        match certificate_type {
+            InstallCertificateUseEnumType::V2GRootCertificate => Self::V2GRoot,
+            InstallCertificateUseEnumType::MORootCertificate => Self::MORoot,
+            InstallCertificateUseEnumType::ManufacturerRootCertificate => Self::ManufacturerRoot,
+            InstallCertificateUseEnumType::CSMSRootCertificate => Self::CSMSRoot,
+            InstallCertificateUseEnumType::OEMRootCertificate => Self::OEMRoot,
+        }
+    }
+}
+
+impl From<&CertificateSigningUseEnumType> for CertificateKind {
+    fn from(certificate_type: &CertificateSigningUseEnumType) -> Self {
+        match certificate_type {
+            CertificateSigningUseEnumType::ChargingStationCertificate => Self::ChargingStation,
+            CertificateSigningUseEnumType::V2GCertificate => Self::V2G,
+            CertificateSigningUseEnumType::V2G20Certificate => Self::V2G20,
+        }
+    }
+}
+
+impl From<&sign_certificate::CertificateSigningUseEnumType> for CertificateSigningUseEnumType {
+    fn from(certificate_type: &sign_certificate::CertificateSigningUseEnumType) -> Self {
+        match certificate_type {
+            sign_certificate::CertificateSigningUseEnumType::ChargingStationCertificate => {
+                Self::ChargingStationCertificate
+            }
+            sign_certificate::CertificateSigningUseEnumType::V2GCertificate => Self::V2GCertificate,
+            sign_certificate::CertificateSigningUseEnumType::V2G20Certificate => {
+                Self::V2G20Certificate
+            }
+        }
+    }
+}
+
+impl From<DeleteStatus> for DeleteCertificateStatusEnumType {
+    fn from(status: DeleteStatus) -> Self {
+        match status {
+            DeleteStatus::Accepted => Self::Accepted,
+            DeleteStatus::Failed => Self::Failed,
+            DeleteStatus::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl From<&CertificateError> for StatusInfoType {
+    fn from(error: &CertificateError) -> Self {
+        StatusInfoType::new(error.reason_code().to_string()).with_additional_info(error.to_string())
+    }
+}
+
+impl From<&FirmwareVerificationError> for StatusInfoType {
+    fn from(error: &FirmwareVerificationError) -> Self {
+        StatusInfoType::new(error.reason_code().to_string()).with_additional_info(error.to_string())
+    }
+}
+
+/// Response to an `UpdateFirmwareRequest` whose signing certificate or signature is invalid.
+impl From<&FirmwareVerificationError> for UpdateFirmwareResponse {
+    fn from(error: &FirmwareVerificationError) -> Self {
+        let status = match error {
+            FirmwareVerificationError::InvalidCertificate(_) => {
+                UpdateFirmwareStatusEnumType::InvalidCertificate
+            }
+            FirmwareVerificationError::InvalidSignature(_) => {
+                UpdateFirmwareStatusEnumType::Rejected
+            }
+        };
+        UpdateFirmwareResponse {
+            custom_data: None,
+            status,
+            status_info: Some(error.into()),
+        }
+    }
+}
+
+/// Certificate type an installed certificate is reported as, if any.
+fn certificate_id_use(kind: CertificateKind) -> Option<GetCertificateIdUseEnumType> {
+    match kind {
+        CertificateKind::V2GRoot => Some(GetCertificateIdUseEnumType::V2GRootCertificate),
+        CertificateKind::MORoot => Some(GetCertificateIdUseEnumType::MORootCertificate),
+        CertificateKind::CSMSRoot => Some(GetCertificateIdUseEnumType::CSMSRootCertificate),
+        CertificateKind::ManufacturerRoot => {
+            Some(GetCertificateIdUseEnumType::ManufacturerRootCertificate)
+        }
+        CertificateKind::OEMRoot => Some(GetCertificateIdUseEnumType::OEMRootCertificate),
+        CertificateKind::V2G | CertificateKind::V2G20 => {
+            Some(GetCertificateIdUseEnumType::V2GCertificateChain)
+        }
+        CertificateKind::ChargingStation => None,
+    }
+}
+
+/// Builds the hash data of an installed certificate chain. The first certificate of
+/// `chain` is the certificate itself, the others become its child certificates.
+pub fn certificate_hash_data_chain(
+    certificate_type: GetCertificateIdUseEnumType,
+    chain: &[Certificate],
+    hash_algorithm: HashAlgorithm,
+) -> Result<CertificateHashDataChainType, CertificateError> {
+    hash_data_chain(certificate_type, chain_hashes(chain, hash_algorithm)?)
+}
+
+fn hash_data_chain(
+    certificate_type: GetCertificateIdUseEnumType,
+    hashes: Vec<CertificateHash>,
+) -> Result<CertificateHashDataChainType, CertificateError> {
+    let mut hashes = hashes.into_iter().map(CertificateHashDataType::from);
+    let certificate_hash_data = hashes.next().ok_or(CertificateError::EmptyChain)?;
+    let children: Vec<_> = hashes.collect();
+    let data = CertificateHashDataChainType::new(certificate_hash_data, certificate_type);
+    Ok(match children.is_empty() {
+        true => data,
+        false => data.with_child_certificate_hash_data(children),
+    })
+}
+
+/// Generates a key and builds the `SignCertificateRequest` for a certificate of
+/// `certificate_type`, a Charging Station certificate when `None`.
+pub fn sign_certificate_request(
+    store: &mut CertificateStore,
+    certificate_type: Option<sign_certificate::CertificateSigningUseEnumType>,
+    subject: &str,
+) -> Result<SignCertificateRequest, CertificateError> {
+    let kind =
+        certificate_type
+            .as_ref()
+            .map_or(CertificateKind::ChargingStation, |certificate_type| {
+                CertificateKind::from(&CertificateSigningUseEnumType::from(certificate_type))
+            });
+    Ok(SignCertificateRequest {
+        custom_data: None,
+        csr: store.certificate_signing_request(kind, subject)?,
+        certificate_type,
+        hash_root_certificate: None,
+        request_id: None,
+    })
+}
+
+/// Signs the certificate signing request of a `SignCertificateRequest` on the CSMS side
+/// and builds the `CertificateSignedRequest` that returns the chain.
+pub fn certificate_signed_request(
+    authority: &CertificateAuthority,
+    request: &SignCertificateRequest,
+    not_before: DateTime<Utc>,
+    not_after: DateTime<Utc>,
+) -> Result<CertificateSignedRequest, CertificateError> {
+    let chain = authority.sign_request(&request.csr, not_before, not_after)?;
+    Ok(CertificateSignedRequest {
+        certificate_chain: to_pem_chain(&chain)?,
+        certificate_type: request.certificate_type.as_ref().map(Into::into),
+        request_id: request.request_id,
+        custom_data: None,
+    })
+}
+
+/// Handles a `CertificateSignedRequest`.
+pub fn certificate_signed(
+    store: &mut CertificateStore,
+    request: &CertificateSignedRequest,
+    now: DateTime<Utc>,
+) -> CertificateSignedResponse {
+    let kind = request
+        .certificate_type
+        .as_ref()
+        .map_or(CertificateKind::ChargingStation, CertificateKind::from);
+    let (status, status_info) =
+        match store.certificate_signed(kind, &request.certificate_chain, now) {
+            Ok(()) => (CertificateSignedStatusEnumType::Accepted, None),
+            Err(error) => (
+                CertificateSignedStatusEnumType::Rejected,
+                Some((&error).into()),
+            ),
+        };
+    CertificateSignedResponse {
+        status,
+        status_info,
+        custom_data: None,
+    }
+}
+
+/// Handles an `InstallCertificateRequest`.
+pub fn install_certificate(
+    store: &mut CertificateStore,
+    request: &InstallCertificateRequest,
+    now: DateTime<Utc>,
+) -> InstallCertificateResponse {
+    let kind = (&request.certificate_type).into();
+    let (status, status_info) = match store.install_root(kind, &request.certificate, now) {
+        Ok(()) => (InstallCertificateStatusEnumType::Accepted, None),
+        Err(CertificateError::StoreFull) => (
+            InstallCertificateStatusEnumType::Failed,
+            Some((&CertificateError::StoreFull).into()),
+        ),
+        Err(error) => (
+            InstallCertificateStatusEnumType::Rejected,
+            Some((&error).into()),
+        ),
+    };
+    InstallCertificateResponse {
+        custom_data: None,
+        status,
+        status_info,
+    }
+}
+
+/// Handles a `GetInstalledCertificateIdsRequest`, reporting SHA-256 hash data.
+pub fn get_installed_certificate_ids(
+    store: &CertificateStore,
+    request: &GetInstalledCertificateIdsRequest,
+) -> GetInstalledCertificateIdsResponse {
+    let requested = |certificate_type: &GetCertificateIdUseEnumType| {
+        request
+            .certificate_type
+            .as_ref()
+            .is_none_or(|types| types.is_empty() || types.contains(certificate_type))
+    };
+    let chains = store
+        .installed_hashes(&[], HashAlgorithm::Sha256)
+        .and_then(|installed| {
+            installed
+                .into_iter()
+                .filter_map(|(kind, hashes)| Some((certificate_id_use(kind)?, hashes)))
+                .filter(|(certificate_type, _)| requested(certificate_type))
+                .map(|(certificate_type, hashes)| hash_data_chain(certificate_type, hashes))
+                .collect::<Result<Vec<_>, _>>()
+        });
+    let (status, chains, status_info) = match chains {
+        Ok(chains) if !chains.is_empty() => (
+            GetInstalledCertificateStatusEnumType::Accepted,
+            Some(chains),
+            None,
+        ),
+        Ok(_) => (GetInstalledCertificateStatusEnumType::NotFound, None, None),
+        Err(error) => (
+            GetInstalledCertificateStatusEnumType::NotFound,
+            None,
+            Some((&error).into()),
+        ),
+    };
+    GetInstalledCertificateIdsResponse {
+        status,
+        certificate_hash_data_chain: chains,
+        status_info,
+        custom_data: None,
+    }
+}
+
+/// Handles a `DeleteCertificateRequest`.
+pub fn delete_certificate(
+    store: &mut CertificateStore,
+    request: &DeleteCertificateRequest,
+) -> DeleteCertificateResponse {
+    DeleteCertificateResponse {
+        status: store
+            .delete(&(&request.certificate_hash_data).into())
+            .into(),
+        status_info: None,
+        custom_data: None,
+    }
+}
+
+/// Verifies the signing certificate of the firmware of an `UpdateFirmwareRequest` against
+/// the installed manufacturer root certificates and returns the verifier of the image.
+///
+/// A failure is answered with an `UpdateFirmwareResponse` built from the error.
+pub fn firmware_verifier(
+    store: &CertificateStore,
+    firmware: &FirmwareType,
+    now: DateTime<Utc>,
+) -> Result<FirmwareVerifier, FirmwareVerificationError> {
+    let signing_certificate = firmware.signing_certificate.as_deref().unwrap_or_default();
+    let signature = firmware.signature.as_str();
+    FirmwareVerifier::new(store, signing_certificate, signature, now)
+}
+
+/// Status reported in a `FirmwareStatusNotificationRequest` once the signature of the
+/// downloaded image has been verified.
+pub fn firmware_status(
+    verification: &Result<(), FirmwareVerificationError>,
+) -> FirmwareStatusEnumType {
+    match verification {
+        Ok(()) => FirmwareStatusEnumType::SignatureVerified,
+        Err(_) => FirmwareStatusEnumType::InvalidSignature,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+    use crate::components::certificates::{
+        certificate_hash,
+        firmware::tests::{signed_firmware, IMAGE},
+        ocsp_request, parse_pem_chain,
+        tests::{LEAF, ROOT, SUB_CA},
+    };
+
+    #[test]
+    fn chain_and_ocsp_request_data() {
+        let chain = parse_pem_chain(&format!("{LEAF}{SUB_CA}{ROOT}")).unwrap();
+        let data = certificate_hash_data_chain(
+            GetCertificateIdUseEnumType::V2GCertificateChain,
+            &chain,
+            HashAlgorithm::Sha512,
+        )
+        .unwrap();
+        let children = data.child_certificate_hash_data.unwrap();
+        assert_eq!(children[0].serial_number, "a1b2c");
+        assert_eq!(children[0].issuer_name_hash.len(), 128);
+
+        let request: OCSPRequestDataType =
+            ocsp_request(&chain[0], &chain[1], HashAlgorithm::Sha256)
+                .unwrap()
+                .into();
+        assert_eq!(request.responder_url, "http://ocsp.example.com");
+        let hash = certificate_hash(&chain[0], &chain[1], HashAlgorithm::Sha256).unwrap();
+        assert_eq!(request.serial_number, hash.serial_number);
+        let data = CertificateHashDataType::from(hash.clone());
+        assert_eq!(CertificateHash::from(&data), hash);
+    }
+
+    #[test]
+    fn v2g_certificate_lifecycle() {
+        let now = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        let not_after = now + Duration::days(365);
+        let root = CertificateAuthority::new_root("CN=V2G Root", now, not_after).unwrap();
+        let sub_ca = root.new_sub_ca("CN=CPO Sub CA", now, not_after).unwrap();
+        let mut store = CertificateStore::new();
+        let request = InstallCertificateRequest {
+            custom_data: None,
+            certificate_type: InstallCertificateUseEnumType::V2GRootCertificate,
+            certificate: to_pem_chain(std::slice::from_ref(root.certificate())).unwrap(),
+        };
+        let response = install_certificate(&mut store, &request, now);
+        assert_eq!(response.status, InstallCertificateStatusEnumType::Accepted);
+
+        let sign = sign_certificate_request(
+            &mut store,
+            Some(sign_certificate::CertificateSigningUseEnumType::V2G20Certificate),
+            "CN=CS001",
+        )
+        .unwrap();
+        let signed = certificate_signed_request(&sub_ca, &sign, now, not_after).unwrap();
+        assert_eq!(
+            signed.certificate_type,
+            Some(CertificateSigningUseEnumType::V2G20Certificate)
+        );
+        let response = certificate_signed(&mut store, &signed, now);
+        assert_eq!(response.status, CertificateSignedStatusEnumType::Accepted);
+
+        let response = get_installed_certificate_ids(
+            &store,
+            &GetInstalledCertificateIdsRequest {
+                certificate_type: Some(vec![GetCertificateIdUseEnumType::V2GCertificateChain]),
+                custom_data: None,
+            },
+        );
+        let chains = response.certificate_hash_data_chain.unwrap();
+        assert_eq!(chains.len(), 1);
+        // the leaf followed by the sub CA
+        assert_eq!(
+            chains[0]
+                .child_certificate_hash_data
+                .as_ref()
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let request = DeleteCertificateRequest {
+            certificate_hash_data: chains[0].certificate_hash_data.clone(),
+            custom_data: None,
+        };
+        let response = delete_certificate(&mut store, &request);
+        assert_eq!(response.status, DeleteCertificateStatusEnumType::Accepted);
+        let response = delete_certificate(&mut store, &request);
+        assert_eq!(response.status, DeleteCertificateStatusEnumType::NotFound);
+    }
+
+    #[test]
+    fn signed_firmware_update() {
+        let now = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        let (store, certificate, signature) = signed_firmware(now);
+        let firmware = FirmwareType::new("https://example.com/firmware.bin".to_string(), signature)
+            .with_signing_certificate(certificate.clone());
+        let mut verifier = firmware_verifier(&store, &firmware, now).unwrap();
+        verifier.update(IMAGE);
+        assert_eq!(
+            firmware_status(&verifier.verify()),
+            FirmwareStatusEnumType::SignatureVerified
+        );
+        let mut verifier = firmware_verifier(&store, &firmware, now).unwrap();
+        verifier.update(b"tampered image");
+        assert_eq!(
+            firmware_status(&verifier.verify()),
+            FirmwareStatusEnumType::InvalidSignature
+        );
+
+        let unsigned = FirmwareType {
+            signing_certificate: None,
+            ..firmware.clone()
+        };
+        let error = firmware_verifier(&store, &unsigned, now).unwrap_err();
+        let response = UpdateFirmwareResponse::from(&error);
+        assert_eq!(
+            response.status,
+            UpdateFirmwareStatusEnumType::InvalidCertificate
+        );
+        let (other_store, _, _) = signed_firmware(now);
+        let error = firmware_verifier(&other_store, &firmware, now).unwrap_err();
+        assert_eq!(
+            error,
+            FirmwareVerificationError::InvalidCertificate(CertificateError::UntrustedRoot)
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use chrono::{DateTime, Utc};
+use p256::ecdsa::signature::hazmat::PrehashVerifier;
+use rsa::{pkcs8::DecodePublicKey, Pkcs1v15Sign, RsaPublicKey};
+use sha2::{Sha256, Sha384, Sha512};
+use x509_cert::{
+    der::{
+        oid::{db::rfc5912, ObjectIdentifier},
+        Encode,
+    },
+    ext::pkix::BasicConstraints,
+    spki::{AlgorithmIdentifierOwned, SubjectPublicKeyInfoOwned},
+    time::Time,
+    Certificate,
+};
+
+use super::{hash::digest, CertificateError, HashAlgorithm};
+
+/// Verifies the `signature` over `message` made with the private key of `public_key`.
+///
+/// Supports ECDSA with the P-256 and P-384 curves and RSA PKCS #1 v1.5 signatures, each
+/// with SHA-256, SHA-384 or SHA-512.
+pub fn verify_signature(
+    public_key: &SubjectPublicKeyInfoOwned,
+    algorithm: &AlgorithmIdentifierOwned,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), CertificateError> {
+    let (key_algorithm, hash_algorithm) = signature_algorithm(&algorithm.oid)?;
+    if public_key.algorithm.oid != key_algorithm {
+        return Err(CertificateError::UnsupportedAlgorithm);
+    }
+    verify_prehash(
+        public_key,
+        hash_algorithm,
+        &digest(hash_algorithm, message),
+        signature,
+    )
+}
+
+/// Verifies the `signature` over the `prehash` digest made with the private key of
+/// `public_key`. The signature algorithm follows from the type of the key.
+pub fn verify_prehash(
+    public_key: &SubjectPublicKeyInfoOwned,
+    hash_algorithm: HashAlgorithm,
+    prehash: &[u8],
+    signature: &[u8],
+) -> Result<(), CertificateError> {
+    let key_bytes = public_key.subject_public_key.raw_bytes();
+    let verified = match public_key.algorithm.oid {
+        rfc5912::ID_EC_PUBLIC_KEY => {
+            let curve = public_key
+                .algorithm
+                .parameters
+                .as_ref()
+                .and_then(|parameters| parameters.decode_as::<ObjectIdentifier>().ok())
+                .ok_or(CertificateError::UnsupportedAlgorithm)?;
+            match curve {
+                rfc5912::SECP_256_R_1 => {
+                    let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(key_bytes)
+                        .map_err(|_| CertificateError::InvalidSignature)?;
+                    p256::ecdsa::Signature::from_der(signature)
+                        .is_ok_and(|signature| key.verify_prehash(prehash, &signature).is_ok())
+                }
+                rfc5912::SECP_384_R_1 => {
+                    let key = p384::ecdsa::VerifyingKey::from_sec1_bytes(key_bytes)
+                        .map_err(|_| CertificateError::InvalidSignature)?;
+                    p384::ecdsa::Signature::from_der(signature)
+                        .is_ok_and(|signature| key.verify_prehash(prehash, &signature).is_ok())
+                }
+                _ => return Err(CertificateError::UnsupportedAlgorithm),
+            }
+        }
+        rfc5912::RSA_ENCRYPTION => {
+            let key = RsaPublicKey::from_public_key_der(&public_key.to_der()?)
+                .map_err(|_| CertificateError::InvalidSignature)?;
+            let padding = match hash_algorithm {
+                HashAlgorithm::Sha256 => Pkcs1v15Sign::new::<Sha256>(),
+                HashAlgorithm::Sha384 => Pkcs1v15Sign::new::<Sha384>(),
+                HashAlgorithm::Sha512 => Pkcs1v15Sign::new::<Sha512>(),
+            };
+            key.verify(padding, prehash, signature).is_ok()
+        }
+        _ => return Err(CertificateError::UnsupportedAlgorithm),
+    };
+    match verified {
+        true => Ok(()),
+        false => Err(CertificateError::InvalidSignature),
+    }
+}
+
+/// Verifies that `certificate` is signed by `issuer`.
+pub fn verify_issued_by(
+    certificate: &Certificate,
+    issuer: &Certificate,
+) -> Result<(), CertificateError> {
+    if certificate.tbs_certificate.issuer != issuer.tbs_certificate.subject {
+        return Err(CertificateError::MissingIssuer);
+    }
+    verify_signature(
+        &issuer.tbs_certificate.subject_public_key_info,
+        &certificate.signature_algorithm,
+        &certificate.tbs_certificate.to_der()?,
+        certificate.signature.raw_bytes(),
+    )
+}
+
+/// Verifies a chain that starts with the leaf certificate and ends with a certificate
+/// issued by one of the `trusted` roots, and returns that root.
+///
+/// Every certificate must be valid at `now` and every issuer must be a certificate
+/// authority. A trusted root at the end of the chain is allowed.
+pub fn verify_chain<'a>(
+    chain: &[Certificate],
+    trusted: &'a [Certificate],
+    now: DateTime<Utc>,
+) -> Result<&'a Certificate, CertificateError> {
+    let chain = match chain.split_last() {
+        Some((last, issued)) if !issued.is_empty() && trusted.contains(last) => issued,
+        _ => chain,
+    };
+    for pair in chain.windows(2) {
+        check_validity(&pair[0], now)?;
+        check_certificate_authority(&pair[1])?;
+        verify_issued_by(&pair[0], &pair[1])?;
+    }
+    let last = chain.last().ok_or(CertificateError::EmptyChain)?;
+    check_validity(last, now)?;
+    let root = trusted
+        .iter()
+        .filter(|root| root.tbs_certificate.subject == last.tbs_certificate.issuer)
+        .find(|root| verify_issued_by(last, root).is_ok())
+        .ok_or(CertificateError::UntrustedRoot)?;
+    check_validity(root, now)?;
+    check_certificate_authority(root)?;
+    Ok(root)
+}
+
+/// Checks that `now` lies within the validity period of `certificate`.
+pub fn check_validity(
+    certificate: &Certificate,
+    now: DateTime<Utc>,
+) -> Result<(), CertificateError> {
+    let validity = &certificate.tbs_certificate.validity;
+    let seconds = |time: Time| i64::try_from(time.to_unix_duration().as_secs()).unwrap_or(i64::MAX);
+    let now = now.timestamp();
+    match seconds(validity.not_before) <= now && now <= seconds(validity.not_after) {
+        true => Ok(()),
+        false => Err(CertificateError::OutsideValidityPeriod),
+    }
+}
+
+/// Checks that `certificate` is a certificate authority according to its basic constraints.
+pub fn check_certificate_authority(certificate: &Certificate) -> Result<(), CertificateError> {
+    let constraints = certificate.tbs_certificate.get::<BasicConstraints>()?;
+    match constraints.is_some_and(|(_, constraints)| constraints.ca) {
+        true => Ok(()),
+        false => Err(CertificateError::NotCertificateAuthority),
+    }
+}
+
+fn signature_algorithm(
+    oid: &ObjectIdentifier,
+) -> Result<(ObjectIdentifier, HashAlgorithm), CertificateError> {
+    match *oid {
+        rfc5912::ECDSA_WITH_SHA_256 => Ok((rfc5912::ID_EC_PUBLIC_KEY, HashAlgorithm::Sha256)),
+        rfc5912::ECDSA_WITH_SHA_384 => Ok((rfc5912::ID_EC_PUBLIC_KEY, HashAlgorithm::Sha384)),
+        rfc5912::ECDSA_WITH_SHA_512 => Ok((rfc5912::ID_EC_PUBLIC_KEY, HashAlgorithm::Sha512)),
+        rfc5912::SHA_256_WITH_RSA_ENCRYPTION => {
+            Ok((rfc5912::RSA_ENCRYPTION, HashAlgorithm::Sha256))
+        }
+        rfc5912::SHA_384_WITH_RSA_ENCRYPTION => {
+            Ok((rfc5912::RSA_ENCRYPTION, HashAlgorithm::Sha384))
+        }
+        rfc5912::SHA_512_WITH_RSA_ENCRYPTION => {
+            Ok((rfc5912::RSA_ENCRYPTION, HashAlgorithm::Sha512))
+        }
+        _ => Err(CertificateError::UnsupportedAlgorithm),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::components::certificates::{
+        parse_pem_chain,
+        tests::{LEAF, ROOT, SUB_CA},
+    };
+
+    const RSA_ROOT: &str = include_str!("../../tests/certificates/rsa_root.pem");
+    const P384_SUB_CA: &str = include_str!("../../tests/certificates/p384_sub_ca.pem");
+    const P384_LEAF: &str = include_str!("../../tests/certificates/p384_leaf.pem");
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn verifies_ecdsa_and_rsa_chains() {
+        // P-256 leaf signed with ECDSA SHA-384 by a P-384 sub CA, signed by an RSA root
+        let chain = parse_pem_chain(&format!("{P384_LEAF}{P384_SUB_CA}")).unwrap();
+        let roots = parse_pem_chain(RSA_ROOT).unwrap();
+        assert_eq!(verify_chain(&chain, &roots, now()), Ok(&roots[0]));
+        verify_issued_by(&roots[0], &roots[0]).unwrap();
+
+        let other_roots = parse_pem_chain(ROOT).unwrap();
+        assert_eq!(
+            verify_chain(&chain, &other_roots, now()),
+            Err(CertificateError::UntrustedRoot)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_chains() {
+        let chain = parse_pem_chain(&format!("{LEAF}{SUB_CA}{ROOT}")).unwrap();
+        let roots = parse_pem_chain(ROOT).unwrap();
+        assert_eq!(verify_chain(&chain, &roots, now()), Ok(&roots[0]));
+
+        // the sub CA did not issue the P-384 leaf
+        let mut forged = parse_pem_chain(P384_LEAF).unwrap();
+        forged[0].tbs_certificate.issuer = chain[1].tbs_certificate.subject.clone();
+        assert_eq!(
+            verify_issued_by(&forged[0], &chain[1]),
+            Err(CertificateError::InvalidSignature)
+        );
+        // a leaf does not issue certificates
+        assert_eq!(
+            verify_chain(&[chain[1].clone(), chain[0].clone()], &roots, now()),
+            Err(CertificateError::NotCertificateAuthority)
+        );
+        let expired = Utc.with_ymd_and_hms(2200, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            verify_chain(&chain, &roots, expired),
+            Err(CertificateError::OutsideValidityPeriod)
+        );
+    }
+}
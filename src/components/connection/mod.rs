@@ -0,0 +1,115 @@
+//! # Connection
+//!
+//! Supervision of the connection of a Charging Station to the CSMS, shared by OCPP 1.6 and
+//! 2.x: when to reconnect, ping the WebSocket and send a `Heartbeat`, and the clock of the
+//! CSMS.
+//!
+//! After the connection is lost, or a reconnect fails, the [`ConnectionSupervisor`] waits
+//! `RetryBackOffWaitMinimum` plus a random part of up to `RetryBackOffRandomRange`, doubling
+//! the wait after each failed attempt up to `RetryBackOffRepeatTimes` times, and never more
+//! than [`BackoffPolicy::MAX_DELAY`]. In 2.x, once the doublings are exhausted, it falls
+//! back to the next network connection profile, the `configurationSlot`s being tried in
+//! `NetworkConfigurationPriority` order.
+//!
+//! While connected it tells when to ping (`WebSocketPingInterval`) and when to send a
+//! `Heartbeat`, at the `interval` of the accepted `BootNotificationResponse` unless another
+//! message was sent meanwhile. The `currentTime` of `BootNotificationResponse` and
+//! `HeartbeatResponse` keeps the clock in sync with the CSMS.
+
+use chrono::Duration;
+
+mod supervisor;
+
+#[cfg(feature = "v1_6")]
+pub mod v1_6;
+#[cfg(feature = "v2_0_1")]
+pub mod v2_0_1;
+#[cfg(feature = "v2_1")]
+pub mod v2_1;
+
+pub use supervisor::ConnectionSupervisor;
+
+/// Back-off between reconnect attempts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackoffPolicy {
+    /// Wait before the first attempt, `RetryBackOffWaitMinimum`.
+    pub wait_minimum: Duration,
+    /// Maximum random wait added to each attempt, `RetryBackOffRandomRange`.
+    pub random_range: Duration,
+    /// Number of times the wait doubles, `RetryBackOffRepeatTimes`.
+    pub repeat_times: u32,
+}
+
+impl BackoffPolicy {
+    /// Longest wait before a reconnect attempt, whatever the configuration.
+    pub const MAX_DELAY: Duration = Duration::days(1);
+
+    /// Creates the policy of the `RetryBackOffWaitMinimum` and `RetryBackOffRandomRange`
+    /// (seconds) and `RetryBackOffRepeatTimes` configuration values. Negative values count as
+    /// zero.
+    pub fn new(wait_minimum: i32, random_range: i32, repeat_times: i32) -> Self {
+        Self {
+            wait_minimum: Duration::seconds(wait_minimum.max(0).into()),
+            random_range: Duration::seconds(random_range.max(0).into()),
+            repeat_times: repeat_times.max(0) as u32,
+        }
+    }
+
+    /// Wait before reconnect attempt `attempt`, 0 being the first, with `random` in `[0, 1]`
+    /// choosing the random part. The wait is at most [`Self::MAX_DELAY`].
+    pub fn delay(&self, attempt: u32, random: f64) -> Duration {
+        let doublings = attempt.min(self.repeat_times).min(30);
+        let random = self.random_range.num_milliseconds() as f64 * random.clamp(0.0, 1.0);
+        self.wait_minimum
+            .checked_mul(1 << doublings)
+            .and_then(|wait| wait.checked_add(&Duration::milliseconds(random as i64)))
+            .map_or(Self::MAX_DELAY, |wait| wait.min(Self::MAX_DELAY))
+    }
+}
+
+impl Default for BackoffPolicy {
+    /// Waits from 10 seconds, with up to 10 random seconds, doubling 3 times.
+    fn default() -> Self {
+        Self::new(10, 10, 3)
+    }
+}
+
+/// Parses the `NetworkConfigurationPriority` variable, a comma separated list of
+/// `configurationSlot`s, skipping invalid entries.
+pub fn parse_network_priority(value: &str) -> Vec<i32> {
+    value
+        .split(',')
+        .filter_map(|slot| slot.trim().parse().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_repeat_times() {
+        let policy = BackoffPolicy::new(5, 10, 2);
+        assert_eq!(policy.delay(0, 0.0), Duration::seconds(5));
+        assert_eq!(policy.delay(0, 0.5), Duration::seconds(10));
+        assert_eq!(policy.delay(1, 1.0), Duration::seconds(20));
+        assert_eq!(policy.delay(2, 0.0), Duration::seconds(20));
+        assert_eq!(policy.delay(7, 0.0), Duration::seconds(20));
+        assert_eq!(policy.delay(7, 2.0), Duration::seconds(30));
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let policy = BackoffPolicy::new(86_400, 0, 40);
+        assert_eq!(policy.delay(0, 0.0), Duration::days(1));
+        assert_eq!(policy.delay(26, 0.0), BackoffPolicy::MAX_DELAY);
+        let policy = BackoffPolicy::new(i32::MAX, i32::MAX, 30);
+        assert_eq!(policy.delay(30, 1.0), BackoffPolicy::MAX_DELAY);
+    }
+
+    #[test]
+    fn parses_network_priority() {
+        assert_eq!(parse_network_priority("2, 1,x,3"), vec![2, 1, 3]);
+        assert!(parse_network_priority("").is_empty());
+    }
+}
@@ -0,0 +1,246 @@
+use chrono::{DateTime, Duration, Utc};
+
+use super::BackoffPolicy;
+
+/// Reconnects, pings, heartbeats and clock of the connection of a Charging Station.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectionSupervisor {
+    backoff: BackoffPolicy,
+    ping_interval: Option<Duration>,
+    heartbeat_interval: Option<Duration>,
+    profiles: Vec<i32>,
+    profile: usize,
+    attempts: u32,
+    connected: bool,
+    reconnect_at: Option<DateTime<Utc>>,
+    last_ping: Option<DateTime<Utc>>,
+    last_message: Option<DateTime<Utc>>,
+    clock_offset: Duration,
+}
+
+impl ConnectionSupervisor {
+    /// Creates the supervisor of a station that is not connected yet.
+    pub fn new(backoff: BackoffPolicy) -> Self {
+        Self {
+            backoff,
+            ping_interval: None,
+            heartbeat_interval: None,
+            profiles: Vec::new(),
+            profile: 0,
+            attempts: 0,
+            connected: false,
+            reconnect_at: None,
+            last_ping: None,
+            last_message: None,
+            clock_offset: Duration::zero(),
+        }
+    }
+
+    /// Pings the WebSocket every `interval`, `WebSocketPingInterval`. Zero disables pings.
+    pub fn with_ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = (interval > Duration::zero()).then_some(interval);
+        self
+    }
+
+    /// Falls back over the network connection profiles in `slots`, in order of priority.
+    pub fn with_network_priority(mut self, slots: Vec<i32>) -> Self {
+        self.profiles = slots;
+        self.profile = 0;
+        self
+    }
+
+    /// Changes the back-off, e.g. when the CSMS changes its configuration.
+    pub fn set_backoff(&mut self, backoff: BackoffPolicy) {
+        self.backoff = backoff;
+    }
+
+    /// Sends a `Heartbeat` every `interval`, zero disabling heartbeats.
+    pub fn set_heartbeat_interval(&mut self, interval: Duration) {
+        self.heartbeat_interval = (interval > Duration::zero()).then_some(interval);
+    }
+
+    /// Returns `true` while connected.
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// `configurationSlot` of the network connection profile to connect with.
+    pub fn profile(&self) -> Option<i32> {
+        self.profiles.get(self.profile).copied()
+    }
+
+    /// Records that the connection was established at `now`.
+    pub fn connected(&mut self, now: DateTime<Utc>) {
+        self.connected = true;
+        self.attempts = 0;
+        self.reconnect_at = None;
+        self.last_ping = Some(now);
+        self.last_message = Some(now);
+    }
+
+    /// Records that the connection was lost, or a connection attempt failed, at `now`,
+    /// returning when to attempt to reconnect. `random` in `[0, 1]` chooses the random part
+    /// of the wait.
+    ///
+    /// Once the wait has doubled `RetryBackOffRepeatTimes` times, the next attempt uses the
+    /// next network connection profile, starting over from the minimum wait.
+    pub fn disconnected(&mut self, now: DateTime<Utc>, random: f64) -> DateTime<Utc> {
+        self.connected = false;
+        if self.attempts > self.backoff.repeat_times && self.profiles.len() > 1 {
+            self.profile = (self.profile + 1) % self.profiles.len();
+            self.attempts = 0;
+        }
+        let reconnect_at = now
+            .checked_add_signed(self.backoff.delay(self.attempts, random))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        self.attempts = self.attempts.saturating_add(1);
+        self.reconnect_at = Some(reconnect_at);
+        reconnect_at
+    }
+
+    /// When to attempt to reconnect, while disconnected.
+    pub fn reconnect_at(&self) -> Option<DateTime<Utc>> {
+        self.reconnect_at.filter(|_| !self.connected)
+    }
+
+    /// Whether to attempt to reconnect at `now`.
+    pub fn reconnect_due(&self, now: DateTime<Utc>) -> bool {
+        self.reconnect_at().is_some_and(|at| at <= now)
+    }
+
+    /// Records that a message was sent to the CSMS at `now`, which postpones the next
+    /// `Heartbeat`.
+    pub fn message_sent(&mut self, now: DateTime<Utc>) {
+        self.last_message = Some(now);
+    }
+
+    /// When to send the next `Heartbeat`, while connected.
+    pub fn next_heartbeat(&self) -> Option<DateTime<Utc>> {
+        let interval = self.heartbeat_interval.filter(|_| self.connected)?;
+        self.last_message.map(|last| last + interval)
+    }
+
+    /// Whether to send a `Heartbeat` at `now`.
+    pub fn heartbeat_due(&self, now: DateTime<Utc>) -> bool {
+        self.next_heartbeat().is_some_and(|at| at <= now)
+    }
+
+    /// Records that the WebSocket was pinged at `now`.
+    pub fn pinged(&mut self, now: DateTime<Utc>) {
+        self.last_ping = Some(now);
+    }
+
+    /// When to ping the WebSocket next, while connected.
+    pub fn next_ping(&self) -> Option<DateTime<Utc>> {
+        let interval = self.ping_interval.filter(|_| self.connected)?;
+        self.last_ping.map(|last| last + interval)
+    }
+
+    /// Whether to ping the WebSocket at `now`.
+    pub fn ping_due(&self, now: DateTime<Utc>) -> bool {
+        self.next_ping().is_some_and(|at| at <= now)
+    }
+
+    /// Synchronizes the clock with the `current_time` of the CSMS, received at `now`.
+    pub fn synchronize(&mut self, current_time: DateTime<Utc>, now: DateTime<Utc>) {
+        self.clock_offset = current_time - now;
+    }
+
+    /// Difference between the clock of the CSMS and the local clock.
+    pub fn clock_offset(&self) -> Duration {
+        self.clock_offset
+    }
+
+    /// Time of the CSMS at local time `now`.
+    pub fn csms_time(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now + self.clock_offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn reconnects_with_backoff_and_falls_back() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut supervisor = ConnectionSupervisor::new(BackoffPolicy::new(10, 0, 1))
+            .with_network_priority(vec![2, 1]);
+        assert_eq!(supervisor.profile(), Some(2));
+
+        assert_eq!(
+            supervisor.disconnected(now, 0.0),
+            now + Duration::seconds(10)
+        );
+        assert!(!supervisor.reconnect_due(now));
+        assert!(supervisor.reconnect_due(now + Duration::seconds(10)));
+        assert_eq!(
+            supervisor.disconnected(now, 0.0),
+            now + Duration::seconds(20)
+        );
+        assert_eq!(supervisor.profile(), Some(2));
+
+        // the doublings are exhausted, fall back to slot 1
+        assert_eq!(
+            supervisor.disconnected(now, 0.0),
+            now + Duration::seconds(10)
+        );
+        assert_eq!(supervisor.profile(), Some(1));
+
+        supervisor.connected(now);
+        assert_eq!(supervisor.reconnect_at(), None);
+        assert_eq!(
+            supervisor.disconnected(now, 0.0),
+            now + Duration::seconds(10)
+        );
+        assert_eq!(supervisor.profile(), Some(1));
+    }
+
+    #[test]
+    fn caps_reconnect_wait() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut supervisor = ConnectionSupervisor::new(BackoffPolicy::new(86_400, 0, 40));
+        for _ in 0..50 {
+            assert!(supervisor.disconnected(now, 1.0) <= now + BackoffPolicy::MAX_DELAY);
+        }
+        let late = DateTime::<Utc>::MAX_UTC - Duration::hours(1);
+        assert_eq!(supervisor.disconnected(late, 0.0), DateTime::<Utc>::MAX_UTC);
+    }
+
+    #[test]
+    fn schedules_heartbeats_and_pings() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut supervisor = ConnectionSupervisor::new(BackoffPolicy::default())
+            .with_ping_interval(Duration::seconds(30));
+        supervisor.set_heartbeat_interval(Duration::seconds(300));
+        assert_eq!(supervisor.next_heartbeat(), None);
+
+        supervisor.connected(now);
+        assert_eq!(supervisor.next_ping(), Some(now + Duration::seconds(30)));
+        assert!(supervisor.ping_due(now + Duration::seconds(30)));
+        supervisor.pinged(now + Duration::seconds(30));
+        assert!(!supervisor.ping_due(now + Duration::seconds(59)));
+
+        assert!(!supervisor.heartbeat_due(now + Duration::seconds(299)));
+        supervisor.message_sent(now + Duration::seconds(200));
+        assert!(!supervisor.heartbeat_due(now + Duration::seconds(300)));
+        assert!(supervisor.heartbeat_due(now + Duration::seconds(500)));
+
+        supervisor.set_heartbeat_interval(Duration::zero());
+        assert_eq!(supervisor.next_heartbeat(), None);
+    }
+
+    #[test]
+    fn synchronizes_the_clock() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut supervisor = ConnectionSupervisor::new(BackoffPolicy::default());
+        supervisor.synchronize(now + Duration::seconds(90), now);
+        assert_eq!(supervisor.clock_offset(), Duration::seconds(90));
+        assert_eq!(
+            supervisor.csms_time(now + Duration::hours(1)),
+            now + Duration::hours(1) + Duration::seconds(90)
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! OCPP 1.6 messages for the [`ConnectionSupervisor`].
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::v1_6::{
+    messages::{boot_notification::BootNotificationResponse, heart_beat::HeartbeatResponse},
+    types::RegistrationStatus,
+};
+
+use super::ConnectionSupervisor;
+
+/// Handles a `BootNotificationResponse` received at `now`, taking its `interval` as the
+/// heartbeat interval when the Charge Point is accepted.
+pub fn boot_notification_response(
+    supervisor: &mut ConnectionSupervisor,
+    response: &BootNotificationResponse,
+    now: DateTime<Utc>,
+) {
+    supervisor.synchronize(response.current_time, now);
+    if response.status == RegistrationStatus::Accepted {
+        supervisor.set_heartbeat_interval(Duration::seconds(response.interval.into()));
+    }
+}
+
+/// Handles a `HeartbeatResponse` received at `now`.
+pub fn heartbeat_response(
+    supervisor: &mut ConnectionSupervisor,
+    response: &HeartbeatResponse,
+    now: DateTime<Utc>,
+) {
+    supervisor.synchronize(response.current_time, now);
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::components::connection::BackoffPolicy;
+
+    #[test]
+    fn heartbeat_interval_and_clock() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut supervisor = ConnectionSupervisor::new(BackoffPolicy::default());
+        supervisor.connected(now);
+
+        let response = BootNotificationResponse {
+            current_time: now + Duration::seconds(5),
+            interval: 120,
+            status: RegistrationStatus::Pending,
+        };
+        boot_notification_response(&mut supervisor, &response, now);
+        assert_eq!(supervisor.next_heartbeat(), None);
+        assert_eq!(supervisor.clock_offset(), Duration::seconds(5));
+
+        let response = BootNotificationResponse {
+            status: RegistrationStatus::Accepted,
+            ..response
+        };
+        boot_notification_response(&mut supervisor, &response, now);
+        assert_eq!(
+            supervisor.next_heartbeat(),
+            Some(now + Duration::seconds(120))
+        );
+
+        let response = HeartbeatResponse {
+            current_time: now - Duration::seconds(1),
+        };
+        heartbeat_response(&mut supervisor, &response, now);
+        assert_eq!(supervisor.clock_offset(), Duration::seconds(-1));
+    }
+}
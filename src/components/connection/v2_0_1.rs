@@ -0,0 +1,104 @@
+//! OCPP 2.0.1 messages for the [`ConnectionSupervisor`].
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::v2_0_1::{
+    datatypes::network_connection_profile_type::NetworkConnectionProfileType,
+    enumerations::registration_status_enum_type::RegistrationStatusEnumType,
+    messages::{
+        boot_notification::BootNotificationResponse, heartbeat::HeartbeatResponse,
+        set_network_profile::SetNetworkProfileRequest,
+    },
+};
+
+use super::ConnectionSupervisor;
+
+/// Handles a `BootNotificationResponse` received at `now`, taking its `interval` as the
+/// heartbeat interval when the Charging Station is accepted.
+pub fn boot_notification_response(
+    supervisor: &mut ConnectionSupervisor,
+    response: &BootNotificationResponse,
+    now: DateTime<Utc>,
+) {
+    supervisor.synchronize(response.current_time, now);
+    if response.status == RegistrationStatusEnumType::Accepted {
+        supervisor.set_heartbeat_interval(Duration::seconds(response.interval.into()));
+    }
+}
+
+/// Handles a `HeartbeatResponse` received at `now`.
+pub fn heartbeat_response(
+    supervisor: &mut ConnectionSupervisor,
+    response: &HeartbeatResponse,
+    now: DateTime<Utc>,
+) {
+    supervisor.synchronize(response.current_time, now);
+}
+
+/// The network connection profile to connect with, among the profiles set by
+/// `SetNetworkProfile`.
+pub fn network_profile<'a>(
+    supervisor: &ConnectionSupervisor,
+    profiles: &'a [SetNetworkProfileRequest],
+) -> Option<&'a NetworkConnectionProfileType> {
+    let slot = supervisor.profile()?;
+    profiles
+        .iter()
+        .find(|profile| profile.configuration_slot == slot)
+        .map(|profile| &profile.connection_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::components::connection::BackoffPolicy;
+
+    #[test]
+    fn falls_back_to_the_next_profile() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let profile = |slot: i32, url: &str| SetNetworkProfileRequest {
+            configuration_slot: slot,
+            connection_data: NetworkConnectionProfileType {
+                ocpp_csms_url: url.to_string(),
+                ..Default::default()
+            },
+        };
+        let profiles = [profile(1, "wss://primary"), profile(2, "wss://backup")];
+        let mut supervisor = ConnectionSupervisor::new(BackoffPolicy::new(1, 0, 0))
+            .with_network_priority(vec![1, 2]);
+        assert_eq!(
+            network_profile(&supervisor, &profiles)
+                .unwrap()
+                .ocpp_csms_url,
+            "wss://primary"
+        );
+        supervisor.disconnected(now, 0.5);
+        supervisor.disconnected(now, 0.5);
+        assert_eq!(
+            network_profile(&supervisor, &profiles)
+                .unwrap()
+                .ocpp_csms_url,
+            "wss://backup"
+        );
+
+        supervisor.connected(now);
+        let response = BootNotificationResponse {
+            current_time: now,
+            interval: 60,
+            status: RegistrationStatusEnumType::Accepted,
+            status_info: None,
+        };
+        boot_notification_response(&mut supervisor, &response, now);
+        assert!(supervisor.heartbeat_due(now + Duration::seconds(60)));
+        heartbeat_response(
+            &mut supervisor,
+            &HeartbeatResponse {
+                current_time: now + Duration::seconds(3),
+            },
+            now,
+        );
+        assert_eq!(supervisor.clock_offset(), Duration::seconds(3));
+    }
+}
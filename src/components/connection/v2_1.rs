@@ -0,0 +1,111 @@
+//! OCPP 2.1 messages for the [`ConnectionSupervisor`].
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::v2_1::{
+    datatypes::NetworkConnectionProfileType,
+    enumerations::RegistrationStatusEnumType,
+    messages::{
+        boot_notification::BootNotificationResponse, heartbeat::HeartbeatResponse,
+        set_network_profile::SetNetworkProfileRequest,
+    },
+};
+
+use super::ConnectionSupervisor;
+
+/// Handles a `BootNotificationResponse` received at `now`, taking its `interval` as the
+/// heartbeat interval when the Charging Station is accepted.
+pub fn boot_notification_response(
+    supervisor: &mut ConnectionSupervisor,
+    response: &BootNotificationResponse,
+    now: DateTime<Utc>,
+) {
+    supervisor.synchronize(response.current_time, now);
+    if response.status == RegistrationStatusEnumType::Accepted {
+        supervisor.set_heartbeat_interval(Duration::seconds(response.interval.into()));
+    }
+}
+
+/// Handles a `HeartbeatResponse` received at `now`.
+pub fn heartbeat_response(
+    supervisor: &mut ConnectionSupervisor,
+    response: &HeartbeatResponse,
+    now: DateTime<Utc>,
+) {
+    supervisor.synchronize(response.current_time, now);
+}
+
+/// The network connection profile to connect with, among the profiles set by
+/// `SetNetworkProfile`.
+pub fn network_profile<'a>(
+    supervisor: &ConnectionSupervisor,
+    profiles: &'a [SetNetworkProfileRequest],
+) -> Option<&'a NetworkConnectionProfileType> {
+    let slot = supervisor.profile()?;
+    profiles
+        .iter()
+        .find(|profile| profile.configuration_slot == slot)
+        .map(|profile| &profile.connection_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::components::connection::{parse_network_priority, BackoffPolicy};
+
+    #[test]
+    fn falls_back_to_the_next_profile() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let profile = |slot: i32, url: &str| SetNetworkProfileRequest {
+            custom_data: None,
+            configuration_slot: slot,
+            connection_data: NetworkConnectionProfileType::new(
+                "Wireless0".to_string(),
+                "JSON".to_string(),
+                "OCPP21".to_string(),
+                url.to_string(),
+                30,
+                2,
+            ),
+        };
+        let profiles = [profile(3, "wss://primary"), profile(1, "wss://backup")];
+        let mut supervisor = ConnectionSupervisor::new(BackoffPolicy::new(1, 0, 1))
+            .with_network_priority(parse_network_priority("3,1"));
+        for _ in 0..2 {
+            supervisor.disconnected(now, 0.0);
+        }
+        assert_eq!(
+            network_profile(&supervisor, &profiles)
+                .unwrap()
+                .ocpp_csms_url,
+            "wss://primary"
+        );
+        supervisor.disconnected(now, 0.0);
+        assert_eq!(
+            network_profile(&supervisor, &profiles)
+                .unwrap()
+                .ocpp_csms_url,
+            "wss://backup"
+        );
+
+        let response = BootNotificationResponse {
+            custom_data: None,
+            current_time: now + Duration::seconds(2),
+            interval: 0,
+            status: RegistrationStatusEnumType::Accepted,
+            status_info: None,
+        };
+        supervisor.connected(now);
+        boot_notification_response(&mut supervisor, &response, now);
+        assert_eq!(supervisor.next_heartbeat(), None);
+        assert_eq!(supervisor.csms_time(now), now + Duration::seconds(2));
+        let response = HeartbeatResponse {
+            custom_data: None,
+            current_time: now,
+        };
+        heartbeat_response(&mut supervisor, &response, now);
+        assert_eq!(supervisor.clock_offset(), Duration::zero());
+    }
+}
@@ -0,0 +1,186 @@
+//! # Cost updates
+//!
+//! Running cost of transactions as sent by the CSMS in `CostUpdatedRequest` and in the
+//! `total_cost` of `TransactionEventResponse` (OCPP 2.0.1 and 2.1).
+//!
+//! The [`CostUpdater`] is fed the current cost of each transaction, for example calculated
+//! with the tariff of the transaction, and decides when an update is due
+//! according to its [`CostUpdateConfig`]. The version submodules turn the updates into
+//! messages.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+
+#[cfg(feature = "v2_0_1")]
+pub mod v2_0_1;
+#[cfg(feature = "v2_1")]
+pub mod v2_1;
+
+/// When running cost updates are sent.
+///
+/// An update is due when the cost changed and either the interval has passed since the
+/// previous update, or the cost changed at least the threshold since the previous update.
+/// Without interval and threshold every change is sent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CostUpdateConfig {
+    /// Minimum time between two updates of a transaction.
+    pub interval: Option<Duration>,
+    /// Change of the cost since the previous update that triggers an update regardless of
+    /// the interval.
+    pub threshold: Option<Decimal>,
+}
+
+impl CostUpdateConfig {
+    /// Creates a configuration that sends every change.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the minimum time between two updates.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    /// Sets the change of the cost that triggers an update regardless of the interval.
+    pub fn with_threshold(mut self, threshold: Decimal) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+}
+
+/// A running cost update to send to the Charging Station.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostUpdate {
+    /// Transaction the cost belongs to.
+    pub transaction_id: String,
+    /// Current total cost of the transaction including taxes.
+    pub total_cost: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Sent {
+    total_cost: Decimal,
+    at: DateTime<Utc>,
+}
+
+/// Decides when the running cost of a transaction is sent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CostUpdater {
+    config: CostUpdateConfig,
+    sent: HashMap<String, Sent>,
+}
+
+impl CostUpdater {
+    /// Creates an updater without transactions.
+    pub fn new(config: CostUpdateConfig) -> Self {
+        Self {
+            config,
+            sent: HashMap::new(),
+        }
+    }
+
+    /// The configuration of the updater.
+    pub fn config(&self) -> &CostUpdateConfig {
+        &self.config
+    }
+
+    /// Last cost sent for a transaction.
+    pub fn last_sent(&self, transaction_id: &str) -> Option<Decimal> {
+        self.sent.get(transaction_id).map(|sent| sent.total_cost)
+    }
+
+    /// Records the current cost of a transaction and returns the update to send, if due.
+    ///
+    /// The first cost of a transaction is always due.
+    pub fn update(
+        &mut self,
+        transaction_id: &str,
+        total_cost: Decimal,
+        now: DateTime<Utc>,
+    ) -> Option<CostUpdate> {
+        let due = match self.sent.get(transaction_id) {
+            None => true,
+            Some(sent) if sent.total_cost == total_cost => false,
+            Some(sent) => {
+                let interval_passed = self
+                    .config
+                    .interval
+                    .is_some_and(|interval| now - sent.at >= interval);
+                let threshold_reached = self
+                    .config
+                    .threshold
+                    .is_some_and(|threshold| (total_cost - sent.total_cost).abs() >= threshold);
+                let every_change =
+                    self.config.interval.is_none() && self.config.threshold.is_none();
+                interval_passed || threshold_reached || every_change
+            }
+        };
+        if !due {
+            return None;
+        }
+        self.sent.insert(
+            transaction_id.to_string(),
+            Sent {
+                total_cost,
+                at: now,
+            },
+        );
+        Some(CostUpdate {
+            transaction_id: transaction_id.to_string(),
+            total_cost,
+        })
+    }
+
+    /// Forgets an ended transaction.
+    pub fn end(&mut self, transaction_id: &str) {
+        self.sent.remove(transaction_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn updates_follow_interval_and_threshold() {
+        let config = CostUpdateConfig::new()
+            .with_interval(Duration::minutes(5))
+            .with_threshold(dec!(1));
+        let mut updater = CostUpdater::new(config);
+
+        assert!(updater.update("tx", dec!(0.50), start()).is_some());
+        // changed, but neither interval nor threshold reached
+        let now = start() + Duration::minutes(1);
+        assert!(updater.update("tx", dec!(1.00), now).is_none());
+        // threshold reached
+        let update = updater.update("tx", dec!(1.50), now).unwrap();
+        assert_eq!(update.total_cost, dec!(1.50));
+        // interval passed, but no change
+        let now = start() + Duration::minutes(10);
+        assert!(updater.update("tx", dec!(1.50), now).is_none());
+        assert!(updater.update("tx", dec!(1.60), now).is_some());
+        assert_eq!(updater.last_sent("tx"), Some(dec!(1.60)));
+
+        updater.end("tx");
+        assert_eq!(updater.last_sent("tx"), None);
+    }
+
+    #[test]
+    fn every_change_without_configuration() {
+        let mut updater = CostUpdater::new(CostUpdateConfig::new());
+        assert!(updater.update("tx", dec!(0), start()).is_some());
+        assert!(updater.update("tx", dec!(0), start()).is_none());
+        assert!(updater.update("tx", dec!(0.01), start()).is_some());
+        assert!(updater.update("other", dec!(0.01), start()).is_some());
+    }
+}
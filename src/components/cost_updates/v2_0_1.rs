@@ -0,0 +1,79 @@
+//! OCPP 2.0.1 messages for the [`CostUpdater`].
+
+use rust_decimal::Decimal;
+
+use crate::v2_0_1::{
+    enumerations::transaction_event_enum_type::TransactionEventEnumType,
+    messages::{cost_updated::CostUpdatedRequest, transaction_event::TransactionEventRequest},
+};
+
+use super::{CostUpdate, CostUpdater};
+
+impl From<CostUpdate> for CostUpdatedRequest {
+    fn from(update: CostUpdate) -> Self {
+        Self {
+            total_cost: update.total_cost,
+            transaction_id: update.transaction_id,
+        }
+    }
+}
+
+/// Returns the `total_cost` of the `TransactionEventResponse` to `request`, given the
+/// current cost of its transaction.
+///
+/// In OCPP 2.0.1 the total cost is only sent when the transaction has ended, as the final
+/// cost. Running cost is sent with `CostUpdatedRequest` instead.
+pub fn transaction_event_total_cost(
+    updater: &mut CostUpdater,
+    request: &TransactionEventRequest,
+    total_cost: Decimal,
+) -> Option<Decimal> {
+    if request.event_type != TransactionEventEnumType::Ended {
+        return None;
+    }
+    updater.end(&request.transaction_info.transaction_id);
+    Some(total_cost)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::{
+        components::cost_updates::CostUpdateConfig,
+        v2_0_1::datatypes::transaction_type::TransactionType,
+    };
+
+    #[test]
+    fn total_cost_is_only_sent_at_the_end() {
+        let now = Utc::now();
+        let mut updater = CostUpdater::new(CostUpdateConfig::new());
+        let update = updater.update("tx", dec!(2.5), now).unwrap();
+        let cost_updated: CostUpdatedRequest = update.into();
+        assert_eq!(cost_updated.total_cost, dec!(2.5));
+        assert_eq!(cost_updated.transaction_id, "tx");
+
+        let mut request = TransactionEventRequest {
+            event_type: TransactionEventEnumType::Updated,
+            timestamp: now,
+            transaction_info: TransactionType {
+                transaction_id: "tx".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            transaction_event_total_cost(&mut updater, &request, dec!(3)),
+            None
+        );
+
+        request.event_type = TransactionEventEnumType::Ended;
+        assert_eq!(
+            transaction_event_total_cost(&mut updater, &request, dec!(3)),
+            Some(dec!(3))
+        );
+        assert_eq!(updater.last_sent("tx"), None);
+    }
+}
@@ -0,0 +1,117 @@
+//! OCPP 2.1 messages for the [`CostUpdater`].
+
+use chrono::{DateTime, Utc};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+
+use crate::{
+    components::tariff::{cost_details, TariffSession},
+    v2_1::{
+        datatypes::TariffType,
+        enumerations::TransactionEventEnumType,
+        messages::{cost_updated::CostUpdatedRequest, transaction_event::TransactionEventRequest},
+    },
+};
+
+use super::{CostUpdate, CostUpdater};
+
+impl From<CostUpdate> for CostUpdatedRequest {
+    fn from(update: CostUpdate) -> Self {
+        Self {
+            total_cost: update.total_cost.to_f64().unwrap_or_default(),
+            transaction_id: update.transaction_id,
+            custom_data: None,
+        }
+    }
+}
+
+/// Current cost including taxes of `session` with `tariff`, as calculated by
+/// [`cost_details`]. The cost excluding taxes is used when the tariff has no taxes.
+pub fn running_cost(tariff: &TariffType, session: &TariffSession, now: DateTime<Utc>) -> Decimal {
+    let total = cost_details(tariff, session, now).total_cost.total;
+    total.incl_tax.or(total.excl_tax).unwrap_or_default()
+}
+
+/// Returns the `total_cost` of the `TransactionEventResponse` to `request`, given the
+/// current cost of its transaction.
+///
+/// The final cost is returned when the transaction has ended. Otherwise the running cost
+/// is returned when an update is due according to `updater`.
+pub fn transaction_event_total_cost(
+    updater: &mut CostUpdater,
+    request: &TransactionEventRequest,
+    total_cost: Decimal,
+) -> Option<f64> {
+    let transaction_id = &request.transaction_info.transaction_id;
+    let total_cost = match request.event_type {
+        TransactionEventEnumType::Ended => {
+            updater.end(transaction_id);
+            total_cost
+        }
+        _ => {
+            updater
+                .update(transaction_id, total_cost, request.timestamp)?
+                .total_cost
+        }
+    };
+    total_cost.to_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::{
+        components::{cost_updates::CostUpdateConfig, tariff::MeterReading},
+        v2_1::{
+            datatypes::{TariffEnergyPriceType, TariffEnergyType, TaxRateType, TransactionType},
+            enumerations::TriggerReasonEnumType,
+        },
+    };
+
+    #[test]
+    fn running_cost_follows_the_tariff() {
+        let start = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let tariff = TariffType::new("T1".to_string(), "EUR".to_string()).with_energy(
+            TariffEnergyType::new(vec![TariffEnergyPriceType::new(dec!(0.50))])
+                .with_tax_rates(vec![TaxRateType::new(dec!(20), "VAT".to_string())]),
+        );
+        let mut session = TariffSession::new(start);
+        session.add_reading(MeterReading::new(start).with_energy(dec!(0)));
+        session.add_reading(MeterReading::new(start + Duration::hours(1)).with_energy(dec!(10000)));
+
+        let mut updater = CostUpdater::new(CostUpdateConfig::new().with_threshold(dec!(1.5)));
+        let mut request = TransactionEventRequest::new(
+            TransactionEventEnumType::Updated,
+            vec![],
+            start + Duration::minutes(10),
+            TriggerReasonEnumType::MeterValuePeriodic,
+            1,
+            TransactionType::new("tx".to_string()),
+        );
+
+        // 10/6 kWh at 0.60 including tax
+        let cost = running_cost(&tariff, &session, request.timestamp);
+        assert_eq!(cost.round_dp(2), dec!(1.00));
+        let total_cost = transaction_event_total_cost(&mut updater, &request, cost);
+        assert_eq!(total_cost, Some(1.0));
+
+        request.timestamp += Duration::minutes(10);
+        let cost = running_cost(&tariff, &session, request.timestamp);
+        assert_eq!(
+            transaction_event_total_cost(&mut updater, &request, cost),
+            None
+        );
+
+        request.timestamp += Duration::minutes(10);
+        let cost = running_cost(&tariff, &session, request.timestamp);
+        let update = updater.update("tx", cost, request.timestamp).unwrap();
+        let cost_updated = CostUpdatedRequest::from(update);
+        assert_eq!(cost_updated.total_cost, 3.0);
+
+        request.event_type = TransactionEventEnumType::Ended;
+        let total_cost = transaction_event_total_cost(&mut updater, &request, dec!(6));
+        assert_eq!(total_cost, Some(6.0));
+    }
+}
@@ -0,0 +1,360 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::{
+    prelude::{FromPrimitive, ToPrimitive},
+    Decimal,
+};
+
+use crate::v2_1::{
+    datatypes::{
+        DERCurvePointsType, DERCurveType, FixedPFType, FixedVarType, FreqDroopType, HysteresisType,
+        LimitMaxDischargeType,
+    },
+    enumerations::DERUnitEnumType,
+};
+
+use super::{seconds, DerRating};
+
+/// Value of the curve through `points` at `x`, interpolated linearly between the points
+/// and constant before the first and after the last point. `None` without points.
+pub fn interpolate(points: &[DERCurvePointsType], x: Decimal) -> Option<Decimal> {
+    let mut points: Vec<_> = points.iter().map(|point| (point.x, point.y)).collect();
+    points.sort_by_key(|(x, _)| *x);
+    let (first, last) = (*points.first()?, *points.last()?);
+    if x <= first.0 {
+        return Some(first.1);
+    }
+    if x >= last.0 {
+        return Some(last.1);
+    }
+    let index = points.partition_point(|(point_x, _)| *point_x <= x);
+    let ((x0, y0), (x1, y1)) = (points[index - 1], points[index]);
+    Some(y0 + (y1 - y0) * (x - x0) / (x1 - x0))
+}
+
+/// Value of `curve` at `x` in W, var or V according to its `y_unit`, or as sent when the
+/// unit is not applicable. The available active and reactive power are taken to be the
+/// maximum of `rating`.
+pub fn curve_value(curve: &DERCurveType, x: Decimal, rating: &DerRating) -> Option<Decimal> {
+    let y = interpolate(&curve.curve_data, x)?;
+    Some(match curve.y_unit {
+        DERUnitEnumType::PctMaxW | DERUnitEnumType::PctWAvail => {
+            percent(y, rating.max_active_power)
+        }
+        DERUnitEnumType::PctMaxVar | DERUnitEnumType::PctVarAvail => {
+            percent(y, rating.max_reactive_power)
+        }
+        DERUnitEnumType::PctEffectiveV => percent(y, rating.nominal_voltage),
+        DERUnitEnumType::NotApplicable => y,
+    })
+}
+
+/// Active power in W at `frequency`, given the active power `power` before the frequency
+/// left the dead band between `under_freq` and `over_freq` of `freq_droop`.
+///
+/// Beyond the dead band the power changes by the maximum active power for every droop
+/// (per unit) of frequency deviation relative to the nominal frequency, within the maximum
+/// active power.
+pub fn freq_droop_power(
+    freq_droop: &FreqDroopType,
+    frequency: Decimal,
+    power: Decimal,
+    rating: &DerRating,
+) -> Decimal {
+    let (deviation, droop) = if frequency > freq_droop.over_freq {
+        (freq_droop.over_freq - frequency, freq_droop.over_droop)
+    } else if frequency < freq_droop.under_freq {
+        (freq_droop.under_freq - frequency, freq_droop.under_droop)
+    } else {
+        return power;
+    };
+    let change = (deviation * rating.max_active_power)
+        .checked_div(rating.nominal_frequency * droop)
+        .unwrap_or_default();
+    (power + change).clamp(-rating.max_active_power, rating.max_active_power)
+}
+
+/// Reactive power in var that gives the power factor of `fixed_pf` at `active_power`,
+/// negative when absorbing.
+pub fn fixed_pf_reactive_power(
+    fixed_pf: &FixedPFType,
+    active_power: Decimal,
+    rating: &DerRating,
+) -> Decimal {
+    power_factor_reactive_power(
+        fixed_pf.displacement,
+        fixed_pf.excitation,
+        active_power,
+        rating,
+    )
+}
+
+/// Reactive power in var of `fixed_var`, a percentage of the maximum reactive power, or of
+/// the maximum active power when given in `PctMaxW`.
+pub fn fixed_var_reactive_power(fixed_var: &FixedVarType, rating: &DerRating) -> Decimal {
+    match fixed_var.unit {
+        DERUnitEnumType::PctMaxVar | DERUnitEnumType::PctVarAvail => {
+            percent(fixed_var.setpoint, rating.max_reactive_power)
+        }
+        DERUnitEnumType::PctMaxW | DERUnitEnumType::PctWAvail => {
+            percent(fixed_var.setpoint, rating.max_active_power)
+        }
+        DERUnitEnumType::PctEffectiveV | DERUnitEnumType::NotApplicable => fixed_var.setpoint,
+    }
+}
+
+/// Discharge power in W allowed by `limit`.
+pub fn max_discharge_power(limit: &LimitMaxDischargeType, rating: &DerRating) -> Decimal {
+    percent(limit.pct_max_discharge_power, rating.max_active_power)
+}
+
+/// Value that moves from `current` towards `target` in `elapsed` seconds, such that 90%
+/// of a change is reached after `response_time` seconds. A response time of zero reaches
+/// the target at once.
+pub fn respond(
+    current: Decimal,
+    target: Decimal,
+    response_time: Decimal,
+    elapsed: Decimal,
+) -> Decimal {
+    if response_time <= Decimal::ZERO {
+        return target;
+    }
+    let remaining = (elapsed / response_time)
+        .to_f64()
+        .map(|periods| 0.1_f64.powf(periods))
+        .and_then(Decimal::from_f64)
+        .unwrap_or_default();
+    target - (target - current) * remaining
+}
+
+/// Value that moves from `current` towards `target` in `elapsed` seconds by at most `rate`
+/// per second. A rate of zero reaches the target at once.
+pub fn ramp(current: Decimal, target: Decimal, rate: Decimal, elapsed: Decimal) -> Decimal {
+    if rate <= Decimal::ZERO {
+        return target;
+    }
+    let step = rate * elapsed;
+    target.clamp(current - step, current + step)
+}
+
+/// `percentage` of `value`.
+pub(super) fn percent(percentage: Decimal, value: Decimal) -> Decimal {
+    percentage * value / Decimal::ONE_HUNDRED
+}
+
+/// Reactive power in var at `active_power` for a power factor, negative when absorbing,
+/// within the maximum reactive power.
+pub(super) fn power_factor_reactive_power(
+    power_factor: Decimal,
+    absorbing: bool,
+    active_power: Decimal,
+    rating: &DerRating,
+) -> Decimal {
+    let power_factor = power_factor.abs().min(Decimal::ONE);
+    let ratio = power_factor
+        .to_f64()
+        .map(|power_factor| (1.0 - power_factor * power_factor).sqrt() / power_factor)
+        .and_then(Decimal::from_f64);
+    let reactive_power = ratio
+        .and_then(|ratio| ratio.checked_mul(active_power.abs()))
+        .unwrap_or(rating.max_reactive_power)
+        .min(rating.max_reactive_power);
+    match absorbing {
+        true => -reactive_power,
+        false => reactive_power,
+    }
+}
+
+/// Whether a curve with hysteresis applies, following the grid parameter on its x-axis.
+///
+/// A curve without hysteresis bounds always applies. Otherwise it starts to apply when the
+/// parameter leaves the bounds and stops once the parameter is back within the bounds
+/// for `hysteresis_delay` seconds.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(super) struct Hysteresis {
+    engaged: bool,
+    normal_since: Option<DateTime<Utc>>,
+}
+
+impl Hysteresis {
+    pub(super) fn update(
+        &mut self,
+        hysteresis: Option<&HysteresisType>,
+        x: Decimal,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let Some(hysteresis) = hysteresis.filter(|hysteresis| {
+            hysteresis
+                .hysteresis_low
+                .or(hysteresis.hysteresis_high)
+                .is_some()
+        }) else {
+            return true;
+        };
+        let normal = hysteresis.hysteresis_low.is_none_or(|low| x >= low)
+            && hysteresis.hysteresis_high.is_none_or(|high| x <= high);
+        if !normal {
+            self.engaged = true;
+            self.normal_since = None;
+        } else if self.engaged {
+            let since = *self.normal_since.get_or_insert(now);
+            let delay = seconds(hysteresis.hysteresis_delay.unwrap_or_default());
+            if now - since >= delay {
+                self.engaged = false;
+                self.normal_since = None;
+            }
+        }
+        self.engaged
+    }
+}
+
+/// Excursion of a grid parameter beyond a trip or cessation curve, whose points give how
+/// many seconds (x) the parameter may stay beyond a limit (y).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(super) struct Excursion {
+    beyond_since: Vec<Option<DateTime<Utc>>>,
+}
+
+impl Excursion {
+    /// Whether `value` has been beyond the limit of a point of `points` for at least the
+    /// time of that point, above the limit when `over` and below it otherwise.
+    pub(super) fn update(
+        &mut self,
+        points: &[DERCurvePointsType],
+        value: Decimal,
+        over: bool,
+        now: DateTime<Utc>,
+    ) -> bool {
+        self.beyond_since.resize(points.len(), None);
+        let mut exceeded = false;
+        for (point, beyond_since) in points.iter().zip(&mut self.beyond_since) {
+            let beyond = match over {
+                true => value > point.y,
+                false => value < point.y,
+            };
+            if !beyond {
+                *beyond_since = None;
+                continue;
+            }
+            let since = *beyond_since.get_or_insert(now);
+            exceeded |= now - since >= seconds(point.x);
+        }
+        exceeded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn rating() -> DerRating {
+        DerRating::new(dec!(11000), dec!(5000), dec!(230), dec!(50))
+    }
+
+    fn points(points: &[(Decimal, Decimal)]) -> Vec<DERCurvePointsType> {
+        points
+            .iter()
+            .map(|(x, y)| DERCurvePointsType::new(*x, *y))
+            .collect()
+    }
+
+    #[test]
+    fn interpolates_curves() {
+        // volt-var: inject below 95%, absorb above 105% of nominal voltage
+        let volt_var = DERCurveType::new(
+            points(&[
+                (dec!(108), dec!(-44)),
+                (dec!(92), dec!(44)),
+                (dec!(98), dec!(0)),
+                (dec!(102), dec!(0)),
+            ]),
+            1,
+            DERUnitEnumType::PctMaxVar,
+        );
+        let value = |x| curve_value(&volt_var, x, &rating()).unwrap();
+        assert_eq!(value(dec!(90)), dec!(2200));
+        assert_eq!(value(dec!(95)), dec!(1100));
+        assert_eq!(value(dec!(100)), dec!(0));
+        assert_eq!(value(dec!(105)), dec!(-1100));
+        assert_eq!(value(dec!(120)), dec!(-2200));
+        assert_eq!(interpolate(&[], dec!(1)), None);
+    }
+
+    #[test]
+    fn droops_beyond_dead_band() {
+        let droop = FreqDroopType::new(1, dec!(50.2), dec!(49.8), dec!(0.05), dec!(0.05), dec!(1));
+        assert_eq!(
+            freq_droop_power(&droop, dec!(50.1), dec!(5000), &rating()),
+            dec!(5000)
+        );
+        // 0.5 Hz above the dead band is 1% of 50 Hz, a fifth of the 5% droop
+        assert_eq!(
+            freq_droop_power(&droop, dec!(50.7), dec!(5000), &rating()),
+            dec!(2800)
+        );
+        assert_eq!(
+            freq_droop_power(&droop, dec!(47.8), dec!(5000), &rating()),
+            dec!(11000)
+        );
+    }
+
+    #[test]
+    fn reactive_power_of_fixed_settings() {
+        let absorb = FixedPFType::new(1, 0.8, true);
+        assert_eq!(
+            fixed_pf_reactive_power(&absorb, dec!(4000), &rating()).round_dp(6),
+            dec!(-3000)
+        );
+        let inject = FixedPFType::new(1, 0.8, false);
+        assert_eq!(
+            fixed_pf_reactive_power(&inject, dec!(8000), &rating()).round_dp(6),
+            dec!(5000)
+        );
+        let fixed_var = FixedVarType::new(1, -20.0);
+        assert_eq!(fixed_var_reactive_power(&fixed_var, &rating()), dec!(-1000));
+        let limit = LimitMaxDischargeType::new(1, dec!(50));
+        assert_eq!(max_discharge_power(&limit, &rating()), dec!(5500));
+    }
+
+    #[test]
+    fn responds_and_ramps() {
+        assert_eq!(
+            respond(dec!(0), dec!(1000), dec!(10), dec!(10)).round_dp(6),
+            dec!(900)
+        );
+        assert_eq!(
+            respond(dec!(0), dec!(1000), dec!(10), dec!(20)).round_dp(6),
+            dec!(990)
+        );
+        assert_eq!(respond(dec!(0), dec!(1000), dec!(0), dec!(1)), dec!(1000));
+        assert_eq!(ramp(dec!(0), dec!(1000), dec!(110), dec!(2)), dec!(220));
+        assert_eq!(ramp(dec!(1000), dec!(0), dec!(110), dec!(20)), dec!(0));
+    }
+
+    #[test]
+    fn hysteresis_and_excursions() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let hysteresis = HysteresisType::new()
+            .with_hysteresis_low(dec!(49.9))
+            .with_hysteresis_high(dec!(50.1))
+            .with_hysteresis_delay(dec!(30));
+        let mut state = Hysteresis::default();
+        assert!(!state.update(Some(&hysteresis), dec!(50), now));
+        assert!(state.update(Some(&hysteresis), dec!(50.4), now));
+        assert!(state.update(Some(&hysteresis), dec!(50), now + Duration::seconds(1)));
+        assert!(!state.update(Some(&hysteresis), dec!(50), now + Duration::seconds(31)));
+        assert!(state.update(None, dec!(50), now));
+
+        // trip after 2 s above 110% or at once above 120%
+        let trip = points(&[(dec!(2), dec!(110)), (dec!(0), dec!(120))]);
+        let mut excursion = Excursion::default();
+        assert!(!excursion.update(&trip, dec!(112), true, now));
+        assert!(!excursion.update(&trip, dec!(112), true, now + Duration::seconds(1)));
+        assert!(excursion.update(&trip, dec!(112), true, now + Duration::seconds(2)));
+        assert!(!excursion.update(&trip, dec!(105), true, now + Duration::seconds(3)));
+        assert!(excursion.update(&trip, dec!(125), true, now + Duration::seconds(4)));
+    }
+}
@@ -0,0 +1,1034 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use validator::Validate;
+
+use crate::v2_1::{
+    datatypes::{
+        DERCurveGetType, DERCurveType, EnterServiceGetType, FixedPFGetType, FixedVarGetType,
+        FreqDroopGetType, GradientGetType, LimitMaxDischargeGetType, StatusInfoType,
+    },
+    enumerations::{DERControlEnumType, DERControlStatusEnumType, GridEventFaultEnumType},
+    messages::{
+        clear_der_control::{ClearDERControlRequest, ClearDERControlResponse},
+        get_der_control::{GetDERControlRequest, GetDERControlResponse},
+        notify_der_alarm::NotifyDERAlarmRequest,
+        report_der_control::ReportDERControlRequest,
+        set_der_control::{SetDERControlRequest, SetDERControlResponse},
+    },
+};
+
+use super::{
+    curve::{percent, power_factor_reactive_power, Excursion, Hysteresis},
+    curve_value, fixed_pf_reactive_power, fixed_var_reactive_power, freq_droop_power, interpolate,
+    max_discharge_power, ramp, respond, seconds, to_seconds, DerControl, DerControlError,
+    DerRating, DerSetpoint, DerSetting, GridMeasurement,
+};
+
+/// Maximum number of settings in one `ReportDERControlRequest`.
+const REPORT_SIZE: usize = 24;
+
+/// Curves that make the EV trip or cease, with the grid event they report.
+const PROTECTIONS: [(DERControlEnumType, Option<GridEventFaultEnumType>); 7] = [
+    (
+        DERControlEnumType::HVMustTrip,
+        Some(GridEventFaultEnumType::OverVoltage),
+    ),
+    (
+        DERControlEnumType::LVMustTrip,
+        Some(GridEventFaultEnumType::UnderVoltage),
+    ),
+    (
+        DERControlEnumType::HFMustTrip,
+        Some(GridEventFaultEnumType::OverFrequency),
+    ),
+    (
+        DERControlEnumType::LFMustTrip,
+        Some(GridEventFaultEnumType::UnderFrequency),
+    ),
+    (
+        DERControlEnumType::HVMomCess,
+        Some(GridEventFaultEnumType::OverVoltage),
+    ),
+    (
+        DERControlEnumType::LVMomCess,
+        Some(GridEventFaultEnumType::UnderVoltage),
+    ),
+    (DERControlEnumType::PowerMonitoringMustTrip, None),
+];
+
+/// Control types that set the reactive power, of which the one with the highest priority
+/// applies.
+const REACTIVE_POWER_CONTROLS: [DERControlEnumType; 6] = [
+    DERControlEnumType::FixedPFAbsorb,
+    DERControlEnumType::FixedPFInject,
+    DERControlEnumType::FixedVar,
+    DERControlEnumType::VoltVar,
+    DERControlEnumType::WattVar,
+    DERControlEnumType::WattPF,
+];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Dynamics {
+    updated: Option<DateTime<Utc>>,
+    active_power: Option<Decimal>,
+    reactive_power: Option<Decimal>,
+    pre_disturbance_power: Option<Decimal>,
+    hysteresis: HashMap<String, Hysteresis>,
+    excursions: HashMap<String, Excursion>,
+    alarms: HashMap<String, (DERControlEnumType, Option<GridEventFaultEnumType>)>,
+    tripped: bool,
+    enter_service_since: Option<DateTime<Utc>>,
+    soft_start: bool,
+}
+
+/// DER controls of a charging station and the setpoint of its EV.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerControlManager {
+    rating: DerRating,
+    controls: Vec<DerControl>,
+    dynamics: Dynamics,
+}
+
+impl DerControlManager {
+    /// Creates a manager without controls for an EV with `rating`.
+    pub fn new(rating: DerRating) -> Self {
+        Self {
+            rating,
+            controls: Vec::new(),
+            dynamics: Dynamics::default(),
+        }
+    }
+
+    /// The ratings of the EV.
+    pub fn rating(&self) -> &DerRating {
+        &self.rating
+    }
+
+    /// All controls, in the order they were set.
+    pub fn controls(&self) -> &[DerControl] {
+        &self.controls
+    }
+
+    /// Control of `control_type` that applies at `now`: the active scheduled control with
+    /// the highest priority, the latest of equal priorities, or else the default control.
+    pub fn active(
+        &self,
+        control_type: &DERControlEnumType,
+        now: DateTime<Utc>,
+    ) -> Option<&DerControl> {
+        active(&self.controls, control_type, now)
+    }
+
+    /// Handles a `SetDERControlRequest`, listing the scheduled controls the new control
+    /// supersedes.
+    pub fn set(&mut self, request: &SetDERControlRequest) -> SetDERControlResponse {
+        let result = DerControl::try_from(request).and_then(|control| self.insert(control));
+        SetDERControlResponse {
+            status: match &result {
+                Ok(_) => DERControlStatusEnumType::Accepted,
+                Err(error) => error.into(),
+            },
+            status_info: result.as_ref().err().map(StatusInfoType::from),
+            superseded_ids: result
+                .ok()
+                .filter(|superseded| !superseded.is_empty())
+                .map(|superseded| superseded.into_iter().take(REPORT_SIZE).collect()),
+            custom_data: None,
+        }
+    }
+
+    /// Inserts a control and returns the ids of the scheduled controls it supersedes.
+    ///
+    /// A control replaces the control with the same id, and a default control replaces
+    /// the default control of the same type.
+    pub fn insert(&mut self, control: DerControl) -> Result<Vec<String>, DerControlError> {
+        control.check()?;
+        let valid = match &control.setting {
+            DerSetting::Curve(curve) => curve.validate(),
+            DerSetting::EnterService(enter_service) => enter_service.validate(),
+            DerSetting::FixedPF(fixed_pf) => fixed_pf.validate(),
+            DerSetting::FixedVar(fixed_var) => fixed_var.validate(),
+            DerSetting::FreqDroop(freq_droop) => freq_droop.validate(),
+            DerSetting::Gradient(gradient) => gradient.validate(),
+            DerSetting::LimitMaxDischarge(limit) => limit.validate(),
+        };
+        valid.map_err(|_| DerControlError::InvalidSetting(control.control_type.clone()))?;
+
+        self.controls.retain(|other| {
+            other.id != control.id
+                && !(control.is_default
+                    && other.is_default
+                    && other.control_type == control.control_type)
+        });
+        let superseded = match control.is_default {
+            true => Vec::new(),
+            false => self
+                .controls
+                .iter()
+                .filter(|other| {
+                    !other.is_default
+                        && other.control_type == control.control_type
+                        && other.priority() >= control.priority()
+                        && other.overlaps(&control)
+                })
+                .map(|other| other.id.clone())
+                .collect(),
+        };
+        self.controls.push(control);
+        Ok(superseded)
+    }
+
+    /// Handles a `ClearDERControlRequest`: the control with `control_id`, or all default or
+    /// scheduled controls of `control_type`, or of any type when absent.
+    pub fn clear(&mut self, request: &ClearDERControlRequest) -> ClearDERControlResponse {
+        let count = self.controls.len();
+        self.controls.retain(|control| {
+            !matches(
+                control,
+                Some(request.is_default),
+                request.control_type.as_ref(),
+                request.control_id.as_deref(),
+            )
+        });
+        let result = match self.controls.len() < count {
+            true => Ok(()),
+            false => Err(DerControlError::NotFound),
+        };
+        ClearDERControlResponse {
+            status: match result {
+                Ok(()) => DERControlStatusEnumType::Accepted,
+                Err(_) => DERControlStatusEnumType::NotFound,
+            },
+            status_info: result.err().as_ref().map(StatusInfoType::from),
+            custom_data: None,
+        }
+    }
+
+    /// Handles a `GetDERControlRequest`, returning the response and, when accepted, the
+    /// `ReportDERControlRequest`s to send after it.
+    pub fn get(
+        &self,
+        request: &GetDERControlRequest,
+        now: DateTime<Utc>,
+    ) -> (GetDERControlResponse, Vec<ReportDERControlRequest>) {
+        let result = self.report(
+            request.request_id,
+            request.is_default,
+            request.control_type.as_ref(),
+            request.control_id.as_deref(),
+            now,
+        );
+        let response = GetDERControlResponse {
+            status: match &result {
+                Ok(_) => DERControlStatusEnumType::Accepted,
+                Err(error) => error.into(),
+            },
+            status_info: result.as_ref().err().map(StatusInfoType::from),
+            custom_data: None,
+        };
+        (response, result.unwrap_or_default())
+    }
+
+    /// Reports of the controls requested by `GetDERControl` with `request_id`: the control
+    /// with `control_id`, or the default or scheduled controls, or both when `is_default`
+    /// is absent, of `control_type`, or of any type when absent. A report carries at most
+    /// 24 settings, all but the last have `tbc` set.
+    pub fn report(
+        &self,
+        request_id: i32,
+        is_default: Option<bool>,
+        control_type: Option<&DERControlEnumType>,
+        control_id: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<ReportDERControlRequest>, DerControlError> {
+        let controls: Vec<_> = self
+            .controls
+            .iter()
+            .filter(|control| matches(control, is_default, control_type, control_id))
+            .collect();
+        if controls.is_empty() {
+            return Err(DerControlError::NotFound);
+        }
+        let chunks = controls.chunks(REPORT_SIZE);
+        let last = chunks.len() - 1;
+        Ok(chunks
+            .enumerate()
+            .map(|(index, controls)| {
+                let mut report = ReportDERControlRequest {
+                    custom_data: None,
+                    request_id,
+                    tbc: (index < last).then_some(true),
+                    curve: None,
+                    enter_service: None,
+                    fixed_pf_absorb: None,
+                    fixed_pf_inject: None,
+                    fixed_var: None,
+                    freq_droop: None,
+                    gradient: None,
+                    limit_max_discharge: None,
+                };
+                for control in controls {
+                    self.add_to_report(&mut report, control, now);
+                }
+                report
+            })
+            .collect())
+    }
+
+    /// Whether a control is superseded at `now`: a scheduled control by an overlapping
+    /// scheduled control of the same type with higher priority, a default control by an
+    /// active scheduled control of the same type.
+    pub fn is_superseded(&self, control: &DerControl, now: DateTime<Utc>) -> bool {
+        let position = self
+            .controls
+            .iter()
+            .position(|other| other.id == control.id);
+        self.controls
+            .iter()
+            .enumerate()
+            .filter(|(_, other)| {
+                !other.is_default
+                    && other.id != control.id
+                    && other.control_type == control.control_type
+            })
+            .any(|(index, other)| match control.is_default {
+                true => other.is_active(now),
+                false => {
+                    other.overlaps(control)
+                        && (other.priority() < control.priority()
+                            || (other.priority() == control.priority()
+                                && position.is_some_and(|position| index > position)))
+                }
+            })
+    }
+
+    fn add_to_report(
+        &self,
+        report: &mut ReportDERControlRequest,
+        control: &DerControl,
+        now: DateTime<Utc>,
+    ) {
+        let id = control.id.clone();
+        let (is_default, is_superseded) = (control.is_default, self.is_superseded(control, now));
+        match &control.setting {
+            DerSetting::Curve(curve) => {
+                report
+                    .curve
+                    .get_or_insert_with(Vec::new)
+                    .push(DERCurveGetType::new(
+                        curve.clone(),
+                        id,
+                        control.control_type.clone(),
+                        is_default,
+                        is_superseded,
+                    ))
+            }
+            DerSetting::EnterService(enter_service) => report
+                .enter_service
+                .get_or_insert_with(Vec::new)
+                .push(EnterServiceGetType::new(enter_service.clone(), id)),
+            DerSetting::FixedPF(fixed_pf) => {
+                let settings = match control.control_type {
+                    DERControlEnumType::FixedPFAbsorb => &mut report.fixed_pf_absorb,
+                    _ => &mut report.fixed_pf_inject,
+                };
+                settings
+                    .get_or_insert_with(Vec::new)
+                    .push(FixedPFGetType::new(
+                        fixed_pf.clone(),
+                        id,
+                        is_superseded,
+                        is_default,
+                    ))
+            }
+            DerSetting::FixedVar(fixed_var) => {
+                report
+                    .fixed_var
+                    .get_or_insert_with(Vec::new)
+                    .push(FixedVarGetType::new(
+                        fixed_var.clone(),
+                        id,
+                        is_superseded,
+                        is_default,
+                    ))
+            }
+            DerSetting::FreqDroop(freq_droop) => report
+                .freq_droop
+                .get_or_insert_with(Vec::new)
+                .push(FreqDroopGetType::new(
+                    freq_droop.clone(),
+                    id,
+                    is_superseded,
+                    is_default,
+                )),
+            DerSetting::Gradient(gradient) => report
+                .gradient
+                .get_or_insert_with(Vec::new)
+                .push(GradientGetType::new(gradient.clone(), id)),
+            DerSetting::LimitMaxDischarge(limit) => report
+                .limit_max_discharge
+                .get_or_insert_with(Vec::new)
+                .push(LimitMaxDischargeGetType::new(
+                    limit.clone(),
+                    id,
+                    is_superseded,
+                    is_default,
+                )),
+        }
+    }
+
+    /// Evaluates the active controls for `measurement` at `now`, returning the setpoint of
+    /// the EV and the `NotifyDERAlarmRequest`s of trips and momentary cessations that
+    /// started or ended.
+    ///
+    /// Once tripped, the EV stays out of service until the voltage and frequency are
+    /// within the bounds of the `EnterService` control for its `delay`, or until the trip
+    /// ends without such control. It then ramps up at the `ramp_rate` of `EnterService` or
+    /// the `soft_gradient` of `Gradients`. Ramp rates are percentages of the maximum active
+    /// power per second. The randomized delay of `EnterService` is left to the caller.
+    pub fn update(
+        &mut self,
+        measurement: &GridMeasurement,
+        now: DateTime<Utc>,
+    ) -> (DerSetpoint, Vec<NotifyDERAlarmRequest>) {
+        let Self {
+            rating,
+            controls,
+            dynamics,
+        } = self;
+        let elapsed = dynamics
+            .updated
+            .map_or(Decimal::ZERO, |updated| to_seconds(now - updated))
+            .max(Decimal::ZERO);
+        dynamics.updated = Some(now);
+        let voltage = (measurement.voltage * Decimal::ONE_HUNDRED)
+            .checked_div(rating.nominal_voltage)
+            .unwrap_or_default();
+        let frequency = measurement.frequency;
+        let power = (measurement.active_power * Decimal::ONE_HUNDRED)
+            .checked_div(rating.max_active_power)
+            .unwrap_or_default();
+        let curve = |control_type: DERControlEnumType| {
+            active(controls, &control_type, now).and_then(|control| match &control.setting {
+                DerSetting::Curve(curve) => Some((control.id.as_str(), curve)),
+                DerSetting::LimitMaxDischarge(limit) => limit
+                    .power_monitoring_must_trip
+                    .as_ref()
+                    .map(|curve| (control.id.as_str(), curve)),
+                _ => None,
+            })
+        };
+
+        let mut setpoint = DerSetpoint::default();
+        let mut alarms = Vec::new();
+        let mut alarmed = Vec::new();
+        for (control_type, grid_event_fault) in PROTECTIONS {
+            let (value, over) = match control_type {
+                DERControlEnumType::HVMustTrip | DERControlEnumType::HVMomCess => (voltage, true),
+                DERControlEnumType::LVMustTrip | DERControlEnumType::LVMomCess => (voltage, false),
+                DERControlEnumType::HFMustTrip => (frequency, true),
+                DERControlEnumType::LFMustTrip => (frequency, false),
+                _ => (power, true),
+            };
+            let curve = match control_type {
+                DERControlEnumType::PowerMonitoringMustTrip => curve(control_type.clone())
+                    .or_else(|| curve(DERControlEnumType::LimitMaxDischarge)),
+                _ => curve(control_type.clone()),
+            };
+            let Some((id, curve)) = curve else {
+                continue;
+            };
+            let excursion = dynamics.excursions.entry(id.to_string()).or_default();
+            if !excursion.update(&curve.curve_data, value, over, now) {
+                continue;
+            }
+            match control_type {
+                DERControlEnumType::HVMomCess | DERControlEnumType::LVMomCess => {
+                    setpoint.cease = true
+                }
+                _ => setpoint.trip = true,
+            }
+            alarmed.push(id.to_string());
+            if !dynamics.alarms.contains_key(id) {
+                dynamics.alarms.insert(
+                    id.to_string(),
+                    (control_type.clone(), grid_event_fault.clone()),
+                );
+                alarms.push(alarm(control_type, grid_event_fault, None, now));
+            }
+        }
+        dynamics
+            .alarms
+            .retain(|id, (control_type, grid_event_fault)| {
+                let ongoing = alarmed.contains(id);
+                if !ongoing {
+                    alarms.push(alarm(
+                        control_type.clone(),
+                        grid_event_fault.clone(),
+                        Some(true),
+                        now,
+                    ));
+                }
+                ongoing
+            });
+        dynamics
+            .excursions
+            .retain(|id, _| controls.iter().any(|control| control.id == *id));
+
+        let enter_service =
+            active(controls, &DERControlEnumType::EnterService, now).and_then(|control| {
+                match &control.setting {
+                    DerSetting::EnterService(enter_service) => Some(enter_service),
+                    _ => None,
+                }
+            });
+        let gradient =
+            active(controls, &DERControlEnumType::Gradients, now).and_then(
+                |control| match &control.setting {
+                    DerSetting::Gradient(gradient) => Some(gradient),
+                    _ => None,
+                },
+            );
+        if setpoint.trip {
+            dynamics.tripped = true;
+            dynamics.enter_service_since = None;
+        } else if dynamics.tripped {
+            let may_enter = enter_service.is_none_or(|enter_service| {
+                (enter_service.low_voltage..=enter_service.high_voltage).contains(&voltage)
+                    && (enter_service.low_freq..=enter_service.high_freq).contains(&frequency)
+            });
+            match may_enter {
+                true => {
+                    let since = *dynamics.enter_service_since.get_or_insert(now);
+                    let delay = enter_service.and_then(|enter_service| enter_service.delay);
+                    if now - since >= seconds(delay.unwrap_or_default()) {
+                        dynamics.tripped = false;
+                        dynamics.enter_service_since = None;
+                        dynamics.soft_start = true;
+                        dynamics.active_power = Some(Decimal::ZERO);
+                    }
+                }
+                false => dynamics.enter_service_since = None,
+            }
+        }
+        setpoint.trip = dynamics.tripped;
+        if setpoint.trip || setpoint.cease {
+            setpoint.active_power = Some(Decimal::ZERO);
+            setpoint.reactive_power = Some(Decimal::ZERO);
+            dynamics.active_power = Some(Decimal::ZERO);
+            dynamics.reactive_power = Some(Decimal::ZERO);
+            return (setpoint, alarms);
+        }
+
+        // active power
+        let mut hysteresis = |id: &str, curve: &DERCurveType, x: Decimal| {
+            dynamics
+                .hysteresis
+                .entry(id.to_string())
+                .or_default()
+                .update(curve.hysteresis.as_ref(), x, now)
+        };
+        let mut target = None;
+        let freq_droop = active(controls, &DERControlEnumType::FreqDroop, now).and_then(
+            |control| match &control.setting {
+                DerSetting::FreqDroop(freq_droop) => Some(freq_droop),
+                _ => None,
+            },
+        );
+        if let Some(freq_droop) = freq_droop {
+            if frequency > freq_droop.over_freq || frequency < freq_droop.under_freq {
+                let power = *dynamics
+                    .pre_disturbance_power
+                    .get_or_insert(measurement.active_power);
+                target = Some((
+                    freq_droop_power(freq_droop, frequency, power, rating),
+                    freq_droop.response_time,
+                ));
+            } else {
+                dynamics.pre_disturbance_power = None;
+            }
+        } else if let Some((id, curve)) = curve(DERControlEnumType::FreqWatt) {
+            if hysteresis(id, curve, frequency) {
+                target = curve_value(curve, frequency, rating)
+                    .map(|value| (value, curve.response_time.unwrap_or_default()));
+            }
+        }
+        let volt_watt = curve(DERControlEnumType::VoltWatt)
+            .filter(|(id, curve)| hysteresis(id, curve, voltage))
+            .and_then(|(_, curve)| curve_value(curve, voltage, rating));
+        let limit =
+            active(controls, &DERControlEnumType::LimitMaxDischarge, now).and_then(|control| {
+                match &control.setting {
+                    DerSetting::LimitMaxDischarge(limit) => {
+                        Some(max_discharge_power(limit, rating))
+                    }
+                    _ => None,
+                }
+            });
+        setpoint.max_discharge_power = match (volt_watt, limit) {
+            (Some(volt_watt), Some(limit)) => Some(volt_watt.min(limit)),
+            (volt_watt, limit) => volt_watt.or(limit),
+        };
+        match target {
+            Some((target, response_time)) => {
+                let target = match setpoint.max_discharge_power {
+                    Some(max) => target.min(max),
+                    None => target,
+                };
+                let current = dynamics.active_power.unwrap_or(measurement.active_power);
+                let next = respond(current, target, response_time, elapsed);
+                let rate = match dynamics.soft_start {
+                    true => enter_service
+                        .and_then(|enter_service| enter_service.ramp_rate)
+                        .or(gradient.map(|gradient| gradient.soft_gradient)),
+                    false => gradient.map(|gradient| gradient.gradient),
+                };
+                let next = match rate {
+                    Some(rate) => ramp(
+                        current,
+                        next,
+                        percent(rate, rating.max_active_power),
+                        elapsed,
+                    ),
+                    None => next,
+                };
+                dynamics.soft_start &= next != target;
+                dynamics.active_power = Some(next);
+                setpoint.active_power = Some(next);
+            }
+            None => dynamics.active_power = None,
+        }
+
+        // reactive power
+        let active_power = setpoint.active_power.unwrap_or(measurement.active_power);
+        let reactive = REACTIVE_POWER_CONTROLS
+            .iter()
+            .filter_map(|control_type| active(controls, control_type, now))
+            .min_by_key(|control| control.priority());
+        let target = reactive.and_then(|control| match (&control.control_type, &control.setting) {
+            (_, DerSetting::FixedPF(fixed_pf)) => Some((
+                fixed_pf_reactive_power(fixed_pf, active_power, rating),
+                Decimal::ZERO,
+            )),
+            (_, DerSetting::FixedVar(fixed_var)) => {
+                Some((fixed_var_reactive_power(fixed_var, rating), Decimal::ZERO))
+            }
+            (DERControlEnumType::VoltVar, DerSetting::Curve(curve)) => {
+                let v_ref = curve
+                    .reactive_power_params
+                    .as_ref()
+                    .and_then(|params| params.v_ref)
+                    .unwrap_or(Decimal::ONE_HUNDRED);
+                let x = voltage - v_ref + Decimal::ONE_HUNDRED;
+                hysteresis(&control.id, curve, x)
+                    .then(|| curve_value(curve, x, rating))
+                    .flatten()
+                    .map(|value| (value, curve.response_time.unwrap_or_default()))
+            }
+            (DERControlEnumType::WattVar, DerSetting::Curve(curve)) => {
+                curve_value(curve, power, rating)
+                    .map(|value| (value, curve.response_time.unwrap_or_default()))
+            }
+            (DERControlEnumType::WattPF, DerSetting::Curve(curve)) => {
+                interpolate(&curve.curve_data, power).map(|power_factor| {
+                    (
+                        power_factor_reactive_power(
+                            power_factor,
+                            power_factor > Decimal::ZERO,
+                            active_power,
+                            rating,
+                        ),
+                        curve.response_time.unwrap_or_default(),
+                    )
+                })
+            }
+            _ => None,
+        });
+        match target {
+            Some((target, response_time)) => {
+                let current = dynamics
+                    .reactive_power
+                    .unwrap_or(measurement.reactive_power);
+                let next = respond(current, target, response_time, elapsed);
+                dynamics.reactive_power = Some(next);
+                setpoint.reactive_power = Some(next);
+            }
+            None => dynamics.reactive_power = None,
+        }
+        (setpoint, alarms)
+    }
+}
+
+fn active<'a>(
+    controls: &'a [DerControl],
+    control_type: &DERControlEnumType,
+    now: DateTime<Utc>,
+) -> Option<&'a DerControl> {
+    let of_type = || {
+        controls
+            .iter()
+            .enumerate()
+            .filter(move |(_, control)| control.control_type == *control_type)
+    };
+    of_type()
+        .filter(|(_, control)| !control.is_default && control.is_active(now))
+        .min_by_key(|(index, control)| (control.priority(), std::cmp::Reverse(*index)))
+        .or_else(|| of_type().find(|(_, control)| control.is_default))
+        .map(|(_, control)| control)
+}
+
+fn matches(
+    control: &DerControl,
+    is_default: Option<bool>,
+    control_type: Option<&DERControlEnumType>,
+    control_id: Option<&str>,
+) -> bool {
+    match control_id {
+        Some(control_id) => control.id == control_id,
+        None => {
+            is_default.is_none_or(|is_default| control.is_default == is_default)
+                && control_type.is_none_or(|control_type| control.control_type == *control_type)
+        }
+    }
+}
+
+fn alarm(
+    control_type: DERControlEnumType,
+    grid_event_fault: Option<GridEventFaultEnumType>,
+    alarm_ended: Option<bool>,
+    timestamp: DateTime<Utc>,
+) -> NotifyDERAlarmRequest {
+    NotifyDERAlarmRequest {
+        custom_data: None,
+        control_type,
+        grid_event_fault,
+        alarm_ended,
+        timestamp,
+        extra_info: None,
+    }
+}
+
+impl From<&DerControlError> for StatusInfoType {
+    fn from(error: &DerControlError) -> Self {
+        StatusInfoType::new(error.reason_code().to_string()).with_additional_info(error.to_string())
+    }
+}
+
+impl From<&DerControlError> for DERControlStatusEnumType {
+    fn from(error: &DerControlError) -> Self {
+        match error {
+            DerControlError::NotSupported(_) => Self::NotSupported,
+            DerControlError::InvalidSetting(_) => Self::Rejected,
+            DerControlError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::v2_1::datatypes::{
+        DERCurvePointsType, EnterServiceType, FixedPFType, FreqDroopType, GradientType,
+        LimitMaxDischargeType,
+    };
+    use crate::v2_1::enumerations::DERUnitEnumType;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn manager() -> DerControlManager {
+        DerControlManager::new(DerRating::new(dec!(10000), dec!(5000), dec!(230), dec!(50)))
+    }
+
+    fn curve(
+        points: &[(Decimal, Decimal)],
+        priority: i32,
+        y_unit: DERUnitEnumType,
+    ) -> DERCurveType {
+        DERCurveType::new(
+            points
+                .iter()
+                .map(|(x, y)| DERCurvePointsType::new(*x, *y))
+                .collect(),
+            priority,
+            y_unit,
+        )
+    }
+
+    fn fixed_pf(id: &str, priority: i32, minutes: i64) -> DerControl {
+        let mut setting = FixedPFType::new(priority, 0.9, true).with_start_time(now());
+        setting.duration = Some(Decimal::from(minutes * 60));
+        DerControl::new(
+            id,
+            false,
+            DERControlEnumType::FixedPFAbsorb,
+            DerSetting::FixedPF(setting),
+        )
+    }
+
+    fn grid(voltage: Decimal, frequency: Decimal) -> GridMeasurement {
+        GridMeasurement::new(voltage, frequency)
+    }
+
+    #[test]
+    fn sets_clears_and_reports_controls() {
+        let mut manager = manager();
+        assert_eq!(manager.insert(fixed_pf("low", 5, 60)), Ok(vec![]));
+        assert_eq!(
+            manager.insert(fixed_pf("high", 1, 30)),
+            Ok(vec!["low".to_string()])
+        );
+        let default = DerControl::new(
+            "default",
+            true,
+            DERControlEnumType::Gradients,
+            DerSetting::Gradient(GradientType::new(0, dec!(10), dec!(1))),
+        );
+        manager.insert(default.clone()).unwrap();
+        manager
+            .insert(DerControl {
+                id: "default2".to_string(),
+                ..default
+            })
+            .unwrap();
+        assert_eq!(manager.controls().len(), 3);
+        assert_eq!(
+            manager.insert(DerControl::new(
+                "target",
+                false,
+                DERControlEnumType::PowerTarget,
+                DerSetting::Gradient(GradientType::new(0, dec!(1), dec!(1))),
+            )),
+            Err(DerControlError::NotSupported(
+                DERControlEnumType::PowerTarget
+            ))
+        );
+
+        let active = manager
+            .active(&DERControlEnumType::FixedPFAbsorb, now())
+            .unwrap();
+        assert_eq!(active.id, "high");
+        let later = now() + Duration::minutes(45);
+        let active = manager
+            .active(&DERControlEnumType::FixedPFAbsorb, later)
+            .unwrap();
+        assert_eq!(active.id, "low");
+
+        let reports = manager.report(1, Some(false), None, None, now()).unwrap();
+        assert_eq!(reports.len(), 1);
+        let settings = reports[0].fixed_pf_absorb.as_ref().unwrap();
+        assert_eq!(
+            (settings[0].id.as_str(), settings[0].is_superseded),
+            ("low", true)
+        );
+        assert_eq!(
+            (settings[1].id.as_str(), settings[1].is_superseded),
+            ("high", false)
+        );
+        assert_eq!(reports[0].tbc, None);
+        let reports = manager.report(2, Some(true), None, None, now()).unwrap();
+        assert_eq!(reports[0].gradient.as_ref().unwrap()[0].id, "default2");
+        assert_eq!(
+            manager.report(
+                3,
+                Some(false),
+                Some(&DERControlEnumType::VoltVar),
+                None,
+                now()
+            ),
+            Err(DerControlError::NotFound)
+        );
+
+        let mut request = ClearDERControlRequest {
+            is_default: false,
+            control_type: None,
+            control_id: Some("high".to_string()),
+            custom_data: None,
+        };
+        let response = manager.clear(&request);
+        assert_eq!(response.status, DERControlStatusEnumType::Accepted);
+        let response = manager.clear(&request);
+        assert_eq!(response.status, DERControlStatusEnumType::NotFound);
+        request.control_id = None;
+        request.control_type = Some(DERControlEnumType::FixedPFAbsorb);
+        assert_eq!(
+            manager.clear(&request).status,
+            DERControlStatusEnumType::Accepted
+        );
+        assert_eq!(manager.controls().len(), 1);
+    }
+
+    #[test]
+    fn handles_set_and_get_requests() {
+        let mut manager = manager();
+        let gradient = GradientType::new(0, dec!(10), dec!(1));
+        let request =
+            SetDERControlRequest::new(true, "gradient".to_string(), DERControlEnumType::Gradients)
+                .with_gradient(gradient.clone());
+        let response = manager.set(&request);
+        assert_eq!(response.status, DERControlStatusEnumType::Accepted);
+        assert_eq!(response.superseded_ids, None);
+
+        let request =
+            SetDERControlRequest::new(false, "pf".to_string(), DERControlEnumType::FixedPFAbsorb)
+                .with_fixed_pf_inject(FixedPFType::new(0, 0.9, true));
+        let response = manager.set(&request);
+        assert_eq!(response.status, DERControlStatusEnumType::Rejected);
+        assert_eq!(response.status_info.unwrap().reason_code, "InvalidValue");
+        let request =
+            SetDERControlRequest::new(false, "target".to_string(), DERControlEnumType::PowerTarget)
+                .with_gradient(gradient);
+        let response = manager.set(&request);
+        assert_eq!(response.status, DERControlStatusEnumType::NotSupported);
+
+        let (response, reports) = manager.get(&GetDERControlRequest::new(1), now());
+        assert_eq!(response.status, DERControlStatusEnumType::Accepted);
+        assert_eq!(reports[0].gradient.as_ref().unwrap()[0].id, "gradient");
+        let request = GetDERControlRequest::new(2).with_is_default(false);
+        let (response, reports) = manager.get(&request, now());
+        assert_eq!(response.status, DERControlStatusEnumType::NotFound);
+        assert!(reports.is_empty());
+    }
+
+    #[test]
+    fn reports_are_split() {
+        let mut manager = manager();
+        for id in 0..30 {
+            manager.insert(fixed_pf(&id.to_string(), id, 60)).unwrap();
+        }
+        let reports = manager.report(1, Some(false), None, None, now()).unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].tbc, Some(true));
+        assert_eq!(reports[0].fixed_pf_absorb.as_ref().unwrap().len(), 24);
+        assert_eq!(reports[1].tbc, None);
+        assert_eq!(reports[1].fixed_pf_absorb.as_ref().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn follows_frequency_droop_with_response_time() {
+        let mut manager = manager();
+        let freq_droop =
+            FreqDroopType::new(0, dec!(50.2), dec!(49.8), dec!(0.05), dec!(0.05), dec!(2));
+        manager
+            .insert(DerControl::new(
+                "droop",
+                true,
+                DERControlEnumType::FreqDroop,
+                DerSetting::FreqDroop(freq_droop),
+            ))
+            .unwrap();
+        let discharging = grid(dec!(230), dec!(50)).with_power(dec!(6000), dec!(0));
+        let (setpoint, _) = manager.update(&discharging, now());
+        assert_eq!(setpoint.active_power, None);
+
+        // 0.5 Hz above the dead band asks for 2000 W less
+        let over = grid(dec!(230), dec!(50.7)).with_power(dec!(6000), dec!(0));
+        let (setpoint, _) = manager.update(&over, now() + Duration::seconds(1));
+        assert_eq!(setpoint.active_power.unwrap().round(), dec!(4632));
+        let (setpoint, _) = manager.update(&over, now() + Duration::seconds(3));
+        assert_eq!(setpoint.active_power.unwrap().round(), dec!(4063));
+
+        let limit = LimitMaxDischargeType::new(0, dec!(30));
+        manager
+            .insert(DerControl::new(
+                "limit",
+                true,
+                DERControlEnumType::LimitMaxDischarge,
+                DerSetting::LimitMaxDischarge(limit),
+            ))
+            .unwrap();
+        let (setpoint, _) = manager.update(&over, now() + Duration::seconds(60));
+        assert_eq!(setpoint.max_discharge_power, Some(dec!(3000)));
+        assert_eq!(setpoint.active_power.unwrap().round(), dec!(3000));
+    }
+
+    #[test]
+    fn follows_volt_var_curve() {
+        let mut manager = manager();
+        let volt_var = curve(
+            &[
+                (dec!(92), dec!(40)),
+                (dec!(98), dec!(0)),
+                (dec!(102), dec!(0)),
+                (dec!(108), dec!(-40)),
+            ],
+            0,
+            DERUnitEnumType::PctMaxVar,
+        );
+        manager
+            .insert(DerControl::new(
+                "vv",
+                true,
+                DERControlEnumType::VoltVar,
+                DerSetting::Curve(volt_var),
+            ))
+            .unwrap();
+        // 241.5 V is 105% of nominal
+        let (setpoint, _) = manager.update(&grid(dec!(241.5), dec!(50)), now());
+        assert_eq!(setpoint.reactive_power, Some(dec!(-1000)));
+    }
+
+    #[test]
+    fn trips_and_enters_service() {
+        let mut manager = manager();
+        let must_trip = curve(
+            &[(dec!(2), dec!(110)), (dec!(0.16), dec!(120))],
+            0,
+            DERUnitEnumType::PctEffectiveV,
+        );
+        manager
+            .insert(DerControl::new(
+                "hv",
+                true,
+                DERControlEnumType::HVMustTrip,
+                DerSetting::Curve(must_trip),
+            ))
+            .unwrap();
+        let enter_service = EnterServiceType::new(
+            0,
+            dec!(105),
+            dec!(95),
+            dec!(50.1),
+            dec!(49.9),
+            dec!(60),
+            dec!(0),
+            dec!(10),
+        );
+        manager
+            .insert(DerControl::new(
+                "enter",
+                true,
+                DERControlEnumType::EnterService,
+                DerSetting::EnterService(enter_service),
+            ))
+            .unwrap();
+
+        let high = grid(dec!(260), dec!(50));
+        let (setpoint, alarms) = manager.update(&high, now());
+        assert!(!setpoint.trip && alarms.is_empty());
+        let (setpoint, alarms) = manager.update(&high, now() + Duration::seconds(2));
+        assert!(setpoint.trip);
+        assert_eq!(setpoint.active_power, Some(dec!(0)));
+        assert_eq!(alarms[0].control_type, DERControlEnumType::HVMustTrip);
+        assert_eq!(
+            alarms[0].grid_event_fault,
+            Some(GridEventFaultEnumType::OverVoltage)
+        );
+        assert_eq!(alarms[0].alarm_ended, None);
+
+        let normal = grid(dec!(230), dec!(50));
+        let (setpoint, alarms) = manager.update(&normal, now() + Duration::seconds(3));
+        assert!(setpoint.trip);
+        assert_eq!(alarms[0].alarm_ended, Some(true));
+        let (setpoint, _) = manager.update(&normal, now() + Duration::seconds(62));
+        assert!(setpoint.trip);
+        let (setpoint, _) = manager.update(&normal, now() + Duration::seconds(63));
+        assert!(!setpoint.trip);
+    }
+}
@@ -0,0 +1,401 @@
+//! # DER control
+//!
+//! Evaluation of the DER (distributed energy resource) controls of OCPP 2.1, which make a
+//! bidirectional EV follow the grid code through the charging station.
+//!
+//! The functions in this module evaluate single settings: [`curve_value`] for a
+//! `DERCurveType`, [`freq_droop_power`] for a `FreqDroopType`, [`fixed_pf_reactive_power`]
+//! and [`fixed_var_reactive_power`] for fixed reactive power, [`max_discharge_power`] for a
+//! `LimitMaxDischargeType`, and [`respond`] and [`ramp`] for the response time and
+//! gradients with which a setpoint follows its target.
+//!
+//! The [`DerControlManager`] holds the default and scheduled [`DerControl`]s of a charging
+//! station, as set by `SetDERControl` and cleared by `ClearDERControl`, and reports them
+//! in `ReportDERControl` on `GetDERControl`. Of every control type the active scheduled
+//! control with the highest priority applies, or else the default control. Fed the
+//! [`GridMeasurement`]s, [`DerControlManager::update`] returns the [`DerSetpoint`] of the EV
+//! and the `NotifyDERAlarmRequest`s of trips and momentary cessations.
+//!
+//! Active and reactive power are positive when the EV discharges into, or injects
+//! reactive power into, the grid. Voltages on the axes of curves and in `EnterServiceType`
+//! are percentages of the nominal voltage, frequencies are in Hz. The bounds of a
+//! `HysteresisType` apply to the grid parameter on the x-axis of its curve.
+
+use std::fmt;
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+
+use crate::v2_1::{
+    datatypes::{
+        DERCurveType, EnterServiceType, FixedPFType, FixedVarType, FreqDroopType, GradientType,
+        LimitMaxDischargeType,
+    },
+    enumerations::DERControlEnumType,
+    messages::set_der_control::SetDERControlRequest,
+};
+
+mod curve;
+mod manager;
+
+pub use curve::{
+    curve_value, fixed_pf_reactive_power, fixed_var_reactive_power, freq_droop_power, interpolate,
+    max_discharge_power, ramp, respond,
+};
+pub use manager::DerControlManager;
+
+/// Ratings of the EV and the grid connection against which percentages are resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerRating {
+    /// Maximum active power in W.
+    pub max_active_power: Decimal,
+    /// Maximum reactive power in var.
+    pub max_reactive_power: Decimal,
+    /// Nominal voltage in V.
+    pub nominal_voltage: Decimal,
+    /// Nominal frequency in Hz.
+    pub nominal_frequency: Decimal,
+}
+
+impl DerRating {
+    /// Creates the ratings of an EV on a grid with the given nominal voltage and frequency.
+    pub fn new(
+        max_active_power: Decimal,
+        max_reactive_power: Decimal,
+        nominal_voltage: Decimal,
+        nominal_frequency: Decimal,
+    ) -> Self {
+        Self {
+            max_active_power,
+            max_reactive_power,
+            nominal_voltage,
+            nominal_frequency,
+        }
+    }
+}
+
+/// State of the grid and the EV at a time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridMeasurement {
+    /// Voltage in V.
+    pub voltage: Decimal,
+    /// Frequency in Hz.
+    pub frequency: Decimal,
+    /// Active power of the EV in W.
+    pub active_power: Decimal,
+    /// Reactive power of the EV in var.
+    pub reactive_power: Decimal,
+}
+
+impl GridMeasurement {
+    /// Creates a measurement of an EV without power flow.
+    pub fn new(voltage: Decimal, frequency: Decimal) -> Self {
+        Self {
+            voltage,
+            frequency,
+            active_power: Decimal::ZERO,
+            reactive_power: Decimal::ZERO,
+        }
+    }
+
+    /// Sets the active and reactive power of the EV.
+    pub fn with_power(mut self, active_power: Decimal, reactive_power: Decimal) -> Self {
+        self.active_power = active_power;
+        self.reactive_power = reactive_power;
+        self
+    }
+}
+
+/// What the EV is required to do.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DerSetpoint {
+    /// Active power in W, `None` when no control sets it.
+    pub active_power: Option<Decimal>,
+    /// Reactive power in var, `None` when no control sets it.
+    pub reactive_power: Option<Decimal>,
+    /// Limit of the discharge power in W.
+    pub max_discharge_power: Option<Decimal>,
+    /// The EV must cease to exchange power for the moment.
+    pub cease: bool,
+    /// The EV is tripped and must not exchange power until it may enter service again.
+    pub trip: bool,
+}
+
+/// Setting of a DER control, one of the optional settings of `SetDERControlRequest`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DerSetting {
+    /// Curve of the curve based control types.
+    Curve(DERCurveType),
+    /// Conditions to enter service after a trip.
+    EnterService(EnterServiceType),
+    /// Fixed power factor of `FixedPFAbsorb` and `FixedPFInject`.
+    FixedPF(FixedPFType),
+    /// Fixed reactive power.
+    FixedVar(FixedVarType),
+    /// Frequency droop.
+    FreqDroop(FreqDroopType),
+    /// Ramp rates of active power.
+    Gradient(GradientType),
+    /// Limit of the discharge power.
+    LimitMaxDischarge(LimitMaxDischargeType),
+}
+
+/// A default or scheduled DER control.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerControl {
+    /// Unique id of the control.
+    pub id: String,
+    /// Whether this is a default control, which applies when no scheduled control does.
+    pub is_default: bool,
+    /// Type of the control.
+    pub control_type: DERControlEnumType,
+    /// Setting of the control.
+    pub setting: DerSetting,
+}
+
+impl DerControl {
+    /// Creates a control.
+    pub fn new(
+        id: impl Into<String>,
+        is_default: bool,
+        control_type: DERControlEnumType,
+        setting: DerSetting,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            is_default,
+            control_type,
+            setting,
+        }
+    }
+
+    /// Priority of the control, 0 is the highest.
+    pub fn priority(&self) -> i32 {
+        match &self.setting {
+            DerSetting::Curve(curve) => curve.priority,
+            DerSetting::EnterService(enter_service) => enter_service.priority,
+            DerSetting::FixedPF(fixed_pf) => fixed_pf.priority,
+            DerSetting::FixedVar(fixed_var) => fixed_var.priority,
+            DerSetting::FreqDroop(freq_droop) => freq_droop.priority,
+            DerSetting::Gradient(gradient) => gradient.priority,
+            DerSetting::LimitMaxDischarge(limit) => limit.priority,
+        }
+    }
+
+    /// Time the control starts and, when limited, ends.
+    pub fn period(&self) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        let (start_time, duration) = match &self.setting {
+            DerSetting::Curve(curve) => (curve.start_time, curve.duration),
+            DerSetting::FixedPF(fixed_pf) => (fixed_pf.start_time, fixed_pf.duration),
+            DerSetting::FixedVar(fixed_var) => (fixed_var.start_time, fixed_var.duration),
+            DerSetting::FreqDroop(freq_droop) => (freq_droop.start_time, freq_droop.duration),
+            DerSetting::LimitMaxDischarge(limit) => (limit.start_time, limit.duration),
+            DerSetting::EnterService(_) | DerSetting::Gradient(_) => (None, None),
+        };
+        let end = start_time
+            .zip(duration)
+            .and_then(|(start_time, duration)| start_time.checked_add_signed(seconds(duration)));
+        (start_time, end)
+    }
+
+    /// Whether the control applies at `now`. A default control always applies.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        let (start, end) = self.period();
+        self.is_default
+            || (start.is_none_or(|start| start <= now) && end.is_none_or(|end| now < end))
+    }
+
+    /// Whether the periods of two controls overlap.
+    pub fn overlaps(&self, other: &DerControl) -> bool {
+        let (start, end) = self.period();
+        let (other_start, other_end) = other.period();
+        let starts_before_other_ends = match (start, other_end) {
+            (Some(start), Some(other_end)) => start < other_end,
+            _ => true,
+        };
+        let other_starts_before_end = match (other_start, end) {
+            (Some(other_start), Some(end)) => other_start < end,
+            _ => true,
+        };
+        starts_before_other_ends && other_starts_before_end
+    }
+
+    /// Checks that the setting fits the control type and the charging station supports
+    /// the control type.
+    pub fn check(&self) -> Result<(), DerControlError> {
+        use DERControlEnumType::*;
+        let fits = match (&self.control_type, &self.setting) {
+            (
+                FreqWatt
+                | HFMustTrip
+                | HFMayTrip
+                | HVMustTrip
+                | HVMomCess
+                | HVMayTrip
+                | LFMustTrip
+                | LVMustTrip
+                | LVMomCess
+                | LVMayTrip
+                | PowerMonitoringMustTrip
+                | VoltVar
+                | VoltWatt
+                | WattPF
+                | WattVar,
+                DerSetting::Curve(_),
+            ) => true,
+            (EnterService, DerSetting::EnterService(_)) => true,
+            (FixedPFAbsorb | FixedPFInject, DerSetting::FixedPF(_)) => true,
+            (FixedVar, DerSetting::FixedVar(_)) => true,
+            (FreqDroop, DerSetting::FreqDroop(_)) => true,
+            (Gradients, DerSetting::Gradient(_)) => true,
+            (LimitMaxDischarge, DerSetting::LimitMaxDischarge(_)) => true,
+            (
+                PowerLimitation | PowerTarget | PowerFactor | VoltageTarget | CurrentTarget
+                | LoadPriority,
+                _,
+            ) => return Err(DerControlError::NotSupported(self.control_type.clone())),
+            _ => false,
+        };
+        match fits {
+            true => Ok(()),
+            false => Err(DerControlError::InvalidSetting(self.control_type.clone())),
+        }
+    }
+}
+
+impl TryFrom<&SetDERControlRequest> for DerControl {
+    type Error = DerControlError;
+
+    /// Takes the control from a request, which must carry exactly one setting. The fixed
+    /// power factor must be in the field of its control type.
+    fn try_from(request: &SetDERControlRequest) -> Result<Self, Self::Error> {
+        let settings = [
+            request.curve.clone().map(DerSetting::Curve),
+            request.enter_service.clone().map(DerSetting::EnterService),
+            request
+                .fixed_pf_absorb
+                .clone()
+                .filter(|_| request.control_type == DERControlEnumType::FixedPFAbsorb)
+                .map(DerSetting::FixedPF),
+            request
+                .fixed_pf_inject
+                .clone()
+                .filter(|_| request.control_type == DERControlEnumType::FixedPFInject)
+                .map(DerSetting::FixedPF),
+            request.fixed_var.clone().map(DerSetting::FixedVar),
+            request.freq_droop.clone().map(DerSetting::FreqDroop),
+            request.gradient.clone().map(DerSetting::Gradient),
+            request
+                .limit_max_discharge
+                .clone()
+                .map(DerSetting::LimitMaxDischarge),
+        ];
+        let mut settings = settings.into_iter().flatten();
+        match (settings.next(), settings.next()) {
+            (Some(setting), None) => Ok(Self::new(
+                request.control_id.clone(),
+                request.is_default,
+                request.control_type.clone(),
+                setting,
+            )),
+            _ => Err(DerControlError::InvalidSetting(
+                request.control_type.clone(),
+            )),
+        }
+    }
+}
+
+/// Reason why a DER control request is not accepted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DerControlError {
+    /// The charging station does not support the control type.
+    NotSupported(DERControlEnumType),
+    /// The setting does not fit the control type or is out of range.
+    InvalidSetting(DERControlEnumType),
+    /// No control matches the request.
+    NotFound,
+}
+
+impl fmt::Display for DerControlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotSupported(control_type) => write!(f, "{control_type:?} is not supported"),
+            Self::InvalidSetting(control_type) => {
+                write!(f, "invalid setting for {control_type:?}")
+            }
+            Self::NotFound => write!(f, "no matching DER control"),
+        }
+    }
+}
+
+impl DerControlError {
+    /// Reason code reported in the `StatusInfoType` of a rejected request.
+    pub fn reason_code(&self) -> &'static str {
+        match self {
+            Self::NotSupported(_) => "UnsupportedRequest",
+            Self::InvalidSetting(_) => "InvalidValue",
+            Self::NotFound => "NotFound",
+        }
+    }
+}
+
+impl std::error::Error for DerControlError {}
+
+/// Duration of `seconds`, saturating at the range of a duration.
+fn seconds(seconds: Decimal) -> Duration {
+    let millis = seconds
+        .checked_mul(Decimal::ONE_THOUSAND)
+        .and_then(|millis| millis.round().to_i64())
+        .unwrap_or(match seconds.is_sign_negative() {
+            true => -i64::MAX,
+            false => i64::MAX,
+        });
+    Duration::milliseconds(millis.max(-i64::MAX))
+}
+
+/// Seconds of `duration`, with millisecond precision.
+fn to_seconds(duration: Duration) -> Decimal {
+    Decimal::new(duration.num_milliseconds(), 3)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn controls_apply_during_their_period() {
+        let start = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let fixed_var = |start_time, duration| {
+            let mut setting = FixedVarType::new(1, 10.0);
+            setting.start_time = Some(start_time);
+            setting.duration = Some(duration);
+            DerControl::new(
+                "a",
+                false,
+                DERControlEnumType::FixedVar,
+                DerSetting::FixedVar(setting),
+            )
+        };
+        let control = fixed_var(start, dec!(600));
+        assert!(!control.is_active(start - Duration::seconds(1)));
+        assert!(control.is_active(start));
+        assert!(!control.is_active(start + Duration::minutes(10)));
+
+        let later = fixed_var(start + Duration::minutes(10), dec!(60));
+        assert!(!control.overlaps(&later));
+        assert!(fixed_var(start + Duration::minutes(5), dec!(60)).overlaps(&control));
+        assert_eq!(control.check(), Ok(()));
+
+        let mismatch = DerControl {
+            control_type: DERControlEnumType::VoltVar,
+            ..control
+        };
+        assert_eq!(
+            mismatch.check(),
+            Err(DerControlError::InvalidSetting(DERControlEnumType::VoltVar))
+        );
+    }
+}
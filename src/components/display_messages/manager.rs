@@ -0,0 +1,309 @@
+use chrono::{DateTime, Utc};
+
+use super::{
+    DisplayCapabilities, DisplayContext, DisplayMessage, DisplayMessageFilter, MessagePriority,
+    SetDisplayMessageStatus,
+};
+
+/// Display messages of a Charging Station.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DisplayMessageManager {
+    capabilities: DisplayCapabilities,
+    messages: Vec<DisplayMessage>,
+}
+
+impl DisplayMessageManager {
+    /// Creates a manager without messages.
+    pub fn new(capabilities: DisplayCapabilities) -> Self {
+        Self {
+            capabilities,
+            messages: Vec::new(),
+        }
+    }
+
+    /// What the Charging Station supports.
+    pub fn capabilities(&self) -> &DisplayCapabilities {
+        &self.capabilities
+    }
+
+    /// All messages, in the order they were set.
+    pub fn messages(&self) -> &[DisplayMessage] {
+        &self.messages
+    }
+
+    /// Message `id`.
+    pub fn get(&self, id: i32) -> Option<&DisplayMessage> {
+        self.messages.iter().find(|message| message.id == id)
+    }
+
+    /// Stores a message, replacing the message with the same id. A message for a
+    /// transaction is only accepted while the transaction is ongoing in `context`.
+    pub fn set(
+        &mut self,
+        message: DisplayMessage,
+        context: &DisplayContext,
+    ) -> SetDisplayMessageStatus {
+        let capabilities = &self.capabilities;
+        let contents = || std::iter::once(&message.content).chain(&message.extra);
+        let status = if !contents().all(|content| capabilities.formats.contains(&content.format)) {
+            SetDisplayMessageStatus::NotSupportedMessageFormat
+        } else if !capabilities.priorities.contains(&message.priority) {
+            SetDisplayMessageStatus::NotSupportedPriority
+        } else if message
+            .state
+            .is_some_and(|state| !capabilities.states.contains(&state))
+        {
+            SetDisplayMessageStatus::NotSupportedState
+        } else if !capabilities.languages.is_empty()
+            && !contents().all(|content| {
+                content
+                    .language
+                    .as_ref()
+                    .is_none_or(|language| capabilities.languages.contains(language))
+            })
+        {
+            SetDisplayMessageStatus::LanguageNotSupported
+        } else if message
+            .transaction_id
+            .as_ref()
+            .is_some_and(|transaction_id| !context.transaction_ids.contains(transaction_id))
+        {
+            SetDisplayMessageStatus::UnknownTransaction
+        } else if self.get(message.id).is_none() && self.messages.len() >= capabilities.max_messages
+        {
+            SetDisplayMessageStatus::Rejected
+        } else {
+            SetDisplayMessageStatus::Accepted
+        };
+        if status == SetDisplayMessageStatus::Accepted {
+            self.messages.retain(|other| other.id != message.id);
+            self.messages.push(message);
+        }
+        status
+    }
+
+    /// Removes message `id`, returning whether it existed.
+    pub fn clear(&mut self, id: i32) -> bool {
+        let count = self.messages.len();
+        self.messages.retain(|message| message.id != id);
+        self.messages.len() < count
+    }
+
+    /// Messages matching `filter`, as requested by `GetDisplayMessages`.
+    pub fn find(&self, filter: &DisplayMessageFilter) -> Vec<&DisplayMessage> {
+        self.messages
+            .iter()
+            .filter(|message| filter.matches(message))
+            .collect()
+    }
+
+    /// Removes the messages of a transaction that ended, returning their ids.
+    pub fn transaction_ended(&mut self, transaction_id: &str) -> Vec<i32> {
+        self.remove(|message| message.transaction_id.as_deref() == Some(transaction_id))
+    }
+
+    /// Removes the messages whose end has passed at `now`, returning their ids.
+    pub fn remove_expired(&mut self, now: DateTime<Utc>) -> Vec<i32> {
+        self.remove(|message| message.end.is_some_and(|end| end <= now))
+    }
+
+    /// Messages that may be shown in `context` at `now`.
+    pub fn eligible<'a>(
+        &'a self,
+        context: &'a DisplayContext,
+        now: DateTime<Utc>,
+    ) -> impl Iterator<Item = &'a DisplayMessage> + 'a {
+        self.messages
+            .iter()
+            .filter(move |message| message.is_eligible(context, now))
+    }
+
+    /// The message to show in `context` at `now`: the most recently set `AlwaysFront`
+    /// message, or else the `InFront` messages in turn, or else the `NormalCycle` messages
+    /// in turn. Each message of a cycle is shown for the cycle interval.
+    pub fn visible(&self, context: &DisplayContext, now: DateTime<Utc>) -> Option<&DisplayMessage> {
+        let of_priority = |priority: MessagePriority| -> Vec<&DisplayMessage> {
+            self.messages
+                .iter()
+                .filter(|message| message.priority == priority)
+                .filter(|message| message.is_eligible(context, now))
+                .collect()
+        };
+        if let Some(message) = of_priority(MessagePriority::AlwaysFront).pop() {
+            return Some(message);
+        }
+        let cycle = match of_priority(MessagePriority::InFront) {
+            in_front if !in_front.is_empty() => in_front,
+            _ => of_priority(MessagePriority::NormalCycle),
+        };
+        if cycle.is_empty() {
+            return None;
+        }
+        let interval = self.capabilities.cycle_interval.num_milliseconds().max(1);
+        let turn = now.timestamp_millis().div_euclid(interval);
+        Some(cycle[turn.rem_euclid(cycle.len() as i64) as usize])
+    }
+
+    fn remove(&mut self, remove: impl Fn(&DisplayMessage) -> bool) -> Vec<i32> {
+        let (removed, kept) = std::mem::take(&mut self.messages)
+            .into_iter()
+            .partition::<Vec<_>, _>(|message| remove(message));
+        self.messages = kept;
+        removed.into_iter().map(|message| message.id).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+    use crate::components::display_messages::{MessageContent, MessageFormat, MessageState};
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn message(id: i32, priority: MessagePriority) -> DisplayMessage {
+        let content = MessageContent {
+            format: MessageFormat::Utf8,
+            language: Some("en".to_string()),
+            content: format!("message {id}"),
+        };
+        DisplayMessage::new(id, priority, content)
+    }
+
+    fn visible_ids(manager: &DisplayMessageManager, context: &DisplayContext) -> Vec<i32> {
+        (0..4)
+            .map(|turn| {
+                let now = now() + Duration::seconds(10 * turn);
+                manager
+                    .visible(context, now)
+                    .map_or(-1, |message| message.id)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rotates_by_priority() {
+        let mut manager = DisplayMessageManager::default();
+        let idle = DisplayContext::new(MessageState::Idle);
+        assert_eq!(manager.visible(&idle, now()), None);
+
+        manager.set(message(1, MessagePriority::NormalCycle), &idle);
+        manager.set(message(2, MessagePriority::NormalCycle), &idle);
+        assert_eq!(visible_ids(&manager, &idle), vec![1, 2, 1, 2]);
+
+        manager.set(message(3, MessagePriority::InFront), &idle);
+        assert_eq!(visible_ids(&manager, &idle), vec![3, 3, 3, 3]);
+        manager.set(message(4, MessagePriority::InFront), &idle);
+        assert_eq!(visible_ids(&manager, &idle), vec![3, 4, 3, 4]);
+
+        manager.set(message(5, MessagePriority::AlwaysFront), &idle);
+        manager.set(message(6, MessagePriority::AlwaysFront), &idle);
+        assert_eq!(visible_ids(&manager, &idle), vec![6, 6, 6, 6]);
+        manager.clear(6);
+        assert_eq!(visible_ids(&manager, &idle), vec![5, 5, 5, 5]);
+    }
+
+    #[test]
+    fn shows_eligible_messages_only() {
+        let mut manager = DisplayMessageManager::default();
+        let charging = DisplayContext::new(MessageState::Charging).with_transaction("tx-1");
+
+        let mut scheduled = message(1, MessagePriority::InFront);
+        scheduled.start = Some(now() + Duration::minutes(1));
+        scheduled.end = Some(now() + Duration::minutes(2));
+        manager.set(scheduled, &charging);
+        let mut idle_only = message(2, MessagePriority::InFront);
+        idle_only.state = Some(MessageState::Idle);
+        manager.set(idle_only, &charging);
+        let mut transaction = message(3, MessagePriority::NormalCycle);
+        transaction.transaction_id = Some("tx-1".to_string());
+        manager.set(transaction, &charging);
+        manager.set(
+            message(4, MessagePriority::AlwaysFront).with_id_token("TOKEN"),
+            &charging,
+        );
+
+        assert_eq!(manager.visible(&charging, now()).unwrap().id, 3);
+        let later = now() + Duration::seconds(90);
+        assert_eq!(manager.visible(&charging, later).unwrap().id, 1);
+        let presented = charging.clone().with_id_token("TOKEN");
+        assert_eq!(manager.visible(&presented, later).unwrap().id, 4);
+        let idle = DisplayContext::new(MessageState::Idle);
+        assert_eq!(manager.visible(&idle, now()).unwrap().id, 2);
+
+        assert_eq!(manager.transaction_ended("tx-1"), vec![3]);
+        assert_eq!(manager.visible(&charging, now()), None);
+        assert_eq!(
+            manager.remove_expired(now() + Duration::minutes(2)),
+            vec![1]
+        );
+        assert_eq!(manager.messages().len(), 2);
+    }
+
+    #[test]
+    fn checks_capabilities() {
+        let capabilities = DisplayCapabilities {
+            max_messages: 2,
+            formats: vec![MessageFormat::Utf8],
+            priorities: vec![MessagePriority::NormalCycle, MessagePriority::InFront],
+            states: vec![MessageState::Idle],
+            languages: vec!["en".to_string()],
+            ..Default::default()
+        };
+        let mut manager = DisplayMessageManager::new(capabilities);
+        let idle = DisplayContext::new(MessageState::Idle);
+
+        let mut html = message(1, MessagePriority::NormalCycle);
+        html.content.format = MessageFormat::Html;
+        assert_eq!(
+            manager.set(html, &idle),
+            SetDisplayMessageStatus::NotSupportedMessageFormat
+        );
+        assert_eq!(
+            manager.set(message(1, MessagePriority::AlwaysFront), &idle),
+            SetDisplayMessageStatus::NotSupportedPriority
+        );
+        let mut faulted = message(1, MessagePriority::NormalCycle);
+        faulted.state = Some(MessageState::Faulted);
+        assert_eq!(
+            manager.set(faulted, &idle),
+            SetDisplayMessageStatus::NotSupportedState
+        );
+        let mut dutch = message(1, MessagePriority::NormalCycle);
+        dutch.extra.push(MessageContent {
+            format: MessageFormat::Utf8,
+            language: Some("nl".to_string()),
+            content: "bericht".to_string(),
+        });
+        assert_eq!(
+            manager.set(dutch, &idle),
+            SetDisplayMessageStatus::LanguageNotSupported
+        );
+        let mut transaction = message(1, MessagePriority::NormalCycle);
+        transaction.transaction_id = Some("tx-1".to_string());
+        assert_eq!(
+            manager.set(transaction, &idle),
+            SetDisplayMessageStatus::UnknownTransaction
+        );
+
+        for id in [1, 2, 2] {
+            let status = manager.set(message(id, MessagePriority::NormalCycle), &idle);
+            assert_eq!(status, SetDisplayMessageStatus::Accepted);
+        }
+        assert_eq!(
+            manager.set(message(3, MessagePriority::NormalCycle), &idle),
+            SetDisplayMessageStatus::Rejected
+        );
+
+        let filter = DisplayMessageFilter {
+            ids: vec![2, 3],
+            ..Default::default()
+        };
+        assert_eq!(manager.find(&filter).len(), 1);
+        assert!(manager.clear(2));
+        assert!(!manager.clear(2));
+    }
+}
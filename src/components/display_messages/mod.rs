@@ -0,0 +1,287 @@
+//! # Display messages
+//!
+//! Display messages set by `SetDisplayMessage` and the message a Charging Station shows at
+//! a time (OCPP 2.0.1 and 2.1).
+//!
+//! [`DisplayMessageManager`] stores the messages, checks them against the
+//! [`DisplayCapabilities`] of the Charging Station, and answers `GetDisplayMessages` and
+//! `ClearDisplayMessage`. [`DisplayMessageManager::visible`] resolves the message to show
+//! for a [`DisplayContext`], following the rotation rules of the priorities:
+//!
+//! - the most recently set `AlwaysFront` message is shown and never cycled,
+//! - otherwise the `InFront` messages are cycled,
+//! - otherwise the `NormalCycle` messages are cycled.
+//!
+//! A message is only eligible from its start until its end, in its state, while its
+//! transaction is ongoing and, for a message bound to an id token such as the personal
+//! message of an `IdTokenInfoType`, while that token is presented. Messages past their end
+//! or of ended transactions are removed.
+//!
+//! The version submodules convert the messages and build the paged
+//! `NotifyDisplayMessagesRequest`s.
+
+use chrono::{DateTime, Duration, Utc};
+
+mod manager;
+
+#[cfg(feature = "v2_0_1")]
+pub mod v2_0_1;
+#[cfg(feature = "v2_1")]
+pub mod v2_1;
+
+pub use manager::DisplayMessageManager;
+
+/// Priority of a message, `MessagePriorityEnumType` in 2.x.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessagePriority {
+    /// Shown in front of all other messages, replaced by a newer `AlwaysFront` message.
+    AlwaysFront,
+    /// Shown in front of the normal cycle, cycled with other `InFront` messages.
+    InFront,
+    /// Shown in the normal cycle of messages.
+    NormalCycle,
+}
+
+/// State of the Charging Station in which a message is shown, `MessageStateEnumType` in 2.x.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageState {
+    /// A transaction is ongoing.
+    Charging,
+    /// The Charging Station is in an error state.
+    Faulted,
+    /// No transaction is ongoing.
+    Idle,
+    /// The Charging Station is unavailable.
+    Unavailable,
+    /// Charging is suspended by the EV or the EVSE (2.1).
+    Suspended,
+    /// The EV discharges (2.1).
+    Discharging,
+}
+
+/// Format of message content, `MessageFormatEnumType` in 2.x.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageFormat {
+    /// Plain ASCII text.
+    Ascii,
+    /// HTML.
+    Html,
+    /// A URI of the content.
+    Uri,
+    /// UTF-8 text.
+    Utf8,
+    /// Content to show as a QR code (2.1).
+    QrCode,
+}
+
+/// Content of a message in one language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageContent {
+    /// Format of the content.
+    pub format: MessageFormat,
+    /// Language code, RFC 5646.
+    pub language: Option<String>,
+    /// The content.
+    pub content: String,
+}
+
+/// Display component that a message concerns.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DisplayComponent {
+    /// Name of the component.
+    pub name: String,
+    /// Instance of the component.
+    pub instance: Option<String>,
+    /// EVSE of the component.
+    pub evse_id: Option<i32>,
+    /// Connector of the EVSE of the component.
+    pub connector_id: Option<i32>,
+}
+
+/// Version independent `MessageInfoType`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayMessage {
+    /// Identifies the message.
+    pub id: i32,
+    /// Priority of the message.
+    pub priority: MessagePriority,
+    /// State in which the message is shown, any state when absent.
+    pub state: Option<MessageState>,
+    /// When the message is shown from, directly when absent.
+    pub start: Option<DateTime<Utc>>,
+    /// When the message is removed.
+    pub end: Option<DateTime<Utc>>,
+    /// Transaction during which the message is shown.
+    pub transaction_id: Option<String>,
+    /// Id token for which the message is shown. Not part of `MessageInfoType`, set by the
+    /// Charging Station for personal messages.
+    pub id_token: Option<String>,
+    /// Display component the message concerns.
+    pub display: Option<DisplayComponent>,
+    /// Content of the message.
+    pub content: MessageContent,
+    /// Content of the message in other languages (2.1).
+    pub extra: Vec<MessageContent>,
+}
+
+impl DisplayMessage {
+    /// Creates a message shown directly, in any state, until cleared.
+    pub fn new(id: i32, priority: MessagePriority, content: MessageContent) -> Self {
+        Self {
+            id,
+            priority,
+            state: None,
+            start: None,
+            end: None,
+            transaction_id: None,
+            id_token: None,
+            display: None,
+            content,
+            extra: Vec::new(),
+        }
+    }
+
+    /// Binds the message to an id token.
+    pub fn with_id_token(mut self, id_token: impl Into<String>) -> Self {
+        self.id_token = Some(id_token.into());
+        self
+    }
+
+    /// Whether the message may be shown in `context` at `now`.
+    pub fn is_eligible(&self, context: &DisplayContext, now: DateTime<Utc>) -> bool {
+        self.start.is_none_or(|start| start <= now)
+            && self.end.is_none_or(|end| now < end)
+            && self.state.is_none_or(|state| state == context.state)
+            && self
+                .transaction_id
+                .as_ref()
+                .is_none_or(|transaction_id| context.transaction_ids.contains(transaction_id))
+            && self
+                .id_token
+                .as_ref()
+                .is_none_or(|id_token| context.id_token.as_ref() == Some(id_token))
+    }
+}
+
+/// What the Charging Station supports, as configured in its `DisplayMessageCtrlr`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayCapabilities {
+    /// Maximum number of stored messages, `NumberOfDisplayMessages`.
+    pub max_messages: usize,
+    /// Supported formats, `DisplayMessageSupportedFormats`.
+    pub formats: Vec<MessageFormat>,
+    /// Supported priorities, `DisplayMessageSupportedPriorities`.
+    pub priorities: Vec<MessagePriority>,
+    /// Supported states, `DisplayMessageSupportedStates`.
+    pub states: Vec<MessageState>,
+    /// Supported languages, any language when empty (2.1).
+    pub languages: Vec<String>,
+    /// How long each message of a cycle is shown.
+    pub cycle_interval: Duration,
+}
+
+impl Default for DisplayCapabilities {
+    /// Supports everything, up to 100 messages, and cycles every 10 seconds.
+    fn default() -> Self {
+        Self {
+            max_messages: 100,
+            formats: vec![
+                MessageFormat::Ascii,
+                MessageFormat::Html,
+                MessageFormat::Uri,
+                MessageFormat::Utf8,
+                MessageFormat::QrCode,
+            ],
+            priorities: vec![
+                MessagePriority::AlwaysFront,
+                MessagePriority::InFront,
+                MessagePriority::NormalCycle,
+            ],
+            states: vec![
+                MessageState::Charging,
+                MessageState::Faulted,
+                MessageState::Idle,
+                MessageState::Unavailable,
+                MessageState::Suspended,
+                MessageState::Discharging,
+            ],
+            languages: Vec::new(),
+            cycle_interval: Duration::seconds(10),
+        }
+    }
+}
+
+/// What the Charging Station is doing, deciding which messages are eligible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayContext {
+    /// Current state.
+    pub state: MessageState,
+    /// Ongoing transactions.
+    pub transaction_ids: Vec<String>,
+    /// The id token presented or authorized, if any.
+    pub id_token: Option<String>,
+}
+
+impl DisplayContext {
+    /// Creates a context without transactions or id token.
+    pub fn new(state: MessageState) -> Self {
+        Self {
+            state,
+            transaction_ids: Vec::new(),
+            id_token: None,
+        }
+    }
+
+    /// Adds an ongoing transaction.
+    pub fn with_transaction(mut self, transaction_id: impl Into<String>) -> Self {
+        self.transaction_ids.push(transaction_id.into());
+        self
+    }
+
+    /// Sets the presented id token.
+    pub fn with_id_token(mut self, id_token: impl Into<String>) -> Self {
+        self.id_token = Some(id_token.into());
+        self
+    }
+}
+
+/// Status of setting a message, convertible into `DisplayMessageStatusEnumType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetDisplayMessageStatus {
+    /// The message has been stored.
+    Accepted,
+    /// The format of the content is not supported.
+    NotSupportedMessageFormat,
+    /// The maximum number of messages is reached.
+    Rejected,
+    /// The priority is not supported.
+    NotSupportedPriority,
+    /// The state is not supported.
+    NotSupportedState,
+    /// The transaction is not ongoing.
+    UnknownTransaction,
+    /// The language of the content is not supported, `Rejected` in 2.0.1.
+    LanguageNotSupported,
+}
+
+/// Filter of `GetDisplayMessagesRequest`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DisplayMessageFilter {
+    /// Ids of the messages, any id when empty.
+    pub ids: Vec<i32>,
+    /// Priority of the messages.
+    pub priority: Option<MessagePriority>,
+    /// State of the messages.
+    pub state: Option<MessageState>,
+}
+
+impl DisplayMessageFilter {
+    /// Whether `message` matches the filter.
+    pub fn matches(&self, message: &DisplayMessage) -> bool {
+        (self.ids.is_empty() || self.ids.contains(&message.id))
+            && self
+                .priority
+                .is_none_or(|priority| priority == message.priority)
+            && self.state.is_none_or(|state| message.state == Some(state))
+    }
+}
@@ -0,0 +1,325 @@
+//! OCPP 2.0.1 messages for the [`DisplayMessageManager`].
+//!
+//! OCPP 2.0.1 has no `QRCODE` format and no `Suspended` or `Discharging` states, so
+//! messages using them are left out of `NotifyDisplayMessagesRequest`s.
+
+use crate::v2_0_1::{
+    datatypes::{
+        component_type::ComponentType, evse_type::EVSEType,
+        message_content_type::MessageContentType, message_info_type::MessageInfoType,
+    },
+    enumerations::{
+        clear_message_status_enum_type::ClearMessageStatusEnumType,
+        display_message_status_enum_type::DisplayMessageStatusEnumType,
+        get_display_messages_status_enum_type::GetDisplayMessagesStatusEnumType,
+        message_format_enum_type::MessageFormatEnumType,
+        message_priority_enum_type::MessagePriorityEnumType,
+        message_state_enum_type::MessageStateEnumType,
+    },
+    messages::{
+        clear_display_message::{ClearDisplayMessageRequest, ClearDisplayMessageResponse},
+        get_display_message::{GetDisplayMessagesRequest, GetDisplayMessagesResponse},
+        notify_display_messages::NotifyDisplayMessagesRequest,
+        set_display_message::{SetDisplayMessageRequest, SetDisplayMessageResponse},
+    },
+};
+
+use super::{
+    DisplayComponent, DisplayContext, DisplayMessage, DisplayMessageFilter, DisplayMessageManager,
+    MessageContent, MessageFormat, MessagePriority, MessageState, SetDisplayMessageStatus,
+};
+
+impl From<&MessagePriorityEnumType> for MessagePriority {
+    fn from(priority: &MessagePriorityEnumType) -> Self {
+        match priority {
+            MessagePriorityEnumType::AlwaysFront => Self::AlwaysFront,
+            MessagePriorityEnumType::InFront => Self::InFront,
+            MessagePriorityEnumType::NormalCycle => Self::NormalCycle,
+        }
+    }
+}
+
+impl From<MessagePriority> for MessagePriorityEnumType {
+    fn from(priority: MessagePriority) -> Self {
+        match priority {
+            MessagePriority::AlwaysFront => Self::AlwaysFront,
+            MessagePriority::InFront => Self::InFront,
+            MessagePriority::NormalCycle => Self::NormalCycle,
+        }
+    }
+}
+
+impl From<&MessageStateEnumType> for MessageState {
+    fn from(state: &MessageStateEnumType) -> Self {
+        match state {
+            MessageStateEnumType::Charging => Self::Charging,
+            MessageStateEnumType::Faulted => Self::Faulted,
+            MessageStateEnumType::Idle => Self::Idle,
+            MessageStateEnumType::Unavailable => Self::Unavailable,
+        }
+    }
+}
+
+impl From<&MessageFormatEnumType> for MessageFormat {
+    fn from(format: &MessageFormatEnumType) -> Self {
+        match format {
+            MessageFormatEnumType::ASCII => Self::Ascii,
+            MessageFormatEnumType::HTML => Self::Html,
+            MessageFormatEnumType::URI => Self::Uri,
+            MessageFormatEnumType::UTF8 => Self::Utf8,
+        }
+    }
+}
+
+impl From<&MessageContentType> for MessageContent {
+    fn from(content: &MessageContentType) -> Self {
+        Self {
+            format: (&content.format).into(),
+            language: content.language.clone(),
+            content: content.content.clone(),
+        }
+    }
+}
+
+impl From<&ComponentType> for DisplayComponent {
+    fn from(component: &ComponentType) -> Self {
+        Self {
+            name: component.name.clone(),
+            instance: component.instance.clone(),
+            evse_id: component.evse.as_ref().map(|evse| evse.id),
+            connector_id: component.evse.as_ref().and_then(|evse| evse.connector_id),
+        }
+    }
+}
+
+impl From<&DisplayComponent> for ComponentType {
+    fn from(component: &DisplayComponent) -> Self {
+        Self {
+            name: component.name.clone(),
+            instance: component.instance.clone(),
+            evse: component.evse_id.map(|id| EVSEType {
+                id,
+                connector_id: component.connector_id,
+            }),
+        }
+    }
+}
+
+impl From<&MessageInfoType> for DisplayMessage {
+    fn from(info: &MessageInfoType) -> Self {
+        Self {
+            id: info.id,
+            priority: (&info.priority).into(),
+            state: info.state.as_ref().map(Into::into),
+            start: info.start_date_time,
+            end: info.end_date_time,
+            transaction_id: info.transaction_id.clone(),
+            id_token: None,
+            display: info.display.as_ref().map(Into::into),
+            content: (&info.message).into(),
+            extra: Vec::new(),
+        }
+    }
+}
+
+impl From<&GetDisplayMessagesRequest> for DisplayMessageFilter {
+    fn from(request: &GetDisplayMessagesRequest) -> Self {
+        Self {
+            ids: request.id.clone().unwrap_or_default(),
+            priority: request.priority.as_ref().map(Into::into),
+            state: request.state.as_ref().map(Into::into),
+        }
+    }
+}
+
+impl From<SetDisplayMessageStatus> for DisplayMessageStatusEnumType {
+    fn from(status: SetDisplayMessageStatus) -> Self {
+        match status {
+            SetDisplayMessageStatus::Accepted => Self::Accepted,
+            SetDisplayMessageStatus::NotSupportedMessageFormat => Self::NotSupportedMessageFormat,
+            SetDisplayMessageStatus::Rejected | SetDisplayMessageStatus::LanguageNotSupported => {
+                Self::Rejected
+            }
+            SetDisplayMessageStatus::NotSupportedPriority => Self::NotSupportedPriority,
+            SetDisplayMessageStatus::NotSupportedState => Self::NotSupportedState,
+            SetDisplayMessageStatus::UnknownTransaction => Self::UnknownTransaction,
+        }
+    }
+}
+
+/// `message` as a 2.0.1 `MessageInfoType`, if it only uses formats and states of 2.0.1.
+fn message_info(message: &DisplayMessage) -> Option<MessageInfoType> {
+    let format = match message.content.format {
+        MessageFormat::Ascii => MessageFormatEnumType::ASCII,
+        MessageFormat::Html => MessageFormatEnumType::HTML,
+        MessageFormat::Uri => MessageFormatEnumType::URI,
+        MessageFormat::Utf8 => MessageFormatEnumType::UTF8,
+        MessageFormat::QrCode => return None,
+    };
+    let state = match message.state {
+        None => None,
+        Some(MessageState::Charging) => Some(MessageStateEnumType::Charging),
+        Some(MessageState::Faulted) => Some(MessageStateEnumType::Faulted),
+        Some(MessageState::Idle) => Some(MessageStateEnumType::Idle),
+        Some(MessageState::Unavailable) => Some(MessageStateEnumType::Unavailable),
+        Some(MessageState::Suspended | MessageState::Discharging) => return None,
+    };
+    Some(MessageInfoType {
+        id: message.id,
+        priority: message.priority.into(),
+        state,
+        start_date_time: message.start,
+        end_date_time: message.end,
+        transaction_id: message.transaction_id.clone(),
+        message: MessageContentType {
+            format,
+            language: message.content.language.clone(),
+            content: message.content.content.clone(),
+        },
+        display: message.display.as_ref().map(Into::into),
+    })
+}
+
+/// Handles a `SetDisplayMessageRequest`, with the ongoing transactions in `context`.
+pub fn set_display_message(
+    manager: &mut DisplayMessageManager,
+    request: &SetDisplayMessageRequest,
+    context: &DisplayContext,
+) -> SetDisplayMessageResponse {
+    SetDisplayMessageResponse {
+        status: manager.set((&request.message).into(), context).into(),
+        status_info: None,
+    }
+}
+
+/// Handles a `GetDisplayMessagesRequest`, returning the response and the
+/// `NotifyDisplayMessagesRequest`s of at most `max_messages` messages each to send after it.
+///
+/// The status is `Unknown`, without requests, when no message matches.
+pub fn get_display_messages(
+    manager: &DisplayMessageManager,
+    request: &GetDisplayMessagesRequest,
+    max_messages: usize,
+) -> (
+    GetDisplayMessagesResponse,
+    Vec<NotifyDisplayMessagesRequest>,
+) {
+    let messages: Vec<_> = manager
+        .find(&request.into())
+        .into_iter()
+        .filter_map(message_info)
+        .collect();
+    let status = match messages.is_empty() {
+        true => GetDisplayMessagesStatusEnumType::Unknown,
+        false => GetDisplayMessagesStatusEnumType::Accepted,
+    };
+    let response = GetDisplayMessagesResponse {
+        status,
+        status_info: None,
+    };
+    let chunks = messages.chunks(max_messages.max(1));
+    let last = chunks.len().saturating_sub(1);
+    let requests = chunks
+        .enumerate()
+        .map(|(index, chunk)| NotifyDisplayMessagesRequest {
+            request_id: request.request_id,
+            tbc: (index < last).then_some(true),
+            message_info: Some(chunk.to_vec()),
+        })
+        .collect();
+    (response, requests)
+}
+
+/// Handles a `ClearDisplayMessageRequest`.
+pub fn clear_display_message(
+    manager: &mut DisplayMessageManager,
+    request: &ClearDisplayMessageRequest,
+) -> ClearDisplayMessageResponse {
+    ClearDisplayMessageResponse {
+        status: match manager.clear(request.id) {
+            true => ClearMessageStatusEnumType::Accepted,
+            false => ClearMessageStatusEnumType::Unknown,
+        },
+        status_info: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::components::display_messages::DisplayCapabilities;
+
+    fn message_info(id: i32, priority: MessagePriorityEnumType) -> MessageInfoType {
+        MessageInfoType {
+            id,
+            priority,
+            message: MessageContentType {
+                format: MessageFormatEnumType::UTF8,
+                language: Some("en".to_string()),
+                content: format!("message {id}"),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn set_get_and_clear() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut manager = DisplayMessageManager::new(DisplayCapabilities::default());
+        let charging = DisplayContext::new(MessageState::Charging).with_transaction("tx-1");
+
+        for id in 1..=3 {
+            let request = SetDisplayMessageRequest {
+                message: message_info(id, MessagePriorityEnumType::NormalCycle),
+            };
+            let response = set_display_message(&mut manager, &request, &charging);
+            assert_eq!(response.status, DisplayMessageStatusEnumType::Accepted);
+        }
+        let mut transaction = message_info(4, MessagePriorityEnumType::InFront);
+        transaction.transaction_id = Some("tx-2".to_string());
+        let request = SetDisplayMessageRequest {
+            message: transaction,
+        };
+        let response = set_display_message(&mut manager, &request, &charging);
+        assert_eq!(
+            response.status,
+            DisplayMessageStatusEnumType::UnknownTransaction
+        );
+        assert_eq!(manager.visible(&charging, now).unwrap().id, 1);
+
+        let request = GetDisplayMessagesRequest {
+            id: None,
+            request_id: 7,
+            priority: Some(MessagePriorityEnumType::NormalCycle),
+            state: None,
+        };
+        let (response, requests) = get_display_messages(&manager, &request, 2);
+        assert_eq!(response.status, GetDisplayMessagesStatusEnumType::Accepted);
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].tbc, Some(true));
+        assert_eq!(requests[1].tbc, None);
+        assert_eq!(requests[1].request_id, 7);
+        assert_eq!(
+            requests[1].message_info,
+            Some(vec![message_info(3, MessagePriorityEnumType::NormalCycle)])
+        );
+
+        let request = ClearDisplayMessageRequest { id: 3 };
+        let response = clear_display_message(&mut manager, &request);
+        assert_eq!(response.status, ClearMessageStatusEnumType::Accepted);
+        let response = clear_display_message(&mut manager, &request);
+        assert_eq!(response.status, ClearMessageStatusEnumType::Unknown);
+
+        let request = GetDisplayMessagesRequest {
+            id: Some(vec![3]),
+            request_id: 8,
+            priority: None,
+            state: None,
+        };
+        let (response, requests) = get_display_messages(&manager, &request, 2);
+        assert_eq!(response.status, GetDisplayMessagesStatusEnumType::Unknown);
+        assert!(requests.is_empty());
+    }
+}
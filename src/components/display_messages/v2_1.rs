@@ -0,0 +1,351 @@
+//! OCPP 2.1 messages for the [`DisplayMessageManager`].
+
+use crate::v2_1::{
+    datatypes::{ComponentType, EVSEType, MessageContentType},
+    enumerations::{
+        ClearMessageStatusEnumType, DisplayMessageStatusEnumType, GetDisplayMessagesStatusEnumType,
+        MessageFormatEnumType, MessagePriorityEnumType, MessageStateEnumType,
+    },
+    messages::{
+        clear_display_message::{ClearDisplayMessageRequest, ClearDisplayMessageResponse},
+        get_display_messages::{GetDisplayMessagesRequest, GetDisplayMessagesResponse},
+        notify_display_messages::{MessageInfoType, NotifyDisplayMessagesRequest},
+        set_display_message::{SetDisplayMessageRequest, SetDisplayMessageResponse},
+    },
+};
+
+use super::{
+    DisplayComponent, DisplayContext, DisplayMessage, DisplayMessageFilter, DisplayMessageManager,
+    MessageContent, MessageFormat, MessagePriority, MessageState, SetDisplayMessageStatus,
+};
+
+impl From<&MessagePriorityEnumType> for MessagePriority {
+    fn from(priority: &MessagePriorityEnumType) -> Self {
+        match priority {
+            MessagePriorityEnumType::AlwaysFront => Self::AlwaysFront,
+            MessagePriorityEnumType::InFront => Self::InFront,
+            MessagePriorityEnumType::NormalCycle => Self::NormalCycle,
+        }
+    }
+}
+
+impl From<MessagePriority> for MessagePriorityEnumType {
+    fn from(priority: MessagePriority) -> Self {
+        match priority {
+            MessagePriority::AlwaysFront => Self::AlwaysFront,
+            MessagePriority::InFront => Self::InFront,
+            MessagePriority::NormalCycle => Self::NormalCycle,
+        }
+    }
+}
+
+impl From<&MessageStateEnumType> for MessageState {
+    fn from(state: &MessageStateEnumType) -> Self {
+        match state {
+            MessageStateEnumType::Charging => Self::Charging,
+            MessageStateEnumType::Faulted => Self::Faulted,
+            MessageStateEnumType::Idle => Self::Idle,
+            MessageStateEnumType::Unavailable => Self::Unavailable,
+            MessageStateEnumType::Suspended => Self::Suspended,
+            MessageStateEnumType::Discharging => Self::Discharging,
+        }
+    }
+}
+
+impl From<MessageState> for MessageStateEnumType {
+    fn from(state: MessageState) -> Self {
+        match state {
+            MessageState::Charging => Self::Charging,
+            MessageState::Faulted => Self::Faulted,
+            MessageState::Idle => Self::Idle,
+            MessageState::Unavailable => Self::Unavailable,
+            MessageState::Suspended => Self::Suspended,
+            MessageState::Discharging => Self::Discharging,
+        }
+    }
+}
+
+impl From<&MessageFormatEnumType> for MessageFormat {
+    fn from(format: &MessageFormatEnumType) -> Self {
+        match format {
+            MessageFormatEnumType::ASCII => Self::Ascii,
+            MessageFormatEnumType::HTML => Self::Html,
+            MessageFormatEnumType::URI => Self::Uri,
+            MessageFormatEnumType::UTF8 => Self::Utf8,
+            MessageFormatEnumType::QRCODE => Self::QrCode,
+        }
+    }
+}
+
+impl From<MessageFormat> for MessageFormatEnumType {
+    fn from(format: MessageFormat) -> Self {
+        match format {
+            MessageFormat::Ascii => Self::ASCII,
+            MessageFormat::Html => Self::HTML,
+            MessageFormat::Uri => Self::URI,
+            MessageFormat::Utf8 => Self::UTF8,
+            MessageFormat::QrCode => Self::QRCODE,
+        }
+    }
+}
+
+impl From<&MessageContentType> for MessageContent {
+    fn from(content: &MessageContentType) -> Self {
+        Self {
+            format: (&content.format).into(),
+            // the language is required in 2.1, an empty language is taken as absent
+            language: Some(content.language.clone()).filter(|language| !language.is_empty()),
+            content: content.content.clone(),
+        }
+    }
+}
+
+impl From<&MessageContent> for MessageContentType {
+    fn from(content: &MessageContent) -> Self {
+        Self {
+            content: content.content.clone(),
+            format: content.format.into(),
+            language: content.language.clone().unwrap_or_default(),
+            custom_data: None,
+        }
+    }
+}
+
+impl From<&ComponentType> for DisplayComponent {
+    fn from(component: &ComponentType) -> Self {
+        Self {
+            name: component.name.clone(),
+            instance: component.instance.clone(),
+            evse_id: component.evse.as_ref().map(|evse| evse.id),
+            connector_id: component.evse.as_ref().and_then(|evse| evse.connector_id),
+        }
+    }
+}
+
+impl From<&DisplayComponent> for ComponentType {
+    fn from(component: &DisplayComponent) -> Self {
+        Self {
+            custom_data: None,
+            evse: component.evse_id.map(|id| EVSEType {
+                id,
+                connector_id: component.connector_id,
+                custom_data: None,
+            }),
+            name: component.name.clone(),
+            instance: component.instance.clone(),
+        }
+    }
+}
+
+impl From<&MessageInfoType> for DisplayMessage {
+    fn from(info: &MessageInfoType) -> Self {
+        Self {
+            id: info.id,
+            priority: (&info.priority).into(),
+            state: info.state.as_ref().map(Into::into),
+            start: info.start_date_time,
+            end: info.end_date_time,
+            transaction_id: info.transaction_id.clone(),
+            id_token: None,
+            display: info.display.as_ref().map(Into::into),
+            content: (&info.message).into(),
+            extra: info
+                .message_extra
+                .iter()
+                .flatten()
+                .map(Into::into)
+                .collect(),
+        }
+    }
+}
+
+impl From<&DisplayMessage> for MessageInfoType {
+    fn from(message: &DisplayMessage) -> Self {
+        Self {
+            display: message.display.as_ref().map(Into::into),
+            id: message.id,
+            priority: message.priority.into(),
+            state: message.state.map(Into::into),
+            start_date_time: message.start,
+            end_date_time: message.end,
+            transaction_id: message.transaction_id.clone(),
+            message: (&message.content).into(),
+            message_extra: (!message.extra.is_empty())
+                .then(|| message.extra.iter().map(Into::into).collect()),
+            custom_data: None,
+        }
+    }
+}
+
+impl From<&GetDisplayMessagesRequest> for DisplayMessageFilter {
+    fn from(request: &GetDisplayMessagesRequest) -> Self {
+        Self {
+            ids: request.id.clone().unwrap_or_default(),
+            priority: request.priority.as_ref().map(Into::into),
+            state: request.state.as_ref().map(Into::into),
+        }
+    }
+}
+
+impl From<SetDisplayMessageStatus> for DisplayMessageStatusEnumType {
+    fn from(status: SetDisplayMessageStatus) -> Self {
+        match status {
+            SetDisplayMessageStatus::Accepted => Self::Accepted,
+            SetDisplayMessageStatus::NotSupportedMessageFormat => Self::NotSupportedMessageFormat,
+            SetDisplayMessageStatus::Rejected => Self::Rejected,
+            SetDisplayMessageStatus::NotSupportedPriority => Self::NotSupportedPriority,
+            SetDisplayMessageStatus::NotSupportedState => Self::NotSupportedState,
+            SetDisplayMessageStatus::UnknownTransaction => Self::UnknownTransaction,
+            SetDisplayMessageStatus::LanguageNotSupported => Self::LanguageNotSupported,
+        }
+    }
+}
+
+/// Handles a `SetDisplayMessageRequest`, with the ongoing transactions in `context`.
+pub fn set_display_message(
+    manager: &mut DisplayMessageManager,
+    request: &SetDisplayMessageRequest,
+    context: &DisplayContext,
+) -> SetDisplayMessageResponse {
+    SetDisplayMessageResponse::new(manager.set((&request.message).into(), context).into())
+}
+
+/// Handles a `GetDisplayMessagesRequest`, returning the response and the
+/// `NotifyDisplayMessagesRequest`s of at most `max_messages` messages each to send after it.
+///
+/// The status is `Unknown`, without requests, when no message matches.
+pub fn get_display_messages(
+    manager: &DisplayMessageManager,
+    request: &GetDisplayMessagesRequest,
+    max_messages: usize,
+) -> (
+    GetDisplayMessagesResponse,
+    Vec<NotifyDisplayMessagesRequest>,
+) {
+    let messages = manager.find(&request.into());
+    let status = match messages.is_empty() {
+        true => GetDisplayMessagesStatusEnumType::Unknown,
+        false => GetDisplayMessagesStatusEnumType::Accepted,
+    };
+    let response = GetDisplayMessagesResponse {
+        status,
+        status_info: None,
+        custom_data: None,
+    };
+    let chunks = messages.chunks(max_messages.max(1));
+    let last = chunks.len().saturating_sub(1);
+    let requests = chunks
+        .enumerate()
+        .map(|(index, chunk)| NotifyDisplayMessagesRequest {
+            request_id: request.request_id,
+            tbc: (index < last).then_some(true),
+            message_info: chunk.iter().map(|&message| message.into()).collect(),
+            custom_data: None,
+        })
+        .collect();
+    (response, requests)
+}
+
+/// Handles a `ClearDisplayMessageRequest`.
+pub fn clear_display_message(
+    manager: &mut DisplayMessageManager,
+    request: &ClearDisplayMessageRequest,
+) -> ClearDisplayMessageResponse {
+    ClearDisplayMessageResponse {
+        status: match manager.clear(request.id) {
+            true => ClearMessageStatusEnumType::Accepted,
+            false => ClearMessageStatusEnumType::Unknown,
+        },
+        status_info: None,
+        custom_data: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::components::display_messages::DisplayCapabilities;
+
+    fn content(language: &str, content: &str) -> MessageContentType {
+        MessageContentType {
+            content: content.to_string(),
+            format: MessageFormatEnumType::UTF8,
+            language: language.to_string(),
+            custom_data: None,
+        }
+    }
+
+    fn message_info(id: i32, priority: MessagePriorityEnumType) -> MessageInfoType {
+        MessageInfoType {
+            display: None,
+            id,
+            priority,
+            state: None,
+            start_date_time: None,
+            end_date_time: None,
+            transaction_id: None,
+            message: content("en", &format!("message {id}")),
+            message_extra: None,
+            custom_data: None,
+        }
+    }
+
+    #[test]
+    fn set_get_and_clear() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let capabilities = DisplayCapabilities {
+            languages: vec!["en".to_string(), "nl".to_string()],
+            ..Default::default()
+        };
+        let mut manager = DisplayMessageManager::new(capabilities);
+        let suspended = DisplayContext::new(MessageState::Suspended);
+
+        let mut info = message_info(1, MessagePriorityEnumType::InFront);
+        info.state = Some(MessageStateEnumType::Suspended);
+        info.message_extra = Some(vec![content("nl", "bericht 1")]);
+        let request = SetDisplayMessageRequest::new(info.clone());
+        let response = set_display_message(&mut manager, &request, &suspended);
+        assert_eq!(response.status, DisplayMessageStatusEnumType::Accepted);
+
+        let mut german = message_info(2, MessagePriorityEnumType::NormalCycle);
+        german.message_extra = Some(vec![content("de", "Nachricht 2")]);
+        let request = SetDisplayMessageRequest::new(german);
+        let response = set_display_message(&mut manager, &request, &suspended);
+        assert_eq!(
+            response.status,
+            DisplayMessageStatusEnumType::LanguageNotSupported
+        );
+
+        let request =
+            SetDisplayMessageRequest::new(message_info(2, MessagePriorityEnumType::NormalCycle));
+        set_display_message(&mut manager, &request, &suspended);
+        assert_eq!(manager.visible(&suspended, now).unwrap().id, 1);
+        let idle = DisplayContext::new(MessageState::Idle);
+        assert_eq!(manager.visible(&idle, now).unwrap().id, 2);
+
+        let request = GetDisplayMessagesRequest {
+            request_id: 3,
+            id: None,
+            priority: None,
+            state: None,
+            custom_data: None,
+        };
+        let (response, requests) = get_display_messages(&manager, &request, 1);
+        assert_eq!(response.status, GetDisplayMessagesStatusEnumType::Accepted);
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].tbc, Some(true));
+        assert_eq!(requests[0].message_info, vec![info]);
+        assert_eq!(requests[1].tbc, None);
+
+        let request = ClearDisplayMessageRequest {
+            id: 1,
+            custom_data: None,
+        };
+        let response = clear_display_message(&mut manager, &request);
+        assert_eq!(response.status, ClearMessageStatusEnumType::Accepted);
+        let response = clear_display_message(&mut manager, &request);
+        assert_eq!(response.status, ClearMessageStatusEnumType::Unknown);
+    }
+}
@@ -0,0 +1,142 @@
+//! # Event streams
+//!
+//! Data of the periodic event streams of OCPP 2.1, opened with `OpenPeriodicEventStream`,
+//! adjusted with `AdjustPeriodicEventStream`, closed with `ClosePeriodicEventStream` and
+//! carrying the samples of a monitored variable in `NotifyPeriodicEventStream`.
+//!
+//! On the Charging Station side an [`EventStream`] buffers [`StreamSample`]s and encodes
+//! them into `StreamDataElementType`s with offsets relative to the `basetime` of the
+//! message, sending when the `values` of its `PeriodicEventStreamParamsType` are collected
+//! or its `interval` has passed. [`EventStreams`] holds the open streams and answers
+//! `AdjustPeriodicEventStream` and `GetPeriodicEventStream`. On the CSMS side the
+//! [`StreamReceiver`] tracks the opened streams and decodes the data back into samples.
+//!
+//! `NotifyPeriodicEventStream` is a SEND message, which has no response and is not
+//! retried. Samples are removed from the stream as soon as they are encoded, and the
+//! receiver reports problems with a message to its caller only, as there is nothing to
+//! answer.
+//!
+//! Offsets are kept as exact decimal seconds with up to nanosecond precision, so that
+//! `basetime` plus offset gives back the timestamp of each sample.
+
+use std::fmt;
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+
+mod receiver;
+mod sender;
+
+pub use receiver::{decode, StreamReceiver};
+pub use sender::{EventStream, EventStreams};
+
+/// Value of the monitored variable of a stream at a time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamSample {
+    /// Time the value was recorded.
+    pub timestamp: DateTime<Utc>,
+    /// Value of the variable.
+    pub value: String,
+}
+
+impl StreamSample {
+    /// Creates a sample.
+    pub fn new(timestamp: DateTime<Utc>, value: impl Into<String>) -> Self {
+        Self {
+            timestamp,
+            value: value.into(),
+        }
+    }
+}
+
+/// Reason why stream data is not accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamError {
+    /// No stream with this id is open.
+    UnknownStream(i32),
+    /// A stream with this id is already open.
+    DuplicateStream(i32),
+    /// The parameters of the stream are out of range.
+    InvalidParams,
+    /// `basetime` plus the offset is not a valid time.
+    InvalidOffset(Decimal),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownStream(id) => write!(f, "no periodic event stream {id} is open"),
+            Self::DuplicateStream(id) => write!(f, "periodic event stream {id} is already open"),
+            Self::InvalidParams => write!(f, "invalid periodic event stream parameters"),
+            Self::InvalidOffset(offset) => write!(f, "invalid offset {offset}"),
+        }
+    }
+}
+
+impl StreamError {
+    /// Reason code reported in the `StatusInfoType` of a rejected request.
+    pub fn reason_code(&self) -> &'static str {
+        match self {
+            Self::UnknownStream(_) => "NotFound",
+            Self::DuplicateStream(_) => "DuplicateRequest",
+            Self::InvalidParams | Self::InvalidOffset(_) => "InvalidValue",
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+/// Offset in seconds of `timestamp` relative to `basetime`.
+pub fn offset(basetime: DateTime<Utc>, timestamp: DateTime<Utc>) -> Decimal {
+    let elapsed = timestamp - basetime;
+    let seconds = elapsed.num_seconds();
+    let nanoseconds = (elapsed - Duration::seconds(seconds))
+        .num_nanoseconds()
+        .unwrap_or_default();
+    (Decimal::from(seconds) + Decimal::new(nanoseconds, 9)).normalize()
+}
+
+/// Time at `offset` seconds after `basetime`, rounded to nanoseconds.
+pub fn timestamp(basetime: DateTime<Utc>, offset: Decimal) -> Result<DateTime<Utc>, StreamError> {
+    let invalid = || StreamError::InvalidOffset(offset);
+    let seconds = offset.trunc().to_i64().ok_or_else(invalid)?;
+    let nanoseconds = (offset.fract() * Decimal::from(1_000_000_000))
+        .round()
+        .to_i64()
+        .ok_or_else(invalid)?;
+    let elapsed =
+        Duration::try_seconds(seconds).ok_or_else(invalid)? + Duration::nanoseconds(nanoseconds);
+    basetime.checked_add_signed(elapsed).ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn offsets_round_trip() {
+        let basetime = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let later = basetime + Duration::milliseconds(1500) + Duration::nanoseconds(7);
+        assert_eq!(offset(basetime, later), dec!(1.500000007));
+        assert_eq!(timestamp(basetime, dec!(1.500000007)), Ok(later));
+        assert_eq!(offset(basetime, basetime), dec!(0));
+
+        let earlier = basetime - Duration::milliseconds(250);
+        assert_eq!(offset(basetime, earlier), dec!(-0.25));
+        assert_eq!(timestamp(basetime, dec!(-0.25)), Ok(earlier));
+
+        // 0.1 + 0.2 is exact in decimal, unlike in f64
+        let t = dec!(0.1) + dec!(0.2);
+        assert_eq!(
+            timestamp(basetime, t),
+            Ok(basetime + Duration::milliseconds(300))
+        );
+        assert_eq!(
+            timestamp(basetime, Decimal::MAX),
+            Err(StreamError::InvalidOffset(Decimal::MAX))
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::BTreeMap;
+
+use validator::Validate;
+
+use crate::v2_1::{
+    datatypes::{ConstantStreamDataType, PeriodicEventStreamParamsType, StatusInfoType},
+    enumerations::GenericStatusEnumType,
+    messages::{
+        adjust_periodic_event_stream::{
+            AdjustPeriodicEventStreamRequest, AdjustPeriodicEventStreamResponse,
+        },
+        close_periodic_event_stream::{
+            ClosePeriodicEventStreamRequest, ClosePeriodicEventStreamResponse,
+        },
+        notify_periodic_event_stream::NotifyPeriodicEventStreamRequest,
+        open_periodic_event_stream::{
+            OpenPeriodicEventStreamRequest, OpenPeriodicEventStreamResponse,
+        },
+    },
+};
+
+use super::{timestamp, StreamError, StreamSample};
+
+/// Periodic event streams opened by a Charging Station, as seen by the CSMS.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreamReceiver {
+    streams: BTreeMap<i32, ConstantStreamDataType>,
+}
+
+impl StreamReceiver {
+    /// Creates a receiver without streams.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Id, parameters and monitor of stream `id`.
+    pub fn stream(&self, id: i32) -> Option<&ConstantStreamDataType> {
+        self.streams.get(&id)
+    }
+
+    /// Handles an `OpenPeriodicEventStreamRequest`, rejecting a stream whose id is already
+    /// open or whose parameters are out of range.
+    pub fn open(
+        &mut self,
+        request: &OpenPeriodicEventStreamRequest,
+    ) -> OpenPeriodicEventStreamResponse {
+        let data = &request.constant_stream_data;
+        let result = match self.streams.contains_key(&data.id) {
+            true => Err(StreamError::DuplicateStream(data.id)),
+            false => data.validate().map_err(|_| StreamError::InvalidParams),
+        };
+        if result.is_ok() {
+            self.streams.insert(data.id, data.clone());
+        }
+        OpenPeriodicEventStreamResponse {
+            status: match result {
+                Ok(()) => GenericStatusEnumType::Accepted,
+                Err(_) => GenericStatusEnumType::Rejected,
+            },
+            status_info: result.err().as_ref().map(StatusInfoType::from),
+            custom_data: None,
+        }
+    }
+
+    /// Records the parameters of an `AdjustPeriodicEventStreamRequest` accepted by the
+    /// Charging Station.
+    pub fn adjusted(
+        &mut self,
+        request: &AdjustPeriodicEventStreamRequest,
+        response: &AdjustPeriodicEventStreamResponse,
+    ) {
+        if response.status != GenericStatusEnumType::Accepted {
+            return;
+        }
+        if let Some(stream) = self.streams.get_mut(&request.id) {
+            stream.params = PeriodicEventStreamParamsType {
+                interval: request.params.interval,
+                values: request.params.values,
+                custom_data: request.params.custom_data.clone(),
+            };
+        }
+    }
+
+    /// Handles a `ClosePeriodicEventStreamRequest`.
+    pub fn close(
+        &mut self,
+        request: &ClosePeriodicEventStreamRequest,
+    ) -> ClosePeriodicEventStreamResponse {
+        self.streams.remove(&request.id);
+        ClosePeriodicEventStreamResponse { custom_data: None }
+    }
+
+    /// Decodes the samples of a `NotifyPeriodicEventStreamRequest` of an open stream.
+    ///
+    /// The message has no response, so an error only tells the caller to drop the data.
+    pub fn receive(
+        &self,
+        request: &NotifyPeriodicEventStreamRequest,
+    ) -> Result<Vec<StreamSample>, StreamError> {
+        if !self.streams.contains_key(&request.id) {
+            return Err(StreamError::UnknownStream(request.id));
+        }
+        decode(request)
+    }
+}
+
+/// Samples of a `NotifyPeriodicEventStreamRequest`, at `basetime` plus the offset of each
+/// data element.
+pub fn decode(
+    request: &NotifyPeriodicEventStreamRequest,
+) -> Result<Vec<StreamSample>, StreamError> {
+    request
+        .data
+        .iter()
+        .map(|element| {
+            Ok(StreamSample {
+                timestamp: timestamp(request.basetime, element.offset)?,
+                value: element.value.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::{
+        components::event_streams::EventStream,
+        v2_1::{datatypes::StreamDataElementType, messages::adjust_periodic_event_stream},
+    };
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn decodes_what_the_stream_encodes() {
+        let data = ConstantStreamDataType::new(2, PeriodicEventStreamParamsType::new(0, 3), 7);
+        let mut stream = EventStream::new(data, now());
+        let mut receiver = StreamReceiver::new();
+        let response = receiver.open(&stream.open_request());
+        assert_eq!(response.status, GenericStatusEnumType::Accepted);
+        let response = receiver.open(&stream.open_request());
+        assert_eq!(
+            response.status_info.unwrap().reason_code,
+            "DuplicateRequest"
+        );
+
+        let samples: Vec<_> = [0, 333, 1_000_001]
+            .into_iter()
+            .map(|micros| StreamSample::new(now() + Duration::microseconds(micros), "230.1"))
+            .collect();
+        let notify = samples
+            .iter()
+            .filter_map(|sample| stream.record(sample.clone()))
+            .next()
+            .unwrap();
+
+        // offsets survive serialization exactly
+        let json = serde_json::to_string(&notify).unwrap();
+        assert!(json.contains(r#""t":1.000001"#));
+        let notify: NotifyPeriodicEventStreamRequest = serde_json::from_str(&json).unwrap();
+        assert_eq!(receiver.receive(&notify), Ok(samples));
+
+        receiver.close(&stream.close_request());
+        assert_eq!(
+            receiver.receive(&notify),
+            Err(StreamError::UnknownStream(2))
+        );
+    }
+
+    #[test]
+    fn tracks_adjusted_params() {
+        let mut receiver = StreamReceiver::new();
+        let data = ConstantStreamDataType::new(2, PeriodicEventStreamParamsType::new(60, 0), 7);
+        receiver.open(&OpenPeriodicEventStreamRequest {
+            constant_stream_data: data,
+            custom_data: None,
+        });
+        let request = AdjustPeriodicEventStreamRequest {
+            custom_data: None,
+            id: 2,
+            params: adjust_periodic_event_stream::PeriodicEventStreamParamsType {
+                custom_data: None,
+                interval: 10,
+                values: 5,
+            },
+        };
+        let mut response = AdjustPeriodicEventStreamResponse {
+            custom_data: None,
+            status: GenericStatusEnumType::Rejected,
+            status_info: None,
+        };
+        receiver.adjusted(&request, &response);
+        assert_eq!(receiver.stream(2).unwrap().params.interval, 60);
+        response.status = GenericStatusEnumType::Accepted;
+        receiver.adjusted(&request, &response);
+        assert_eq!(receiver.stream(2).unwrap().params.values, 5);
+
+        let notify = NotifyPeriodicEventStreamRequest {
+            basetime: now(),
+            data: vec![StreamDataElementType::new(Decimal::MAX, "1".to_string())],
+            id: 2,
+            pending: 0,
+            custom_data: None,
+        };
+        assert!(matches!(
+            receiver.receive(&notify),
+            Err(StreamError::InvalidOffset(_))
+        ));
+    }
+}
@@ -0,0 +1,383 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use chrono::{DateTime, Duration, Utc};
+use validator::Validate;
+
+use crate::v2_1::{
+    datatypes::{
+        ConstantStreamDataType, PeriodicEventStreamParamsType, StatusInfoType,
+        StreamDataElementType,
+    },
+    enumerations::GenericStatusEnumType,
+    messages::{
+        adjust_periodic_event_stream::{
+            AdjustPeriodicEventStreamRequest, AdjustPeriodicEventStreamResponse,
+        },
+        close_periodic_event_stream::ClosePeriodicEventStreamRequest,
+        get_periodic_event_stream::{
+            GetPeriodicEventStreamRequest, GetPeriodicEventStreamResponse,
+        },
+        notify_periodic_event_stream::NotifyPeriodicEventStreamRequest,
+        open_periodic_event_stream::{
+            OpenPeriodicEventStreamRequest, OpenPeriodicEventStreamResponse,
+        },
+    },
+};
+
+use super::{offset, StreamError, StreamSample};
+
+/// One periodic event stream of a Charging Station.
+///
+/// Data is due when `values` samples are buffered or `interval` seconds have passed since
+/// the previous message. A parameter of zero imposes no limit, with both zero every sample
+/// is sent on its own. A message carries at most `values` samples, the remaining samples
+/// are reported as `pending`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventStream {
+    data: ConstantStreamDataType,
+    samples: VecDeque<StreamSample>,
+    last_sent: DateTime<Utc>,
+}
+
+impl EventStream {
+    /// Creates a stream opened at `now`.
+    pub fn new(data: ConstantStreamDataType, now: DateTime<Utc>) -> Self {
+        Self {
+            data,
+            samples: VecDeque::new(),
+            last_sent: now,
+        }
+    }
+
+    /// Id of the stream.
+    pub fn id(&self) -> i32 {
+        self.data.id
+    }
+
+    /// Id, parameters and monitor of the stream.
+    pub fn constant_stream_data(&self) -> &ConstantStreamDataType {
+        &self.data
+    }
+
+    /// Number of samples not sent yet.
+    pub fn pending(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Request that opens the stream.
+    pub fn open_request(&self) -> OpenPeriodicEventStreamRequest {
+        OpenPeriodicEventStreamRequest {
+            constant_stream_data: self.data.clone(),
+            custom_data: None,
+        }
+    }
+
+    /// Request that closes the stream.
+    pub fn close_request(&self) -> ClosePeriodicEventStreamRequest {
+        ClosePeriodicEventStreamRequest {
+            id: self.id(),
+            custom_data: None,
+        }
+    }
+
+    /// Replaces the parameters of the stream.
+    pub fn adjust(&mut self, params: PeriodicEventStreamParamsType) -> Result<(), StreamError> {
+        params.validate().map_err(|_| StreamError::InvalidParams)?;
+        self.data.params = params;
+        Ok(())
+    }
+
+    /// Buffers a sample and returns the data to send, if due at the time of the sample.
+    pub fn record(&mut self, sample: StreamSample) -> Option<NotifyPeriodicEventStreamRequest> {
+        let now = sample.timestamp;
+        let index = self
+            .samples
+            .partition_point(|other| other.timestamp <= sample.timestamp);
+        self.samples.insert(index, sample);
+        self.tick(now)
+    }
+
+    /// Advances time to `now` and returns the data to send, if due.
+    pub fn tick(&mut self, now: DateTime<Utc>) -> Option<NotifyPeriodicEventStreamRequest> {
+        let params = &self.data.params;
+        let interval_passed = params.interval > 0
+            && now - self.last_sent >= Duration::seconds(params.interval.into());
+        let values_collected = params.values > 0 && self.samples.len() >= params.values as usize;
+        let unlimited = params.interval == 0 && params.values == 0;
+        if interval_passed && self.samples.is_empty() {
+            self.last_sent = now;
+        }
+        match interval_passed || values_collected || unlimited {
+            true => self.flush(now),
+            false => None,
+        }
+    }
+
+    /// Returns the buffered samples to send regardless of the parameters, such as before
+    /// closing the stream.
+    ///
+    /// The samples are removed from the stream, as the message is not acknowledged.
+    pub fn flush(&mut self, now: DateTime<Utc>) -> Option<NotifyPeriodicEventStreamRequest> {
+        let basetime = self.samples.front()?.timestamp;
+        let count = match self.data.params.values {
+            values if values > 0 => self.samples.len().min(values as usize),
+            _ => self.samples.len(),
+        };
+        let data = self
+            .samples
+            .drain(..count)
+            .map(|sample| {
+                StreamDataElementType::new(offset(basetime, sample.timestamp), sample.value)
+            })
+            .collect();
+        self.last_sent = now;
+        Some(NotifyPeriodicEventStreamRequest {
+            basetime,
+            data,
+            id: self.id(),
+            pending: i32::try_from(self.samples.len()).unwrap_or(i32::MAX),
+            custom_data: None,
+        })
+    }
+}
+
+/// Open periodic event streams of a Charging Station, by id.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventStreams {
+    streams: BTreeMap<i32, EventStream>,
+}
+
+impl EventStreams {
+    /// Creates a Charging Station without streams.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens a stream at `now` and returns the request to send.
+    pub fn open(
+        &mut self,
+        data: ConstantStreamDataType,
+        now: DateTime<Utc>,
+    ) -> Result<OpenPeriodicEventStreamRequest, StreamError> {
+        if self.streams.contains_key(&data.id) {
+            return Err(StreamError::DuplicateStream(data.id));
+        }
+        data.validate().map_err(|_| StreamError::InvalidParams)?;
+        let stream = EventStream::new(data, now);
+        let request = stream.open_request();
+        self.streams.insert(stream.id(), stream);
+        Ok(request)
+    }
+
+    /// Handles the response to opening stream `id`, forgetting the stream when the CSMS
+    /// rejected it. Returns whether the stream is open.
+    pub fn opened(&mut self, id: i32, response: &OpenPeriodicEventStreamResponse) -> bool {
+        if response.status == GenericStatusEnumType::Rejected {
+            self.streams.remove(&id);
+        }
+        self.streams.contains_key(&id)
+    }
+
+    /// Stream with id `id`.
+    pub fn get(&self, id: i32) -> Option<&EventStream> {
+        self.streams.get(&id)
+    }
+
+    /// Stream with id `id`, to record samples.
+    pub fn get_mut(&mut self, id: i32) -> Option<&mut EventStream> {
+        self.streams.get_mut(&id)
+    }
+
+    /// Advances time of all streams to `now` and returns the data to send.
+    pub fn tick(&mut self, now: DateTime<Utc>) -> Vec<NotifyPeriodicEventStreamRequest> {
+        self.streams
+            .values_mut()
+            .filter_map(|stream| stream.tick(now))
+            .collect()
+    }
+
+    /// Closes stream `id` and returns its remaining data followed by the request to send.
+    pub fn close(
+        &mut self,
+        id: i32,
+        now: DateTime<Utc>,
+    ) -> Option<(
+        Vec<NotifyPeriodicEventStreamRequest>,
+        ClosePeriodicEventStreamRequest,
+    )> {
+        let mut stream = self.streams.remove(&id)?;
+        let data = std::iter::from_fn(|| stream.flush(now)).collect();
+        Some((data, stream.close_request()))
+    }
+
+    /// Handles an `AdjustPeriodicEventStreamRequest` of the CSMS.
+    pub fn adjust(
+        &mut self,
+        request: &AdjustPeriodicEventStreamRequest,
+    ) -> AdjustPeriodicEventStreamResponse {
+        let params = PeriodicEventStreamParamsType {
+            interval: request.params.interval,
+            values: request.params.values,
+            custom_data: request.params.custom_data.clone(),
+        };
+        let result = match self.streams.get_mut(&request.id) {
+            Some(stream) => stream.adjust(params),
+            None => Err(StreamError::UnknownStream(request.id)),
+        };
+        AdjustPeriodicEventStreamResponse {
+            custom_data: None,
+            status: match result {
+                Ok(()) => GenericStatusEnumType::Accepted,
+                Err(_) => GenericStatusEnumType::Rejected,
+            },
+            status_info: result.err().as_ref().map(StatusInfoType::from),
+        }
+    }
+
+    /// Handles a `GetPeriodicEventStreamRequest` of the CSMS.
+    pub fn get_response(
+        &self,
+        _request: &GetPeriodicEventStreamRequest,
+    ) -> GetPeriodicEventStreamResponse {
+        let data: Vec<_> = self
+            .streams
+            .values()
+            .map(|stream| stream.data.clone())
+            .collect();
+        GetPeriodicEventStreamResponse {
+            constant_stream_data: Some(data).filter(|data| !data.is_empty()),
+            custom_data: None,
+        }
+    }
+}
+
+impl From<&StreamError> for StatusInfoType {
+    fn from(error: &StreamError) -> Self {
+        StatusInfoType::new(error.reason_code().to_string()).with_additional_info(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::v2_1::messages::adjust_periodic_event_stream;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn sample(millis: i64, value: &str) -> StreamSample {
+        StreamSample::new(now() + Duration::milliseconds(millis), value)
+    }
+
+    fn stream(interval: i32, values: i32) -> EventStream {
+        let params = PeriodicEventStreamParamsType::new(interval, values);
+        EventStream::new(ConstantStreamDataType::new(1, params, 7), now())
+    }
+
+    #[test]
+    fn sends_when_values_are_collected() {
+        let mut stream = stream(0, 3);
+        assert!(stream.record(sample(100, "1")).is_none());
+        assert!(stream.record(sample(350, "2")).is_none());
+        let notify = stream.record(sample(1600, "3")).unwrap();
+        assert_eq!(notify.basetime, now() + Duration::milliseconds(100));
+        let offsets: Vec<_> = notify.data.iter().map(|element| element.offset).collect();
+        assert_eq!(offsets, vec![dec!(0), dec!(0.25), dec!(1.5)]);
+        assert_eq!(notify.data[2].value, "3");
+        assert_eq!((notify.id, notify.pending), (1, 0));
+        assert_eq!(stream.pending(), 0);
+        assert!(stream.flush(now()).is_none());
+    }
+
+    #[test]
+    fn sends_when_interval_passed() {
+        let mut stream = stream(10, 0);
+        assert!(stream.record(sample(1000, "1")).is_none());
+        assert!(stream.tick(now() + Duration::seconds(9)).is_none());
+        let notify = stream.tick(now() + Duration::seconds(10)).unwrap();
+        assert_eq!(notify.data.len(), 1);
+        assert!(stream.record(sample(15_000, "2")).is_none());
+
+        // a message carries at most `values` samples after lowering it
+        let mut params = PeriodicEventStreamParamsType::new(10, 0);
+        stream.adjust(params.clone()).unwrap();
+        for millis in [16_000, 17_000] {
+            assert!(stream.record(sample(millis, "3")).is_none());
+        }
+        params.values = 2;
+        stream.adjust(params).unwrap();
+        let notify = stream.tick(now() + Duration::seconds(20)).unwrap();
+        assert_eq!((notify.data.len(), notify.pending), (2, 1));
+        let notify = stream.flush(now() + Duration::seconds(20)).unwrap();
+        assert_eq!((notify.data.len(), notify.pending), (1, 0));
+    }
+
+    #[test]
+    fn sends_every_sample_without_limits() {
+        let mut stream = stream(0, 0);
+        assert_eq!(stream.record(sample(0, "1")).unwrap().data.len(), 1);
+        assert_eq!(
+            stream.adjust(PeriodicEventStreamParamsType::new(-1, 0)),
+            Err(StreamError::InvalidParams)
+        );
+    }
+
+    #[test]
+    fn manages_streams() {
+        let mut streams = EventStreams::new();
+        let data = ConstantStreamDataType::new(4, PeriodicEventStreamParamsType::new(60, 10), 7);
+        let request = streams.open(data.clone(), now()).unwrap();
+        assert_eq!(request.constant_stream_data, data);
+        assert_eq!(
+            streams.open(data.clone(), now()),
+            Err(StreamError::DuplicateStream(4))
+        );
+        let response = OpenPeriodicEventStreamResponse {
+            status: GenericStatusEnumType::Accepted,
+            status_info: None,
+            custom_data: None,
+        };
+        assert!(streams.opened(4, &response));
+
+        let adjust = |id, interval| AdjustPeriodicEventStreamRequest {
+            custom_data: None,
+            id,
+            params: adjust_periodic_event_stream::PeriodicEventStreamParamsType {
+                custom_data: None,
+                interval,
+                values: 10,
+            },
+        };
+        let response = streams.adjust(&adjust(4, 30));
+        assert_eq!(response.status, GenericStatusEnumType::Accepted);
+        let response = streams.adjust(&adjust(5, 30));
+        assert_eq!(response.status_info.unwrap().reason_code, "NotFound");
+        let response = streams.adjust(&adjust(4, 100_000));
+        assert_eq!(response.status, GenericStatusEnumType::Rejected);
+
+        let stream = streams.get_mut(4).unwrap();
+        assert!(stream.record(sample(0, "1")).is_none());
+        let notifies = streams.tick(now() + Duration::seconds(30));
+        assert_eq!(notifies.len(), 1);
+        assert!(streams
+            .get_mut(4)
+            .unwrap()
+            .record(sample(31_000, "2"))
+            .is_none());
+
+        let response = streams.get_response(&GetPeriodicEventStreamRequest { custom_data: None });
+        let data = response.constant_stream_data.unwrap();
+        assert_eq!(data[0].params.interval, 30);
+
+        let (notifies, close) = streams.close(4, now() + Duration::seconds(40)).unwrap();
+        assert_eq!(notifies.len(), 1);
+        assert_eq!(close.id, 4);
+        assert!(streams.get(4).is_none());
+        let response = streams.get_response(&GetPeriodicEventStreamRequest { custom_data: None });
+        assert_eq!(response.constant_stream_data, None);
+    }
+}
@@ -0,0 +1,482 @@
+use chrono::{DateTime, Duration, Utc};
+
+use super::{Retries, SequenceError};
+
+/// Status of a firmware update, `FirmwareStatusEnumType` in 2.x and `FirmwareStatus` in 1.6.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FirmwareStatus {
+    /// No firmware update in progress.
+    Idle,
+    /// Waiting for the retrieve date time to download.
+    DownloadScheduled,
+    /// Downloading the firmware.
+    Downloading,
+    /// Download paused, for example during a transaction.
+    DownloadPaused,
+    /// Firmware downloaded.
+    Downloaded,
+    /// Download failed, possibly followed by a retry.
+    DownloadFailed,
+    /// Signature of the downloaded firmware verified.
+    SignatureVerified,
+    /// Signature of the downloaded firmware invalid.
+    InvalidSignature,
+    /// Waiting for the install date time to install.
+    InstallScheduled,
+    /// Installing the firmware.
+    Installing,
+    /// Rebooting to install or to activate the firmware.
+    InstallRebooting,
+    /// Firmware installed.
+    Installed,
+    /// Installation failed.
+    InstallationFailed,
+    /// Verification of the installed firmware failed.
+    InstallVerificationFailed,
+}
+
+impl FirmwareStatus {
+    /// Statuses that may follow this status.
+    pub fn successors(self) -> &'static [FirmwareStatus] {
+        use FirmwareStatus::*;
+        match self {
+            Idle => &[DownloadScheduled, Downloading],
+            DownloadScheduled => &[Downloading],
+            Downloading => &[Downloaded, DownloadFailed, DownloadPaused],
+            DownloadPaused => &[Downloading, DownloadFailed],
+            DownloadFailed => &[Downloading],
+            Downloaded => &[
+                SignatureVerified,
+                InvalidSignature,
+                InstallScheduled,
+                Installing,
+                InstallRebooting,
+            ],
+            SignatureVerified => &[InstallScheduled, Installing, InstallRebooting],
+            InstallScheduled => &[Installing, InstallRebooting],
+            Installing | InstallRebooting => &[
+                Installing,
+                InstallRebooting,
+                Installed,
+                InstallationFailed,
+                InstallVerificationFailed,
+            ],
+            InvalidSignature | Installed | InstallationFailed | InstallVerificationFailed => &[],
+        }
+    }
+
+    fn is_install(self) -> bool {
+        matches!(
+            self,
+            Self::InstallScheduled | Self::Installing | Self::InstallRebooting
+        )
+    }
+}
+
+/// Status sequence of one firmware update request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirmwareUpdate {
+    request_id: i32,
+    retrieve_date_time: DateTime<Utc>,
+    install_date_time: Option<DateTime<Utc>>,
+    retries: Retries,
+    signed: bool,
+    intermediate_statuses: bool,
+    status: FirmwareStatus,
+    updated_at: DateTime<Utc>,
+    history: Vec<(FirmwareStatus, DateTime<Utc>)>,
+}
+
+impl FirmwareUpdate {
+    /// Creates the update of a request accepted at `requested_at`, without retries, install
+    /// date time or signature.
+    pub fn new(
+        request_id: i32,
+        retrieve_date_time: DateTime<Utc>,
+        requested_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            request_id,
+            retrieve_date_time,
+            install_date_time: None,
+            retries: Retries::default(),
+            signed: false,
+            intermediate_statuses: true,
+            status: FirmwareStatus::Idle,
+            updated_at: requested_at,
+            history: Vec::new(),
+        }
+    }
+
+    /// Sets the date time from which the firmware may be installed.
+    pub fn with_install_date_time(mut self, install_date_time: DateTime<Utc>) -> Self {
+        self.install_date_time = Some(install_date_time);
+        self
+    }
+
+    /// Sets the retries of a failed download.
+    pub fn with_retries(mut self, retries: Retries) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Requires the signature to be verified before installing.
+    pub fn with_signature(mut self) -> Self {
+        self.signed = true;
+        self
+    }
+
+    /// Sets whether the statuses added in 2.0.1 are reported, which is not the case in 1.6:
+    /// without them downloads and installations wait silently for their date time, and
+    /// installing ends with `Installed` or `InstallationFailed`.
+    pub fn with_intermediate_statuses(mut self, intermediate_statuses: bool) -> Self {
+        self.intermediate_statuses = intermediate_statuses;
+        self
+    }
+
+    /// Request id of the update.
+    pub fn request_id(&self) -> i32 {
+        self.request_id
+    }
+
+    /// Last reported status, `Idle` before the first notification.
+    pub fn status(&self) -> FirmwareStatus {
+        self.status
+    }
+
+    /// Retries left.
+    pub fn retries(&self) -> Retries {
+        self.retries
+    }
+
+    /// Reported statuses with the time they were reported.
+    pub fn history(&self) -> &[(FirmwareStatus, DateTime<Utc>)] {
+        &self.history
+    }
+
+    /// Whether no further status is expected.
+    pub fn is_finished(&self) -> bool {
+        match self.status {
+            FirmwareStatus::DownloadFailed => self.retries.remaining == 0,
+            status => status != FirmwareStatus::Idle && status.successors().is_empty(),
+        }
+    }
+
+    /// Applies a status reported at `now`.
+    ///
+    /// `Idle` is accepted before the first and after the last status, as reported when
+    /// triggered without an update in progress.
+    pub fn report(
+        &mut self,
+        status: FirmwareStatus,
+        now: DateTime<Utc>,
+    ) -> Result<(), SequenceError<FirmwareStatus>> {
+        let invalid = SequenceError::InvalidTransition {
+            from: self.status,
+            to: status,
+        };
+        if status == FirmwareStatus::Idle {
+            return match self.status == FirmwareStatus::Idle || self.is_finished() {
+                true => Ok(()),
+                false => Err(invalid),
+            };
+        }
+        if !self.status.successors().contains(&status) {
+            return Err(invalid);
+        }
+        let unverified = self.signed && self.status == FirmwareStatus::Downloaded;
+        if unverified && status.is_install() {
+            return Err(invalid);
+        }
+        let earliest = match (self.status, status) {
+            (FirmwareStatus::DownloadFailed, _) if self.retries.remaining == 0 => {
+                return Err(SequenceError::RetriesExhausted);
+            }
+            (FirmwareStatus::DownloadFailed, _) => Some(self.updated_at + self.retries.interval),
+            (_, FirmwareStatus::Downloading) => Some(self.retrieve_date_time),
+            (_, FirmwareStatus::Installing | FirmwareStatus::InstallRebooting) => {
+                self.install_date_time
+            }
+            _ => None,
+        };
+        if let Some(earliest) = earliest.filter(|earliest| now < *earliest) {
+            return Err(SequenceError::TooEarly { status, earliest });
+        }
+        if self.status == FirmwareStatus::DownloadFailed {
+            self.retries.remaining -= 1;
+        }
+        self.status = status;
+        self.updated_at = now;
+        self.history.push((status, now));
+        Ok(())
+    }
+
+    /// Time by which the next status is expected, allowing `timeout` for each step after
+    /// the retrieve, install or retry time. `None` when the update is finished.
+    pub fn expected_by(&self, timeout: Duration) -> Option<DateTime<Utc>> {
+        if self.is_finished() {
+            return None;
+        }
+        let start = match self.status {
+            FirmwareStatus::Idle | FirmwareStatus::DownloadScheduled => {
+                self.updated_at.max(self.retrieve_date_time)
+            }
+            FirmwareStatus::InstallScheduled => self
+                .install_date_time
+                .map_or(self.updated_at, |install| self.updated_at.max(install)),
+            FirmwareStatus::DownloadFailed => self.updated_at + self.retries.interval,
+            _ => self.updated_at,
+        };
+        Some(start + timeout)
+    }
+
+    /// Whether the next status is overdue at `now`, see [`Self::expected_by`].
+    pub fn is_stuck(&self, now: DateTime<Utc>, timeout: Duration) -> bool {
+        self.expected_by(timeout)
+            .is_some_and(|expected_by| now > expected_by)
+    }
+
+    /// Produces a valid sequence of the remaining statuses, `step` apart, that ends with
+    /// `outcome`: `Installed`, `DownloadFailed` after all retries, `InvalidSignature`,
+    /// `InstallationFailed` or `InstallVerificationFailed`. Outcomes that cannot occur,
+    /// such as `InvalidSignature` of unsigned firmware, end with `Installed`.
+    pub fn simulate(
+        &self,
+        outcome: FirmwareStatus,
+        step: Duration,
+    ) -> Vec<(FirmwareStatus, DateTime<Utc>)> {
+        let mut update = self.clone();
+        let mut sequence = Vec::new();
+        while let Some((status, at)) = update.next_status(outcome, step) {
+            if update.report(status, at).is_err() {
+                break;
+            }
+            sequence.push((status, at));
+        }
+        sequence
+    }
+
+    fn next_status(
+        &self,
+        outcome: FirmwareStatus,
+        step: Duration,
+    ) -> Option<(FirmwareStatus, DateTime<Utc>)> {
+        if self.is_finished() {
+            return None;
+        }
+        let at = self.updated_at + step;
+        let download = match self.intermediate_statuses && at < self.retrieve_date_time {
+            true => (FirmwareStatus::DownloadScheduled, at),
+            false => (FirmwareStatus::Downloading, at.max(self.retrieve_date_time)),
+        };
+        let install = match self.install_date_time {
+            Some(install) if self.intermediate_statuses && at < install => {
+                (FirmwareStatus::InstallScheduled, at)
+            }
+            install => (
+                FirmwareStatus::Installing,
+                install.map_or(at, |install| at.max(install)),
+            ),
+        };
+        let next = match self.status {
+            FirmwareStatus::Idle => download,
+            FirmwareStatus::DownloadScheduled => {
+                (FirmwareStatus::Downloading, at.max(self.retrieve_date_time))
+            }
+            FirmwareStatus::DownloadPaused => (FirmwareStatus::Downloading, at),
+            FirmwareStatus::DownloadFailed => (
+                FirmwareStatus::Downloading,
+                at.max(self.updated_at + self.retries.interval),
+            ),
+            FirmwareStatus::Downloading if outcome == FirmwareStatus::DownloadFailed => {
+                (FirmwareStatus::DownloadFailed, at)
+            }
+            FirmwareStatus::Downloading => (FirmwareStatus::Downloaded, at),
+            FirmwareStatus::Downloaded if self.signed => match outcome {
+                FirmwareStatus::InvalidSignature => (FirmwareStatus::InvalidSignature, at),
+                _ => (FirmwareStatus::SignatureVerified, at),
+            },
+            FirmwareStatus::Downloaded | FirmwareStatus::SignatureVerified => install,
+            FirmwareStatus::InstallScheduled => (
+                FirmwareStatus::Installing,
+                self.install_date_time.map_or(at, |install| at.max(install)),
+            ),
+            FirmwareStatus::Installing => match outcome {
+                FirmwareStatus::InstallationFailed => (FirmwareStatus::InstallationFailed, at),
+                _ if self.intermediate_statuses => (FirmwareStatus::InstallRebooting, at),
+                FirmwareStatus::InstallVerificationFailed => {
+                    (FirmwareStatus::InstallationFailed, at)
+                }
+                _ => (FirmwareStatus::Installed, at),
+            },
+            FirmwareStatus::InstallRebooting => match outcome {
+                FirmwareStatus::InstallVerificationFailed => {
+                    (FirmwareStatus::InstallVerificationFailed, at)
+                }
+                _ => (FirmwareStatus::Installed, at),
+            },
+            FirmwareStatus::InvalidSignature
+            | FirmwareStatus::Installed
+            | FirmwareStatus::InstallationFailed
+            | FirmwareStatus::InstallVerificationFailed => return None,
+        };
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use FirmwareStatus::*;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn statuses(sequence: &[(FirmwareStatus, DateTime<Utc>)]) -> Vec<FirmwareStatus> {
+        sequence.iter().map(|(status, _)| *status).collect()
+    }
+
+    #[test]
+    fn accepts_valid_sequence() {
+        let mut update = FirmwareUpdate::new(1, now() + Duration::minutes(10), now())
+            .with_install_date_time(now() + Duration::hours(1))
+            .with_signature();
+        let reports = [
+            (DownloadScheduled, 1),
+            (Downloading, 10),
+            (Downloaded, 20),
+            (SignatureVerified, 21),
+            (InstallScheduled, 22),
+            (Installing, 60),
+            (InstallRebooting, 61),
+            (Installed, 63),
+        ];
+        for (status, minutes) in reports {
+            assert!(!update.is_finished());
+            update
+                .report(status, now() + Duration::minutes(minutes))
+                .unwrap();
+        }
+        assert!(update.is_finished());
+        assert_eq!(update.history().len(), 8);
+        assert_eq!(update.report(Idle, now() + Duration::hours(2)), Ok(()));
+    }
+
+    #[test]
+    fn rejects_invalid_transitions_and_early_statuses() {
+        let mut update = FirmwareUpdate::new(1, now() + Duration::minutes(10), now())
+            .with_install_date_time(now() + Duration::hours(1))
+            .with_signature();
+        assert_eq!(
+            update.report(Installed, now()),
+            Err(SequenceError::InvalidTransition {
+                from: Idle,
+                to: Installed
+            })
+        );
+        assert_eq!(
+            update.report(Downloading, now()),
+            Err(SequenceError::TooEarly {
+                status: Downloading,
+                earliest: now() + Duration::minutes(10)
+            })
+        );
+        let later = now() + Duration::minutes(20);
+        update.report(Downloading, later).unwrap();
+        assert!(update.report(Idle, later).is_err());
+        update.report(Downloaded, later).unwrap();
+        // the signature must be verified first
+        assert!(matches!(
+            update.report(Installing, later),
+            Err(SequenceError::InvalidTransition { .. })
+        ));
+        update.report(SignatureVerified, later).unwrap();
+        assert!(matches!(
+            update.report(Installing, later),
+            Err(SequenceError::TooEarly { .. })
+        ));
+    }
+
+    #[test]
+    fn retries_failed_downloads() {
+        let mut update =
+            FirmwareUpdate::new(1, now(), now()).with_retries(Retries::new(Some(1), Some(60)));
+        update.report(Downloading, now()).unwrap();
+        update.report(DownloadFailed, now()).unwrap();
+        assert!(!update.is_finished());
+        assert!(matches!(
+            update.report(Downloading, now() + Duration::seconds(30)),
+            Err(SequenceError::TooEarly { .. })
+        ));
+        update
+            .report(Downloading, now() + Duration::seconds(60))
+            .unwrap();
+        update
+            .report(DownloadFailed, now() + Duration::seconds(90))
+            .unwrap();
+        assert!(update.is_finished());
+        assert_eq!(
+            update.report(Downloading, now() + Duration::hours(1)),
+            Err(SequenceError::RetriesExhausted)
+        );
+    }
+
+    #[test]
+    fn detects_stuck_updates() {
+        let timeout = Duration::minutes(5);
+        let mut update = FirmwareUpdate::new(1, now() + Duration::hours(1), now());
+        assert!(!update.is_stuck(now() + Duration::minutes(30), timeout));
+        update.report(DownloadScheduled, now()).unwrap();
+        assert!(!update.is_stuck(now() + Duration::minutes(64), timeout));
+        assert!(update.is_stuck(now() + Duration::minutes(66), timeout));
+        update
+            .report(Downloading, now() + Duration::hours(1))
+            .unwrap();
+        assert_eq!(
+            update.expected_by(timeout),
+            Some(now() + Duration::minutes(65))
+        );
+    }
+
+    #[test]
+    fn simulates_valid_sequences() {
+        let update = FirmwareUpdate::new(1, now() + Duration::minutes(10), now())
+            .with_install_date_time(now() + Duration::hours(1))
+            .with_retries(Retries::new(Some(2), Some(60)))
+            .with_signature();
+        let step = Duration::seconds(10);
+        assert_eq!(
+            statuses(&update.simulate(Installed, step)),
+            vec![
+                DownloadScheduled,
+                Downloading,
+                Downloaded,
+                SignatureVerified,
+                InstallScheduled,
+                Installing,
+                InstallRebooting,
+                Installed
+            ]
+        );
+        let sequence = update.simulate(DownloadFailed, step);
+        assert_eq!(sequence.len(), 7);
+        assert_eq!(sequence.last().unwrap().0, DownloadFailed);
+        assert_eq!(
+            statuses(&update.simulate(InvalidSignature, step)).last(),
+            Some(&InvalidSignature)
+        );
+
+        // 1.6 has no scheduled, signature or rebooting statuses
+        let update = FirmwareUpdate::new(1, now() + Duration::minutes(10), now())
+            .with_intermediate_statuses(false);
+        let sequence = update.simulate(InstallVerificationFailed, step);
+        assert_eq!(
+            statuses(&sequence),
+            vec![Downloading, Downloaded, Installing, InstallationFailed]
+        );
+        assert_eq!(sequence[0].1, now() + Duration::minutes(10));
+    }
+}
@@ -0,0 +1,241 @@
+use chrono::{DateTime, Duration, Utc};
+
+use super::{Retries, SequenceError};
+
+/// Status of a log upload, `UploadLogStatusEnumType` in 2.x and `DiagnosticsStatus` in 1.6.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogStatus {
+    /// No log upload in progress.
+    Idle,
+    /// Uploading the log.
+    Uploading,
+    /// Log uploaded.
+    Uploaded,
+    /// Upload failed, possibly followed by a retry.
+    UploadFailure,
+    /// The server rejected the upload request.
+    BadMessage,
+    /// The server does not support the upload operation.
+    NotSupportedOperation,
+    /// The server denied access.
+    PermissionDenied,
+    /// The upload was canceled by a new request.
+    AcceptedCanceled,
+}
+
+impl LogStatus {
+    /// Statuses that may follow this status.
+    pub fn successors(self) -> &'static [LogStatus] {
+        use LogStatus::*;
+        match self {
+            Idle | Uploading => &[
+                Uploading,
+                Uploaded,
+                UploadFailure,
+                BadMessage,
+                NotSupportedOperation,
+                PermissionDenied,
+                AcceptedCanceled,
+            ],
+            UploadFailure => &[Uploading, AcceptedCanceled],
+            Uploaded | BadMessage | NotSupportedOperation | PermissionDenied | AcceptedCanceled => {
+                &[]
+            }
+        }
+    }
+}
+
+/// Status sequence of one log upload request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogUpload {
+    request_id: i32,
+    retries: Retries,
+    status: LogStatus,
+    updated_at: DateTime<Utc>,
+    history: Vec<(LogStatus, DateTime<Utc>)>,
+}
+
+impl LogUpload {
+    /// Creates the upload of a request accepted at `requested_at`, without retries.
+    pub fn new(request_id: i32, requested_at: DateTime<Utc>) -> Self {
+        Self {
+            request_id,
+            retries: Retries::default(),
+            status: LogStatus::Idle,
+            updated_at: requested_at,
+            history: Vec::new(),
+        }
+    }
+
+    /// Sets the retries of a failed upload.
+    pub fn with_retries(mut self, retries: Retries) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Request id of the upload.
+    pub fn request_id(&self) -> i32 {
+        self.request_id
+    }
+
+    /// Last reported status, `Idle` before the first notification.
+    pub fn status(&self) -> LogStatus {
+        self.status
+    }
+
+    /// Retries left.
+    pub fn retries(&self) -> Retries {
+        self.retries
+    }
+
+    /// Reported statuses with the time they were reported.
+    pub fn history(&self) -> &[(LogStatus, DateTime<Utc>)] {
+        &self.history
+    }
+
+    /// Whether no further status is expected.
+    pub fn is_finished(&self) -> bool {
+        match self.status {
+            LogStatus::Idle => false,
+            LogStatus::UploadFailure => self.retries.remaining == 0,
+            status => status.successors().is_empty(),
+        }
+    }
+
+    /// Applies a status reported at `now`.
+    ///
+    /// `Idle` is accepted before the first and after the last status, as reported when
+    /// triggered without an upload in progress.
+    pub fn report(
+        &mut self,
+        status: LogStatus,
+        now: DateTime<Utc>,
+    ) -> Result<(), SequenceError<LogStatus>> {
+        let invalid = SequenceError::InvalidTransition {
+            from: self.status,
+            to: status,
+        };
+        if status == LogStatus::Idle {
+            return match self.status == LogStatus::Idle || self.is_finished() {
+                true => Ok(()),
+                false => Err(invalid),
+            };
+        }
+        if !self.status.successors().contains(&status) {
+            return Err(invalid);
+        }
+        let retry = self.status == LogStatus::UploadFailure && status == LogStatus::Uploading;
+        if retry {
+            if self.retries.remaining == 0 {
+                return Err(SequenceError::RetriesExhausted);
+            }
+            let earliest = self.updated_at + self.retries.interval;
+            if now < earliest {
+                return Err(SequenceError::TooEarly { status, earliest });
+            }
+            self.retries.remaining -= 1;
+        }
+        self.status = status;
+        self.updated_at = now;
+        self.history.push((status, now));
+        Ok(())
+    }
+
+    /// Time by which the next status is expected, allowing `timeout` after the request,
+    /// the previous status or the retry interval. `None` when the upload is finished.
+    pub fn expected_by(&self, timeout: Duration) -> Option<DateTime<Utc>> {
+        let start = match self.status {
+            _ if self.is_finished() => return None,
+            LogStatus::UploadFailure => self.updated_at + self.retries.interval,
+            _ => self.updated_at,
+        };
+        Some(start + timeout)
+    }
+
+    /// Whether the next status is overdue at `now`, see [`Self::expected_by`].
+    pub fn is_stuck(&self, now: DateTime<Utc>, timeout: Duration) -> bool {
+        self.expected_by(timeout)
+            .is_some_and(|expected_by| now > expected_by)
+    }
+
+    /// Produces a valid sequence of the remaining statuses, `step` apart, that ends with
+    /// `outcome`. An `UploadFailure` outcome fails every retry, other failures end the
+    /// first attempt and `Idle` or `Uploading` end with `Uploaded`.
+    pub fn simulate(&self, outcome: LogStatus, step: Duration) -> Vec<(LogStatus, DateTime<Utc>)> {
+        let mut upload = self.clone();
+        let mut sequence = Vec::new();
+        while !upload.is_finished() {
+            let at = upload.updated_at + step;
+            let next = match (upload.status, outcome) {
+                (LogStatus::UploadFailure, _) => (
+                    LogStatus::Uploading,
+                    at.max(upload.updated_at + upload.retries.interval),
+                ),
+                (LogStatus::Idle, _) => (LogStatus::Uploading, at),
+                (_, LogStatus::Idle | LogStatus::Uploading) => (LogStatus::Uploaded, at),
+                (_, outcome) => (outcome, at),
+            };
+            if upload.report(next.0, next.1).is_err() {
+                break;
+            }
+            sequence.push(next);
+        }
+        sequence
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use LogStatus::*;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn follows_upload_sequence() {
+        let mut upload = LogUpload::new(7, now()).with_retries(Retries::new(Some(1), Some(30)));
+        upload.report(Uploading, now()).unwrap();
+        upload.report(UploadFailure, now()).unwrap();
+        assert!(!upload.is_finished());
+        assert!(upload.is_stuck(now() + Duration::seconds(91), Duration::minutes(1)));
+        assert!(matches!(
+            upload.report(Uploading, now() + Duration::seconds(10)),
+            Err(SequenceError::TooEarly { .. })
+        ));
+        upload
+            .report(Uploading, now() + Duration::seconds(30))
+            .unwrap();
+        upload
+            .report(Uploaded, now() + Duration::seconds(40))
+            .unwrap();
+        assert!(upload.is_finished());
+        assert_eq!(upload.expected_by(Duration::minutes(1)), None);
+        assert_eq!(
+            upload.report(Uploading, now() + Duration::seconds(50)),
+            Err(SequenceError::InvalidTransition {
+                from: Uploaded,
+                to: Uploading
+            })
+        );
+    }
+
+    #[test]
+    fn simulates_valid_sequences() {
+        let upload = LogUpload::new(7, now()).with_retries(Retries::new(Some(2), Some(30)));
+        let step = Duration::seconds(5);
+        let sequence = upload.simulate(Uploading, step);
+        assert_eq!(
+            sequence,
+            vec![(Uploading, now() + step), (Uploaded, now() + step * 2)]
+        );
+        let sequence = upload.simulate(UploadFailure, step);
+        assert_eq!(sequence.len(), 6);
+        assert_eq!(sequence[2].1, sequence[1].1 + Duration::seconds(30));
+        let sequence = upload.simulate(PermissionDenied, step);
+        assert_eq!(sequence.last().unwrap().0, PermissionDenied);
+    }
+}
@@ -0,0 +1,129 @@
+//! # File transfers
+//!
+//! Status sequences of firmware updates requested with `UpdateFirmware` and of log uploads
+//! requested with `GetLog` (`GetDiagnostics` in 1.6), as reported in
+//! `FirmwareStatusNotification` and `LogStatusNotification` (`DiagnosticsStatusNotification`
+//! in 1.6).
+//!
+//! A [`FirmwareUpdate`] or [`LogUpload`] follows one request. On the CSMS side every
+//! notification is passed to `report`, which rejects statuses that cannot follow the
+//! previous one, retries beyond the requested number and statuses reported before the
+//! retrieve, install or retry time. `is_stuck` detects transfers whose next status is
+//! overdue. On the Charging Station side, `simulate` produces a valid sequence of statuses
+//! ending with a chosen outcome.
+
+use std::fmt;
+
+use chrono::{DateTime, Duration, Utc};
+
+mod firmware;
+mod log_upload;
+
+#[cfg(feature = "v1_6")]
+pub mod v1_6;
+#[cfg(feature = "v2_0_1")]
+pub mod v2_0_1;
+#[cfg(feature = "v2_1")]
+pub mod v2_1;
+
+pub use firmware::{FirmwareStatus, FirmwareUpdate};
+pub use log_upload::{LogStatus, LogUpload};
+
+/// Retries of a failed transfer, as the `retries` and `retryInterval` of the request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Retries {
+    /// Number of retries left.
+    pub remaining: u32,
+    /// Minimum time between a failure and the next attempt.
+    pub interval: Duration,
+}
+
+impl Retries {
+    /// Creates the retries of a request. Missing or negative values count as zero.
+    pub fn new(retries: Option<i32>, retry_interval: Option<i32>) -> Self {
+        let non_negative = |value: Option<i32>| value.unwrap_or_default().max(0);
+        Self {
+            remaining: non_negative(retries) as u32,
+            interval: Duration::seconds(non_negative(retry_interval).into()),
+        }
+    }
+}
+
+impl Default for Retries {
+    fn default() -> Self {
+        Self {
+            remaining: 0,
+            interval: Duration::zero(),
+        }
+    }
+}
+
+/// Reason why a reported status does not fit the sequence of a transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceError<S> {
+    /// The status cannot follow the previous status.
+    InvalidTransition {
+        /// Status of the transfer.
+        from: S,
+        /// Reported status.
+        to: S,
+    },
+    /// The status was reported before the retrieve, install or retry time.
+    TooEarly {
+        /// Reported status.
+        status: S,
+        /// Earliest time the status may be reported.
+        earliest: DateTime<Utc>,
+    },
+    /// A new attempt was reported after all retries were used.
+    RetriesExhausted,
+    /// The status was reported for another request.
+    UnknownRequest(Option<i32>),
+}
+
+impl<S: fmt::Debug> fmt::Display for SequenceError<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidTransition { from, to } => {
+                write!(f, "status {to:?} cannot follow {from:?}")
+            }
+            Self::TooEarly { status, earliest } => {
+                write!(f, "status {status:?} reported before {earliest}")
+            }
+            Self::RetriesExhausted => write!(f, "no retries left"),
+            Self::UnknownRequest(Some(request_id)) => write!(f, "unknown request {request_id}"),
+            Self::UnknownRequest(None) => write!(f, "missing request id"),
+        }
+    }
+}
+
+impl<S: fmt::Debug> std::error::Error for SequenceError<S> {}
+
+/// Checks the request id of a notification against the request of the transfer.
+///
+/// Only an `Idle` status may be reported without request id.
+#[cfg(any(feature = "v2_0_1", feature = "v2_1"))]
+pub(crate) fn check_request_id<S>(
+    request_id: i32,
+    reported: Option<i32>,
+    idle: bool,
+) -> Result<(), SequenceError<S>> {
+    match reported {
+        Some(reported) if reported == request_id => Ok(()),
+        None if idle => Ok(()),
+        reported => Err(SequenceError::UnknownRequest(reported)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_of_request() {
+        let retries = Retries::new(Some(3), Some(60));
+        assert_eq!(retries.remaining, 3);
+        assert_eq!(retries.interval, Duration::seconds(60));
+        assert_eq!(Retries::new(Some(-1), None), Retries::default());
+    }
+}
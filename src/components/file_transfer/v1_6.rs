@@ -0,0 +1,148 @@
+//! OCPP 1.6 firmware status and diagnostics status notifications.
+//!
+//! 1.6 reports fewer statuses and has no request ids, the sequences are created without
+//! intermediate statuses and with request id 0.
+
+use chrono::{DateTime, Utc};
+
+use crate::v1_6::{
+    messages::{
+        diagnostics_status_notification::DiagnosticsStatusNotificationRequest,
+        firmware_status_notification::FirmwareStatusNotificationRequest,
+        get_diagnostics::GetDiagnosticsRequest, update_firmware::UpdateFirmwareRequest,
+    },
+    types,
+};
+
+use super::{FirmwareStatus, FirmwareUpdate, LogStatus, LogUpload, Retries, SequenceError};
+
+impl From<&types::FirmwareStatus> for FirmwareStatus {
+    fn from(status: &types::FirmwareStatus) -> Self {
+        match status {
+            types::FirmwareStatus::Downloaded => Self::Downloaded,
+            types::FirmwareStatus::DownloadFailed => Self::DownloadFailed,
+            types::FirmwareStatus::Downloading => Self::Downloading,
+            types::FirmwareStatus::Idle => Self::Idle,
+            types::FirmwareStatus::InstallationFailed => Self::InstallationFailed,
+            types::FirmwareStatus::Installing => Self::Installing,
+            types::FirmwareStatus::Installed => Self::Installed,
+        }
+    }
+}
+
+impl From<&types::DiagnosticsStatus> for LogStatus {
+    fn from(status: &types::DiagnosticsStatus) -> Self {
+        match status {
+            types::DiagnosticsStatus::Idle => Self::Idle,
+            types::DiagnosticsStatus::Uploaded => Self::Uploaded,
+            types::DiagnosticsStatus::UploadFailed => Self::UploadFailure,
+            types::DiagnosticsStatus::Uploading => Self::Uploading,
+        }
+    }
+}
+
+/// Creates the status sequence of an `UpdateFirmwareRequest` accepted at `now`.
+pub fn firmware_update(request: &UpdateFirmwareRequest, now: DateTime<Utc>) -> FirmwareUpdate {
+    FirmwareUpdate::new(0, request.retrieve_date, now)
+        .with_retries(Retries::new(request.retries, request.retry_interval))
+        .with_intermediate_statuses(false)
+}
+
+/// Creates the status sequence of a `GetDiagnosticsRequest` accepted at `now`.
+pub fn log_upload(request: &GetDiagnosticsRequest, now: DateTime<Utc>) -> LogUpload {
+    LogUpload::new(0, now).with_retries(Retries::new(request.retries, request.retry_interval))
+}
+
+/// Handles a `FirmwareStatusNotificationRequest` of `update` received at `now`.
+pub fn firmware_status_notification(
+    update: &mut FirmwareUpdate,
+    request: &FirmwareStatusNotificationRequest,
+    now: DateTime<Utc>,
+) -> Result<(), SequenceError<FirmwareStatus>> {
+    update.report((&request.status).into(), now)
+}
+
+/// Handles a `DiagnosticsStatusNotificationRequest` of `upload` received at `now`.
+pub fn diagnostics_status_notification(
+    upload: &mut LogUpload,
+    request: &DiagnosticsStatusNotificationRequest,
+    now: DateTime<Utc>,
+) -> Result<(), SequenceError<LogStatus>> {
+    upload.report((&request.status).into(), now)
+}
+
+/// Builds the `FirmwareStatusNotificationRequest` reporting `status`, `None` for the
+/// statuses added in 2.0.1.
+pub fn firmware_status_notification_request(
+    status: FirmwareStatus,
+) -> Option<FirmwareStatusNotificationRequest> {
+    let status = match status {
+        FirmwareStatus::Idle => types::FirmwareStatus::Idle,
+        FirmwareStatus::Downloading => types::FirmwareStatus::Downloading,
+        FirmwareStatus::Downloaded => types::FirmwareStatus::Downloaded,
+        FirmwareStatus::DownloadFailed => types::FirmwareStatus::DownloadFailed,
+        FirmwareStatus::Installing => types::FirmwareStatus::Installing,
+        FirmwareStatus::Installed => types::FirmwareStatus::Installed,
+        FirmwareStatus::InstallationFailed => types::FirmwareStatus::InstallationFailed,
+        _ => return None,
+    };
+    Some(FirmwareStatusNotificationRequest { status })
+}
+
+/// Builds the `DiagnosticsStatusNotificationRequest` reporting `status`, `None` for the
+/// statuses added in 2.0.1.
+pub fn diagnostics_status_notification_request(
+    status: LogStatus,
+) -> Option<DiagnosticsStatusNotificationRequest> {
+    let status = match status {
+        LogStatus::Idle => types::DiagnosticsStatus::Idle,
+        LogStatus::Uploading => types::DiagnosticsStatus::Uploading,
+        LogStatus::Uploaded => types::DiagnosticsStatus::Uploaded,
+        LogStatus::UploadFailure => types::DiagnosticsStatus::UploadFailed,
+        _ => return None,
+    };
+    Some(DiagnosticsStatusNotificationRequest { status })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+
+    #[test]
+    fn simulated_notifications_are_accepted() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let request = UpdateFirmwareRequest {
+            location: "ftp://example.com/firmware.bin".to_string(),
+            retries: Some(2),
+            retrieve_date: now + Duration::hours(1),
+            retry_interval: Some(30),
+        };
+        let mut csms = firmware_update(&request, now);
+        let sequence = csms.simulate(FirmwareStatus::DownloadFailed, Duration::seconds(10));
+        assert_eq!(sequence.len(), 6);
+        for (status, at) in sequence {
+            let notification = firmware_status_notification_request(status).unwrap();
+            firmware_status_notification(&mut csms, &notification, at).unwrap();
+        }
+        assert!(csms.is_finished());
+
+        let request = GetDiagnosticsRequest {
+            location: "ftp://example.com/logs".to_string(),
+            ..Default::default()
+        };
+        let mut csms = log_upload(&request, now);
+        let notification = diagnostics_status_notification_request(LogStatus::Uploading).unwrap();
+        diagnostics_status_notification(&mut csms, &notification, now).unwrap();
+        let notification = DiagnosticsStatusNotificationRequest {
+            status: types::DiagnosticsStatus::UploadFailed,
+        };
+        diagnostics_status_notification(&mut csms, &notification, now).unwrap();
+        assert!(csms.is_finished());
+        assert_eq!(
+            diagnostics_status_notification_request(LogStatus::PermissionDenied),
+            None
+        );
+    }
+}
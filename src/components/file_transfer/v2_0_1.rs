@@ -0,0 +1,225 @@
+//! OCPP 2.0.1 firmware status and log status notifications.
+
+use chrono::{DateTime, Utc};
+
+use crate::v2_0_1::{
+    enumerations::{
+        firmware_status_enum_type::FirmwareStatusEnumType,
+        upload_log_status_enum_type::UploadLogStatusEnumType,
+    },
+    messages::{
+        firmware_status_notification::FirmwareStatusNotificationRequest, get_log::GetLogRequest,
+        log_status_notification::LogStatusNotificationRequest,
+        update_firmware::UpdateFirmwareRequest,
+    },
+};
+
+use super::{
+    check_request_id, FirmwareStatus, FirmwareUpdate, LogStatus, LogUpload, Retries, SequenceError,
+};
+
+impl From<&FirmwareStatusEnumType> for FirmwareStatus {
+    fn from(status: &FirmwareStatusEnumType) -> Self {
+        match status {
+            FirmwareStatusEnumType::Downloaded => Self::Downloaded,
+            FirmwareStatusEnumType::DownloadFailed => Self::DownloadFailed,
+            FirmwareStatusEnumType::Downloading => Self::Downloading,
+            FirmwareStatusEnumType::DownloadScheduled => Self::DownloadScheduled,
+            FirmwareStatusEnumType::DownloadPaused => Self::DownloadPaused,
+            FirmwareStatusEnumType::Idle => Self::Idle,
+            FirmwareStatusEnumType::InstallationFailed => Self::InstallationFailed,
+            FirmwareStatusEnumType::Installing => Self::Installing,
+            FirmwareStatusEnumType::Installed => Self::Installed,
+            FirmwareStatusEnumType::InstallRebooting => Self::InstallRebooting,
+            FirmwareStatusEnumType::InstallScheduled => Self::InstallScheduled,
+            FirmwareStatusEnumType::InstallVerificationFailed => Self::InstallVerificationFailed,
+            FirmwareStatusEnumType::InvalidSignature => Self::InvalidSignature,
+            FirmwareStatusEnumType::SignatureVerified => Self::SignatureVerified,
+        }
+    }
+}
+
+impl From<FirmwareStatus> for FirmwareStatusEnumType {
+    fn from(status: FirmwareStatus) -> Self {
+        match status {
+            FirmwareStatus::Idle => Self::Idle,
+            FirmwareStatus::DownloadScheduled => Self::DownloadScheduled,
+            FirmwareStatus::Downloading => Self::Downloading,
+            FirmwareStatus::DownloadPaused => Self::DownloadPaused,
+            FirmwareStatus::Downloaded => Self::Downloaded,
+            FirmwareStatus::DownloadFailed => Self::DownloadFailed,
+            FirmwareStatus::SignatureVerified => Self::SignatureVerified,
+            FirmwareStatus::InvalidSignature => Self::InvalidSignature,
+            FirmwareStatus::InstallScheduled => Self::InstallScheduled,
+            FirmwareStatus::Installing => Self::Installing,
+            FirmwareStatus::InstallRebooting => Self::InstallRebooting,
+            FirmwareStatus::Installed => Self::Installed,
+            FirmwareStatus::InstallationFailed => Self::InstallationFailed,
+            FirmwareStatus::InstallVerificationFailed => Self::InstallVerificationFailed,
+        }
+    }
+}
+
+impl From<&UploadLogStatusEnumType> for LogStatus {
+    fn from(status: &UploadLogStatusEnumType) -> Self {
+        match status {
+            UploadLogStatusEnumType::BadMessage => Self::BadMessage,
+            UploadLogStatusEnumType::Idle => Self::Idle,
+            UploadLogStatusEnumType::NotSupportedOperation => Self::NotSupportedOperation,
+            UploadLogStatusEnumType::PermissionDenied => Self::PermissionDenied,
+            UploadLogStatusEnumType::Uploaded => Self::Uploaded,
+            UploadLogStatusEnumType::UploadFailure => Self::UploadFailure,
+            UploadLogStatusEnumType::Uploading => Self::Uploading,
+            UploadLogStatusEnumType::AcceptedCanceled => Self::AcceptedCanceled,
+        }
+    }
+}
+
+impl From<LogStatus> for UploadLogStatusEnumType {
+    fn from(status: LogStatus) -> Self {
+        match status {
+            LogStatus::Idle => Self::Idle,
+            LogStatus::Uploading => Self::Uploading,
+            LogStatus::Uploaded => Self::Uploaded,
+            LogStatus::UploadFailure => Self::UploadFailure,
+            LogStatus::BadMessage => Self::BadMessage,
+            LogStatus::NotSupportedOperation => Self::NotSupportedOperation,
+            LogStatus::PermissionDenied => Self::PermissionDenied,
+            LogStatus::AcceptedCanceled => Self::AcceptedCanceled,
+        }
+    }
+}
+
+/// Creates the status sequence of an `UpdateFirmwareRequest` accepted at `now`. Firmware
+/// with a signature must report `SignatureVerified` before installing.
+pub fn firmware_update(request: &UpdateFirmwareRequest, now: DateTime<Utc>) -> FirmwareUpdate {
+    let update = FirmwareUpdate::new(request.request_id, request.firmware.retrieve_date_time, now)
+        .with_retries(Retries::new(request.retries, request.retry_interval));
+    let update = match request.firmware.install_date_time {
+        Some(install_date_time) => update.with_install_date_time(install_date_time),
+        None => update,
+    };
+    match request.firmware.signature.is_some() {
+        true => update.with_signature(),
+        false => update,
+    }
+}
+
+/// Creates the status sequence of a `GetLogRequest` accepted at `now`.
+pub fn log_upload(request: &GetLogRequest, now: DateTime<Utc>) -> LogUpload {
+    LogUpload::new(request.request_id, now)
+        .with_retries(Retries::new(request.retries, request.retry_interval))
+}
+
+/// Handles a `FirmwareStatusNotificationRequest` of `update` received at `now`.
+pub fn firmware_status_notification(
+    update: &mut FirmwareUpdate,
+    request: &FirmwareStatusNotificationRequest,
+    now: DateTime<Utc>,
+) -> Result<(), SequenceError<FirmwareStatus>> {
+    let status = FirmwareStatus::from(&request.status);
+    check_request_id(
+        update.request_id(),
+        request.request_id,
+        status == FirmwareStatus::Idle,
+    )?;
+    update.report(status, now)
+}
+
+/// Handles a `LogStatusNotificationRequest` of `upload` received at `now`.
+pub fn log_status_notification(
+    upload: &mut LogUpload,
+    request: &LogStatusNotificationRequest,
+    now: DateTime<Utc>,
+) -> Result<(), SequenceError<LogStatus>> {
+    let status = LogStatus::from(&request.status);
+    check_request_id(
+        upload.request_id(),
+        request.request_id,
+        status == LogStatus::Idle,
+    )?;
+    upload.report(status, now)
+}
+
+/// Builds the `FirmwareStatusNotificationRequest` reporting `status` of the update of
+/// `request_id`. The request id is left out for `Idle`.
+pub fn firmware_status_notification_request(
+    request_id: i32,
+    status: FirmwareStatus,
+) -> FirmwareStatusNotificationRequest {
+    FirmwareStatusNotificationRequest {
+        status: status.into(),
+        request_id: (status != FirmwareStatus::Idle).then_some(request_id),
+    }
+}
+
+/// Builds the `LogStatusNotificationRequest` reporting `status` of the upload of
+/// `request_id`. The request id is left out for `Idle`.
+pub fn log_status_notification_request(
+    request_id: i32,
+    status: LogStatus,
+) -> LogStatusNotificationRequest {
+    LogStatusNotificationRequest {
+        status: status.into(),
+        request_id: (status != LogStatus::Idle).then_some(request_id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+    use crate::v2_0_1::{
+        datatypes::{firmware_type::FirmwareType, log_parameters_type::LogParametersType},
+        enumerations::log_enum_type::LogEnumType,
+    };
+
+    #[test]
+    fn simulated_notifications_are_accepted() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let request = UpdateFirmwareRequest {
+            retries: Some(1),
+            retry_interval: Some(60),
+            request_id: 5,
+            firmware: FirmwareType {
+                location: "https://example.com/firmware.bin".to_string(),
+                retrieve_date_time: now,
+                install_date_time: None,
+                signing_certificate: None,
+                signature: Some("c2lnbmF0dXJl".to_string()),
+            },
+        };
+        let station = firmware_update(&request, now);
+        let mut csms = firmware_update(&request, now);
+        let sequence = station.simulate(FirmwareStatus::Installed, Duration::seconds(10));
+        assert_eq!(sequence[2].0, FirmwareStatus::SignatureVerified);
+        for (status, at) in sequence {
+            let notification = firmware_status_notification_request(5, status);
+            firmware_status_notification(&mut csms, &notification, at).unwrap();
+        }
+        assert!(csms.is_finished());
+        let idle = firmware_status_notification_request(5, FirmwareStatus::Idle);
+        assert_eq!(idle.request_id, None);
+        firmware_status_notification(&mut csms, &idle, now).unwrap();
+        let other = firmware_status_notification_request(4, FirmwareStatus::Downloading);
+        assert_eq!(
+            firmware_status_notification(&mut csms, &other, now),
+            Err(SequenceError::UnknownRequest(Some(4)))
+        );
+
+        let request = GetLogRequest {
+            log_type: LogEnumType::DiagnosticsLog,
+            request_id: 6,
+            retries: None,
+            retry_interval: None,
+            log: LogParametersType::default(),
+        };
+        let mut csms = log_upload(&request, now);
+        for (status, at) in csms.simulate(LogStatus::Uploaded, Duration::seconds(10)) {
+            let notification = log_status_notification_request(6, status);
+            log_status_notification(&mut csms, &notification, at).unwrap();
+        }
+        assert_eq!(csms.status(), LogStatus::Uploaded);
+    }
+}
@@ -0,0 +1,530 @@
+//! # Meter values
+//!
+//! Typed meter readings extracted from the `MeterValue`s of `MeterValues`,
+//! `StopTransaction` (1.6) and `TransactionEvent` (2.x).
+//!
+//! Every sampled value becomes a [`Reading`] identified by a [`ReadingKey`], the measurand,
+//! phase, location and context of the value. Values are normalized to the base unit of
+//! their measurand, such as Wh for energy, W for power, A for current, V for voltage and K
+//! for temperature, applying the `multiplier` of 2.x and unit prefixes such as `kWh`. A unit
+//! that does not fit the measurand is rejected, as is a unit that differs from the earlier
+//! readings of the same key in [`MeterReadings`]. Without unit a value is taken to be in the
+//! base unit of its measurand.
+//!
+//! Signed meter data is kept with the reading. In 1.6 a value in the `SignedData` format
+//! carries the signed data instead of a number, so its reading has no value.
+//!
+//! On the side of the Charging Station, the [`MeterValueScheduler`] tells when to sample
+//! which measurands, following the [`SamplingConfig`]: `Transaction.Begin` and
+//! `Transaction.End` values at the start and end of a transaction, `Sample.Periodic` values
+//! at an interval from the start of the transaction, and `Sample.Clock` values at an
+//! interval from midnight UTC. The version modules turn a [`Sample`] into the `MeterValue`
+//! of a `MeterValuesRequest`, `StopTransactionRequest` or `TransactionEventRequest`.
+
+use std::fmt;
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+
+mod scheduler;
+mod series;
+
+#[cfg(feature = "v1_6")]
+pub mod v1_6;
+#[cfg(feature = "v2_0_1")]
+pub mod v2_0_1;
+#[cfg(feature = "v2_1")]
+pub mod v2_1;
+
+pub use scheduler::{Destination, MeterValueScheduler, Sample};
+pub use series::MeterReadings;
+
+/// Measured quantity, `Measurand` in 1.6 and `MeasurandEnumType` in 2.x.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Measurand {
+    /// Current from the EV to the grid.
+    CurrentExport,
+    /// Current from the grid to the EV.
+    CurrentImport,
+    /// Maximum current offered to the EV.
+    CurrentOffered,
+    /// Active energy exported to the grid, register reading.
+    EnergyActiveExportRegister,
+    /// Active energy imported from the grid, register reading.
+    #[default]
+    EnergyActiveImportRegister,
+    /// Reactive energy exported to the grid, register reading.
+    EnergyReactiveExportRegister,
+    /// Reactive energy imported from the grid, register reading.
+    EnergyReactiveImportRegister,
+    /// Active energy exported during an interval.
+    EnergyActiveExportInterval,
+    /// Active energy imported during an interval.
+    EnergyActiveImportInterval,
+    /// Imported minus exported active energy.
+    EnergyActiveNet,
+    /// Reactive energy exported during an interval.
+    EnergyReactiveExportInterval,
+    /// Reactive energy imported during an interval.
+    EnergyReactiveImportInterval,
+    /// Imported minus exported reactive energy.
+    EnergyReactiveNet,
+    /// Imported minus exported apparent energy.
+    EnergyApparentNet,
+    /// Apparent energy imported from the grid.
+    EnergyApparentImport,
+    /// Apparent energy exported to the grid.
+    EnergyApparentExport,
+    /// Frequency of the grid.
+    Frequency,
+    /// Active power exported to the grid.
+    PowerActiveExport,
+    /// Active power imported from the grid.
+    PowerActiveImport,
+    /// Power factor.
+    PowerFactor,
+    /// Maximum power offered to the EV.
+    PowerOffered,
+    /// Reactive power exported to the grid.
+    PowerReactiveExport,
+    /// Reactive power imported from the grid.
+    PowerReactiveImport,
+    /// Fan speed (1.6).
+    Rpm,
+    /// State of charge of the EV battery.
+    SoC,
+    /// Temperature inside the Charge Point (1.6).
+    Temperature,
+    /// Voltage.
+    Voltage,
+    /// Any other measurand of 2.1, by its OCPP name.
+    Other(String),
+}
+
+impl Measurand {
+    /// Base unit of the measurand, `None` when any unit is accepted.
+    pub fn base_unit(&self) -> Option<Unit> {
+        use Measurand::*;
+        match self {
+            EnergyActiveExportRegister
+            | EnergyActiveImportRegister
+            | EnergyActiveExportInterval
+            | EnergyActiveImportInterval
+            | EnergyActiveNet => Some(Unit::Wh),
+            EnergyReactiveExportRegister
+            | EnergyReactiveImportRegister
+            | EnergyReactiveExportInterval
+            | EnergyReactiveImportInterval
+            | EnergyReactiveNet => Some(Unit::Varh),
+            EnergyApparentNet | EnergyApparentImport | EnergyApparentExport => Some(Unit::VAh),
+            PowerActiveExport | PowerActiveImport | PowerOffered => Some(Unit::W),
+            PowerReactiveExport | PowerReactiveImport => Some(Unit::Var),
+            PowerFactor => Some(Unit::Dimensionless),
+            CurrentExport | CurrentImport | CurrentOffered => Some(Unit::A),
+            Voltage => Some(Unit::V),
+            Frequency => Some(Unit::Hz),
+            SoC => Some(Unit::Percent),
+            Temperature => Some(Unit::K),
+            Rpm | Other(_) => None,
+        }
+    }
+}
+
+/// Phase of a measured value, `Phase` in 1.6 and `PhaseEnumType` in 2.x.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Phase {
+    /// Phase L1.
+    L1,
+    /// Phase L2.
+    L2,
+    /// Phase L3.
+    L3,
+    /// Neutral conductor.
+    N,
+    /// Between L1 and N.
+    L1N,
+    /// Between L2 and N.
+    L2N,
+    /// Between L3 and N.
+    L3N,
+    /// Between L1 and L2.
+    L1L2,
+    /// Between L2 and L3.
+    L2L3,
+    /// Between L3 and L1.
+    L3L1,
+}
+
+/// Where a value was measured, `Location` in 1.6 and `LocationEnumType` in 2.x.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Location {
+    /// Inside the Charging Station.
+    Body,
+    /// In the cable to the EV.
+    Cable,
+    /// Measured by the EV.
+    Ev,
+    /// At the network connection.
+    Inlet,
+    /// At the connector to the EV.
+    #[default]
+    Outlet,
+}
+
+/// Why a value was measured, `ReadingContext` in 1.6 and `ReadingContextEnumType` in 2.x.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReadingContext {
+    /// Start of an interruption of the transaction.
+    InterruptionBegin,
+    /// End of an interruption of the transaction.
+    InterruptionEnd,
+    /// Any other reason.
+    Other,
+    /// Clock aligned sample.
+    SampleClock,
+    /// Periodic sample during a transaction.
+    #[default]
+    SamplePeriodic,
+    /// Start of a transaction.
+    TransactionBegin,
+    /// End of a transaction.
+    TransactionEnd,
+    /// Requested with `TriggerMessage`.
+    Trigger,
+}
+
+/// Identification of a series of readings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ReadingKey {
+    /// Measured quantity, `Energy.Active.Import.Register` when absent.
+    pub measurand: Measurand,
+    /// Phase, `None` for a value over all phases.
+    pub phase: Option<Phase>,
+    /// Where the value was measured, `Outlet` when absent.
+    pub location: Location,
+    /// Why the value was measured, `Sample.Periodic` when absent.
+    pub context: ReadingContext,
+}
+
+/// Base unit of a normalized reading.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Unit {
+    /// Watt hour.
+    Wh,
+    /// Volt-ampere reactive hour.
+    Varh,
+    /// Volt-ampere hour.
+    VAh,
+    /// Watt.
+    W,
+    /// Volt-ampere reactive.
+    Var,
+    /// Volt-ampere.
+    VA,
+    /// Ampere.
+    A,
+    /// Volt.
+    V,
+    /// Hertz.
+    Hz,
+    /// Kelvin.
+    K,
+    /// Percent.
+    Percent,
+    /// No unit, such as a power factor.
+    Dimensionless,
+    /// Any other unit, as sent.
+    Other(String),
+}
+
+impl Unit {
+    /// Parses a unit of measure, returning its base unit and converting `value` to it, or
+    /// `None` when the converted value is out of range.
+    ///
+    /// The prefixes `k` and `M`, and `m` for current and voltage, are applied to electrical
+    /// units. Temperatures are converted to Kelvin.
+    pub fn normalize(unit: &str, value: Decimal) -> Option<(Unit, Decimal)> {
+        let celsius = |value: Decimal| value.checked_add(Decimal::new(27315, 2));
+        match unit {
+            "Celsius" | "Celcius" => return Some((Unit::K, celsius(value)?)),
+            "Fahrenheit" => {
+                let value = value
+                    .checked_sub(Decimal::from(32))?
+                    .checked_mul(Decimal::from(5))?
+                    / Decimal::from(9);
+                return Some((Unit::K, celsius(value)?));
+            }
+            "K" => return Some((Unit::K, value)),
+            "Percent" => return Some((Unit::Percent, value)),
+            "" => return Some((Unit::Dimensionless, value)),
+            _ => {}
+        }
+        let (prefix, base) = match unit.split_at_checked(1) {
+            Some(("k", base)) => (Decimal::ONE_THOUSAND, base),
+            Some(("M", base)) => (Decimal::ONE_THOUSAND * Decimal::ONE_THOUSAND, base),
+            Some(("m", base @ ("A" | "V"))) => (Decimal::new(1, 3), base),
+            _ => (Decimal::ONE, unit),
+        };
+        let base = match base {
+            "Wh" => Unit::Wh,
+            "varh" => Unit::Varh,
+            "VAh" => Unit::VAh,
+            "W" => Unit::W,
+            "var" => Unit::Var,
+            "VA" => Unit::VA,
+            "A" => Unit::A,
+            "V" => Unit::V,
+            "Hz" => Unit::Hz,
+            _ => return Some((Unit::Other(unit.to_string()), value)),
+        };
+        Some((base, value.checked_mul(prefix)?))
+    }
+}
+
+/// Signed meter data sent with a value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignedData {
+    /// Signed meter data, such as an OCMF document.
+    pub data: String,
+    /// Format of the signed meter data, such as `OCMF`.
+    pub encoding_method: Option<String>,
+    /// Signature algorithm.
+    pub signing_method: Option<String>,
+    /// Public key of the meter.
+    pub public_key: Option<String>,
+}
+
+/// One normalized sampled value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reading {
+    /// Time of the meter value.
+    pub timestamp: DateTime<Utc>,
+    /// Series the reading belongs to.
+    pub key: ReadingKey,
+    /// Value in `unit`, `None` for signed data without value.
+    pub value: Option<Decimal>,
+    /// Base unit of the value.
+    pub unit: Unit,
+    /// Signed meter data of the value.
+    pub signed_data: Option<SignedData>,
+}
+
+impl Reading {
+    /// Creates a reading of `value` times ten to the power of `multiplier` in `unit`,
+    /// normalized to the base unit of the measurand of `key`.
+    pub fn new(
+        timestamp: DateTime<Utc>,
+        key: ReadingKey,
+        value: Decimal,
+        unit: Option<&str>,
+        multiplier: i32,
+    ) -> Result<Self, ReadingError> {
+        let value = with_multiplier(value, multiplier)
+            .ok_or_else(|| ReadingError::InvalidValue(format!("{value}e{multiplier}")))?;
+        let (unit, value) = match (unit, key.measurand.base_unit()) {
+            (Some(unit), _) => Unit::normalize(unit, value)
+                .ok_or_else(|| ReadingError::InvalidValue(format!("{value} {unit}")))?,
+            (None, Some(base_unit)) => (base_unit, value),
+            (None, None) => (Unit::Dimensionless, value),
+        };
+        check_unit(&key.measurand, &unit)?;
+        Ok(Self {
+            timestamp,
+            key,
+            value: Some(value),
+            unit,
+            signed_data: None,
+        })
+    }
+
+    /// Creates a reading of signed data without value.
+    pub fn signed(
+        timestamp: DateTime<Utc>,
+        key: ReadingKey,
+        unit: Option<&str>,
+        signed_data: SignedData,
+    ) -> Result<Self, ReadingError> {
+        let mut reading = Self::new(timestamp, key, Decimal::ZERO, unit, 0)?;
+        reading.value = None;
+        reading.signed_data = Some(signed_data);
+        Ok(reading)
+    }
+
+    /// Attaches signed meter data to the reading.
+    pub fn with_signed_data(mut self, signed_data: SignedData) -> Self {
+        self.signed_data = Some(signed_data);
+        self
+    }
+}
+
+/// What to sample and how often, from the configuration of the Charging Station.
+///
+/// Measurands are OCPP names such as `Energy.Active.Import.Register`, see
+/// [`parse_measurands`]. A zero interval disables the samples.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SamplingConfig {
+    /// Interval of `Sample.Periodic` values during a transaction, `MeterValueSampleInterval`
+    /// (1.6) or `SampledDataCtrlr.TxUpdatedInterval` (2.x).
+    pub sampled_interval: Duration,
+    /// Measurands sampled at `sampled_interval`, `MeterValuesSampledData` (1.6) or
+    /// `SampledDataCtrlr.TxUpdatedMeasurands` (2.x).
+    pub sampled_measurands: Vec<String>,
+    /// Measurands sampled at the start of a transaction, `MeterValuesSampledData` (1.6) or
+    /// `SampledDataCtrlr.TxStartedMeasurands` (2.x).
+    pub started_measurands: Vec<String>,
+    /// Interval of the `Sample.Periodic` values kept for the end of the transaction,
+    /// `MeterValueSampleInterval` (1.6) or `SampledDataCtrlr.TxEndedInterval` (2.x).
+    pub ended_interval: Duration,
+    /// Measurands sampled at `ended_interval` and at the end of a transaction,
+    /// `StopTxnSampledData` (1.6) or `SampledDataCtrlr.TxEndedMeasurands` (2.x).
+    pub ended_measurands: Vec<String>,
+    /// Interval of `Sample.Clock` values, `ClockAlignedDataInterval` (1.6) or
+    /// `AlignedDataCtrlr.Interval` (2.x).
+    pub aligned_interval: Duration,
+    /// Measurands sampled at `aligned_interval`, `MeterValuesAlignedData` (1.6) or
+    /// `AlignedDataCtrlr.Measurands` (2.x).
+    pub aligned_measurands: Vec<String>,
+    /// Interval of the `Sample.Clock` values kept for the end of the transaction,
+    /// `ClockAlignedDataInterval` (1.6) or `AlignedDataCtrlr.TxEndedInterval` (2.x).
+    pub aligned_ended_interval: Duration,
+    /// Measurands sampled at `aligned_ended_interval`, `StopTxnAlignedData` (1.6) or
+    /// `AlignedDataCtrlr.TxEndedMeasurands` (2.x).
+    pub aligned_ended_measurands: Vec<String>,
+    /// Sends no `Sample.Clock` values during a transaction, `AlignedDataCtrlr.SendDuringIdle`
+    /// (2.x).
+    pub aligned_only_when_idle: bool,
+}
+
+/// Parses a comma separated list of measurands, such as `MeterValuesSampledData`.
+pub fn parse_measurands(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|measurand| !measurand.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Reason why a sampled value cannot be turned into a reading.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadingError {
+    /// The value is not a number or out of range.
+    InvalidValue(String),
+    /// The unit does not fit the measurand or earlier readings of the same series.
+    InconsistentUnit {
+        /// Measurand of the value.
+        measurand: Measurand,
+        /// Base unit of the value.
+        unit: Unit,
+        /// Base unit of the measurand or series.
+        expected: Unit,
+    },
+}
+
+impl fmt::Display for ReadingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidValue(value) => write!(f, "invalid value {value:?}"),
+            Self::InconsistentUnit {
+                measurand,
+                unit,
+                expected,
+            } => write!(f, "{measurand:?} in {unit:?} instead of {expected:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ReadingError {}
+
+fn check_unit(measurand: &Measurand, unit: &Unit) -> Result<(), ReadingError> {
+    match measurand.base_unit() {
+        Some(expected) if expected != *unit => Err(ReadingError::InconsistentUnit {
+            measurand: measurand.clone(),
+            unit: unit.clone(),
+            expected,
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn with_multiplier(value: Decimal, multiplier: i32) -> Option<Decimal> {
+    let exponent = multiplier.unsigned_abs();
+    if exponent > 28 {
+        return None;
+    }
+    match multiplier < 0 {
+        true => value.checked_mul(Decimal::new(1, exponent)),
+        false => value.checked_mul(Decimal::from_i128_with_scale(10_i128.pow(exponent), 0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn normalizes_units() {
+        let normalized = |unit, value| Unit::normalize(unit, value).unwrap();
+        assert_eq!(normalized("kWh", dec!(1.5)), (Unit::Wh, dec!(1500)));
+        assert_eq!(normalized("mA", dec!(250)), (Unit::A, dec!(0.25)));
+        assert_eq!(normalized("MW", dec!(2)), (Unit::W, dec!(2000000)));
+        assert_eq!(normalized("Celsius", dec!(20)), (Unit::K, dec!(293.15)));
+        assert_eq!(normalized("Fahrenheit", dec!(212)), (Unit::K, dec!(373.15)));
+        assert_eq!(
+            normalized("kPa", dec!(1)),
+            (Unit::Other("kPa".to_string()), dec!(1))
+        );
+        assert_eq!(Unit::normalize("kWh", Decimal::MAX), None);
+        assert_eq!(Unit::normalize("Celsius", Decimal::MAX), None);
+    }
+
+    #[test]
+    fn parses_measurands() {
+        assert_eq!(
+            parse_measurands("Energy.Active.Import.Register, SoC,,"),
+            vec!["Energy.Active.Import.Register", "SoC"]
+        );
+    }
+
+    #[test]
+    fn creates_normalized_readings() {
+        let timestamp = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let reading =
+            Reading::new(timestamp, ReadingKey::default(), dec!(12), Some("Wh"), 3).unwrap();
+        assert_eq!(reading.value, Some(dec!(12000)));
+        assert_eq!(reading.unit, Unit::Wh);
+
+        let voltage = ReadingKey {
+            measurand: Measurand::Voltage,
+            phase: Some(Phase::L1N),
+            ..Default::default()
+        };
+        let reading = Reading::new(timestamp, voltage.clone(), dec!(2301), None, -1).unwrap();
+        assert_eq!((reading.value, reading.unit), (Some(dec!(230.1)), Unit::V));
+        assert_eq!(
+            Reading::new(timestamp, voltage, dec!(230), Some("kWh"), 0),
+            Err(ReadingError::InconsistentUnit {
+                measurand: Measurand::Voltage,
+                unit: Unit::Wh,
+                expected: Unit::V
+            })
+        );
+        assert!(matches!(
+            Reading::new(timestamp, ReadingKey::default(), dec!(1), None, 40),
+            Err(ReadingError::InvalidValue(_))
+        ));
+        assert!(matches!(
+            Reading::new(
+                timestamp,
+                ReadingKey::default(),
+                Decimal::MAX,
+                Some("kWh"),
+                0
+            ),
+            Err(ReadingError::InvalidValue(_))
+        ));
+    }
+}
@@ -0,0 +1,356 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use serde::de::DeserializeOwned;
+
+use super::{ReadingContext, SamplingConfig};
+
+/// Message a [`Sample`] is sent in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Destination {
+    /// A `MeterValuesRequest` outside of a transaction.
+    MeterValues,
+    /// The messages of the transaction: a `MeterValuesRequest` with its `transactionId`
+    /// (1.6) or a `TransactionEventRequest` (2.x).
+    Transaction,
+    /// The end of the transaction: the `transactionData` of the `StopTransactionRequest`
+    /// (1.6) or the `meterValue` of the `Ended` `TransactionEventRequest` (2.x).
+    TransactionEnd,
+}
+
+/// Meter values to sample at one EVSE, or connector in 1.6.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+    /// EVSE, or connector in 1.6, 0 being the main meter.
+    pub evse_id: i32,
+    /// Time of the meter value.
+    pub timestamp: DateTime<Utc>,
+    /// Context of the sampled values.
+    pub context: ReadingContext,
+    /// OCPP names of the measurands to sample.
+    pub measurands: Vec<String>,
+    /// Message to send the values in.
+    pub destination: Destination,
+}
+
+impl Sample {
+    /// The measurands of the sample known to an OCPP version, such as the `Measurand` of
+    /// 1.6 or the `MeasurandEnumType` of 2.x, skipping the others.
+    pub fn measurands_as<M: DeserializeOwned>(&self) -> impl Iterator<Item = M> + '_ {
+        self.measurands
+            .iter()
+            .filter_map(|name| serde_json::from_value(name.as_str().into()).ok())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Transaction {
+    next_sampled: Option<DateTime<Utc>>,
+    next_ended: Option<DateTime<Utc>>,
+}
+
+/// Tells a Charging Station when to sample meter values.
+///
+/// Periodic samples are timed from the start of the transaction, clock aligned samples
+/// from midnight UTC. Samples missed because [`due`](Self::due) was called late are
+/// skipped, only the latest one is taken.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeterValueScheduler {
+    config: SamplingConfig,
+    evses: BTreeMap<i32, Option<Transaction>>,
+    next_aligned: Option<DateTime<Utc>>,
+    next_aligned_ended: Option<DateTime<Utc>>,
+}
+
+impl MeterValueScheduler {
+    /// Creates the scheduler of the EVSEs, or connectors in 1.6, `evse_ids` at `now`,
+    /// without transaction. Clock aligned values are sampled for each of them.
+    pub fn new(config: SamplingConfig, evse_ids: &[i32], now: DateTime<Utc>) -> Self {
+        let mut scheduler = Self {
+            config: SamplingConfig::default(),
+            evses: evse_ids.iter().map(|&evse_id| (evse_id, None)).collect(),
+            next_aligned: None,
+            next_aligned_ended: None,
+        };
+        scheduler.set_config(config, now);
+        scheduler
+    }
+
+    /// The sampling configuration.
+    pub fn config(&self) -> &SamplingConfig {
+        &self.config
+    }
+
+    /// Changes the sampling configuration at `now`, e.g. when the CSMS changes a variable.
+    /// Periodic samples of ongoing transactions are timed from `now`.
+    pub fn set_config(&mut self, config: SamplingConfig, now: DateTime<Utc>) {
+        self.next_aligned = next_boundary(now, config.aligned_interval);
+        self.next_aligned_ended = next_boundary(now, config.aligned_ended_interval);
+        for transaction in self.evses.values_mut().flatten() {
+            transaction.next_sampled = after(now, config.sampled_interval);
+            transaction.next_ended = after(now, config.ended_interval);
+        }
+        self.config = config;
+    }
+
+    /// Returns `true` while a transaction is ongoing at `evse_id`.
+    pub fn in_transaction(&self, evse_id: i32) -> bool {
+        matches!(self.evses.get(&evse_id), Some(Some(_)))
+    }
+
+    /// Records that a transaction started at `evse_id` at `now`, returning the
+    /// `Transaction.Begin` sample.
+    pub fn transaction_started(&mut self, evse_id: i32, now: DateTime<Utc>) -> Option<Sample> {
+        let transaction = Transaction {
+            next_sampled: after(now, self.config.sampled_interval),
+            next_ended: after(now, self.config.ended_interval),
+        };
+        self.evses.insert(evse_id, Some(transaction));
+        sample(
+            evse_id,
+            now,
+            ReadingContext::TransactionBegin,
+            &self.config.started_measurands,
+            Destination::Transaction,
+        )
+    }
+
+    /// Records that the transaction at `evse_id` ended at `now`, returning the
+    /// `Transaction.End` sample for the end of the transaction.
+    pub fn transaction_ended(&mut self, evse_id: i32, now: DateTime<Utc>) -> Option<Sample> {
+        self.evses.get_mut(&evse_id)?.take()?;
+        sample(
+            evse_id,
+            now,
+            ReadingContext::TransactionEnd,
+            &self.config.ended_measurands,
+            Destination::TransactionEnd,
+        )
+    }
+
+    /// When the next sample is due.
+    pub fn next_due(&self) -> Option<DateTime<Utc>> {
+        self.evses
+            .values()
+            .flatten()
+            .flat_map(|transaction| [transaction.next_sampled, transaction.next_ended])
+            .chain([self.next_aligned, self.next_aligned_ended])
+            .flatten()
+            .min()
+    }
+
+    /// The samples due at `now`.
+    pub fn due(&mut self, now: DateTime<Utc>) -> Vec<Sample> {
+        let config = &self.config;
+        let mut samples = Vec::new();
+        for (&evse_id, transaction) in &mut self.evses {
+            let Some(transaction) = transaction else {
+                continue;
+            };
+            if let Some(at) = transaction.next_sampled.filter(|at| *at <= now) {
+                transaction.next_sampled = Some(advance(at, config.sampled_interval, now));
+                samples.extend(sample(
+                    evse_id,
+                    now,
+                    ReadingContext::SamplePeriodic,
+                    &config.sampled_measurands,
+                    Destination::Transaction,
+                ));
+            }
+            if let Some(at) = transaction.next_ended.filter(|at| *at <= now) {
+                transaction.next_ended = Some(advance(at, config.ended_interval, now));
+                samples.extend(sample(
+                    evse_id,
+                    now,
+                    ReadingContext::SamplePeriodic,
+                    &config.ended_measurands,
+                    Destination::TransactionEnd,
+                ));
+            }
+        }
+
+        if let Some(at) = self.next_aligned.filter(|at| *at <= now) {
+            self.next_aligned = next_boundary(now, config.aligned_interval);
+            for (&evse_id, transaction) in &self.evses {
+                let destination = match transaction {
+                    None => Destination::MeterValues,
+                    Some(_) if config.aligned_only_when_idle => continue,
+                    Some(_) => Destination::Transaction,
+                };
+                samples.extend(sample(
+                    evse_id,
+                    at,
+                    ReadingContext::SampleClock,
+                    &config.aligned_measurands,
+                    destination,
+                ));
+            }
+        }
+        if let Some(at) = self.next_aligned_ended.filter(|at| *at <= now) {
+            self.next_aligned_ended = next_boundary(now, config.aligned_ended_interval);
+            for (&evse_id, _) in self.evses.iter().filter(|(_, t)| t.is_some()) {
+                samples.extend(sample(
+                    evse_id,
+                    at,
+                    ReadingContext::SampleClock,
+                    &config.aligned_ended_measurands,
+                    Destination::TransactionEnd,
+                ));
+            }
+        }
+        samples
+    }
+}
+
+fn sample(
+    evse_id: i32,
+    timestamp: DateTime<Utc>,
+    context: ReadingContext,
+    measurands: &[String],
+    destination: Destination,
+) -> Option<Sample> {
+    (!measurands.is_empty()).then(|| Sample {
+        evse_id,
+        timestamp,
+        context,
+        measurands: measurands.to_vec(),
+        destination,
+    })
+}
+
+fn after(start: DateTime<Utc>, interval: Duration) -> Option<DateTime<Utc>> {
+    (interval > Duration::zero()).then(|| start + interval)
+}
+
+/// First time after `now` of the series `at`, `at + interval`, ...
+fn advance(at: DateTime<Utc>, interval: Duration, now: DateTime<Utc>) -> DateTime<Utc> {
+    let interval = interval.num_milliseconds().max(1);
+    let missed = (now - at).num_milliseconds() / interval;
+    at + Duration::milliseconds(interval.saturating_mul(missed + 1))
+}
+
+/// First clock aligned time after `now`, at multiples of `interval` from midnight UTC.
+/// The last interval of a day ends at midnight.
+fn next_boundary(now: DateTime<Utc>, interval: Duration) -> Option<DateTime<Utc>> {
+    if interval <= Duration::zero() {
+        return None;
+    }
+    let midnight = now.date_naive().and_time(NaiveTime::MIN).and_utc();
+    let interval = interval.num_milliseconds().max(1);
+    let intervals = (now - midnight).num_milliseconds() / interval + 1;
+    let boundary = midnight + Duration::milliseconds(interval.saturating_mul(intervals));
+    Some(boundary.min(midnight + Duration::days(1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn names(measurands: &[&str]) -> Vec<String> {
+        measurands.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn samples_transactions() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let config = SamplingConfig {
+            sampled_interval: Duration::seconds(60),
+            sampled_measurands: names(&["Power.Active.Import"]),
+            started_measurands: names(&["Energy.Active.Import.Register"]),
+            ended_interval: Duration::seconds(300),
+            ended_measurands: names(&["Energy.Active.Import.Register"]),
+            ..Default::default()
+        };
+        let mut scheduler = MeterValueScheduler::new(config, &[1, 2], now);
+        assert_eq!(scheduler.next_due(), None);
+
+        let begin = scheduler.transaction_started(1, now).unwrap();
+        assert_eq!(begin.context, ReadingContext::TransactionBegin);
+        assert_eq!(begin.destination, Destination::Transaction);
+        assert!(scheduler.in_transaction(1));
+        assert!(scheduler.due(now + Duration::seconds(30)).is_empty());
+
+        let samples = scheduler.due(now + Duration::seconds(60));
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].context, ReadingContext::SamplePeriodic);
+        assert_eq!(samples[0].measurands, names(&["Power.Active.Import"]));
+
+        // the samples of 12:02 and 12:03 are missed
+        let samples = scheduler.due(now + Duration::seconds(210));
+        assert_eq!(samples.len(), 1);
+        assert_eq!(scheduler.next_due(), Some(now + Duration::seconds(240)));
+
+        let samples = scheduler.due(now + Duration::seconds(300));
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[1].destination, Destination::TransactionEnd);
+        assert_eq!(samples[1].context, ReadingContext::SamplePeriodic);
+
+        let end = scheduler.transaction_ended(1, now + Duration::seconds(330));
+        let end = end.unwrap();
+        assert_eq!(end.context, ReadingContext::TransactionEnd);
+        assert_eq!(end.destination, Destination::TransactionEnd);
+        assert_eq!(scheduler.next_due(), None);
+        assert_eq!(scheduler.transaction_ended(1, now), None);
+    }
+
+    #[test]
+    fn samples_clock_aligned() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 5, 0).unwrap();
+        let quarter = Utc.with_ymd_and_hms(2025, 3, 1, 12, 15, 0).unwrap();
+        let config = SamplingConfig {
+            aligned_interval: Duration::minutes(15),
+            aligned_measurands: names(&["Energy.Active.Import.Register"]),
+            aligned_ended_interval: Duration::minutes(15),
+            aligned_ended_measurands: names(&["Energy.Active.Import.Interval"]),
+            ..Default::default()
+        };
+        let mut scheduler = MeterValueScheduler::new(config.clone(), &[1, 2], now);
+        assert_eq!(scheduler.next_due(), Some(quarter));
+        assert_eq!(scheduler.transaction_started(1, now), None);
+
+        let samples = scheduler.due(quarter + Duration::seconds(2));
+        let destinations: Vec<_> = samples
+            .iter()
+            .map(|sample| (sample.evse_id, sample.destination))
+            .collect();
+        assert_eq!(
+            destinations,
+            vec![
+                (1, Destination::Transaction),
+                (2, Destination::MeterValues),
+                (1, Destination::TransactionEnd)
+            ]
+        );
+        assert!(samples
+            .iter()
+            .all(|sample| sample.timestamp == quarter
+                && sample.context == ReadingContext::SampleClock));
+        assert_eq!(scheduler.next_due(), Some(quarter + Duration::minutes(15)));
+
+        let config = SamplingConfig {
+            aligned_only_when_idle: true,
+            ..config
+        };
+        scheduler.set_config(config, quarter);
+        let samples = scheduler.due(quarter + Duration::minutes(15));
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].evse_id, 2);
+        assert_eq!(samples[1].destination, Destination::TransactionEnd);
+    }
+
+    #[test]
+    fn aligns_to_midnight() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 22, 0, 0).unwrap();
+        assert_eq!(
+            next_boundary(now, Duration::hours(7)),
+            Some(Utc.with_ymd_and_hms(2025, 3, 2, 0, 0, 0).unwrap())
+        );
+        assert_eq!(
+            next_boundary(now, Duration::hours(1)),
+            Some(Utc.with_ymd_and_hms(2025, 3, 1, 23, 0, 0).unwrap())
+        );
+        assert_eq!(next_boundary(now, Duration::zero()), None);
+    }
+}
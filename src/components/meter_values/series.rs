@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+use super::{Location, Measurand, Reading, ReadingError, ReadingKey};
+
+/// Readings of one EVSE, grouped into series by [`ReadingKey`] and ordered by time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeterReadings {
+    series: BTreeMap<ReadingKey, Vec<Reading>>,
+}
+
+impl MeterReadings {
+    /// Creates an empty set of readings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a reading to its series. A reading of the same time, such as a meter value sent
+    /// again or the signed data of a 1.6 value, replaces the earlier reading but keeps its
+    /// value or signed data when the new reading has none.
+    ///
+    /// A reading in another unit than the earlier readings of its series is rejected.
+    pub fn add(&mut self, reading: Reading) -> Result<(), ReadingError> {
+        let series = self.series.entry(reading.key.clone()).or_default();
+        if let Some(first) = series.first() {
+            if first.unit != reading.unit {
+                return Err(ReadingError::InconsistentUnit {
+                    measurand: reading.key.measurand,
+                    unit: reading.unit,
+                    expected: first.unit.clone(),
+                });
+            }
+        }
+        let index = series.partition_point(|other| other.timestamp < reading.timestamp);
+        match series.get(index) {
+            Some(other) if other.timestamp == reading.timestamp => {
+                let previous = std::mem::replace(&mut series[index], reading);
+                let merged = &mut series[index];
+                merged.value = merged.value.or(previous.value);
+                merged.signed_data = merged.signed_data.take().or(previous.signed_data);
+            }
+            _ => series.insert(index, reading),
+        }
+        Ok(())
+    }
+
+    /// Adds readings in order, stopping at the first rejected reading.
+    pub fn extend(
+        &mut self,
+        readings: impl IntoIterator<Item = Reading>,
+    ) -> Result<(), ReadingError> {
+        readings
+            .into_iter()
+            .try_for_each(|reading| self.add(reading))
+    }
+
+    /// Keys of the series with readings.
+    pub fn keys(&self) -> impl Iterator<Item = &ReadingKey> {
+        self.series.keys()
+    }
+
+    /// Readings of a series, ordered by time.
+    pub fn series(&self, key: &ReadingKey) -> &[Reading] {
+        self.series.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    /// Canonical energy time series in Wh: the `Energy.Active.Import.Register` over all
+    /// phases at the outlet, merged over all reading contexts. A time with readings in
+    /// several contexts appears once.
+    pub fn energy(&self) -> Vec<(DateTime<Utc>, Decimal)> {
+        let mut energy: Vec<_> = self
+            .series
+            .iter()
+            .filter(|(key, _)| {
+                key.measurand == Measurand::EnergyActiveImportRegister
+                    && key.phase.is_none()
+                    && key.location == Location::Outlet
+            })
+            .flat_map(|(_, series)| series)
+            .filter_map(|reading| Some((reading.timestamp, reading.value?)))
+            .collect();
+        energy.sort_by_key(|(timestamp, _)| *timestamp);
+        energy.dedup_by_key(|(timestamp, _)| *timestamp);
+        energy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::components::meter_values::{ReadingContext, Unit};
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn energy(minutes: i64, context: ReadingContext, kwh: Decimal) -> Reading {
+        let key = ReadingKey {
+            context,
+            ..Default::default()
+        };
+        Reading::new(now() + Duration::minutes(minutes), key, kwh, Some("kWh"), 0).unwrap()
+    }
+
+    #[test]
+    fn merges_energy_series() {
+        let mut readings = MeterReadings::new();
+        readings
+            .extend([
+                energy(10, ReadingContext::SamplePeriodic, dec!(1.5)),
+                energy(0, ReadingContext::TransactionBegin, dec!(1)),
+                energy(5, ReadingContext::SamplePeriodic, dec!(1.2)),
+                energy(5, ReadingContext::SampleClock, dec!(1.2)),
+                energy(10, ReadingContext::SamplePeriodic, dec!(1.4)),
+            ])
+            .unwrap();
+        assert_eq!(readings.keys().count(), 3);
+        let periodic = ReadingKey::default();
+        assert_eq!(readings.series(&periodic).len(), 2);
+        assert_eq!(
+            readings.energy(),
+            vec![
+                (now(), dec!(1000)),
+                (now() + Duration::minutes(5), dec!(1200)),
+                (now() + Duration::minutes(10), dec!(1400)),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unit_changes_within_series() {
+        let mut readings = MeterReadings::new();
+        let key = ReadingKey {
+            measurand: Measurand::Other("Display.PresentSOC".to_string()),
+            ..Default::default()
+        };
+        readings
+            .add(Reading::new(now(), key.clone(), dec!(40), Some("Percent"), 0).unwrap())
+            .unwrap();
+        let reading = Reading::new(now(), key, dec!(0.4), None, 0).unwrap();
+        assert_eq!(reading.unit, Unit::Dimensionless);
+        assert!(matches!(
+            readings.add(reading),
+            Err(ReadingError::InconsistentUnit { .. })
+        ));
+    }
+}
@@ -0,0 +1,330 @@
+//! OCPP 1.6 meter values.
+//!
+//! Values are sent as strings, a value in the `SignedData` format is kept as signed data
+//! without value.
+//!
+//! [`meter_value`] builds the `MeterValue` of a [`Sample`] of the
+//! [`MeterValueScheduler`](super::MeterValueScheduler), to send in a `MeterValuesRequest` or
+//! the `transactionData` of a `StopTransactionRequest`.
+
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+
+use crate::v1_6::{
+    messages::meter_values::MeterValuesRequest,
+    types::{self, MeterValue, SampledValue, ValueFormat},
+};
+
+use super::{
+    Location, Measurand, Phase, Reading, ReadingContext, ReadingError, ReadingKey, Sample,
+    SignedData,
+};
+
+impl From<&types::Measurand> for Measurand {
+    fn from(measurand: &types::Measurand) -> Self {
+        match measurand {
+            types::Measurand::CurrentExport => Self::CurrentExport,
+            types::Measurand::CurrentImport => Self::CurrentImport,
+            types::Measurand::CurrentOffered => Self::CurrentOffered,
+            types::Measurand::EnergyActiveExportRegister => Self::EnergyActiveExportRegister,
+            types::Measurand::EnergyActiveImportRegister => Self::EnergyActiveImportRegister,
+            types::Measurand::EnergyReactiveExportRegister => Self::EnergyReactiveExportRegister,
+            types::Measurand::EnergyReactiveImportRegister => Self::EnergyReactiveImportRegister,
+            types::Measurand::EnergyActiveExportInterval => Self::EnergyActiveExportInterval,
+            types::Measurand::EnergyActiveImportInterval => Self::EnergyActiveImportInterval,
+            types::Measurand::EnergyReactiveExportInterval => Self::EnergyReactiveExportInterval,
+            types::Measurand::EnergyReactiveImportInterval => Self::EnergyReactiveImportInterval,
+            types::Measurand::Frequency => Self::Frequency,
+            types::Measurand::PowerActiveExport => Self::PowerActiveExport,
+            types::Measurand::PowerActiveImport => Self::PowerActiveImport,
+            types::Measurand::PowerFactor => Self::PowerFactor,
+            types::Measurand::PowerOffered => Self::PowerOffered,
+            types::Measurand::PowerReactiveExport => Self::PowerReactiveExport,
+            types::Measurand::PowerReactiveImport => Self::PowerReactiveImport,
+            types::Measurand::SoC => Self::SoC,
+            types::Measurand::Voltage => Self::Voltage,
+            types::Measurand::Rpm => Self::Rpm,
+            types::Measurand::Temperature => Self::Temperature,
+        }
+    }
+}
+
+impl From<&types::Phase> for Phase {
+    fn from(phase: &types::Phase) -> Self {
+        match phase {
+            types::Phase::L1 => Self::L1,
+            types::Phase::L2 => Self::L2,
+            types::Phase::L3 => Self::L3,
+            types::Phase::N => Self::N,
+            types::Phase::L1N => Self::L1N,
+            types::Phase::L2N => Self::L2N,
+            types::Phase::L3N => Self::L3N,
+            types::Phase::L1L2 => Self::L1L2,
+            types::Phase::L2L3 => Self::L2L3,
+            types::Phase::L3L1 => Self::L3L1,
+        }
+    }
+}
+
+impl From<&types::Location> for Location {
+    fn from(location: &types::Location) -> Self {
+        match location {
+            types::Location::Body => Self::Body,
+            types::Location::Cable => Self::Cable,
+            types::Location::Ev => Self::Ev,
+            types::Location::Inlet => Self::Inlet,
+            types::Location::Outlet => Self::Outlet,
+        }
+    }
+}
+
+impl From<&types::ReadingContext> for ReadingContext {
+    fn from(context: &types::ReadingContext) -> Self {
+        match context {
+            types::ReadingContext::InterruptionBegin => Self::InterruptionBegin,
+            types::ReadingContext::InterruptionEnd => Self::InterruptionEnd,
+            types::ReadingContext::Other => Self::Other,
+            types::ReadingContext::SampleClock => Self::SampleClock,
+            types::ReadingContext::SamplePeriodic => Self::SamplePeriodic,
+            types::ReadingContext::TransactionBegin => Self::TransactionBegin,
+            types::ReadingContext::TransactionEnd => Self::TransactionEnd,
+            types::ReadingContext::Trigger => Self::Trigger,
+        }
+    }
+}
+
+impl From<ReadingContext> for types::ReadingContext {
+    fn from(context: ReadingContext) -> Self {
+        match context {
+            ReadingContext::InterruptionBegin => Self::InterruptionBegin,
+            ReadingContext::InterruptionEnd => Self::InterruptionEnd,
+            ReadingContext::Other => Self::Other,
+            ReadingContext::SampleClock => Self::SampleClock,
+            ReadingContext::SamplePeriodic => Self::SamplePeriodic,
+            ReadingContext::TransactionBegin => Self::TransactionBegin,
+            ReadingContext::TransactionEnd => Self::TransactionEnd,
+            ReadingContext::Trigger => Self::Trigger,
+        }
+    }
+}
+
+impl From<&SampledValue> for ReadingKey {
+    fn from(sampled_value: &SampledValue) -> Self {
+        Self {
+            measurand: sampled_value
+                .measurand
+                .as_ref()
+                .map(Measurand::from)
+                .unwrap_or_default(),
+            phase: sampled_value.phase.as_ref().map(Phase::from),
+            location: sampled_value
+                .location
+                .as_ref()
+                .map(Location::from)
+                .unwrap_or_default(),
+            context: sampled_value
+                .context
+                .as_ref()
+                .map(ReadingContext::from)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+fn unit_name(unit: &types::UnitOfMeasure) -> &'static str {
+    match unit {
+        types::UnitOfMeasure::Wh => "Wh",
+        types::UnitOfMeasure::KWh => "kWh",
+        types::UnitOfMeasure::Varh => "varh",
+        types::UnitOfMeasure::Kvarh => "kvarh",
+        types::UnitOfMeasure::W => "W",
+        types::UnitOfMeasure::Kw => "kW",
+        types::UnitOfMeasure::Va => "VA",
+        types::UnitOfMeasure::Kva => "kVA",
+        types::UnitOfMeasure::Var => "var",
+        types::UnitOfMeasure::Kvar => "kvar",
+        types::UnitOfMeasure::A => "A",
+        types::UnitOfMeasure::V => "V",
+        types::UnitOfMeasure::Celsius => "Celsius",
+        types::UnitOfMeasure::Fahrenheit => "Fahrenheit",
+        types::UnitOfMeasure::K => "K",
+        types::UnitOfMeasure::Percent => "Percent",
+    }
+}
+
+/// Normalized readings of the sampled values of `meter_values`, such as those of a
+/// `MeterValuesRequest` or the `transaction_data` of a `StopTransactionRequest`.
+pub fn readings(meter_values: &[MeterValue]) -> Result<Vec<Reading>, ReadingError> {
+    meter_values
+        .iter()
+        .flat_map(|meter_value| {
+            meter_value
+                .sampled_value
+                .iter()
+                .map(|sampled_value| reading(meter_value, sampled_value))
+        })
+        .collect()
+}
+
+fn reading(
+    meter_value: &MeterValue,
+    sampled_value: &SampledValue,
+) -> Result<Reading, ReadingError> {
+    let key = ReadingKey::from(sampled_value);
+    let unit = sampled_value.unit.as_ref().map(unit_name);
+    if sampled_value.format == Some(ValueFormat::SignedData) {
+        let signed_data = SignedData {
+            data: sampled_value.value.clone(),
+            ..Default::default()
+        };
+        return Reading::signed(meter_value.timestamp, key, unit, signed_data);
+    }
+    let value = sampled_value.value.trim();
+    let value = Decimal::from_str(value)
+        .or_else(|_| Decimal::from_scientific(value))
+        .map_err(|_| ReadingError::InvalidValue(sampled_value.value.clone()))?;
+    Reading::new(meter_value.timestamp, key, value, unit, 0)
+}
+
+/// The `MeterValue` of `sample`, with the values that `read` measures for each of its
+/// measurands, such as one value per phase. The context and measurand of the values are
+/// set from the sample. Returns `None` when no value was measured.
+pub fn meter_value(
+    sample: &Sample,
+    mut read: impl FnMut(&types::Measurand) -> Vec<SampledValue>,
+) -> Option<MeterValue> {
+    let sampled_value: Vec<_> = sample
+        .measurands_as::<types::Measurand>()
+        .flat_map(|measurand| {
+            read(&measurand).into_iter().map(move |value| SampledValue {
+                context: Some(sample.context.into()),
+                measurand: Some(measurand.clone()),
+                ..value
+            })
+        })
+        .collect();
+    if sampled_value.is_empty() {
+        return None;
+    }
+    Some(MeterValue {
+        timestamp: sample.timestamp,
+        sampled_value,
+    })
+}
+
+/// The `MeterValuesRequest` of `sample`, for the transaction `transaction_id` if any, see
+/// [`meter_value`].
+pub fn meter_values_request(
+    sample: &Sample,
+    transaction_id: Option<i32>,
+    read: impl FnMut(&types::Measurand) -> Vec<SampledValue>,
+) -> Option<MeterValuesRequest> {
+    Some(MeterValuesRequest {
+        connector_id: u32::try_from(sample.evse_id).ok()?,
+        transaction_id,
+        meter_value: vec![meter_value(sample, read)?],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::components::meter_values::{Destination, MeterReadings, Unit};
+
+    #[test]
+    fn reads_sampled_values() {
+        let timestamp = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let meter_value = MeterValue {
+            timestamp,
+            sampled_value: vec![
+                SampledValue {
+                    value: "12.345".to_string(),
+                    unit: Some(types::UnitOfMeasure::KWh),
+                    ..Default::default()
+                },
+                SampledValue {
+                    value: "16".to_string(),
+                    measurand: Some(types::Measurand::CurrentImport),
+                    phase: Some(types::Phase::L2),
+                    unit: Some(types::UnitOfMeasure::A),
+                    ..Default::default()
+                },
+                SampledValue {
+                    value: "OCMF|{}|{}".to_string(),
+                    format: Some(ValueFormat::SignedData),
+                    ..Default::default()
+                },
+            ],
+        };
+        let readings = readings(std::slice::from_ref(&meter_value)).unwrap();
+        assert_eq!(readings[0].value, Some(dec!(12345)));
+        assert_eq!(readings[0].unit, Unit::Wh);
+        assert_eq!(readings[1].key.phase, Some(Phase::L2));
+        assert_eq!(readings[1].unit, Unit::A);
+        assert_eq!(readings[2].value, None);
+        assert_eq!(readings[2].signed_data.as_ref().unwrap().data, "OCMF|{}|{}");
+
+        let mut series = MeterReadings::new();
+        series.extend(readings).unwrap();
+        assert_eq!(series.energy(), vec![(timestamp, dec!(12345))]);
+        assert!(series.series(&ReadingKey::default())[0]
+            .signed_data
+            .is_some());
+
+        let mut invalid = meter_value;
+        invalid.sampled_value[1].unit = Some(types::UnitOfMeasure::Kw);
+        assert!(matches!(
+            super::readings(&[invalid.clone()]),
+            Err(ReadingError::InconsistentUnit { .. })
+        ));
+        invalid.sampled_value[1].value = "n/a".to_string();
+        assert_eq!(
+            super::readings(&[invalid]),
+            Err(ReadingError::InvalidValue("n/a".to_string()))
+        );
+    }
+
+    #[test]
+    fn builds_meter_values_of_samples() {
+        let sample = Sample {
+            evse_id: 1,
+            timestamp: Utc.with_ymd_and_hms(2025, 3, 1, 12, 15, 0).unwrap(),
+            context: ReadingContext::SampleClock,
+            measurands: vec![
+                "Energy.Active.Import.Register".to_string(),
+                "Current.Import".to_string(),
+                "Display.PresentSOC".to_string(),
+            ],
+            destination: Destination::Transaction,
+        };
+        let request = meter_values_request(&sample, Some(42), |measurand| match measurand {
+            types::Measurand::CurrentImport => [types::Phase::L1, types::Phase::L2]
+                .map(|phase| SampledValue {
+                    value: "16".to_string(),
+                    phase: Some(phase),
+                    ..Default::default()
+                })
+                .to_vec(),
+            _ => vec![SampledValue {
+                value: "1200".to_string(),
+                ..Default::default()
+            }],
+        })
+        .unwrap();
+        assert_eq!(request.transaction_id, Some(42));
+        let sampled_value = &request.meter_value[0].sampled_value;
+        assert_eq!(sampled_value.len(), 3);
+        assert_eq!(
+            sampled_value[1].measurand,
+            Some(types::Measurand::CurrentImport)
+        );
+        assert!(sampled_value
+            .iter()
+            .all(|value| value.context == Some(types::ReadingContext::SampleClock)));
+        assert_eq!(meter_value(&sample, |_| Vec::new()), None);
+    }
+}
@@ -0,0 +1,342 @@
+//! OCPP 2.0.1 meter values.
+//!
+//! [`meter_value`] builds the `MeterValue` of a [`Sample`] of the
+//! [`MeterValueScheduler`](super::MeterValueScheduler), to send in a `MeterValuesRequest` or a
+//! `TransactionEventRequest`.
+
+use crate::v2_0_1::{
+    datatypes::{
+        meter_value_type::MeterValueType, sampled_value_type::SampledValueType,
+        signed_meter_value_type::SignedMeterValueType,
+    },
+    enumerations::{
+        location_enum_type::LocationEnumType, measurand_enum_type::MeasurandEnumType,
+        phase_enum_type::PhaseEnumType, reading_context_enum_type::ReadingContextEnumType,
+        trigger_reason_enum_type::TriggerReasonEnumType,
+    },
+    messages::meter_values::MeterValuesRequest,
+};
+
+use super::{
+    Destination, Location, Measurand, Phase, Reading, ReadingContext, ReadingError, ReadingKey,
+    Sample, SignedData,
+};
+
+impl From<&MeasurandEnumType> for Measurand {
+    fn from(measurand: &MeasurandEnumType) -> Self {
+        match measurand {
+            MeasurandEnumType::CurrentExport => Self::CurrentExport,
+            MeasurandEnumType::CurrentImport => Self::CurrentImport,
+            MeasurandEnumType::CurrentOffered => Self::CurrentOffered,
+            MeasurandEnumType::EnergyActiveExportRegister => Self::EnergyActiveExportRegister,
+            MeasurandEnumType::EnergyActiveImportRegister => Self::EnergyActiveImportRegister,
+            MeasurandEnumType::EnergyReactiveExportRegister => Self::EnergyReactiveExportRegister,
+            MeasurandEnumType::EnergyReactiveImportRegister => Self::EnergyReactiveImportRegister,
+            MeasurandEnumType::EnergyActiveExportInterval => Self::EnergyActiveExportInterval,
+            MeasurandEnumType::EnergyActiveImportInterval => Self::EnergyActiveImportInterval,
+            MeasurandEnumType::EnergyReactiveExportInterval => Self::EnergyReactiveExportInterval,
+            MeasurandEnumType::EnergyReactiveImportInterval => Self::EnergyReactiveImportInterval,
+            MeasurandEnumType::Frequency => Self::Frequency,
+            MeasurandEnumType::PowerActiveExport => Self::PowerActiveExport,
+            MeasurandEnumType::PowerActiveImport => Self::PowerActiveImport,
+            MeasurandEnumType::PowerFactor => Self::PowerFactor,
+            MeasurandEnumType::PowerOffered => Self::PowerOffered,
+            MeasurandEnumType::PowerReactiveExport => Self::PowerReactiveExport,
+            MeasurandEnumType::PowerReactiveImport => Self::PowerReactiveImport,
+            MeasurandEnumType::SoC => Self::SoC,
+            MeasurandEnumType::Voltage => Self::Voltage,
+            MeasurandEnumType::EnergyActiveNet => Self::EnergyActiveNet,
+            MeasurandEnumType::EnergyReactiveNet => Self::EnergyReactiveNet,
+            MeasurandEnumType::EnergyApparentNet => Self::EnergyApparentNet,
+            MeasurandEnumType::EnergyApparentImport => Self::EnergyApparentImport,
+            MeasurandEnumType::EnergyApparentExport => Self::EnergyApparentExport,
+        }
+    }
+}
+
+impl From<&PhaseEnumType> for Phase {
+    fn from(phase: &PhaseEnumType) -> Self {
+        match phase {
+            PhaseEnumType::L1 => Self::L1,
+            PhaseEnumType::L2 => Self::L2,
+            PhaseEnumType::L3 => Self::L3,
+            PhaseEnumType::N => Self::N,
+            PhaseEnumType::L1N => Self::L1N,
+            PhaseEnumType::L2N => Self::L2N,
+            PhaseEnumType::L3N => Self::L3N,
+            PhaseEnumType::L1L2 => Self::L1L2,
+            PhaseEnumType::L2L3 => Self::L2L3,
+            PhaseEnumType::L3L1 => Self::L3L1,
+        }
+    }
+}
+
+impl From<&LocationEnumType> for Location {
+    fn from(location: &LocationEnumType) -> Self {
+        match location {
+            LocationEnumType::Body => Self::Body,
+            LocationEnumType::Cable => Self::Cable,
+            LocationEnumType::EV => Self::Ev,
+            LocationEnumType::Inlet => Self::Inlet,
+            LocationEnumType::Outlet => Self::Outlet,
+        }
+    }
+}
+
+impl From<&ReadingContextEnumType> for ReadingContext {
+    fn from(context: &ReadingContextEnumType) -> Self {
+        match context {
+            ReadingContextEnumType::InterruptionBegin => Self::InterruptionBegin,
+            ReadingContextEnumType::InterruptionEnd => Self::InterruptionEnd,
+            ReadingContextEnumType::Other => Self::Other,
+            ReadingContextEnumType::SampleClock => Self::SampleClock,
+            ReadingContextEnumType::SamplePeriodic => Self::SamplePeriodic,
+            ReadingContextEnumType::TransactionBegin => Self::TransactionBegin,
+            ReadingContextEnumType::TransactionEnd => Self::TransactionEnd,
+            ReadingContextEnumType::Trigger => Self::Trigger,
+        }
+    }
+}
+
+impl From<ReadingContext> for ReadingContextEnumType {
+    fn from(context: ReadingContext) -> Self {
+        match context {
+            ReadingContext::InterruptionBegin => Self::InterruptionBegin,
+            ReadingContext::InterruptionEnd => Self::InterruptionEnd,
+            ReadingContext::Other => Self::Other,
+            ReadingContext::SampleClock => Self::SampleClock,
+            ReadingContext::SamplePeriodic => Self::SamplePeriodic,
+            ReadingContext::TransactionBegin => Self::TransactionBegin,
+            ReadingContext::TransactionEnd => Self::TransactionEnd,
+            ReadingContext::Trigger => Self::Trigger,
+        }
+    }
+}
+
+impl From<&SampledValueType> for ReadingKey {
+    fn from(sampled_value: &SampledValueType) -> Self {
+        Self {
+            measurand: sampled_value
+                .measurand
+                .as_ref()
+                .map(Measurand::from)
+                .unwrap_or_default(),
+            phase: sampled_value.phase.as_ref().map(Phase::from),
+            location: sampled_value
+                .location
+                .as_ref()
+                .map(Location::from)
+                .unwrap_or_default(),
+            context: sampled_value
+                .context
+                .as_ref()
+                .map(ReadingContext::from)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&SignedMeterValueType> for SignedData {
+    fn from(signed: &SignedMeterValueType) -> Self {
+        let non_empty = |value: &String| Some(value.clone()).filter(|value| !value.is_empty());
+        Self {
+            data: signed.signed_meter_data.clone(),
+            encoding_method: non_empty(&signed.encoding_method),
+            signing_method: non_empty(&signed.signing_method),
+            public_key: non_empty(&signed.public_key),
+        }
+    }
+}
+
+/// Normalized readings of the sampled values of `meter_values`, such as those of a
+/// `MeterValuesRequest` or a `TransactionEventRequest`.
+pub fn readings(meter_values: &[MeterValueType]) -> Result<Vec<Reading>, ReadingError> {
+    meter_values
+        .iter()
+        .flat_map(|meter_value| {
+            meter_value.sampled_value.iter().map(|sampled_value| {
+                let unit = sampled_value.unit_of_measure.as_ref();
+                let reading = Reading::new(
+                    meter_value.timestamp,
+                    ReadingKey::from(sampled_value),
+                    sampled_value.value,
+                    unit.and_then(|unit| unit.unit.as_deref()),
+                    unit.and_then(|unit| unit.multiplier).unwrap_or_default(),
+                )?;
+                Ok(match &sampled_value.signed_meter_value {
+                    Some(signed) => reading.with_signed_data(signed.into()),
+                    None => reading,
+                })
+            })
+        })
+        .collect()
+}
+
+/// The `MeterValue` of `sample`, with the values that `read` measures for each of its
+/// measurands, such as one value per phase. The context and measurand of the values are
+/// set from the sample. Returns `None` when no value was measured.
+pub fn meter_value(
+    sample: &Sample,
+    mut read: impl FnMut(&MeasurandEnumType) -> Vec<SampledValueType>,
+) -> Option<MeterValueType> {
+    let sampled_value: Vec<_> = sample
+        .measurands_as::<MeasurandEnumType>()
+        .flat_map(|measurand| {
+            read(&measurand)
+                .into_iter()
+                .map(move |value| SampledValueType {
+                    context: Some(sample.context.into()),
+                    measurand: Some(measurand.clone()),
+                    ..value
+                })
+        })
+        .collect();
+    if sampled_value.is_empty() {
+        return None;
+    }
+    Some(MeterValueType {
+        timestamp: sample.timestamp,
+        sampled_value,
+    })
+}
+
+/// The `MeterValuesRequest` of a sample outside of a transaction, see [`meter_value`].
+pub fn meter_values_request(
+    sample: &Sample,
+    read: impl FnMut(&MeasurandEnumType) -> Vec<SampledValueType>,
+) -> Option<MeterValuesRequest> {
+    Some(MeterValuesRequest {
+        evse_id: sample.evse_id,
+        meter_value: vec![meter_value(sample, read)?],
+    })
+}
+
+/// `triggerReason` of the `Updated` `TransactionEventRequest` sending a periodic or clock
+/// aligned sample of the transaction, `None` for other samples.
+pub fn trigger_reason(sample: &Sample) -> Option<TriggerReasonEnumType> {
+    if sample.destination != Destination::Transaction {
+        return None;
+    }
+    match sample.context {
+        ReadingContext::SampleClock => Some(TriggerReasonEnumType::MeterValueClock),
+        ReadingContext::SamplePeriodic => Some(TriggerReasonEnumType::MeterValuePeriodic),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::{
+        components::meter_values::{MeterReadings, Unit},
+        v2_0_1::datatypes::unit_of_measure_type::UnitOfMeasureType,
+    };
+
+    #[test]
+    fn reads_sampled_values() {
+        let timestamp = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let meter_value = MeterValueType {
+            timestamp,
+            sampled_value: vec![
+                SampledValueType {
+                    value: dec!(12.345),
+                    context: Some(ReadingContextEnumType::TransactionBegin),
+                    unit_of_measure: Some(UnitOfMeasureType {
+                        unit: Some("Wh".to_string()),
+                        multiplier: Some(3),
+                    }),
+                    signed_meter_value: Some(SignedMeterValueType {
+                        signed_meter_data: "OCMF|{}|{}".to_string(),
+                        encoding_method: "OCMF".to_string(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                SampledValueType {
+                    value: dec!(7.4),
+                    measurand: Some(MeasurandEnumType::PowerActiveImport),
+                    unit_of_measure: Some(UnitOfMeasureType {
+                        unit: Some("kW".to_string()),
+                        multiplier: None,
+                    }),
+                    ..Default::default()
+                },
+            ],
+        };
+        let readings = readings(std::slice::from_ref(&meter_value)).unwrap();
+        assert_eq!(readings[0].value, Some(dec!(12345)));
+        assert_eq!(readings[0].key.context, ReadingContext::TransactionBegin);
+        let signed_data = readings[0].signed_data.as_ref().unwrap();
+        assert_eq!(signed_data.encoding_method.as_deref(), Some("OCMF"));
+        assert_eq!(signed_data.public_key, None);
+        assert_eq!(
+            (readings[1].value, &readings[1].unit),
+            (Some(dec!(7400)), &Unit::W)
+        );
+
+        let mut series = MeterReadings::new();
+        series.extend(readings).unwrap();
+        assert_eq!(series.energy(), vec![(timestamp, dec!(12345))]);
+
+        let mut invalid = meter_value;
+        invalid.sampled_value[1].unit_of_measure = None;
+        invalid.sampled_value[1].measurand = Some(MeasurandEnumType::Voltage);
+        assert!(super::readings(&[invalid.clone()]).is_ok());
+        invalid.sampled_value[1].unit_of_measure = Some(UnitOfMeasureType {
+            unit: Some("A".to_string()),
+            multiplier: None,
+        });
+        assert!(matches!(
+            super::readings(&[invalid]),
+            Err(ReadingError::InconsistentUnit { .. })
+        ));
+    }
+
+    #[test]
+    fn builds_meter_values_of_samples() {
+        let sample = Sample {
+            evse_id: 1,
+            timestamp: Utc.with_ymd_and_hms(2025, 3, 1, 12, 1, 0).unwrap(),
+            context: ReadingContext::SamplePeriodic,
+            measurands: vec![
+                "Energy.Active.Import.Register".to_string(),
+                "Power.Active.Import".to_string(),
+            ],
+            destination: Destination::Transaction,
+        };
+        let meter_value = meter_value(&sample, |measurand| match measurand {
+            MeasurandEnumType::PowerActiveImport => vec![SampledValueType {
+                value: dec!(7400),
+                ..Default::default()
+            }],
+            _ => Vec::new(),
+        })
+        .unwrap();
+        assert_eq!(meter_value.timestamp, sample.timestamp);
+        assert_eq!(meter_value.sampled_value.len(), 1);
+        assert_eq!(
+            meter_value.sampled_value[0].measurand,
+            Some(MeasurandEnumType::PowerActiveImport)
+        );
+        assert_eq!(
+            meter_value.sampled_value[0].context,
+            Some(ReadingContextEnumType::SamplePeriodic)
+        );
+        assert_eq!(
+            trigger_reason(&sample),
+            Some(TriggerReasonEnumType::MeterValuePeriodic)
+        );
+
+        let sample = Sample {
+            context: ReadingContext::TransactionEnd,
+            destination: Destination::TransactionEnd,
+            ..sample
+        };
+        assert_eq!(trigger_reason(&sample), None);
+        let request = meter_values_request(&sample, |_| vec![SampledValueType::default()]);
+        assert_eq!(request.unwrap().meter_value[0].sampled_value.len(), 2);
+    }
+}
@@ -0,0 +1,315 @@
+//! OCPP 2.1 meter values.
+//!
+//! Measurands added in 2.1, such as the `Display.*` and `EnergyRequest.*` measurands, are
+//! kept as [`Measurand::Other`] without unit check.
+//!
+//! [`meter_value`] builds the `MeterValue` of a [`Sample`] of the
+//! [`MeterValueScheduler`](super::MeterValueScheduler), to send in a `MeterValuesRequest` or a
+//! `TransactionEventRequest`.
+
+use rust_decimal::Decimal;
+
+use crate::v2_1::{
+    datatypes::{MeterValueType, SampledValueType, SignedMeterValueType},
+    enumerations::{
+        LocationEnumType, MeasurandEnumType, PhaseEnumType, ReadingContextEnumType,
+        TriggerReasonEnumType,
+    },
+    messages::meter_values::MeterValuesRequest,
+};
+
+use super::{
+    Destination, Location, Measurand, Phase, Reading, ReadingContext, ReadingError, ReadingKey,
+    Sample, SignedData,
+};
+
+impl From<&MeasurandEnumType> for Measurand {
+    fn from(measurand: &MeasurandEnumType) -> Self {
+        match measurand {
+            MeasurandEnumType::CurrentExport => Self::CurrentExport,
+            MeasurandEnumType::CurrentImport => Self::CurrentImport,
+            MeasurandEnumType::CurrentOffered => Self::CurrentOffered,
+            MeasurandEnumType::EnergyActiveExportRegister => Self::EnergyActiveExportRegister,
+            MeasurandEnumType::EnergyActiveImportRegister => Self::EnergyActiveImportRegister,
+            MeasurandEnumType::EnergyReactiveExportRegister => Self::EnergyReactiveExportRegister,
+            MeasurandEnumType::EnergyReactiveImportRegister => Self::EnergyReactiveImportRegister,
+            MeasurandEnumType::EnergyActiveExportInterval => Self::EnergyActiveExportInterval,
+            MeasurandEnumType::EnergyActiveImportInterval => Self::EnergyActiveImportInterval,
+            MeasurandEnumType::EnergyReactiveExportInterval => Self::EnergyReactiveExportInterval,
+            MeasurandEnumType::EnergyReactiveImportInterval => Self::EnergyReactiveImportInterval,
+            MeasurandEnumType::Frequency => Self::Frequency,
+            MeasurandEnumType::PowerActiveExport => Self::PowerActiveExport,
+            MeasurandEnumType::PowerActiveImport => Self::PowerActiveImport,
+            MeasurandEnumType::PowerFactor => Self::PowerFactor,
+            MeasurandEnumType::PowerOffered => Self::PowerOffered,
+            MeasurandEnumType::PowerReactiveExport => Self::PowerReactiveExport,
+            MeasurandEnumType::PowerReactiveImport => Self::PowerReactiveImport,
+            MeasurandEnumType::SoC => Self::SoC,
+            MeasurandEnumType::Voltage => Self::Voltage,
+            MeasurandEnumType::EnergyActiveNet => Self::EnergyActiveNet,
+            MeasurandEnumType::EnergyReactiveNet => Self::EnergyReactiveNet,
+            MeasurandEnumType::EnergyApparentNet => Self::EnergyApparentNet,
+            MeasurandEnumType::EnergyApparentImport => Self::EnergyApparentImport,
+            MeasurandEnumType::EnergyApparentExport => Self::EnergyApparentExport,
+            other => Self::Other(
+                serde_json::to_value(other)
+                    .ok()
+                    .and_then(|name| name.as_str().map(str::to_string))
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+impl From<&PhaseEnumType> for Phase {
+    fn from(phase: &PhaseEnumType) -> Self {
+        match phase {
+            PhaseEnumType::L1 => Self::L1,
+            PhaseEnumType::L2 => Self::L2,
+            PhaseEnumType::L3 => Self::L3,
+            PhaseEnumType::N => Self::N,
+            PhaseEnumType::L1N => Self::L1N,
+            PhaseEnumType::L2N => Self::L2N,
+            PhaseEnumType::L3N => Self::L3N,
+            PhaseEnumType::L1L2 => Self::L1L2,
+            PhaseEnumType::L2L3 => Self::L2L3,
+            PhaseEnumType::L3L1 => Self::L3L1,
+        }
+    }
+}
+
+impl From<&LocationEnumType> for Location {
+    fn from(location: &LocationEnumType) -> Self {
+        match location {
+            LocationEnumType::Body => Self::Body,
+            LocationEnumType::Cable => Self::Cable,
+            LocationEnumType::EV => Self::Ev,
+            LocationEnumType::Inlet => Self::Inlet,
+            LocationEnumType::Outlet => Self::Outlet,
+        }
+    }
+}
+
+impl From<&ReadingContextEnumType> for ReadingContext {
+    fn from(context: &ReadingContextEnumType) -> Self {
+        match context {
+            ReadingContextEnumType::InterruptionBegin => Self::InterruptionBegin,
+            ReadingContextEnumType::InterruptionEnd => Self::InterruptionEnd,
+            ReadingContextEnumType::Other => Self::Other,
+            ReadingContextEnumType::SampleClock => Self::SampleClock,
+            ReadingContextEnumType::SamplePeriodic => Self::SamplePeriodic,
+            ReadingContextEnumType::TransactionBegin => Self::TransactionBegin,
+            ReadingContextEnumType::TransactionEnd => Self::TransactionEnd,
+            ReadingContextEnumType::Trigger => Self::Trigger,
+        }
+    }
+}
+
+impl From<ReadingContext> for ReadingContextEnumType {
+    fn from(context: ReadingContext) -> Self {
+        match context {
+            ReadingContext::InterruptionBegin => Self::InterruptionBegin,
+            ReadingContext::InterruptionEnd => Self::InterruptionEnd,
+            ReadingContext::Other => Self::Other,
+            ReadingContext::SampleClock => Self::SampleClock,
+            ReadingContext::SamplePeriodic => Self::SamplePeriodic,
+            ReadingContext::TransactionBegin => Self::TransactionBegin,
+            ReadingContext::TransactionEnd => Self::TransactionEnd,
+            ReadingContext::Trigger => Self::Trigger,
+        }
+    }
+}
+
+impl From<&SampledValueType> for ReadingKey {
+    fn from(sampled_value: &SampledValueType) -> Self {
+        Self {
+            measurand: sampled_value
+                .measurand
+                .as_ref()
+                .map(Measurand::from)
+                .unwrap_or_default(),
+            phase: sampled_value.phase.as_ref().map(Phase::from),
+            location: sampled_value
+                .location
+                .as_ref()
+                .map(Location::from)
+                .unwrap_or_default(),
+            context: sampled_value
+                .context
+                .as_ref()
+                .map(ReadingContext::from)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&SignedMeterValueType> for SignedData {
+    fn from(signed: &SignedMeterValueType) -> Self {
+        Self {
+            data: signed.signed_meter_data.clone(),
+            encoding_method: Some(signed.encoding_method.clone()).filter(|value| !value.is_empty()),
+            signing_method: signed.signing_method.clone(),
+            public_key: signed.public_key.clone(),
+        }
+    }
+}
+
+/// Normalized readings of the sampled values of `meter_values`, such as those of a
+/// `MeterValuesRequest` or a `TransactionEventRequest`.
+pub fn readings(meter_values: &[MeterValueType]) -> Result<Vec<Reading>, ReadingError> {
+    meter_values
+        .iter()
+        .flat_map(|meter_value| {
+            meter_value.sampled_value.iter().map(|sampled_value| {
+                let value = Decimal::try_from(sampled_value.value)
+                    .map_err(|_| ReadingError::InvalidValue(sampled_value.value.to_string()))?;
+                let unit = sampled_value.unit_of_measure.as_ref();
+                let reading = Reading::new(
+                    meter_value.timestamp,
+                    ReadingKey::from(sampled_value),
+                    value,
+                    unit.map(|unit| unit.unit.as_str()),
+                    unit.map_or(0, |unit| unit.multiplier),
+                )?;
+                Ok(match &sampled_value.signed_meter_value {
+                    Some(signed) => reading.with_signed_data(signed.into()),
+                    None => reading,
+                })
+            })
+        })
+        .collect()
+}
+
+/// The `MeterValue` of `sample`, with the values that `read` measures for each of its
+/// measurands, such as one value per phase. The context and measurand of the values are
+/// set from the sample. Returns `None` when no value was measured.
+pub fn meter_value(
+    sample: &Sample,
+    mut read: impl FnMut(&MeasurandEnumType) -> Vec<SampledValueType>,
+) -> Option<MeterValueType> {
+    let sampled_value: Vec<_> = sample
+        .measurands_as::<MeasurandEnumType>()
+        .flat_map(|measurand| {
+            read(&measurand)
+                .into_iter()
+                .map(move |value| SampledValueType {
+                    context: Some(sample.context.into()),
+                    measurand: Some(measurand.clone()),
+                    ..value
+                })
+        })
+        .collect();
+    if sampled_value.is_empty() {
+        return None;
+    }
+    Some(MeterValueType::new(sample.timestamp, sampled_value))
+}
+
+/// The `MeterValuesRequest` of a sample outside of a transaction, see [`meter_value`].
+pub fn meter_values_request(
+    sample: &Sample,
+    read: impl FnMut(&MeasurandEnumType) -> Vec<SampledValueType>,
+) -> Option<MeterValuesRequest> {
+    Some(MeterValuesRequest {
+        custom_data: None,
+        evse_id: sample.evse_id,
+        meter_value: vec![meter_value(sample, read)?],
+    })
+}
+
+/// `triggerReason` of the `Updated` `TransactionEventRequest` sending a periodic or clock
+/// aligned sample of the transaction, `None` for other samples.
+pub fn trigger_reason(sample: &Sample) -> Option<TriggerReasonEnumType> {
+    if sample.destination != Destination::Transaction {
+        return None;
+    }
+    match sample.context {
+        ReadingContext::SampleClock => Some(TriggerReasonEnumType::MeterValueClock),
+        ReadingContext::SamplePeriodic => Some(TriggerReasonEnumType::MeterValuePeriodic),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::{components::meter_values::Unit, v2_1::datatypes::UnitOfMeasureType};
+
+    #[test]
+    fn reads_sampled_values() {
+        let timestamp = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let meter_value = MeterValueType::new(
+            timestamp,
+            vec![
+                SampledValueType::new(12.345)
+                    .with_unit_of_measure(UnitOfMeasureType::new_with_unit("kWh".to_string())),
+                SampledValueType::new(2301.0)
+                    .with_measurand(MeasurandEnumType::Voltage)
+                    .with_phase(PhaseEnumType::L1N)
+                    .with_unit_of_measure(
+                        UnitOfMeasureType::new_with_unit("V".to_string()).with_multiplier(-1),
+                    ),
+                SampledValueType::new(80.0).with_measurand(MeasurandEnumType::DisplayPresentSOC),
+                SampledValueType::new(f64::NAN),
+            ],
+        );
+        let mut meter_values = [meter_value];
+        assert!(matches!(
+            readings(&meter_values),
+            Err(ReadingError::InvalidValue(_))
+        ));
+        meter_values[0].sampled_value.pop();
+        let readings = readings(&meter_values).unwrap();
+        assert_eq!(readings[0].value, Some(dec!(12345)));
+        assert_eq!(readings[1].value, Some(dec!(230.1)));
+        assert_eq!(readings[1].key.phase, Some(Phase::L1N));
+        assert_eq!(
+            readings[2].key.measurand,
+            Measurand::Other("Display.PresentSOC".to_string())
+        );
+        assert_eq!(readings[2].unit, Unit::Dimensionless);
+    }
+
+    #[test]
+    fn builds_meter_values_of_samples() {
+        let sample = Sample {
+            evse_id: 2,
+            timestamp: Utc.with_ymd_and_hms(2025, 3, 1, 12, 15, 0).unwrap(),
+            context: ReadingContext::SampleClock,
+            measurands: vec![
+                "Energy.Active.Import.Register".to_string(),
+                "Display.PresentSOC".to_string(),
+            ],
+            destination: Destination::MeterValues,
+        };
+        let request = meter_values_request(&sample, |measurand| match measurand {
+            MeasurandEnumType::DisplayPresentSOC => vec![SampledValueType::new(80.0)],
+            _ => vec![SampledValueType::new(1200.0)],
+        })
+        .unwrap();
+        assert_eq!(request.evse_id, 2);
+        let sampled_value = &request.meter_value[0].sampled_value;
+        assert_eq!(
+            sampled_value[1].measurand,
+            Some(MeasurandEnumType::DisplayPresentSOC)
+        );
+        assert_eq!(
+            sampled_value[0].context,
+            Some(ReadingContextEnumType::SampleClock)
+        );
+        assert_eq!(meter_value(&sample, |_| Vec::new()), None);
+
+        assert_eq!(trigger_reason(&sample), None);
+        let sample = Sample {
+            destination: Destination::Transaction,
+            ..sample
+        };
+        assert_eq!(
+            trigger_reason(&sample),
+            Some(TriggerReasonEnumType::MeterValueClock)
+        );
+    }
+}
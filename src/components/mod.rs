@@ -0,0 +1,78 @@
+//! # Components
+//!
+//! Reusable behaviour built on top of the message types of the enabled OCPP versions.
+//!
+//! Each component keeps its logic in a version independent model. Conversions from and to
+//! the messages of a specific version live in a submodule named after that version and are
+//! only compiled when the matching feature is enabled.
+
+/// local authorization list and authorization cache
+pub mod authorization;
+
+/// status and availability of connectors and EVSEs
+pub mod availability;
+
+/// certificate hash data of the security profiles
+#[cfg(feature = "certs")]
+pub mod certificates;
+
+/// reconnect back-off, heartbeats and clock of the connection to the CSMS
+pub mod connection;
+
+/// running cost updates of transactions
+#[cfg(any(feature = "v2_0_1", feature = "v2_1"))]
+pub mod cost_updates;
+
+/// DER control of bidirectional charging in OCPP 2.1
+#[cfg(feature = "v2_1")]
+pub mod der_control;
+
+/// display messages and their rotation
+#[cfg(any(feature = "v2_0_1", feature = "v2_1"))]
+pub mod display_messages;
+
+/// data of OCPP 2.1 periodic event streams
+#[cfg(feature = "v2_1")]
+pub mod event_streams;
+
+/// status sequences of firmware updates and log uploads
+pub mod file_transfer;
+
+/// typed meter readings normalized to base units
+pub mod meter_values;
+
+/// variable monitoring and event notification
+#[cfg(any(feature = "v2_0_1", feature = "v2_1"))]
+pub mod monitoring;
+
+/// price levels and sales tariffs over time
+#[cfg(any(feature = "v2_0_1", feature = "v2_1"))]
+pub mod price_curve;
+
+/// evaluation of ISO 15118-20 absolute price schedules
+#[cfg(feature = "v2_1")]
+pub mod price_schedule;
+
+/// boot notification and registration state of Charging Stations
+pub mod registration;
+
+/// remote start and stop of transactions and messages triggered by the CSMS
+pub mod remote_control;
+
+/// reservations of EVSEs and connectors
+pub mod reservations;
+
+/// decoding and verification of signed meter values
+#[cfg(feature = "ocmf")]
+pub mod signed_meter_values;
+
+/// local cost calculation of tariffs
+#[cfg(feature = "v2_1")]
+pub mod tariff;
+
+/// persistent queue of transaction-related messages
+pub mod transaction_queue;
+
+/// start and end of transactions at the `TxStartPoint` and `TxStopPoint`
+#[cfg(any(feature = "v2_0_1", feature = "v2_1"))]
+pub mod transactions;
@@ -0,0 +1,735 @@
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use super::{
+    ClearMonitoringStatus, ComponentVariable, EventTrigger, Monitor, MonitorKind, MonitorOrigin,
+    MonitoringBase, MonitoringEvent, SetMonitor, SetMonitoringResult, SetMonitoringStatus,
+};
+
+/// Highest (least severe) severity level, `Debug`.
+const MAX_SEVERITY: u8 = 9;
+
+#[derive(Debug, Clone, PartialEq)]
+struct MonitorState {
+    monitor: Monitor,
+    /// Id of the event that reported an ongoing threshold or target deviation.
+    active_event: Option<i32>,
+    /// Value reported last, the reference for delta monitors.
+    reference: Option<String>,
+    /// Next report of a periodic monitor.
+    next_report: Option<DateTime<Utc>>,
+}
+
+/// Evaluates variable monitors and produces the events to report in `NotifyEvent`.
+///
+/// Events of monitors with a severity higher than the monitoring level are evaluated but not
+/// returned. Monitors flagged `transaction` are only evaluated while a transaction is ongoing
+/// on the EVSE of their component, or on any EVSE for station wide components.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitoringEngine {
+    monitors: BTreeMap<i32, MonitorState>,
+    values: HashMap<ComponentVariable, String>,
+    targets: HashMap<ComponentVariable, Decimal>,
+    transactions: BTreeMap<i32, String>,
+    level: u8,
+    base: MonitoringBase,
+    next_monitor_id: i32,
+    next_event_id: i32,
+}
+
+impl Default for MonitoringEngine {
+    fn default() -> Self {
+        Self {
+            monitors: BTreeMap::new(),
+            values: HashMap::new(),
+            targets: HashMap::new(),
+            transactions: BTreeMap::new(),
+            level: MAX_SEVERITY,
+            base: MonitoringBase::All,
+            next_monitor_id: 1,
+            next_event_id: 1,
+        }
+    }
+}
+
+impl MonitoringEngine {
+    /// Creates an engine without monitors that reports events of every severity.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The current monitoring level.
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    /// Sets the monitoring level as requested by `SetMonitoringLevel`.
+    ///
+    /// Returns `false` and keeps the current level when `severity` is above 9.
+    pub fn set_level(&mut self, severity: u8) -> bool {
+        if severity > MAX_SEVERITY {
+            return false;
+        }
+        self.level = severity;
+        true
+    }
+
+    /// The current monitoring base.
+    pub fn base(&self) -> MonitoringBase {
+        self.base
+    }
+
+    /// Sets the monitoring base as requested by `SetMonitoringBase`.
+    ///
+    /// `FactoryDefault` and `HardWiredOnly` remove all custom monitors.
+    pub fn set_base(&mut self, base: MonitoringBase) {
+        if base != MonitoringBase::All {
+            self.monitors
+                .retain(|_, state| state.monitor.origin != MonitorOrigin::Custom);
+        }
+        self.base = base;
+    }
+
+    /// Returns the monitor with `id`.
+    pub fn monitor(&self, id: i32) -> Option<&Monitor> {
+        self.monitors.get(&id).map(|state| &state.monitor)
+    }
+
+    /// Iterates over all monitors ordered by id.
+    pub fn monitors(&self) -> impl Iterator<Item = &Monitor> {
+        self.monitors.values().map(|state| &state.monitor)
+    }
+
+    /// Adds a hardwired or preconfigured monitor, replacing a monitor with the same id.
+    pub fn add_monitor(&mut self, monitor: Monitor, now: DateTime<Utc>) {
+        self.next_monitor_id = self.next_monitor_id.max(monitor.id.saturating_add(1));
+        let state = self.new_state(monitor, now);
+        self.monitors.insert(state.monitor.id, state);
+    }
+
+    /// Sets a custom monitor as requested by one `SetMonitoringDataType`.
+    pub fn set_monitor(&mut self, request: SetMonitor, now: DateTime<Utc>) -> SetMonitoringResult {
+        let rejected = SetMonitoringResult {
+            status: SetMonitoringStatus::Rejected,
+            id: None,
+        };
+
+        let value_in_range = match request.kind {
+            MonitorKind::Periodic | MonitorKind::PeriodicClockAligned => {
+                request.value > Decimal::ZERO
+                    && interval(request.value)
+                        .is_some_and(|interval| now.checked_add_signed(interval).is_some())
+            }
+            MonitorKind::Delta | MonitorKind::TargetDelta | MonitorKind::TargetDeltaRelative => {
+                request.value >= Decimal::ZERO
+            }
+            MonitorKind::UpperThreshold | MonitorKind::LowerThreshold => true,
+        };
+        if !value_in_range || request.severity > MAX_SEVERITY {
+            return rejected;
+        }
+
+        if let Some(id) = request.id {
+            match self.monitors.get(&id) {
+                Some(state) if state.monitor.origin != MonitorOrigin::HardWired => {}
+                _ => return rejected,
+            }
+        }
+
+        let duplicate = self.monitors.values().any(|state| {
+            Some(state.monitor.id) != request.id
+                && state.monitor.component_variable == request.component_variable
+                && state.monitor.kind == request.kind
+                && state.monitor.severity == request.severity
+        });
+        if duplicate {
+            return SetMonitoringResult {
+                status: SetMonitoringStatus::Duplicate,
+                id: None,
+            };
+        }
+
+        let id = request.id.unwrap_or(self.next_monitor_id);
+        self.add_monitor(
+            Monitor {
+                id,
+                kind: request.kind,
+                value: request.value,
+                severity: request.severity,
+                transaction: request.transaction,
+                origin: MonitorOrigin::Custom,
+                component_variable: request.component_variable,
+            },
+            now,
+        );
+
+        SetMonitoringResult {
+            status: SetMonitoringStatus::Accepted,
+            id: Some(id),
+        }
+    }
+
+    /// Removes a monitor as requested by `ClearVariableMonitoring`.
+    pub fn clear_monitor(&mut self, id: i32) -> ClearMonitoringStatus {
+        match self.monitors.get(&id) {
+            None => ClearMonitoringStatus::NotFound,
+            Some(state) if state.monitor.origin == MonitorOrigin::HardWired => {
+                ClearMonitoringStatus::Rejected
+            }
+            Some(_) => {
+                self.monitors.remove(&id);
+                ClearMonitoringStatus::Accepted
+            }
+        }
+    }
+
+    /// Registers a transaction that started on an EVSE.
+    pub fn start_transaction(&mut self, evse_id: i32, transaction_id: impl Into<String>) {
+        self.transactions.insert(evse_id, transaction_id.into());
+    }
+
+    /// Removes the transaction of an EVSE.
+    pub fn end_transaction(&mut self, evse_id: i32) {
+        self.transactions.remove(&evse_id);
+    }
+
+    /// Sets the target value of a variable, the reference of `TargetDelta` monitors.
+    pub fn set_target(
+        &mut self,
+        component_variable: ComponentVariable,
+        target: Decimal,
+        now: DateTime<Utc>,
+    ) -> Vec<MonitoringEvent> {
+        self.targets.insert(component_variable.clone(), target);
+        match self.values.get(&component_variable).cloned() {
+            Some(value) => self.evaluate(&component_variable, &value, now),
+            None => Vec::new(),
+        }
+    }
+
+    /// Records a new value of a variable and returns the events it triggers.
+    pub fn update(
+        &mut self,
+        component_variable: ComponentVariable,
+        value: impl Into<String>,
+        now: DateTime<Utc>,
+    ) -> Vec<MonitoringEvent> {
+        let value = value.into();
+        self.values
+            .insert(component_variable.clone(), value.clone());
+        self.evaluate(&component_variable, &value, now)
+    }
+
+    /// Advances time to `now` and returns the reports of periodic monitors that are due.
+    ///
+    /// Variables without a known value are not reported.
+    pub fn tick(&mut self, now: DateTime<Utc>) -> Vec<MonitoringEvent> {
+        let ids: Vec<i32> = self.monitors.keys().copied().collect();
+        let mut events = Vec::new();
+
+        for id in ids {
+            let state = &self.monitors[&id];
+            let Some(due) = state.next_report else {
+                continue;
+            };
+            if due > now {
+                continue;
+            }
+
+            let next = next_report(&state.monitor, now);
+            let monitor = state.monitor.clone();
+            self.monitors.get_mut(&id).unwrap().next_report = next;
+
+            if !self.is_active(&monitor) {
+                continue;
+            }
+            if let Some(value) = self.values.get(&monitor.component_variable).cloned() {
+                events.push(self.event(&monitor, EventTrigger::Periodic, value, None, None, now));
+            }
+        }
+
+        self.filter(events)
+    }
+
+    fn new_state(&self, monitor: Monitor, now: DateTime<Utc>) -> MonitorState {
+        let next_report = match monitor.kind {
+            MonitorKind::Periodic | MonitorKind::PeriodicClockAligned => next_report(&monitor, now),
+            _ => None,
+        };
+        MonitorState {
+            reference: self.values.get(&monitor.component_variable).cloned(),
+            monitor,
+            active_event: None,
+            next_report,
+        }
+    }
+
+    fn is_active(&self, monitor: &Monitor) -> bool {
+        let origin_active = match self.base {
+            MonitoringBase::All => true,
+            MonitoringBase::FactoryDefault => monitor.origin != MonitorOrigin::Preconfigured,
+            MonitoringBase::HardWiredOnly => monitor.origin == MonitorOrigin::HardWired,
+        };
+        origin_active && (!monitor.transaction || self.transaction_id(monitor).is_some())
+    }
+
+    fn transaction_id(&self, monitor: &Monitor) -> Option<&String> {
+        match monitor.component_variable.evse_id {
+            Some(evse_id) => self.transactions.get(&evse_id),
+            None => self.transactions.values().next(),
+        }
+    }
+
+    fn evaluate(
+        &mut self,
+        component_variable: &ComponentVariable,
+        value: &str,
+        now: DateTime<Utc>,
+    ) -> Vec<MonitoringEvent> {
+        let numeric = Decimal::from_str(value.trim()).ok();
+        let target = self.targets.get(component_variable).copied();
+        let ids: Vec<i32> = self
+            .monitors
+            .values()
+            .filter(|state| &state.monitor.component_variable == component_variable)
+            .map(|state| state.monitor.id)
+            .collect();
+
+        let mut events = Vec::new();
+        for id in ids {
+            let state = self.monitors[&id].clone();
+            if !self.is_active(&state.monitor) {
+                continue;
+            }
+            let monitor = &state.monitor;
+
+            match monitor.kind {
+                MonitorKind::UpperThreshold | MonitorKind::LowerThreshold => {
+                    let Some(numeric) = numeric else {
+                        continue;
+                    };
+                    let exceeded = match monitor.kind {
+                        MonitorKind::UpperThreshold => numeric > monitor.value,
+                        _ => numeric < monitor.value,
+                    };
+                    match (exceeded, state.active_event) {
+                        (true, None) => {
+                            let event = self.event(
+                                monitor,
+                                EventTrigger::Alerting,
+                                value.to_string(),
+                                None,
+                                Some(false),
+                                now,
+                            );
+                            self.state_mut(id).active_event = Some(event.event_id);
+                            events.push(event);
+                        }
+                        (false, Some(cause)) => {
+                            events.push(self.event(
+                                monitor,
+                                EventTrigger::Alerting,
+                                value.to_string(),
+                                Some(cause),
+                                Some(true),
+                                now,
+                            ));
+                            self.state_mut(id).active_event = None;
+                        }
+                        _ => {}
+                    }
+                }
+                MonitorKind::Delta => {
+                    let changed = match (&state.reference, numeric) {
+                        (None, _) => {
+                            self.state_mut(id).reference = Some(value.to_string());
+                            false
+                        }
+                        (Some(reference), Some(numeric)) => {
+                            match Decimal::from_str(reference.trim()) {
+                                Ok(reference) => (numeric - reference).abs() > monitor.value,
+                                Err(_) => true,
+                            }
+                        }
+                        (Some(reference), None) => reference != value,
+                    };
+                    if changed {
+                        events.push(self.event(
+                            monitor,
+                            EventTrigger::Delta,
+                            value.to_string(),
+                            None,
+                            None,
+                            now,
+                        ));
+                        self.state_mut(id).reference = Some(value.to_string());
+                    }
+                }
+                MonitorKind::TargetDelta | MonitorKind::TargetDeltaRelative => {
+                    let (Some(numeric), Some(target)) = (numeric, target) else {
+                        continue;
+                    };
+                    let allowed = match monitor.kind {
+                        MonitorKind::TargetDelta => monitor.value,
+                        _ => monitor.value * target.abs(),
+                    };
+                    if (numeric - target).abs() <= allowed {
+                        self.state_mut(id).active_event = None;
+                        continue;
+                    }
+                    // while deviating, report again once the value moved more than the
+                    // allowed deviation since the last report
+                    let report = match (state.active_event, &state.reference) {
+                        (Some(_), Some(reference)) => Decimal::from_str(reference.trim())
+                            .map_or(true, |reference| (numeric - reference).abs() > allowed),
+                        _ => true,
+                    };
+                    if report {
+                        let event = self.event(
+                            monitor,
+                            EventTrigger::Delta,
+                            value.to_string(),
+                            None,
+                            None,
+                            now,
+                        );
+                        let state = self.state_mut(id);
+                        state.active_event = Some(event.event_id);
+                        state.reference = Some(value.to_string());
+                        events.push(event);
+                    }
+                }
+                MonitorKind::Periodic | MonitorKind::PeriodicClockAligned => {}
+            }
+        }
+
+        self.filter(events)
+    }
+
+    fn state_mut(&mut self, id: i32) -> &mut MonitorState {
+        self.monitors.get_mut(&id).expect("monitor exists")
+    }
+
+    fn event(
+        &mut self,
+        monitor: &Monitor,
+        trigger: EventTrigger,
+        actual_value: String,
+        cause: Option<i32>,
+        cleared: Option<bool>,
+        now: DateTime<Utc>,
+    ) -> MonitoringEvent {
+        let event_id = self.next_event_id;
+        self.next_event_id = self.next_event_id.wrapping_add(1).max(0);
+        MonitoringEvent {
+            event_id,
+            timestamp: now,
+            trigger,
+            cause,
+            actual_value,
+            cleared,
+            transaction_id: self.transaction_id(monitor).cloned(),
+            variable_monitoring_id: monitor.id,
+            event_notification: monitor.origin.into(),
+            severity: monitor.severity,
+            component_variable: monitor.component_variable.clone(),
+        }
+    }
+
+    fn filter(&self, events: Vec<MonitoringEvent>) -> Vec<MonitoringEvent> {
+        events
+            .into_iter()
+            .filter(|event| event.severity <= self.level)
+            .collect()
+    }
+}
+
+/// Interval of a periodic monitor of `value` seconds, `None` when out of range.
+fn interval(value: Decimal) -> Option<Duration> {
+    let interval_ms = value.checked_mul(Decimal::ONE_THOUSAND)?.trunc().to_i64()?;
+    Duration::try_milliseconds(interval_ms.max(1))
+}
+
+/// Returns the first report of a periodic monitor after `now`, `None` when it never reports
+/// because its interval is out of range.
+fn next_report(monitor: &Monitor, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let interval = interval(monitor.value)?;
+
+    match monitor.kind {
+        MonitorKind::PeriodicClockAligned => {
+            let midnight = now
+                .date_naive()
+                .and_hms_opt(0, 0, 0)
+                .expect("midnight exists")
+                .and_utc();
+            let next_midnight = midnight + Duration::days(1);
+            let interval_ms = interval.num_milliseconds();
+            let elapsed_ms = (now - midnight).num_milliseconds();
+            let next = Duration::try_milliseconds((elapsed_ms / interval_ms + 1) * interval_ms)
+                .and_then(|aligned| midnight.checked_add_signed(aligned))
+                .unwrap_or(next_midnight);
+            Some(next.min(next_midnight))
+        }
+        _ => now.checked_add_signed(interval),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    use super::super::EventNotification;
+    use super::*;
+
+    fn temperature() -> ComponentVariable {
+        ComponentVariable::new("EVSE", "Temperature").with_evse(1)
+    }
+
+    fn set(kind: MonitorKind, value: Decimal, severity: u8) -> SetMonitor {
+        SetMonitor {
+            id: None,
+            kind,
+            value,
+            severity,
+            transaction: false,
+            component_variable: temperature(),
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn upper_threshold_triggers_and_clears() {
+        let mut engine = MonitoringEngine::new();
+        let result = engine.set_monitor(set(MonitorKind::UpperThreshold, dec!(50), 4), now());
+        assert_eq!(result.status, SetMonitoringStatus::Accepted);
+        let id = result.id.unwrap();
+
+        assert!(engine.update(temperature(), "40", now()).is_empty());
+
+        let events = engine.update(temperature(), "55.5", now());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].trigger, EventTrigger::Alerting);
+        assert_eq!(events[0].cleared, Some(false));
+        assert_eq!(events[0].actual_value, "55.5");
+        assert_eq!(events[0].variable_monitoring_id, id);
+        assert_eq!(
+            events[0].event_notification,
+            EventNotification::CustomMonitor
+        );
+
+        // no repeated events while the threshold stays exceeded
+        assert!(engine.update(temperature(), "60", now()).is_empty());
+
+        let cleared = engine.update(temperature(), "50", now());
+        assert_eq!(cleared.len(), 1);
+        assert_eq!(cleared[0].cleared, Some(true));
+        assert_eq!(cleared[0].cause, Some(events[0].event_id));
+    }
+
+    #[test]
+    fn lower_threshold_triggers() {
+        let mut engine = MonitoringEngine::new();
+        engine.set_monitor(set(MonitorKind::LowerThreshold, dec!(-10), 4), now());
+        assert_eq!(engine.update(temperature(), "-10.5", now()).len(), 1);
+        assert_eq!(
+            engine.update(temperature(), "-9", now())[0].cleared,
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn delta_reports_changes_since_last_report() {
+        let mut engine = MonitoringEngine::new();
+        engine.update(temperature(), "20", now());
+        engine.set_monitor(set(MonitorKind::Delta, dec!(5), 5), now());
+
+        assert!(engine.update(temperature(), "24", now()).is_empty());
+        let events = engine.update(temperature(), "25.5", now());
+        assert_eq!(events[0].trigger, EventTrigger::Delta);
+        assert!(engine.update(temperature(), "30", now()).is_empty());
+        assert_eq!(engine.update(temperature(), "31", now()).len(), 1);
+
+        // non numeric values report every change
+        let state = ComponentVariable::new("Connector", "AvailabilityState");
+        engine.set_monitor(
+            SetMonitor {
+                component_variable: state.clone(),
+                ..set(MonitorKind::Delta, dec!(1), 5)
+            },
+            now(),
+        );
+        assert!(engine.update(state.clone(), "Available", now()).is_empty());
+        assert!(engine.update(state.clone(), "Available", now()).is_empty());
+        assert_eq!(engine.update(state, "Occupied", now()).len(), 1);
+    }
+
+    #[test]
+    fn target_delta_monitors() {
+        let mut engine = MonitoringEngine::new();
+        engine.set_monitor(set(MonitorKind::TargetDelta, dec!(2), 5), now());
+        engine.set_monitor(set(MonitorKind::TargetDeltaRelative, dec!(0.5), 6), now());
+
+        assert!(engine.update(temperature(), "21", now()).is_empty());
+        assert!(engine.set_target(temperature(), dec!(20), now()).is_empty());
+
+        let events = engine.update(temperature(), "23", now());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].severity, 5);
+
+        let events = engine.update(temperature(), "31", now());
+        assert_eq!(events.len(), 2);
+    }
+
+    #[test]
+    fn periodic_monitors_report_on_tick() {
+        let mut engine = MonitoringEngine::new();
+        engine.update(temperature(), "20", now());
+        engine.set_monitor(set(MonitorKind::Periodic, dec!(60), 5), now());
+        engine.set_monitor(
+            set(MonitorKind::PeriodicClockAligned, dec!(900), 6),
+            now() + Duration::seconds(10),
+        );
+
+        assert!(engine.tick(now() + Duration::seconds(59)).is_empty());
+        let events = engine.tick(now() + Duration::seconds(60));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].trigger, EventTrigger::Periodic);
+        assert_eq!(events[0].actual_value, "20");
+
+        let events = engine.tick(now() + Duration::minutes(15));
+        assert_eq!(events.len(), 2);
+        assert!(engine.tick(now() + Duration::minutes(15)).is_empty());
+    }
+
+    #[test]
+    fn severity_level_filters_events() {
+        let mut engine = MonitoringEngine::new();
+        engine.set_monitor(set(MonitorKind::UpperThreshold, dec!(50), 7), now());
+        assert!(!engine.set_level(10));
+        assert!(engine.set_level(5));
+
+        assert!(engine.update(temperature(), "60", now()).is_empty());
+        engine.set_level(9);
+        // the threshold was already exceeded, only the clearing is reported
+        let events = engine.update(temperature(), "40", now());
+        assert_eq!(events[0].cleared, Some(true));
+    }
+
+    #[test]
+    fn transaction_monitors_only_run_during_transactions() {
+        let mut engine = MonitoringEngine::new();
+        engine.set_monitor(
+            SetMonitor {
+                transaction: true,
+                ..set(MonitorKind::UpperThreshold, dec!(50), 4)
+            },
+            now(),
+        );
+        assert!(engine.update(temperature(), "60", now()).is_empty());
+        engine.update(temperature(), "40", now());
+
+        engine.start_transaction(1, "tx-1");
+        let events = engine.update(temperature(), "60", now());
+        assert_eq!(events[0].transaction_id.as_deref(), Some("tx-1"));
+    }
+
+    #[test]
+    fn set_and_clear_monitor_statuses() {
+        let mut engine = MonitoringEngine::new();
+        engine.add_monitor(
+            Monitor {
+                id: 10,
+                kind: MonitorKind::UpperThreshold,
+                value: dec!(80),
+                severity: 2,
+                transaction: false,
+                origin: MonitorOrigin::HardWired,
+                component_variable: temperature(),
+            },
+            now(),
+        );
+
+        let result = engine.set_monitor(set(MonitorKind::UpperThreshold, dec!(50), 2), now());
+        assert_eq!(result.status, SetMonitoringStatus::Duplicate);
+        let result = engine.set_monitor(set(MonitorKind::Periodic, dec!(0), 2), now());
+        assert_eq!(result.status, SetMonitoringStatus::Rejected);
+        let result = engine.set_monitor(set(MonitorKind::Periodic, dec!(1e13), 2), now());
+        assert_eq!(result.status, SetMonitoringStatus::Rejected);
+        let result = engine.set_monitor(set(MonitorKind::Periodic, Decimal::MAX, 2), now());
+        assert_eq!(result.status, SetMonitoringStatus::Rejected);
+        let result = engine.set_monitor(set(MonitorKind::UpperThreshold, dec!(50), 10), now());
+        assert_eq!(result.status, SetMonitoringStatus::Rejected);
+        let result = engine.set_monitor(
+            SetMonitor {
+                id: Some(10),
+                ..set(MonitorKind::UpperThreshold, dec!(50), 3)
+            },
+            now(),
+        );
+        assert_eq!(result.status, SetMonitoringStatus::Rejected);
+
+        let result = engine.set_monitor(set(MonitorKind::UpperThreshold, dec!(50), 3), now());
+        assert_eq!(result.id, Some(11));
+        let replaced = engine.set_monitor(
+            SetMonitor {
+                id: Some(11),
+                ..set(MonitorKind::UpperThreshold, dec!(55), 3)
+            },
+            now(),
+        );
+        assert_eq!(replaced.status, SetMonitoringStatus::Accepted);
+        assert_eq!(engine.monitor(11).unwrap().value, dec!(55));
+
+        assert_eq!(engine.clear_monitor(10), ClearMonitoringStatus::Rejected);
+        assert_eq!(engine.clear_monitor(11), ClearMonitoringStatus::Accepted);
+        assert_eq!(engine.clear_monitor(11), ClearMonitoringStatus::NotFound);
+    }
+
+    #[test]
+    fn monitoring_base_selects_monitors() {
+        let mut engine = MonitoringEngine::new();
+        for (id, origin) in [
+            (1, MonitorOrigin::HardWired),
+            (2, MonitorOrigin::FactoryDefault),
+            (3, MonitorOrigin::Preconfigured),
+        ] {
+            engine.add_monitor(
+                Monitor {
+                    id,
+                    kind: MonitorKind::UpperThreshold,
+                    value: dec!(50),
+                    severity: id as u8,
+                    transaction: false,
+                    origin,
+                    component_variable: temperature(),
+                },
+                now(),
+            );
+        }
+        engine.set_monitor(set(MonitorKind::UpperThreshold, dec!(50), 4), now());
+
+        assert_eq!(engine.update(temperature(), "60", now()).len(), 4);
+        engine.update(temperature(), "40", now());
+
+        engine.set_base(MonitoringBase::FactoryDefault);
+        assert_eq!(engine.monitors().count(), 3);
+        assert_eq!(engine.update(temperature(), "60", now()).len(), 2);
+        engine.update(temperature(), "40", now());
+
+        engine.set_base(MonitoringBase::HardWiredOnly);
+        let events = engine.update(temperature(), "60", now());
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].event_notification,
+            EventNotification::HardWiredMonitor
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! # Monitoring
+//!
+//! Evaluation of variable monitors as configured by `SetVariableMonitoring`, producing the
+//! events reported in `NotifyEvent` (OCPP 2.0.1 and 2.1).
+//!
+//! [`MonitoringEngine`] holds the monitors, the monitoring level set by `SetMonitoringLevel`
+//! and the monitoring base set by `SetMonitoringBase`. Variable value changes are fed to
+//! [`MonitoringEngine::update`] and time is advanced with [`MonitoringEngine::tick`] for
+//! periodic monitors. Both return the [`MonitoringEvent`]s to report, which the version
+//! submodules turn into `NotifyEventRequest`s.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+mod engine;
+
+#[cfg(feature = "v2_0_1")]
+pub mod v2_0_1;
+#[cfg(feature = "v2_1")]
+pub mod v2_1;
+
+pub use engine::MonitoringEngine;
+
+/// Version independent identification of a variable of a component.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ComponentVariable {
+    /// Name of the component.
+    pub component: String,
+    /// Instance of the component.
+    pub component_instance: Option<String>,
+    /// EVSE the component belongs to.
+    pub evse_id: Option<i32>,
+    /// Connector of the EVSE the component belongs to.
+    pub connector_id: Option<i32>,
+    /// Name of the variable.
+    pub variable: String,
+    /// Instance of the variable.
+    pub variable_instance: Option<String>,
+}
+
+impl ComponentVariable {
+    /// Creates a variable of a component without instances or EVSE.
+    pub fn new(component: impl Into<String>, variable: impl Into<String>) -> Self {
+        Self {
+            component: component.into(),
+            variable: variable.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the EVSE the component belongs to.
+    pub fn with_evse(mut self, evse_id: i32) -> Self {
+        self.evse_id = Some(evse_id);
+        self
+    }
+}
+
+/// Type of a monitor, `MonitorEnumType` in 2.x.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MonitorKind {
+    /// Triggers when the value exceeds the monitor value.
+    UpperThreshold,
+    /// Triggers when the value drops below the monitor value.
+    LowerThreshold,
+    /// Triggers when the value changed more than the monitor value since the last report.
+    Delta,
+    /// Reports the value every monitor value seconds.
+    Periodic,
+    /// Reports the value every monitor value seconds, aligned to midnight.
+    PeriodicClockAligned,
+    /// Triggers when the value differs more than the monitor value from the target value (2.1).
+    TargetDelta,
+    /// Triggers when the value differs more than the monitor value times the target value
+    /// from the target value (2.1).
+    TargetDeltaRelative,
+}
+
+/// Where a monitor comes from, deciding its `EventNotificationEnumType` and whether it is
+/// active for a [`MonitoringBase`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonitorOrigin {
+    /// Built into the Charging Station, cannot be removed.
+    HardWired,
+    /// Preconfigured by the manufacturer and part of the factory default monitors.
+    FactoryDefault,
+    /// Preconfigured by the manufacturer.
+    Preconfigured,
+    /// Set by the CSMS with `SetVariableMonitoring`.
+    Custom,
+}
+
+/// Monitoring base, `MonitoringBaseEnumType` in 2.x.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MonitoringBase {
+    /// All preconfigured monitors are active.
+    #[default]
+    All,
+    /// Only the factory default preconfigured monitors are active, custom monitors are removed.
+    FactoryDefault,
+    /// Only hardwired monitors are active, custom monitors are removed.
+    HardWiredOnly,
+}
+
+/// A variable monitor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monitor {
+    /// Identifies the monitor.
+    pub id: i32,
+    /// Type of the monitor.
+    pub kind: MonitorKind,
+    /// Threshold, delta or interval in seconds, depending on the type.
+    pub value: Decimal,
+    /// Severity, from 0 (Danger) to 9 (Debug).
+    pub severity: u8,
+    /// Only active while a transaction is ongoing on the EVSE of the component.
+    pub transaction: bool,
+    /// Where the monitor comes from.
+    pub origin: MonitorOrigin,
+    /// The monitored variable.
+    pub component_variable: ComponentVariable,
+}
+
+/// Version independent content of a `SetMonitoringDataType`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetMonitor {
+    /// Id of an existing monitor to replace, `None` to create a new monitor.
+    pub id: Option<i32>,
+    /// Type of the monitor.
+    pub kind: MonitorKind,
+    /// Threshold, delta or interval in seconds, depending on the type.
+    pub value: Decimal,
+    /// Severity, from 0 (Danger) to 9 (Debug).
+    pub severity: u8,
+    /// Only active while a transaction is ongoing.
+    pub transaction: bool,
+    /// The monitored variable.
+    pub component_variable: ComponentVariable,
+}
+
+/// Status of setting a monitor, convertible into `SetMonitoringStatusEnumType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetMonitoringStatus {
+    /// The monitor has been set.
+    Accepted,
+    /// The value or severity is out of range, or the monitor to replace is unknown or hardwired.
+    Rejected,
+    /// A monitor of the same type and severity already exists for the variable.
+    Duplicate,
+}
+
+/// Outcome of setting a monitor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetMonitoringResult {
+    /// Whether the monitor has been set.
+    pub status: SetMonitoringStatus,
+    /// Id of the monitor when accepted.
+    pub id: Option<i32>,
+}
+
+/// Status of clearing a monitor, convertible into `ClearMonitoringStatusEnumType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClearMonitoringStatus {
+    /// The monitor has been removed.
+    Accepted,
+    /// Hardwired monitors cannot be removed.
+    Rejected,
+    /// No monitor with the id exists.
+    NotFound,
+}
+
+/// What triggered an event, `EventTriggerEnumType` in 2.x.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventTrigger {
+    /// A threshold has been crossed.
+    Alerting,
+    /// The value changed more than the delta.
+    Delta,
+    /// Periodic report.
+    Periodic,
+}
+
+/// Type of monitor that caused an event, `EventNotificationEnumType` in 2.x.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventNotification {
+    /// Event of a hardwired monitor.
+    HardWiredMonitor,
+    /// Event of a preconfigured monitor.
+    PreconfiguredMonitor,
+    /// Event of a custom monitor.
+    CustomMonitor,
+}
+
+impl From<MonitorOrigin> for EventNotification {
+    fn from(origin: MonitorOrigin) -> Self {
+        match origin {
+            MonitorOrigin::HardWired => Self::HardWiredMonitor,
+            MonitorOrigin::FactoryDefault | MonitorOrigin::Preconfigured => {
+                Self::PreconfiguredMonitor
+            }
+            MonitorOrigin::Custom => Self::CustomMonitor,
+        }
+    }
+}
+
+/// Version independent `EventDataType`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitoringEvent {
+    /// Identifies the event.
+    pub event_id: i32,
+    /// When the event occurred.
+    pub timestamp: DateTime<Utc>,
+    /// What triggered the event.
+    pub trigger: EventTrigger,
+    /// Id of the event that caused this event, set for the clearing of a threshold event.
+    pub cause: Option<i32>,
+    /// The value of the variable.
+    pub actual_value: String,
+    /// `Some(true)` when a threshold situation returned to normal, `Some(false)` when it started.
+    pub cleared: Option<bool>,
+    /// The transaction ongoing on the EVSE of the component.
+    pub transaction_id: Option<String>,
+    /// Id of the monitor that triggered the event.
+    pub variable_monitoring_id: i32,
+    /// Type of the monitor that triggered the event.
+    pub event_notification: EventNotification,
+    /// Severity of the monitor that triggered the event.
+    pub severity: u8,
+    /// The monitored variable.
+    pub component_variable: ComponentVariable,
+}
@@ -0,0 +1,381 @@
+//! OCPP 2.0.1 messages for the [`MonitoringEngine`].
+
+use chrono::{DateTime, Utc};
+
+use crate::v2_0_1::{
+    datatypes::{
+        clear_monitoring_result_type::ClearMonitoringResultType, component_type::ComponentType,
+        event_data_type::EventDataType, evse_type::EVSEType,
+        set_monitoring_data_type::SetMonitoringDataType,
+        set_monitoring_result_type::SetMonitoringResultType, variable_type::VariableType,
+    },
+    enumerations::{
+        clear_monitoring_status_enum_type::ClearMonitoringStatusEnumType,
+        event_notification_enum_type::EventNotificationEnumType,
+        event_trigger_enum_type::EventTriggerEnumType,
+        generic_device_model_status_enum_type::GenericDeviceModelStatusEnumType,
+        generic_status_enum_type::GenericStatusEnumType, monitor_enum_type::MonitorEnumType,
+        monitoring_base_enum_type::MonitoringBaseEnumType,
+        set_monitoring_status_enum_type::SetMonitoringStatusEnumType,
+    },
+    messages::{
+        clear_variable_monitoring::{
+            ClearVariableMonitoringRequest, ClearVariableMonitoringResponse,
+        },
+        notify_event::NotifyEventRequest,
+        set_monitoring_base::{SetMonitoringBaseRequest, SetMonitoringBaseResponse},
+        set_monitoring_level::{SetMonitoringLevelRequest, SetMonitoringLevelResponse},
+        set_variable_monitoring::{SetVariableMonitoringRequest, SetVariableMonitoringResponse},
+    },
+};
+
+use super::{
+    ClearMonitoringStatus, ComponentVariable, EventNotification, EventTrigger, MonitorKind,
+    MonitoringBase, MonitoringEngine, MonitoringEvent, SetMonitor, SetMonitoringStatus,
+};
+
+impl From<(&ComponentType, &VariableType)> for ComponentVariable {
+    fn from((component, variable): (&ComponentType, &VariableType)) -> Self {
+        Self {
+            component: component.name.clone(),
+            component_instance: component.instance.clone(),
+            evse_id: component.evse.as_ref().map(|evse| evse.id),
+            connector_id: component.evse.as_ref().and_then(|evse| evse.connector_id),
+            variable: variable.name.clone(),
+            variable_instance: variable.instance.clone(),
+        }
+    }
+}
+
+impl From<&ComponentVariable> for ComponentType {
+    fn from(component_variable: &ComponentVariable) -> Self {
+        Self {
+            name: component_variable.component.clone(),
+            instance: component_variable.component_instance.clone(),
+            evse: component_variable.evse_id.map(|id| EVSEType {
+                id,
+                connector_id: component_variable.connector_id,
+            }),
+        }
+    }
+}
+
+impl From<&ComponentVariable> for VariableType {
+    fn from(component_variable: &ComponentVariable) -> Self {
+        Self {
+            name: component_variable.variable.clone(),
+            instance: component_variable.variable_instance.clone(),
+        }
+    }
+}
+
+impl From<&MonitorEnumType> for MonitorKind {
+    fn from(kind: &MonitorEnumType) -> Self {
+        match kind {
+            MonitorEnumType::UpperThreshold => Self::UpperThreshold,
+            MonitorEnumType::LowerThreshold => Self::LowerThreshold,
+            MonitorEnumType::Delta => Self::Delta,
+            MonitorEnumType::Periodic => Self::Periodic,
+            MonitorEnumType::PeriodicClockAligned => Self::PeriodicClockAligned,
+        }
+    }
+}
+
+impl From<&SetMonitoringDataType> for SetMonitor {
+    fn from(data: &SetMonitoringDataType) -> Self {
+        Self {
+            id: data.id,
+            kind: (&data.kind).into(),
+            value: data.value,
+            severity: data.severity,
+            transaction: data.transaction.unwrap_or(false),
+            component_variable: (&data.component, &data.variable).into(),
+        }
+    }
+}
+
+impl From<&MonitoringBaseEnumType> for MonitoringBase {
+    fn from(base: &MonitoringBaseEnumType) -> Self {
+        match base {
+            MonitoringBaseEnumType::All => Self::All,
+            MonitoringBaseEnumType::FactoryDefault => Self::FactoryDefault,
+            MonitoringBaseEnumType::HardWiredOnly => Self::HardWiredOnly,
+        }
+    }
+}
+
+impl From<SetMonitoringStatus> for SetMonitoringStatusEnumType {
+    fn from(status: SetMonitoringStatus) -> Self {
+        match status {
+            SetMonitoringStatus::Accepted => Self::Accepted,
+            SetMonitoringStatus::Rejected => Self::Rejected,
+            SetMonitoringStatus::Duplicate => Self::Duplicate,
+        }
+    }
+}
+
+impl From<ClearMonitoringStatus> for ClearMonitoringStatusEnumType {
+    fn from(status: ClearMonitoringStatus) -> Self {
+        match status {
+            ClearMonitoringStatus::Accepted => Self::Accepted,
+            ClearMonitoringStatus::Rejected => Self::Rejected,
+            ClearMonitoringStatus::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl From<EventTrigger> for EventTriggerEnumType {
+    fn from(trigger: EventTrigger) -> Self {
+        match trigger {
+            EventTrigger::Alerting => Self::Alerting,
+            EventTrigger::Delta => Self::Delta,
+            EventTrigger::Periodic => Self::Periodic,
+        }
+    }
+}
+
+impl From<EventNotification> for EventNotificationEnumType {
+    fn from(notification: EventNotification) -> Self {
+        match notification {
+            EventNotification::HardWiredMonitor => Self::HardWiredMonitor,
+            EventNotification::PreconfiguredMonitor => Self::PreconfiguredMonitor,
+            EventNotification::CustomMonitor => Self::CustomMonitor,
+        }
+    }
+}
+
+impl From<&MonitoringEvent> for EventDataType {
+    fn from(event: &MonitoringEvent) -> Self {
+        Self {
+            event_id: event.event_id,
+            timestamp: event.timestamp,
+            trigger: event.trigger.into(),
+            cause: event.cause,
+            actual_value: event.actual_value.clone(),
+            tech_code: None,
+            tech_info: None,
+            cleared: event.cleared,
+            transaction_id: event.transaction_id.clone(),
+            variable_monitoring_id: Some(event.variable_monitoring_id),
+            event_notification_type: event.event_notification.into(),
+            component: (&event.component_variable).into(),
+            variable: (&event.component_variable).into(),
+        }
+    }
+}
+
+/// Handles a `SetVariableMonitoringRequest`, returning one result per requested monitor.
+pub fn set_variable_monitoring(
+    engine: &mut MonitoringEngine,
+    request: &SetVariableMonitoringRequest,
+    now: DateTime<Utc>,
+) -> SetVariableMonitoringResponse {
+    SetVariableMonitoringResponse {
+        set_monitoring_result: request
+            .set_monitoring_data
+            .iter()
+            .map(|data| {
+                let result = engine.set_monitor(data.into(), now);
+                SetMonitoringResultType {
+                    id: result.id,
+                    status: result.status.into(),
+                    kind: data.kind.clone(),
+                    severity: data.severity,
+                    component: data.component.clone(),
+                    variable: data.variable.clone(),
+                    status_info: None,
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Handles a `ClearVariableMonitoringRequest`.
+pub fn clear_variable_monitoring(
+    engine: &mut MonitoringEngine,
+    request: &ClearVariableMonitoringRequest,
+) -> ClearVariableMonitoringResponse {
+    ClearVariableMonitoringResponse {
+        clear_monitoring_result: request
+            .id
+            .iter()
+            .map(|&id| ClearMonitoringResultType {
+                status: engine.clear_monitor(id).into(),
+                id,
+                status_info: None,
+            })
+            .collect(),
+    }
+}
+
+/// Handles a `SetMonitoringLevelRequest`.
+pub fn set_monitoring_level(
+    engine: &mut MonitoringEngine,
+    request: &SetMonitoringLevelRequest,
+) -> SetMonitoringLevelResponse {
+    SetMonitoringLevelResponse {
+        status: match engine.set_level(request.severity) {
+            true => GenericStatusEnumType::Accepted,
+            false => GenericStatusEnumType::Rejected,
+        },
+        status_info: None,
+    }
+}
+
+/// Handles a `SetMonitoringBaseRequest`.
+pub fn set_monitoring_base(
+    engine: &mut MonitoringEngine,
+    request: &SetMonitoringBaseRequest,
+) -> SetMonitoringBaseResponse {
+    engine.set_base((&request.monitoring_base).into());
+    SetMonitoringBaseResponse {
+        status: GenericDeviceModelStatusEnumType::Accepted,
+        status_info: None,
+    }
+}
+
+/// Splits `events` into `NotifyEventRequest`s of at most `max_events` events each.
+///
+/// The requests are numbered from 0 and all but the last have `tbc` set.
+pub fn notify_event_requests(
+    events: &[MonitoringEvent],
+    generated_at: DateTime<Utc>,
+    max_events: usize,
+) -> Vec<NotifyEventRequest> {
+    let chunks = events.chunks(max_events.max(1));
+    let last = chunks.len().saturating_sub(1);
+    chunks
+        .enumerate()
+        .map(|(seq_no, chunk)| NotifyEventRequest {
+            generated_at,
+            tbc: (seq_no < last).then_some(true),
+            seq_no: seq_no as i32,
+            event_data: chunk.iter().map(Into::into).collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn monitoring_data(kind: MonitorEnumType, severity: u8) -> SetMonitoringDataType {
+        SetMonitoringDataType {
+            id: None,
+            transaction: None,
+            value: dec!(80),
+            kind,
+            severity,
+            component: ComponentType {
+                name: "EVSE".to_string(),
+                instance: None,
+                evse: Some(EVSEType {
+                    id: 1,
+                    connector_id: None,
+                }),
+            },
+            variable: VariableType {
+                name: "Power".to_string(),
+                instance: None,
+            },
+        }
+    }
+
+    #[test]
+    fn set_monitoring_and_notify_event() {
+        let now = Utc::now();
+        let mut engine = MonitoringEngine::new();
+        let request = SetVariableMonitoringRequest {
+            set_monitoring_data: vec![
+                monitoring_data(MonitorEnumType::UpperThreshold, 3),
+                monitoring_data(MonitorEnumType::UpperThreshold, 3),
+            ],
+        };
+        let response = set_variable_monitoring(&mut engine, &request, now);
+        assert_eq!(
+            response.set_monitoring_result[0].status,
+            SetMonitoringStatusEnumType::Accepted
+        );
+        assert_eq!(
+            response.set_monitoring_result[1].status,
+            SetMonitoringStatusEnumType::Duplicate
+        );
+
+        let data = &request.set_monitoring_data[0];
+        let events = engine.update((&data.component, &data.variable).into(), "81", now);
+        let requests = notify_event_requests(&events, now, 10);
+        assert_eq!(requests.len(), 1);
+        let event = &requests[0].event_data[0];
+        assert_eq!(event.trigger, EventTriggerEnumType::Alerting);
+        assert_eq!(event.cleared, Some(false));
+        assert_eq!(event.actual_value, "81");
+        assert_eq!(
+            event.variable_monitoring_id,
+            response.set_monitoring_result[0].id
+        );
+        assert_eq!(
+            event.event_notification_type,
+            EventNotificationEnumType::CustomMonitor
+        );
+        assert_eq!(event.component, data.component);
+    }
+
+    #[test]
+    fn level_base_and_clear() {
+        let now = Utc::now();
+        let mut engine = MonitoringEngine::new();
+        let response =
+            set_monitoring_level(&mut engine, &SetMonitoringLevelRequest { severity: 12 });
+        assert_eq!(response.status, GenericStatusEnumType::Rejected);
+
+        let request = SetVariableMonitoringRequest {
+            set_monitoring_data: vec![monitoring_data(MonitorEnumType::Delta, 3)],
+        };
+        let id = set_variable_monitoring(&mut engine, &request, now).set_monitoring_result[0]
+            .id
+            .unwrap();
+
+        set_monitoring_base(
+            &mut engine,
+            &SetMonitoringBaseRequest {
+                monitoring_base: MonitoringBaseEnumType::HardWiredOnly,
+            },
+        );
+        let response = clear_variable_monitoring(
+            &mut engine,
+            &ClearVariableMonitoringRequest { id: vec![id] },
+        );
+        assert_eq!(
+            response.clear_monitoring_result[0].status,
+            ClearMonitoringStatusEnumType::NotFound
+        );
+    }
+
+    #[test]
+    fn notify_event_requests_are_split() {
+        let now = Utc::now();
+        let mut engine = MonitoringEngine::new();
+        let mut events = Vec::new();
+        for severity in 0..5 {
+            let data = monitoring_data(MonitorEnumType::Delta, severity);
+            engine.set_monitor((&data).into(), now);
+            engine.update((&data.component, &data.variable).into(), "1", now);
+        }
+        let data = monitoring_data(MonitorEnumType::Delta, 0);
+        events.extend(engine.update((&data.component, &data.variable).into(), "100", now));
+        assert_eq!(events.len(), 5);
+
+        let requests = notify_event_requests(&events, now, 2);
+        assert_eq!(requests.len(), 3);
+        assert_eq!(
+            requests.iter().map(|r| r.seq_no).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(requests[0].tbc, Some(true));
+        assert_eq!(requests[2].tbc, None);
+        assert_eq!(requests[2].event_data.len(), 1);
+        assert!(notify_event_requests(&[], now, 2).is_empty());
+    }
+}
@@ -0,0 +1,347 @@
+//! OCPP 2.1 messages for the [`MonitoringEngine`].
+
+use chrono::{DateTime, Utc};
+
+use crate::v2_1::{
+    datatypes::{ComponentType, EVSEType, SetMonitoringDataType, VariableType},
+    enumerations::{
+        ClearMonitoringStatusEnumType, EventNotificationEnumType, EventTriggerEnumType,
+        GenericDeviceModelStatusEnumType, GenericStatusEnumType, MonitorEnumType,
+        MonitoringBaseEnumType,
+    },
+    messages::{
+        clear_variable_monitoring::{
+            ClearMonitoringResultType, ClearVariableMonitoringRequest,
+            ClearVariableMonitoringResponse,
+        },
+        notify_event::{EventDataType, NotifyEventRequest},
+        set_monitoring_base::{SetMonitoringBaseRequest, SetMonitoringBaseResponse},
+        set_monitoring_level::{SetMonitoringLevelRequest, SetMonitoringLevelResponse},
+        set_variable_monitoring::{
+            SetMonitoringResultType, SetMonitoringStatusEnumType, SetVariableMonitoringRequest,
+            SetVariableMonitoringResponse,
+        },
+    },
+};
+
+use super::{
+    ClearMonitoringStatus, ComponentVariable, EventNotification, EventTrigger, MonitorKind,
+    MonitoringBase, MonitoringEngine, MonitoringEvent, SetMonitor, SetMonitoringStatus,
+};
+
+impl From<(&ComponentType, &VariableType)> for ComponentVariable {
+    fn from((component, variable): (&ComponentType, &VariableType)) -> Self {
+        Self {
+            component: component.name.clone(),
+            component_instance: component.instance.clone(),
+            evse_id: component.evse.as_ref().map(|evse| evse.id),
+            connector_id: component.evse.as_ref().and_then(|evse| evse.connector_id),
+            variable: variable.name.clone(),
+            variable_instance: variable.instance.clone(),
+        }
+    }
+}
+
+impl From<&ComponentVariable> for ComponentType {
+    fn from(component_variable: &ComponentVariable) -> Self {
+        Self {
+            custom_data: None,
+            evse: component_variable.evse_id.map(|id| EVSEType {
+                id,
+                connector_id: component_variable.connector_id,
+                custom_data: None,
+            }),
+            name: component_variable.component.clone(),
+            instance: component_variable.component_instance.clone(),
+        }
+    }
+}
+
+impl From<&ComponentVariable> for VariableType {
+    fn from(component_variable: &ComponentVariable) -> Self {
+        Self {
+            name: component_variable.variable.clone(),
+            instance: component_variable.variable_instance.clone(),
+            custom_data: None,
+        }
+    }
+}
+
+impl From<&MonitorEnumType> for MonitorKind {
+    fn from(kind: &MonitorEnumType) -> Self {
+        match kind {
+            MonitorEnumType::UpperThreshold => Self::UpperThreshold,
+            MonitorEnumType::LowerThreshold => Self::LowerThreshold,
+            MonitorEnumType::Delta => Self::Delta,
+            MonitorEnumType::Periodic => Self::Periodic,
+            MonitorEnumType::PeriodicClockAligned => Self::PeriodicClockAligned,
+            MonitorEnumType::TargetDelta => Self::TargetDelta,
+            MonitorEnumType::TargetDeltaRelative => Self::TargetDeltaRelative,
+        }
+    }
+}
+
+impl From<&SetMonitoringDataType> for SetMonitor {
+    fn from(data: &SetMonitoringDataType) -> Self {
+        Self {
+            id: data.id,
+            kind: (&data.kind).into(),
+            value: data.value,
+            // out of range severities are rejected by the engine
+            severity: u8::try_from(data.severity).unwrap_or(u8::MAX),
+            transaction: data.transaction.unwrap_or(false),
+            component_variable: (&data.component, &data.variable).into(),
+        }
+    }
+}
+
+impl From<&MonitoringBaseEnumType> for MonitoringBase {
+    fn from(base: &MonitoringBaseEnumType) -> Self {
+        match base {
+            MonitoringBaseEnumType::All => Self::All,
+            MonitoringBaseEnumType::FactoryDefault => Self::FactoryDefault,
+            MonitoringBaseEnumType::HardWiredOnly => Self::HardWiredOnly,
+        }
+    }
+}
+
+impl From<SetMonitoringStatus> for SetMonitoringStatusEnumType {
+    fn from(status: SetMonitoringStatus) -> Self {
+        match status {
+            SetMonitoringStatus::Accepted => Self::Accepted,
+            SetMonitoringStatus::Rejected => Self::Rejected,
+            SetMonitoringStatus::Duplicate => Self::Duplicate,
+        }
+    }
+}
+
+impl From<ClearMonitoringStatus> for ClearMonitoringStatusEnumType {
+    fn from(status: ClearMonitoringStatus) -> Self {
+        match status {
+            ClearMonitoringStatus::Accepted => Self::Accepted,
+            ClearMonitoringStatus::Rejected => Self::Rejected,
+            ClearMonitoringStatus::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl From<EventTrigger> for EventTriggerEnumType {
+    fn from(trigger: EventTrigger) -> Self {
+        match trigger {
+            EventTrigger::Alerting => Self::Alerting,
+            EventTrigger::Delta => Self::Delta,
+            EventTrigger::Periodic => Self::Periodic,
+        }
+    }
+}
+
+impl From<EventNotification> for EventNotificationEnumType {
+    fn from(notification: EventNotification) -> Self {
+        match notification {
+            EventNotification::HardWiredMonitor => Self::HardWiredMonitor,
+            EventNotification::PreconfiguredMonitor => Self::PreconfiguredMonitor,
+            EventNotification::CustomMonitor => Self::CustomMonitor,
+        }
+    }
+}
+
+impl From<&MonitoringEvent> for EventDataType {
+    fn from(event: &MonitoringEvent) -> Self {
+        Self {
+            actual_value: event.actual_value.clone(),
+            event_id: event.event_id,
+            timestamp: event.timestamp,
+            trigger: event.trigger.into(),
+            cause: event.cause,
+            tech_code: None,
+            tech_info: None,
+            cleared: event.cleared,
+            transaction_id: event.transaction_id.clone(),
+            component: (&event.component_variable).into(),
+            variable_monitoring_id: Some(event.variable_monitoring_id),
+            event_notification_type: event.event_notification.into(),
+            variable: (&event.component_variable).into(),
+            severity: Some(event.severity.into()),
+            custom_data: None,
+        }
+    }
+}
+
+/// Handles a `SetVariableMonitoringRequest`, returning one result per requested monitor.
+pub fn set_variable_monitoring(
+    engine: &mut MonitoringEngine,
+    request: &SetVariableMonitoringRequest,
+    now: DateTime<Utc>,
+) -> SetVariableMonitoringResponse {
+    SetVariableMonitoringResponse {
+        custom_data: None,
+        set_monitoring_result: request
+            .set_monitoring_data
+            .iter()
+            .map(|data| {
+                let result = engine.set_monitor(data.into(), now);
+                let response = SetMonitoringResultType::new(
+                    result.status.into(),
+                    data.kind.clone(),
+                    data.component.clone(),
+                    data.variable.clone(),
+                    data.severity,
+                );
+                match result.id {
+                    Some(id) => response.with_id(id),
+                    None => response,
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Handles a `ClearVariableMonitoringRequest`.
+pub fn clear_variable_monitoring(
+    engine: &mut MonitoringEngine,
+    request: &ClearVariableMonitoringRequest,
+) -> ClearVariableMonitoringResponse {
+    ClearVariableMonitoringResponse {
+        clear_monitoring_result: request
+            .id
+            .iter()
+            .map(|&id| ClearMonitoringResultType {
+                status: engine.clear_monitor(id).into(),
+                id,
+                status_info: None,
+                custom_data: None,
+            })
+            .collect(),
+        custom_data: None,
+    }
+}
+
+/// Handles a `SetMonitoringLevelRequest`.
+pub fn set_monitoring_level(
+    engine: &mut MonitoringEngine,
+    request: &SetMonitoringLevelRequest,
+) -> SetMonitoringLevelResponse {
+    let accepted = u8::try_from(request.severity).is_ok_and(|severity| engine.set_level(severity));
+    SetMonitoringLevelResponse {
+        custom_data: None,
+        status: match accepted {
+            true => GenericStatusEnumType::Accepted,
+            false => GenericStatusEnumType::Rejected,
+        },
+        status_info: None,
+    }
+}
+
+/// Handles a `SetMonitoringBaseRequest`.
+pub fn set_monitoring_base(
+    engine: &mut MonitoringEngine,
+    request: &SetMonitoringBaseRequest,
+) -> SetMonitoringBaseResponse {
+    engine.set_base((&request.monitoring_base).into());
+    SetMonitoringBaseResponse {
+        custom_data: None,
+        status: GenericDeviceModelStatusEnumType::Accepted,
+        status_info: None,
+    }
+}
+
+/// Splits `events` into `NotifyEventRequest`s of at most `max_events` events each.
+///
+/// The requests are numbered from 0 and all but the last have `tbc` set.
+pub fn notify_event_requests(
+    events: &[MonitoringEvent],
+    generated_at: DateTime<Utc>,
+    max_events: usize,
+) -> Vec<NotifyEventRequest> {
+    let chunks = events.chunks(max_events.max(1));
+    let last = chunks.len().saturating_sub(1);
+    chunks
+        .enumerate()
+        .map(|(seq_no, chunk)| NotifyEventRequest {
+            event_data: chunk.iter().map(Into::into).collect(),
+            tbc: (seq_no < last).then_some(true),
+            seq_no: seq_no as i32,
+            generated_at,
+            custom_data: None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn monitoring_data(
+        kind: MonitorEnumType,
+        value: rust_decimal::Decimal,
+    ) -> SetMonitoringDataType {
+        SetMonitoringDataType {
+            id: None,
+            periodic_event_stream: None,
+            transaction: None,
+            value,
+            kind,
+            severity: 4,
+            component: ComponentType::new("EVSE".to_string()).with_evse(EVSEType::new(1)),
+            variable: VariableType::new("Power".to_string()),
+            custom_data: None,
+        }
+    }
+
+    #[test]
+    fn target_delta_monitor_produces_notify_event() {
+        let now = Utc::now();
+        let mut engine = MonitoringEngine::new();
+        let request = SetVariableMonitoringRequest {
+            custom_data: None,
+            set_monitoring_data: vec![monitoring_data(MonitorEnumType::TargetDelta, dec!(100))],
+        };
+        let response = set_variable_monitoring(&mut engine, &request, now);
+        let result = &response.set_monitoring_result[0];
+        assert_eq!(result.status, SetMonitoringStatusEnumType::Accepted);
+
+        let data = &request.set_monitoring_data[0];
+        let power: ComponentVariable = (&data.component, &data.variable).into();
+        engine.set_target(power.clone(), dec!(11000), now);
+        assert!(engine.update(power.clone(), "10950", now).is_empty());
+
+        let events = engine.update(power, "10800", now);
+        let requests = notify_event_requests(&events, now, 4);
+        let event = &requests[0].event_data[0];
+        assert_eq!(event.trigger, EventTriggerEnumType::Delta);
+        assert_eq!(event.severity, Some(4));
+        assert_eq!(event.variable_monitoring_id, result.id);
+        assert_eq!(
+            event.event_notification_type,
+            EventNotificationEnumType::CustomMonitor
+        );
+    }
+
+    #[test]
+    fn out_of_range_severity_is_rejected() {
+        let now = Utc::now();
+        let mut engine = MonitoringEngine::new();
+        let mut data = monitoring_data(MonitorEnumType::UpperThreshold, dec!(1));
+        data.severity = -1;
+        let request = SetVariableMonitoringRequest {
+            custom_data: None,
+            set_monitoring_data: vec![data],
+        };
+        let response = set_variable_monitoring(&mut engine, &request, now);
+        assert_eq!(
+            response.set_monitoring_result[0].status,
+            SetMonitoringStatusEnumType::Rejected
+        );
+
+        let response = set_monitoring_level(
+            &mut engine,
+            &SetMonitoringLevelRequest {
+                custom_data: None,
+                severity: -3,
+            },
+        );
+        assert_eq!(response.status, GenericStatusEnumType::Rejected);
+    }
+}
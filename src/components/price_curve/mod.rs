@@ -0,0 +1,299 @@
+//! # Price curve
+//!
+//! Time-indexed prices of the `SalesTariffType` (OCPP 2.0.1 and 2.1) and the
+//! `PriceLevelScheduleType` (OCPP 2.1) of a charging schedule.
+//!
+//! The version submodules convert these into a [`PriceCurve`], which answers the questions
+//! of a smart charging optimizer: the price at an instant with [`PriceCurve::price_at`],
+//! and the cheapest window to charge before departure with [`PriceCurve::cheapest_window`].
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+
+#[cfg(feature = "v2_0_1")]
+pub mod v2_0_1;
+#[cfg(feature = "v2_1")]
+pub mod v2_1;
+
+/// Value of a `CostType` amount with its multiplier, a power of ten, `None` when it is out
+/// of range.
+pub(crate) fn with_multiplier(amount: i32, multiplier: i8) -> Option<Decimal> {
+    let exponent = u32::from(multiplier.unsigned_abs());
+    match multiplier < 0 {
+        true => Decimal::try_new(i64::from(amount), exponent).ok(),
+        false => {
+            let power =
+                Decimal::try_from_i128_with_scale(10_i128.checked_pow(exponent)?, 0).ok()?;
+            Decimal::from(amount).checked_mul(power)
+        }
+    }
+}
+
+/// Price during a period of time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceSegment {
+    /// Start of the period.
+    pub start: DateTime<Utc>,
+    /// End of the period, `None` when it lasts until the end of the schedule.
+    pub end: Option<DateTime<Utc>>,
+    /// Price or price level. Only the order of prices is meaningful for price levels.
+    pub price: Decimal,
+}
+
+impl PriceSegment {
+    /// Segment of a sales tariff entry whose relative time interval starts `start` seconds
+    /// after `start_schedule`. A duration that is not positive lasts until the next entry.
+    /// Returns `None` when the interval is out of range.
+    pub(crate) fn relative(
+        start_schedule: DateTime<Utc>,
+        start: i32,
+        duration: i32,
+        price: Decimal,
+    ) -> Option<Self> {
+        let start = start_schedule.checked_add_signed(Duration::seconds(i64::from(start)))?;
+        let end = match duration > 0 {
+            true => Some(start.checked_add_signed(Duration::seconds(i64::from(duration)))?),
+            false => None,
+        };
+        Some(Self { start, end, price })
+    }
+
+    fn contains(&self, timestamp: DateTime<Utc>) -> bool {
+        self.start <= timestamp && self.end.is_none_or(|end| timestamp < end)
+    }
+}
+
+/// Window of time with the lowest average price.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceWindow {
+    /// Start of the window.
+    pub start: DateTime<Utc>,
+    /// End of the window.
+    pub end: DateTime<Utc>,
+    /// Time weighted average price during the window.
+    pub average_price: Decimal,
+}
+
+/// Piecewise constant prices over time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PriceCurve {
+    segments: Vec<PriceSegment>,
+}
+
+impl PriceCurve {
+    /// Creates a curve from segments. Overlapping segments are cut at the start of the
+    /// next segment.
+    pub fn new(mut segments: Vec<PriceSegment>) -> Self {
+        segments.sort_by_key(|segment| segment.start);
+        for index in 1..segments.len() {
+            let next_start = segments[index].start;
+            let previous = &mut segments[index - 1];
+            if previous.end.is_none_or(|end| end > next_start) {
+                previous.end = Some(next_start);
+            }
+        }
+        segments.retain(|segment| segment.end.is_none_or(|end| end > segment.start));
+        Self { segments }
+    }
+
+    /// Segments ordered by start.
+    pub fn segments(&self) -> &[PriceSegment] {
+        &self.segments
+    }
+
+    /// Price at `timestamp`, `None` outside the curve.
+    pub fn price_at(&self, timestamp: DateTime<Utc>) -> Option<Decimal> {
+        self.segments
+            .iter()
+            .find(|segment| segment.contains(timestamp))
+            .map(|segment| segment.price)
+    }
+
+    /// Window of `duration` between `earliest` and `departure` with the lowest average
+    /// price, the earliest one when several windows have the same price.
+    ///
+    /// Returns `None` when no window of `duration` fits before departure within the curve, or
+    /// when the cost of every window is out of range.
+    pub fn cheapest_window(
+        &self,
+        duration: Duration,
+        earliest: DateTime<Utc>,
+        departure: DateTime<Utc>,
+    ) -> Option<PriceWindow> {
+        if duration <= Duration::zero() {
+            return None;
+        }
+        let latest = departure.checked_sub_signed(duration)?;
+        // the cost of a window changes linearly between these starts
+        let boundaries = self
+            .segments
+            .iter()
+            .flat_map(|segment| [Some(segment.start), segment.end])
+            .flatten();
+        let mut starts: Vec<_> = boundaries
+            .flat_map(|boundary| [Some(boundary), boundary.checked_sub_signed(duration)])
+            .flatten()
+            .chain([earliest, latest])
+            .filter(|start| *start >= earliest && *start <= latest)
+            .collect();
+        starts.sort();
+        starts.dedup();
+
+        let mut cheapest: Option<(DateTime<Utc>, Decimal)> = None;
+        for start in starts {
+            let Some(cost) = start
+                .checked_add_signed(duration)
+                .and_then(|end| self.cost(start, end))
+            else {
+                continue;
+            };
+            if cheapest.as_ref().is_none_or(|(_, lowest)| cost < *lowest) {
+                cheapest = Some((start, cost));
+            }
+        }
+        cheapest.map(|(start, cost)| PriceWindow {
+            start,
+            end: start + duration,
+            average_price: cost / Decimal::from(duration.num_seconds().max(1)),
+        })
+    }
+
+    /// Sum of price times seconds from `start` to `end`, `None` when not fully covered or out
+    /// of range.
+    fn cost(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Option<Decimal> {
+        let mut covered_until = start;
+        let mut cost = Decimal::ZERO;
+        for segment in &self.segments {
+            if covered_until >= end {
+                break;
+            }
+            if !segment.contains(covered_until) {
+                continue;
+            }
+            let until = segment.end.map_or(end, |segment_end| segment_end.min(end));
+            let seconds = Decimal::from((until - covered_until).num_seconds());
+            cost = cost.checked_add(segment.price.checked_mul(seconds)?)?;
+            covered_until = until;
+        }
+        (covered_until >= end).then_some(cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, hour, 0, 0).unwrap()
+    }
+
+    fn segment(start: u32, end: Option<u32>, price: Decimal) -> PriceSegment {
+        PriceSegment {
+            start: at(start),
+            end: end.map(at),
+            price,
+        }
+    }
+
+    fn curve() -> PriceCurve {
+        PriceCurve::new(vec![
+            segment(18, Some(22), dec!(3)),
+            segment(22, Some(23), dec!(2)),
+            segment(23, None, dec!(1)),
+        ])
+    }
+
+    #[test]
+    fn applies_cost_multiplier() {
+        assert_eq!(with_multiplier(755, -1), Some(dec!(75.5)));
+        assert_eq!(with_multiplier(3, 2), Some(dec!(300)));
+        assert_eq!(with_multiplier(1, 28), Some(dec!(1e28)));
+        assert_eq!(with_multiplier(1, -29), None);
+        assert_eq!(with_multiplier(1, 29), None);
+        assert_eq!(with_multiplier(i32::MAX, 28), None);
+    }
+
+    #[test]
+    fn price_at_instant() {
+        let curve = curve();
+        assert_eq!(curve.price_at(at(17)), None);
+        assert_eq!(curve.price_at(at(18)), Some(dec!(3)));
+        assert_eq!(curve.price_at(at(22)), Some(dec!(2)));
+        assert_eq!(curve.price_at(at(23) + Duration::days(2)), Some(dec!(1)));
+    }
+
+    #[test]
+    fn overlapping_segments_are_cut() {
+        let curve = PriceCurve::new(vec![segment(20, None, dec!(2)), segment(18, None, dec!(1))]);
+        assert_eq!(curve.segments()[0].end, Some(at(20)));
+        assert_eq!(curve.price_at(at(19)), Some(dec!(1)));
+    }
+
+    #[test]
+    fn cheapest_window_before_departure() {
+        let curve = curve();
+        let window = curve
+            .cheapest_window(
+                Duration::minutes(90),
+                at(18),
+                at(23) + Duration::minutes(30),
+            )
+            .unwrap();
+        // 60 minutes at 2 and 30 minutes at 1
+        assert_eq!(window.start, at(22));
+        assert_eq!(window.average_price, dec!(5) / dec!(3));
+
+        let window = curve
+            .cheapest_window(Duration::hours(2), at(18), at(21))
+            .unwrap();
+        assert_eq!(window.start, at(18));
+        assert_eq!(window.average_price, dec!(3));
+
+        assert_eq!(
+            curve.cheapest_window(Duration::hours(2), at(16), at(19)),
+            None
+        );
+    }
+
+    #[test]
+    fn out_of_range_windows_are_skipped() {
+        let curve = curve();
+        assert_eq!(curve.cheapest_window(Duration::MAX, at(18), at(23)), None);
+        let late = DateTime::<Utc>::MAX_UTC;
+        assert_eq!(
+            curve.cheapest_window(Duration::hours(1), late - Duration::hours(3), late),
+            Some(PriceWindow {
+                start: late - Duration::hours(3),
+                end: late - Duration::hours(2),
+                average_price: dec!(1),
+            })
+        );
+
+        let expensive = PriceCurve::new(vec![
+            segment(18, Some(20), Decimal::MAX),
+            segment(20, Some(22), dec!(2)),
+        ]);
+        let window = expensive
+            .cheapest_window(Duration::hours(1), at(18), at(22))
+            .unwrap();
+        assert_eq!(window.start, at(20));
+    }
+
+    #[test]
+    fn out_of_range_relative_segments_are_skipped() {
+        let late = DateTime::<Utc>::MAX_UTC - Duration::hours(1);
+        assert_eq!(PriceSegment::relative(late, 7200, 0, dec!(1)), None);
+        assert_eq!(PriceSegment::relative(late, 0, 7200, dec!(1)), None);
+        assert_eq!(
+            PriceSegment::relative(late, 0, 0, dec!(1)),
+            Some(PriceSegment {
+                start: late,
+                end: None,
+                price: dec!(1),
+            })
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Price curves of OCPP 2.0.1 sales tariffs.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::v2_0_1::{
+    datatypes::{
+        charging_schedule_type::ChargingScheduleType,
+        sales_tariff_entry_type::SalesTariffEntryType, sales_tariff_type::SalesTariffType,
+    },
+    enumerations::cost_kind_enum_type::CostKindEnumType,
+};
+
+use super::{with_multiplier, PriceCurve, PriceSegment};
+
+/// Converts a sales tariff, whose entries are relative to `start_schedule`, into a curve.
+///
+/// The price of an entry is its `e_price_level`, or else the relative price percentage of
+/// its first consumption cost, whose multiplier must be within -3 and 3 as in OCPP 2.1.
+/// Entries without either are left out of the curve.
+pub fn sales_tariff_curve(
+    sales_tariff: &SalesTariffType,
+    start_schedule: DateTime<Utc>,
+) -> PriceCurve {
+    let segments = sales_tariff
+        .sales_tariff_entry
+        .iter()
+        .filter_map(|entry| {
+            let interval = &entry.relative_time_interval;
+            entry_price(entry).and_then(|price| {
+                PriceSegment::relative(start_schedule, interval.start, interval.duration, price)
+            })
+        })
+        .collect();
+    PriceCurve::new(segments)
+}
+
+/// Curve of the sales tariff of an absolute charging schedule, `None` when the schedule
+/// has no sales tariff or no `start_schedule`.
+pub fn charging_schedule_curve(schedule: &ChargingScheduleType) -> Option<PriceCurve> {
+    let sales_tariff = schedule.sales_tariff.as_ref()?;
+    Some(sales_tariff_curve(sales_tariff, schedule.start_schedule?))
+}
+
+fn entry_price(entry: &SalesTariffEntryType) -> Option<Decimal> {
+    if let Some(level) = entry.e_price_level {
+        return Some(Decimal::from(level));
+    }
+    let consumption_cost = entry
+        .consumption_cost
+        .as_ref()?
+        .iter()
+        .min_by_key(|consumption_cost| consumption_cost.start_value)?;
+    consumption_cost
+        .cost
+        .iter()
+        .filter(|cost| cost.cost_kind == CostKindEnumType::RelativePricePercentage)
+        .filter(|cost| {
+            cost.amount_multiplier
+                .is_none_or(|multiplier| (-3..=3).contains(&multiplier))
+        })
+        .find_map(|cost| with_multiplier(cost.amount, cost.amount_multiplier.unwrap_or(0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::v2_0_1::datatypes::{
+        consumption_cost_type::ConsumptionCostType, cost_type::CostType,
+        relative_time_interval_type::RelativeTimeIntervalType,
+    };
+
+    fn entry(start: i32, duration: i32) -> SalesTariffEntryType {
+        SalesTariffEntryType {
+            relative_time_interval: RelativeTimeIntervalType { start, duration },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sales_tariff_to_curve() {
+        let start = Utc.with_ymd_and_hms(2025, 3, 1, 18, 0, 0).unwrap();
+        let percentage = ConsumptionCostType {
+            start_value: 0,
+            cost: vec![
+                CostType {
+                    cost_kind: CostKindEnumType::RelativePricePercentage,
+                    amount: 2_000_000_000,
+                    amount_multiplier: Some(18),
+                },
+                CostType {
+                    cost_kind: CostKindEnumType::RelativePricePercentage,
+                    amount: 755,
+                    amount_multiplier: Some(-1),
+                },
+            ],
+        };
+        let sales_tariff = SalesTariffType {
+            sales_tariff_entry: vec![
+                SalesTariffEntryType {
+                    e_price_level: Some(3),
+                    ..entry(0, 3600)
+                },
+                SalesTariffEntryType {
+                    consumption_cost: Some(vec![percentage]),
+                    ..entry(3600, 0)
+                },
+            ],
+            ..Default::default()
+        };
+
+        let curve = sales_tariff_curve(&sales_tariff, start);
+        assert_eq!(curve.price_at(start), Some(dec!(3)));
+        assert_eq!(curve.price_at(start + Duration::hours(5)), Some(dec!(75.5)));
+        let window = curve
+            .cheapest_window(Duration::hours(1), start, start + Duration::hours(6))
+            .unwrap();
+        assert_eq!(window.start, start);
+
+        let schedule = ChargingScheduleType {
+            sales_tariff: Some(sales_tariff),
+            ..Default::default()
+        };
+        assert_eq!(charging_schedule_curve(&schedule), None);
+    }
+}
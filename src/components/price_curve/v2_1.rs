@@ -0,0 +1,169 @@
+//! Price curves of OCPP 2.1 price level schedules and sales tariffs.
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+
+use crate::v2_1::{
+    datatypes::{
+        ChargingScheduleType, PriceLevelScheduleType, SalesTariffEntryType, SalesTariffType,
+    },
+    enumerations::CostKindEnumType,
+};
+
+use super::{with_multiplier, PriceCurve, PriceSegment};
+
+/// The entries of a price level schedule follow each other from the time anchor. The
+/// price of a segment is the price level. Entries ending out of range are left out.
+impl From<&PriceLevelScheduleType> for PriceCurve {
+    fn from(schedule: &PriceLevelScheduleType) -> Self {
+        let mut start = schedule.time_anchor;
+        let segments = schedule
+            .price_level_schedule_entries
+            .iter()
+            .map_while(|entry| {
+                let end = start.checked_add_signed(Duration::seconds(i64::from(entry.duration)))?;
+                let segment = PriceSegment {
+                    start,
+                    end: Some(end),
+                    price: Decimal::from(entry.price_level),
+                };
+                start = end;
+                Some(segment)
+            })
+            .collect();
+        PriceCurve::new(segments)
+    }
+}
+
+/// Converts a sales tariff, whose entries are relative to `start_schedule`, into a curve.
+///
+/// The price of an entry is its `e_price_level`, or else the relative price percentage of
+/// its first consumption cost. Entries without either are left out of the curve.
+pub fn sales_tariff_curve(
+    sales_tariff: &SalesTariffType,
+    start_schedule: DateTime<Utc>,
+) -> PriceCurve {
+    let segments = sales_tariff
+        .sales_tariff_entry
+        .iter()
+        .filter_map(|entry| {
+            let interval = &entry.relative_time_interval;
+            entry_price(entry).and_then(|price| {
+                PriceSegment::relative(start_schedule, interval.start, interval.duration, price)
+            })
+        })
+        .collect();
+    PriceCurve::new(segments)
+}
+
+/// Curve of the price level schedule of a charging schedule, or else of the sales tariff
+/// of an absolute charging schedule.
+pub fn charging_schedule_curve(schedule: &ChargingScheduleType) -> Option<PriceCurve> {
+    if let Some(price_level_schedule) = &schedule.price_level_schedule {
+        return Some(price_level_schedule.into());
+    }
+    let sales_tariff = schedule.sales_tariff.as_ref()?;
+    Some(sales_tariff_curve(sales_tariff, schedule.start_schedule?))
+}
+
+fn entry_price(entry: &SalesTariffEntryType) -> Option<Decimal> {
+    if let Some(level) = entry.e_price_level {
+        return Some(Decimal::from(level));
+    }
+    let consumption_cost = entry
+        .consumption_cost
+        .as_ref()?
+        .iter()
+        .min_by_key(|consumption_cost| consumption_cost.start_value)?;
+    consumption_cost
+        .cost
+        .iter()
+        .filter(|cost| cost.cost_kind == CostKindEnumType::RelativePricePercentage)
+        .find_map(|cost| with_multiplier(cost.amount, cost.amount_multiplier.unwrap_or(0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::v2_1::{
+        datatypes::{PriceLevelScheduleEntryType, RelativeTimeIntervalType},
+        enumerations::ChargingRateUnitEnumType,
+    };
+
+    fn anchor() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 18, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn price_level_schedule_to_curve() {
+        let schedule = PriceLevelScheduleType::new(
+            anchor(),
+            1,
+            3,
+            vec![
+                PriceLevelScheduleEntryType::new(3600, 2),
+                PriceLevelScheduleEntryType::new(1800, 0),
+                PriceLevelScheduleEntryType::new(3600, 1),
+            ],
+        );
+        let curve = PriceCurve::from(&schedule);
+        assert_eq!(
+            curve.price_at(anchor() + Duration::minutes(70)),
+            Some(dec!(0))
+        );
+        assert_eq!(curve.price_at(anchor() + Duration::minutes(150)), None);
+
+        let window = curve
+            .cheapest_window(Duration::hours(1), anchor(), anchor() + Duration::hours(3))
+            .unwrap();
+        assert_eq!(window.start, anchor() + Duration::hours(1));
+        assert_eq!(window.average_price, dec!(0.5));
+    }
+
+    #[test]
+    fn charging_schedule_prefers_price_levels() {
+        let sales_tariff = SalesTariffType::new(
+            1,
+            vec![
+                SalesTariffEntryType::new(RelativeTimeIntervalType::new(0, 7200))
+                    .with_e_price_level(4),
+            ],
+        );
+        let mut schedule = ChargingScheduleType::new(1, ChargingRateUnitEnumType::W, vec![]);
+        schedule.sales_tariff = Some(sales_tariff);
+        assert_eq!(charging_schedule_curve(&schedule), None);
+
+        schedule.start_schedule = Some(anchor());
+        let curve = charging_schedule_curve(&schedule).unwrap();
+        assert_eq!(curve.price_at(anchor()), Some(dec!(4)));
+
+        schedule.price_level_schedule = Some(PriceLevelScheduleType::new(
+            anchor(),
+            1,
+            1,
+            vec![PriceLevelScheduleEntryType::new(3600, 1)],
+        ));
+        let curve = charging_schedule_curve(&schedule).unwrap();
+        assert_eq!(curve.price_at(anchor()), Some(dec!(1)));
+    }
+
+    #[test]
+    fn out_of_range_price_levels_are_left_out() {
+        let anchor = DateTime::<Utc>::MAX_UTC - Duration::hours(2);
+        let schedule = PriceLevelScheduleType::new(
+            anchor,
+            1,
+            2,
+            vec![
+                PriceLevelScheduleEntryType::new(3600, 1),
+                PriceLevelScheduleEntryType::new(7200, 0),
+            ],
+        );
+        let curve = PriceCurve::from(&schedule);
+        assert_eq!(curve.segments().len(), 1);
+        assert_eq!(curve.price_at(anchor), Some(dec!(1)));
+    }
+}
@@ -0,0 +1,452 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+
+use crate::v2_1::datatypes::{
+    AbsolutePriceScheduleType, OverstayRuleListType, PriceRuleType, RationalNumberType,
+};
+
+use super::{
+    decimal_from_rational, CostLimit, EvaluationError, PriceAlgorithm, PriceBreakdown,
+    ProfileInterval, TaxAmount,
+};
+
+struct Rule {
+    power_range_start: Decimal,
+    energy_fee: Decimal,
+    parking_fee: Option<Decimal>,
+    parking_fee_period: Option<i64>,
+}
+
+struct Stack {
+    start: DateTime<Utc>,
+    rules: Vec<Rule>,
+}
+
+impl Stack {
+    /// Index of the rule of the power range `power` lies in. Power below the first range
+    /// uses the first rule.
+    fn rule(&self, power: Decimal) -> usize {
+        self.rules
+            .iter()
+            .rposition(|rule| rule.power_range_start <= power)
+            .unwrap_or(0)
+    }
+}
+
+/// Part of a profile interval within one price rule stack.
+struct Piece {
+    stack: usize,
+    seconds: i64,
+    power: Decimal,
+}
+
+impl Piece {
+    fn energy(&self) -> Decimal {
+        self.power * Decimal::from(self.seconds) / Decimal::from(3600)
+    }
+}
+
+fn number(number: &RationalNumberType) -> Result<Decimal, EvaluationError> {
+    decimal_from_rational(number).ok_or(EvaluationError::InvalidNumber)
+}
+
+/// Prices a charging profile with an absolute price schedule.
+///
+/// The price rule stacks follow each other from the time anchor, each lasting its
+/// duration. The first stack also applies before the time anchor and the last stack after
+/// its duration. Within a stack, each price rule applies from its power range start up to
+/// the power range start of the next rule. Energy fees are per kWh and are computed with
+/// the [`PriceAlgorithm`] of the schedule. Parking fees are charged for every started
+/// parking fee period spent in a rule, or once when the rule has no period.
+///
+/// Overstay starts when the power drops below the overstay power threshold or when the
+/// overstay time threshold has passed since the time anchor, whichever comes first. Each
+/// overstay rule charges its fee for every started fee period from its start time until the
+/// start time of the next rule.
+///
+/// Tax rates are fractions, e.g. 0.19 for 19%. Taxes included in the price are reported
+/// but not added to the total. The total is limited to the minimum and maximum cost, with
+/// the taxes that apply to them added.
+pub fn evaluate(
+    schedule: &AbsolutePriceScheduleType,
+    profile: &[ProfileInterval],
+) -> Result<PriceBreakdown, EvaluationError> {
+    let algorithm = PriceAlgorithm::from_urn(&schedule.price_algorithm)
+        .ok_or_else(|| EvaluationError::UnknownPriceAlgorithm(schedule.price_algorithm.clone()))?;
+    let stacks = stacks(schedule)?;
+    let pieces = pieces(&stacks, profile);
+
+    let mut peaks: HashMap<usize, Decimal> = HashMap::new();
+    for piece in &pieces {
+        peaks
+            .entry(piece.stack)
+            .and_modify(|peak| *peak = (*peak).max(piece.power))
+            .or_insert(piece.power);
+    }
+
+    let mut energy = Decimal::ZERO;
+    let mut energy_fee = Decimal::ZERO;
+    let mut parking_seconds: HashMap<(usize, usize), i64> = HashMap::new();
+    for piece in &pieces {
+        let stack = &stacks[piece.stack];
+        let rule = match algorithm {
+            PriceAlgorithm::PeakPower => stack.rule(peaks[&piece.stack]),
+            PriceAlgorithm::Power | PriceAlgorithm::StackedEnergy => stack.rule(piece.power),
+        };
+        energy += piece.energy();
+        energy_fee += match algorithm {
+            PriceAlgorithm::Power | PriceAlgorithm::PeakPower => {
+                stack.rules[rule].energy_fee * piece.energy() / Decimal::ONE_THOUSAND
+            }
+            PriceAlgorithm::StackedEnergy => stacked_energy_fee(stack, piece),
+        };
+        *parking_seconds.entry((piece.stack, rule)).or_default() += piece.seconds;
+    }
+
+    let parking_fee = parking_seconds
+        .iter()
+        .filter(|(_, seconds)| **seconds > 0)
+        .map(|((stack, rule), seconds)| {
+            let rule = &stacks[*stack].rules[*rule];
+            let periods = rule
+                .parking_fee_period
+                .filter(|period| *period > 0)
+                .map_or(1, |period| (seconds + period - 1) / period);
+            rule.parking_fee.unwrap_or_default() * Decimal::from(periods)
+        })
+        .sum();
+
+    let overstay_fee = match &schedule.overstay_rule_list {
+        Some(list) => overstay_fee(list, schedule.time_anchor, profile)?,
+        None => Decimal::ZERO,
+    };
+
+    let services_fee = schedule
+        .additional_selected_services
+        .iter()
+        .flatten()
+        .map(|service| number(&service.service_fee))
+        .sum::<Result<Decimal, _>>()?;
+
+    let mut taxes = Vec::new();
+    let mut total = energy_fee + parking_fee + overstay_fee + services_fee;
+    let mut limit_tax_rate = Decimal::ZERO;
+    for tax_rule in schedule.tax_rules.iter().flatten() {
+        let rate = number(&tax_rule.tax_rate)?;
+        let included_in_price = tax_rule.tax_included_in_price.unwrap_or(false);
+        let base = [
+            (tax_rule.applies_to_energy_fee, energy_fee),
+            (tax_rule.applies_to_parking_fee, parking_fee),
+            (tax_rule.applies_to_overstay_fee, overstay_fee),
+        ]
+        .into_iter()
+        .filter_map(|(applies, fee)| applies.then_some(fee))
+        .sum::<Decimal>();
+        let amount = match included_in_price {
+            true => base - base / (Decimal::ONE + rate),
+            false => {
+                total += base * rate;
+                if tax_rule.applies_to_minimum_maximum_cost {
+                    limit_tax_rate += rate;
+                }
+                base * rate
+            }
+        };
+        taxes.push(TaxAmount {
+            tax_rule_id: tax_rule.tax_rule_id,
+            included_in_price,
+            amount,
+        });
+    }
+
+    let with_tax = |cost: Decimal| cost * (Decimal::ONE + limit_tax_rate);
+    let minimum = schedule.minimum_cost.as_ref().map(number).transpose()?;
+    let maximum = schedule.maximum_cost.as_ref().map(number).transpose()?;
+    let mut limit = None;
+    if let Some(minimum) = minimum.map(with_tax).filter(|minimum| total < *minimum) {
+        total = minimum;
+        limit = Some(CostLimit::Minimum);
+    } else if let Some(maximum) = maximum.map(with_tax).filter(|maximum| total > *maximum) {
+        total = maximum;
+        limit = Some(CostLimit::Maximum);
+    }
+
+    Ok(PriceBreakdown {
+        energy,
+        energy_fee,
+        parking_fee,
+        overstay_fee,
+        services_fee,
+        taxes,
+        total,
+        limit,
+    })
+}
+
+fn stacks(schedule: &AbsolutePriceScheduleType) -> Result<Vec<Stack>, EvaluationError> {
+    let mut start = schedule.time_anchor;
+    let mut stacks = Vec::with_capacity(schedule.price_rule_stacks.len());
+    for price_rule_stack in &schedule.price_rule_stacks {
+        if price_rule_stack.price_rules.is_empty() {
+            return Err(EvaluationError::NoPriceRules);
+        }
+        let mut rules = price_rule_stack
+            .price_rules
+            .iter()
+            .map(rule)
+            .collect::<Result<Vec<_>, _>>()?;
+        rules.sort_by_key(|rule| rule.power_range_start);
+        stacks.push(Stack { start, rules });
+        start += Duration::seconds(price_rule_stack.duration.into());
+    }
+    if stacks.is_empty() {
+        return Err(EvaluationError::NoPriceRules);
+    }
+    Ok(stacks)
+}
+
+fn rule(price_rule: &PriceRuleType) -> Result<Rule, EvaluationError> {
+    Ok(Rule {
+        power_range_start: number(&price_rule.power_range_start)?,
+        energy_fee: number(&price_rule.energy_fee)?,
+        parking_fee: price_rule.parking_fee.as_ref().map(number).transpose()?,
+        parking_fee_period: price_rule.parking_fee_period.map(i64::from),
+    })
+}
+
+/// Splits the profile at the starts of the stacks.
+fn pieces(stacks: &[Stack], profile: &[ProfileInterval]) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    for interval in profile {
+        let mut from = interval.start;
+        while from < interval.end {
+            let stack = stacks
+                .iter()
+                .rposition(|stack| stack.start <= from)
+                .unwrap_or(0);
+            let to = stacks
+                .get(stack + 1)
+                .map_or(interval.end, |next| next.start.min(interval.end));
+            pieces.push(Piece {
+                stack,
+                seconds: (to - from).num_seconds(),
+                power: interval.power,
+            });
+            from = to;
+        }
+    }
+    pieces
+}
+
+fn stacked_energy_fee(stack: &Stack, piece: &Piece) -> Decimal {
+    stack
+        .rules
+        .iter()
+        .enumerate()
+        .map(|(index, rule)| {
+            let upper = stack
+                .rules
+                .get(index + 1)
+                .map(|next| next.power_range_start);
+            let mut power = (piece.power - rule.power_range_start).max(Decimal::ZERO);
+            if let Some(upper) = upper {
+                power = power.min(upper - rule.power_range_start);
+            }
+            let energy = power * Decimal::from(piece.seconds) / Decimal::from(3600);
+            rule.energy_fee * energy / Decimal::ONE_THOUSAND
+        })
+        .sum()
+}
+
+fn overstay_fee(
+    list: &OverstayRuleListType,
+    time_anchor: DateTime<Utc>,
+    profile: &[ProfileInterval],
+) -> Result<Decimal, EvaluationError> {
+    let Some(end) = profile.iter().map(|interval| interval.end).max() else {
+        return Ok(Decimal::ZERO);
+    };
+    let by_time = list
+        .overstay_time_threshold
+        .map(|seconds| time_anchor + Duration::seconds(seconds.into()));
+    let by_power = match &list.overstay_power_threshold {
+        Some(threshold) => {
+            let threshold = number(threshold)?;
+            profile
+                .iter()
+                .find(|interval| interval.power < threshold)
+                .map(|interval| interval.start)
+        }
+        None => None,
+    };
+    let Some(trigger) = by_time.into_iter().chain(by_power).min() else {
+        return Ok(Decimal::ZERO);
+    };
+    let overstay = (end - trigger).num_seconds();
+
+    let mut rules: Vec<_> = list.overstay_rule.iter().collect();
+    rules.sort_by_key(|rule| rule.start_time);
+    let mut fee = Decimal::ZERO;
+    for (index, rule) in rules.iter().enumerate() {
+        let until = rules
+            .get(index + 1)
+            .map_or(overstay, |next| i64::from(next.start_time).min(overstay));
+        let seconds = until - i64::from(rule.start_time);
+        if seconds <= 0 {
+            continue;
+        }
+        let period = i64::from(rule.overstay_fee_period).max(1);
+        let periods = (seconds + period - 1) / period;
+        fee += number(&rule.overstay_fee)? * Decimal::from(periods);
+    }
+    Ok(fee)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::v2_1::datatypes::{
+        AdditionalSelectedServicesType, OverstayRuleType, PriceRuleStackType, TaxRuleType,
+    };
+
+    const POWER: &str = "urn:iso:std:iso:15118:-20:PriceAlgorithm:1-Power";
+
+    fn anchor() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn rational(decimal: Decimal) -> RationalNumberType {
+        super::super::rational_from_decimal(decimal).unwrap()
+    }
+
+    /// 0.30/kWh up to 11 kW and 0.50/kWh above for the first hour, 0.20/kWh after.
+    fn schedule(algorithm: &str) -> AbsolutePriceScheduleType {
+        let mut expensive = PriceRuleType::new(rational(dec!(0.50)), rational(dec!(11000)));
+        expensive.parking_fee = Some(rational(dec!(1)));
+        expensive.parking_fee_period = Some(1800);
+        AbsolutePriceScheduleType::new(
+            anchor(),
+            1,
+            "EUR".to_string(),
+            "en".to_string(),
+            algorithm.to_string(),
+            vec![
+                PriceRuleStackType::new(
+                    3600,
+                    vec![
+                        expensive,
+                        PriceRuleType::new(rational(dec!(0.30)), rational(dec!(0))),
+                    ],
+                ),
+                PriceRuleStackType::new(
+                    3600,
+                    vec![PriceRuleType::new(rational(dec!(0.20)), rational(dec!(0)))],
+                ),
+            ],
+        )
+    }
+
+    fn profile() -> Vec<ProfileInterval> {
+        vec![
+            ProfileInterval::new(anchor(), anchor() + Duration::minutes(30), dec!(22000)),
+            ProfileInterval::new(
+                anchor() + Duration::minutes(30),
+                anchor() + Duration::minutes(90),
+                dec!(10000),
+            ),
+        ]
+    }
+
+    #[test]
+    fn power_algorithm_per_interval() {
+        let breakdown = evaluate(&schedule(POWER), &profile()).unwrap();
+
+        // 11 kWh at 0.50, 5 kWh at 0.30 and 5 kWh at 0.20
+        assert_eq!(breakdown.energy, dec!(21000));
+        assert_eq!(breakdown.energy_fee, dec!(8.00));
+        // one started parking fee period in the expensive rule
+        assert_eq!(breakdown.parking_fee, dec!(1));
+        assert_eq!(breakdown.total, dec!(9.00));
+        assert_eq!(breakdown.limit, None);
+    }
+
+    #[test]
+    fn peak_power_and_stacked_energy() {
+        let urn = "urn:iso:std:iso:15118:-20:PriceAlgorithm:2-PeakPower";
+        let breakdown = evaluate(&schedule(urn), &profile()).unwrap();
+        // 16 kWh in the first stack at the peak rule, 5 kWh at 0.20
+        assert_eq!(breakdown.energy_fee, dec!(9.00));
+
+        let urn = "urn:iso:std:iso:15118:-20:PriceAlgorithm:3-StackedEnergy";
+        let breakdown = evaluate(&schedule(urn), &profile()).unwrap();
+        // 5.5 kWh at 0.30 and 5.5 kWh at 0.50, then 5 kWh at 0.30 and 5 kWh at 0.20
+        assert_eq!(breakdown.energy_fee, dec!(6.90));
+
+        assert_eq!(
+            evaluate(&schedule("urn:unknown"), &profile()),
+            Err(EvaluationError::UnknownPriceAlgorithm(
+                "urn:unknown".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn taxes_overstay_services_and_limits() {
+        let mut overstay = OverstayRuleListType::new(vec![
+            OverstayRuleType::new(0, 600, rational(dec!(2))),
+            OverstayRuleType::new(1200, 600, rational(dec!(5))),
+        ]);
+        overstay.overstay_power_threshold = Some(rational(dec!(11000)));
+        let mut included = TaxRuleType::new(2, true, false, false, false, rational(dec!(0.25)));
+        included.tax_included_in_price = Some(true);
+        let schedule = schedule(POWER)
+            .with_overstay_rule_list(overstay)
+            .with_additional_selected_services(vec![AdditionalSelectedServicesType::new(
+                rational(dec!(1.50)),
+                "Car wash".to_string(),
+            )])
+            .with_tax_rules(vec![
+                TaxRuleType::new(1, true, true, true, true, rational(dec!(0.1))),
+                included,
+            ]);
+
+        let breakdown = evaluate(&schedule, &profile()).unwrap();
+        // overstay from 30 to 90 minutes: 2 periods at 2, then 4 periods at 5
+        assert_eq!(breakdown.overstay_fee, dec!(24));
+        assert_eq!(breakdown.services_fee, dec!(1.50));
+        assert_eq!(
+            breakdown.taxes,
+            vec![
+                TaxAmount {
+                    tax_rule_id: 1,
+                    included_in_price: false,
+                    amount: dec!(3.3),
+                },
+                TaxAmount {
+                    tax_rule_id: 2,
+                    included_in_price: true,
+                    amount: dec!(1.6),
+                },
+            ]
+        );
+        assert_eq!(breakdown.total, dec!(37.80));
+
+        let breakdown = evaluate(
+            &schedule.clone().with_maximum_cost(rational(dec!(20))),
+            &profile(),
+        )
+        .unwrap();
+        assert_eq!(breakdown.limit, Some(CostLimit::Maximum));
+        assert_eq!(breakdown.total, dec!(22.0));
+
+        let breakdown =
+            evaluate(&schedule.with_minimum_cost(rational(dec!(50))), &profile()).unwrap();
+        assert_eq!(breakdown.limit, Some(CostLimit::Minimum));
+        assert_eq!(breakdown.total, dec!(55.0));
+    }
+}
@@ -0,0 +1,134 @@
+//! # Price schedule
+//!
+//! Evaluation of the ISO 15118-20 absolute price schedules that the CSMS sends in the
+//! `absolute_price_schedule` of an OCPP 2.1 `ChargingScheduleType`.
+//!
+//! [`evaluate`] prices a charging profile, given as [`ProfileInterval`]s of constant power,
+//! with an `AbsolutePriceScheduleType` and returns the [`PriceBreakdown`]. The exact
+//! `RationalNumberType` values of a schedule are converted with [`decimal_from_rational`]
+//! and [`rational_from_decimal`].
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+mod evaluate;
+mod rational;
+
+pub use evaluate::evaluate;
+pub use rational::{decimal_from_rational, rational_from_decimal};
+
+/// Part of a charging profile during which the power is constant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileInterval {
+    /// Start of the interval.
+    pub start: DateTime<Utc>,
+    /// End of the interval.
+    pub end: DateTime<Utc>,
+    /// Power in W, negative when discharging.
+    pub power: Decimal,
+}
+
+impl ProfileInterval {
+    /// Creates an interval.
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>, power: Decimal) -> Self {
+        Self { start, end, power }
+    }
+
+    /// Energy in Wh transferred during the interval.
+    pub fn energy(&self) -> Decimal {
+        self.power * Decimal::from((self.end - self.start).num_seconds()) / Decimal::from(3600)
+    }
+}
+
+/// Algorithm that computes the energy fee from the price rules, identified by the URN in
+/// `price_algorithm`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceAlgorithm {
+    /// All energy is priced by the rule of the power range the power lies in.
+    Power,
+    /// All energy of a price rule stack is priced by the rule of the power range the peak
+    /// power of the stack lies in.
+    PeakPower,
+    /// Energy is split over the power ranges and each part is priced by the rule of its
+    /// range.
+    StackedEnergy,
+}
+
+impl PriceAlgorithm {
+    /// Parses a price algorithm URN such as
+    /// `urn:iso:std:iso:15118:-20:PriceAlgorithm:1-Power`.
+    pub fn from_urn(urn: &str) -> Option<Self> {
+        match urn.rsplit(':').next()? {
+            "1-Power" => Some(Self::Power),
+            "2-PeakPower" => Some(Self::PeakPower),
+            "3-StackedEnergy" => Some(Self::StackedEnergy),
+            _ => None,
+        }
+    }
+}
+
+/// Tax calculated for a tax rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaxAmount {
+    /// Id of the tax rule.
+    pub tax_rule_id: i32,
+    /// Whether the tax is already included in the fees.
+    pub included_in_price: bool,
+    /// Amount of tax.
+    pub amount: Decimal,
+}
+
+/// Limit of the price schedule that determined the total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostLimit {
+    /// The cost was raised to the minimum cost.
+    Minimum,
+    /// The cost was lowered to the maximum cost.
+    Maximum,
+}
+
+/// Cost of a charging profile according to an absolute price schedule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceBreakdown {
+    /// Energy in Wh of the profile.
+    pub energy: Decimal,
+    /// Sum of the energy fees.
+    pub energy_fee: Decimal,
+    /// Sum of the parking fees.
+    pub parking_fee: Decimal,
+    /// Sum of the overstay fees.
+    pub overstay_fee: Decimal,
+    /// Sum of the fees of the additional selected services.
+    pub services_fee: Decimal,
+    /// Tax per tax rule.
+    pub taxes: Vec<TaxAmount>,
+    /// Total cost including all taxes.
+    pub total: Decimal,
+    /// Set when the total has been limited to the minimum or maximum cost.
+    pub limit: Option<CostLimit>,
+}
+
+/// Reason why a price schedule cannot be evaluated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvaluationError {
+    /// The price algorithm URN is not supported.
+    UnknownPriceAlgorithm(String),
+    /// A price rule stack has no price rules.
+    NoPriceRules,
+    /// A rational number does not fit in a `Decimal`.
+    InvalidNumber,
+}
+
+impl fmt::Display for EvaluationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownPriceAlgorithm(urn) => write!(f, "unknown price algorithm {urn}"),
+            Self::NoPriceRules => write!(f, "price rule stack without price rules"),
+            Self::InvalidNumber => write!(f, "rational number out of range"),
+        }
+    }
+}
+
+impl std::error::Error for EvaluationError {}
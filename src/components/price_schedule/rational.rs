@@ -0,0 +1,73 @@
+use rust_decimal::Decimal;
+
+use crate::v2_1::datatypes::RationalNumberType;
+
+/// Exact value of a `RationalNumberType`, `value * 10^exponent`.
+///
+/// Returns `None` when the value does not fit in a `Decimal`.
+pub fn decimal_from_rational(number: &RationalNumberType) -> Option<Decimal> {
+    let value = Decimal::from(number.value);
+    if number.exponent < 0 {
+        let scale = number.exponent.unsigned_abs();
+        return (scale <= Decimal::MAX_SCALE)
+            .then(|| Decimal::from_i128_with_scale(number.value.into(), scale));
+    }
+    (0..number.exponent).try_fold(value, |value, _| value.checked_mul(Decimal::TEN))
+}
+
+/// `RationalNumberType` with the exact value of `decimal` and the smallest exponent
+/// magnitude.
+///
+/// Returns `None` when the significant digits of `decimal` do not fit in the `i32` value.
+pub fn rational_from_decimal(decimal: Decimal) -> Option<RationalNumberType> {
+    let decimal = decimal.normalize();
+    let mut mantissa = decimal.mantissa();
+    let mut exponent = -i32::try_from(decimal.scale()).ok()?;
+    while mantissa != 0 && mantissa % 10 == 0 {
+        mantissa /= 10;
+        exponent += 1;
+    }
+    let value = i32::try_from(mantissa).ok()?;
+    Some(RationalNumberType::new(
+        if value == 0 { 0 } else { exponent },
+        value,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for (exponent, value, decimal) in [
+            (-3, 125, dec!(0.125)),
+            (0, -7, dec!(-7)),
+            (3, 22, dec!(22000)),
+            (0, 0, dec!(0)),
+        ] {
+            let number = RationalNumberType::new(exponent, value);
+            assert_eq!(decimal_from_rational(&number), Some(decimal));
+            assert_eq!(rational_from_decimal(decimal), Some(number));
+        }
+        assert_eq!(
+            rational_from_decimal(dec!(0.2500)),
+            Some(RationalNumberType::new(-2, 25))
+        );
+    }
+
+    #[test]
+    fn unrepresentable_values() {
+        assert_eq!(
+            decimal_from_rational(&RationalNumberType::new(-29, 1)),
+            None
+        );
+        assert_eq!(
+            decimal_from_rational(&RationalNumberType::new(30, i32::MAX)),
+            None
+        );
+        assert_eq!(rational_from_decimal(dec!(12345678901.1)), None);
+    }
+}
@@ -0,0 +1,184 @@
+use chrono::{DateTime, Duration, Utc};
+
+use super::{
+    BootReason, RegistrationStatus, BOOT_NOTIFICATION, DEFAULT_RETRY_INTERVAL, NOT_WHILE_PENDING,
+};
+
+/// Registration state of a Charging Station, kept by the station itself or by the CSMS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistrationManager {
+    reason: BootReason,
+    status: Option<RegistrationStatus>,
+    interval: Duration,
+    retry_at: Option<DateTime<Utc>>,
+    triggered: Vec<String>,
+}
+
+impl RegistrationManager {
+    /// Creates the state of a Charging Station that booted for `reason` and has no answer to
+    /// its `BootNotification` yet.
+    pub fn new(reason: BootReason) -> Self {
+        Self {
+            reason,
+            status: None,
+            interval: Duration::zero(),
+            retry_at: None,
+            triggered: Vec::new(),
+        }
+    }
+
+    /// Why the Charging Station last booted.
+    pub fn reason(&self) -> BootReason {
+        self.reason
+    }
+
+    /// Last answer to a `BootNotification`, `None` before the first one.
+    pub fn status(&self) -> Option<RegistrationStatus> {
+        self.status
+    }
+
+    /// Returns `true` once the CSMS has accepted the Charging Station.
+    pub fn is_accepted(&self) -> bool {
+        self.status == Some(RegistrationStatus::Accepted)
+    }
+
+    /// Heartbeat interval given by the CSMS when it accepted the Charging Station.
+    pub fn heartbeat_interval(&self) -> Option<Duration> {
+        self.is_accepted().then_some(self.interval)
+    }
+
+    /// When the next `BootNotification` may be sent after a `Pending` or `Rejected`.
+    pub fn retry_at(&self) -> Option<DateTime<Utc>> {
+        self.retry_at
+    }
+
+    /// Starts over after the Charging Station booted again for `reason`, e.g. when the
+    /// CSMS receives a `BootNotification`.
+    pub fn reboot(&mut self, reason: BootReason) {
+        *self = Self::new(reason);
+    }
+
+    /// Applies the answer to a `BootNotification`, with the `interval` of the response.
+    pub fn registered(
+        &mut self,
+        status: RegistrationStatus,
+        interval: Duration,
+        now: DateTime<Utc>,
+    ) {
+        self.status = Some(status);
+        self.interval = interval;
+        self.triggered.clear();
+        self.retry_at = match status {
+            RegistrationStatus::Accepted => None,
+            _ if interval <= Duration::zero() => Some(now + DEFAULT_RETRY_INTERVAL),
+            _ => Some(now + interval),
+        };
+    }
+
+    /// Records a `TriggerMessage` of the CSMS for `action`, returning whether the Charging
+    /// Station may accept it. While `Pending`, the triggered request may then be sent once.
+    pub fn trigger(&mut self, action: &str) -> bool {
+        match self.status {
+            Some(RegistrationStatus::Accepted) => true,
+            Some(RegistrationStatus::Pending) => {
+                self.triggered.push(action.to_string());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the Charging Station may send a request for `action` at `now`, recording it
+    /// as sent when it may.
+    pub fn send(&mut self, action: &str, now: DateTime<Utc>) -> bool {
+        if self.is_accepted() {
+            return true;
+        }
+        if let Some(index) = self.triggered.iter().position(|sent| sent == action) {
+            self.triggered.remove(index);
+            return true;
+        }
+        action == BOOT_NOTIFICATION && self.retry_at.is_none_or(|retry_at| retry_at <= now)
+    }
+
+    /// Whether the Charging Station may answer requests of the CSMS at `now`, which it may
+    /// not while `Rejected`.
+    pub fn may_respond(&self, now: DateTime<Utc>) -> bool {
+        self.status != Some(RegistrationStatus::Rejected)
+            || self.retry_at.is_none_or(|retry_at| retry_at <= now)
+    }
+
+    /// Whether the CSMS may send a request for `action` to the Charging Station.
+    pub fn may_initiate(&self, action: &str) -> bool {
+        match self.status {
+            Some(RegistrationStatus::Accepted) => true,
+            Some(RegistrationStatus::Pending) => !NOT_WHILE_PENDING.contains(&action),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn only_boot_notification_before_registration() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut manager = RegistrationManager::new(BootReason::PowerUp);
+        assert!(!manager.send("StatusNotification", now));
+        assert!(!manager.may_initiate("GetVariables"));
+        assert!(manager.send(BOOT_NOTIFICATION, now));
+
+        manager.registered(RegistrationStatus::Accepted, Duration::seconds(300), now);
+        assert!(manager.send("StatusNotification", now));
+        assert!(manager.may_initiate("RequestStartTransaction"));
+        assert_eq!(manager.heartbeat_interval(), Some(Duration::seconds(300)));
+
+        manager.reboot(BootReason::RemoteReset);
+        assert_eq!(manager.reason(), BootReason::RemoteReset);
+        assert_eq!(manager.status(), None);
+        assert!(!manager.send("Heartbeat", now));
+    }
+
+    #[test]
+    fn pending_allows_triggered_requests() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut manager = RegistrationManager::new(BootReason::PowerUp);
+        manager.registered(RegistrationStatus::Pending, Duration::seconds(30), now);
+        assert_eq!(manager.heartbeat_interval(), None);
+        assert!(manager.may_respond(now));
+        assert!(manager.may_initiate("GetVariables"));
+        assert!(!manager.may_initiate("RemoteStartTransaction"));
+        assert!(!manager.may_initiate("RequestStopTransaction"));
+
+        assert!(!manager.send("StatusNotification", now));
+        assert!(manager.trigger("StatusNotification"));
+        assert!(manager.send("StatusNotification", now));
+        assert!(!manager.send("StatusNotification", now));
+
+        assert!(!manager.send(BOOT_NOTIFICATION, now + Duration::seconds(29)));
+        assert!(manager.send(BOOT_NOTIFICATION, now + Duration::seconds(30)));
+        assert!(manager.trigger(BOOT_NOTIFICATION));
+        assert!(manager.send(BOOT_NOTIFICATION, now));
+    }
+
+    #[test]
+    fn rejected_blocks_everything_until_the_interval_expired() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut manager = RegistrationManager::new(BootReason::Watchdog);
+        manager.registered(RegistrationStatus::Rejected, Duration::zero(), now);
+        assert_eq!(manager.retry_at(), Some(now + DEFAULT_RETRY_INTERVAL));
+        assert!(!manager.may_respond(now));
+        assert!(!manager.may_initiate("GetVariables"));
+        assert!(!manager.trigger(BOOT_NOTIFICATION));
+        assert!(!manager.send(BOOT_NOTIFICATION, now));
+
+        let later = now + DEFAULT_RETRY_INTERVAL;
+        assert!(manager.may_respond(later));
+        assert!(!manager.send("Heartbeat", later));
+        assert!(manager.send(BOOT_NOTIFICATION, later));
+    }
+}
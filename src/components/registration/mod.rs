@@ -0,0 +1,78 @@
+//! # Registration
+//!
+//! The registration of a Charging Station at the CSMS by `BootNotification`, shared by OCPP
+//! 1.6 and 2.x and usable on both sides of the connection.
+//!
+//! Until the CSMS answers `Accepted`, the Charging Station may only send a
+//! `BootNotification`:
+//!
+//! - while `Pending`, other requests only when triggered by the CSMS with `TriggerMessage`,
+//!   and the CSMS may not initiate `RemoteStartTransaction`/`RemoteStopTransaction` (1.6) or
+//!   `RequestStartTransaction`/`RequestStopTransaction` (2.x),
+//! - while `Rejected`, nothing, not even responses, until the retry interval has expired.
+//!
+//! After a `Pending` or `Rejected`, the next `BootNotification` may only be sent once the
+//! `interval` of the response has passed, or a default interval when it is zero.
+//! [`RegistrationManager`] keeps this state along with the [`BootReason`] reported in the
+//! `BootNotification`. Messages are named by their OCPP action, e.g. `"StatusNotification"`.
+
+use chrono::Duration;
+
+mod manager;
+
+#[cfg(feature = "v1_6")]
+pub mod v1_6;
+#[cfg(feature = "v2_0_1")]
+pub mod v2_0_1;
+#[cfg(feature = "v2_1")]
+pub mod v2_1;
+
+pub use manager::RegistrationManager;
+
+/// Action of the `BootNotification`.
+pub const BOOT_NOTIFICATION: &str = "BootNotification";
+
+/// Actions the CSMS may not initiate while the registration is `Pending`.
+pub const NOT_WHILE_PENDING: [&str; 4] = [
+    "RemoteStartTransaction",
+    "RemoteStopTransaction",
+    "RequestStartTransaction",
+    "RequestStopTransaction",
+];
+
+/// Wait before the next `BootNotification` when the CSMS sent an `interval` of zero.
+pub const DEFAULT_RETRY_INTERVAL: Duration = Duration::seconds(60);
+
+/// Why the Charging Station booted, `BootReasonEnumType` in 2.x.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BootReason {
+    /// Reset of the application only.
+    ApplicationReset,
+    /// Firmware update.
+    FirmwareUpdate,
+    /// Reset triggered locally.
+    LocalReset,
+    /// Power up.
+    PowerUp,
+    /// `Reset` requested by the CSMS.
+    RemoteReset,
+    /// Reset at a scheduled time.
+    ScheduledReset,
+    /// `TriggerMessage` requested by the CSMS.
+    Triggered,
+    /// Unknown reason, as in 1.6 which does not report one.
+    Unknown,
+    /// Reset by a watchdog.
+    Watchdog,
+}
+
+/// Answer of the CSMS to a `BootNotification`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegistrationStatus {
+    /// The Charging Station is registered.
+    Accepted,
+    /// The CSMS wants to retrieve or set information before accepting.
+    Pending,
+    /// The Charging Station is not accepted.
+    Rejected,
+}
@@ -0,0 +1,84 @@
+//! OCPP 1.6 messages for the [`RegistrationManager`].
+//!
+//! A 1.6 `BootNotification` carries no reason, the CSMS side records [`BootReason::Unknown`].
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::v1_6::{
+    messages::boot_notification::{BootNotificationRequest, BootNotificationResponse},
+    types,
+};
+
+use super::{BootReason, RegistrationManager, RegistrationStatus};
+
+impl From<&types::RegistrationStatus> for RegistrationStatus {
+    fn from(status: &types::RegistrationStatus) -> Self {
+        match status {
+            types::RegistrationStatus::Accepted => Self::Accepted,
+            types::RegistrationStatus::Pending => Self::Pending,
+            types::RegistrationStatus::Rejected => Self::Rejected,
+        }
+    }
+}
+
+impl From<RegistrationStatus> for types::RegistrationStatus {
+    fn from(status: RegistrationStatus) -> Self {
+        match status {
+            RegistrationStatus::Accepted => Self::Accepted,
+            RegistrationStatus::Pending => Self::Pending,
+            RegistrationStatus::Rejected => Self::Rejected,
+        }
+    }
+}
+
+/// Handles a `BootNotificationRequest` received by the CSMS.
+pub fn boot_notification(manager: &mut RegistrationManager, _request: &BootNotificationRequest) {
+    manager.reboot(BootReason::Unknown);
+}
+
+/// Applies a `BootNotificationResponse`, received by the Charge Point or sent by the CSMS
+/// at `now`.
+pub fn boot_notification_response(
+    manager: &mut RegistrationManager,
+    response: &BootNotificationResponse,
+    now: DateTime<Utc>,
+) {
+    manager.registered(
+        (&response.status).into(),
+        Duration::seconds(response.interval.into()),
+        now,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn register_pending_then_accepted() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut manager = RegistrationManager::new(BootReason::PowerUp);
+        boot_notification(&mut manager, &BootNotificationRequest::default());
+        assert_eq!(manager.reason(), BootReason::Unknown);
+
+        let response = BootNotificationResponse {
+            current_time: now,
+            interval: 60,
+            status: types::RegistrationStatus::Pending,
+        };
+        boot_notification_response(&mut manager, &response, now);
+        assert!(!manager.may_initiate("RemoteStartTransaction"));
+        assert!(manager.may_initiate("ChangeConfiguration"));
+        assert_eq!(manager.retry_at(), Some(now + Duration::seconds(60)));
+
+        let response = BootNotificationResponse {
+            status: types::RegistrationStatus::Accepted,
+            ..response
+        };
+        boot_notification_response(&mut manager, &response, now);
+        assert!(manager.send("StatusNotification", now));
+        assert_eq!(manager.heartbeat_interval(), Some(Duration::seconds(60)));
+    }
+}
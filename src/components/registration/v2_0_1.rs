@@ -0,0 +1,135 @@
+//! OCPP 2.0.1 messages for the [`RegistrationManager`].
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::v2_0_1::{
+    datatypes::charging_station_type::ChargingStationType,
+    enumerations::{
+        boot_reason_enum_type::BootReasonEnumType,
+        registration_status_enum_type::RegistrationStatusEnumType,
+    },
+    messages::boot_notification::{BootNotificationRequest, BootNotificationResponse},
+};
+
+use super::{BootReason, RegistrationManager, RegistrationStatus};
+
+impl From<&BootReasonEnumType> for BootReason {
+    fn from(reason: &BootReasonEnumType) -> Self {
+        match reason {
+            BootReasonEnumType::ApplicationReset => Self::ApplicationReset,
+            BootReasonEnumType::FirmwareUpdate => Self::FirmwareUpdate,
+            BootReasonEnumType::LocalReset => Self::LocalReset,
+            BootReasonEnumType::PowerUp => Self::PowerUp,
+            BootReasonEnumType::RemoteReset => Self::RemoteReset,
+            BootReasonEnumType::ScheduledReset => Self::ScheduledReset,
+            BootReasonEnumType::Triggered => Self::Triggered,
+            BootReasonEnumType::Unknown => Self::Unknown,
+            BootReasonEnumType::Watchdog => Self::Watchdog,
+        }
+    }
+}
+
+impl From<BootReason> for BootReasonEnumType {
+    fn from(reason: BootReason) -> Self {
+        match reason {
+            BootReason::ApplicationReset => Self::ApplicationReset,
+            BootReason::FirmwareUpdate => Self::FirmwareUpdate,
+            BootReason::LocalReset => Self::LocalReset,
+            BootReason::PowerUp => Self::PowerUp,
+            BootReason::RemoteReset => Self::RemoteReset,
+            BootReason::ScheduledReset => Self::ScheduledReset,
+            BootReason::Triggered => Self::Triggered,
+            BootReason::Unknown => Self::Unknown,
+            BootReason::Watchdog => Self::Watchdog,
+        }
+    }
+}
+
+impl From<&RegistrationStatusEnumType> for RegistrationStatus {
+    fn from(status: &RegistrationStatusEnumType) -> Self {
+        match status {
+            RegistrationStatusEnumType::Accepted => Self::Accepted,
+            RegistrationStatusEnumType::Pending => Self::Pending,
+            RegistrationStatusEnumType::Rejected => Self::Rejected,
+        }
+    }
+}
+
+impl From<RegistrationStatus> for RegistrationStatusEnumType {
+    fn from(status: RegistrationStatus) -> Self {
+        match status {
+            RegistrationStatus::Accepted => Self::Accepted,
+            RegistrationStatus::Pending => Self::Pending,
+            RegistrationStatus::Rejected => Self::Rejected,
+        }
+    }
+}
+
+/// `BootNotificationRequest` of `charging_station` with the reason it last booted.
+pub fn boot_notification_request(
+    manager: &RegistrationManager,
+    charging_station: ChargingStationType,
+) -> BootNotificationRequest {
+    BootNotificationRequest {
+        reason: manager.reason().into(),
+        charging_station,
+    }
+}
+
+/// Handles a `BootNotificationRequest` received by the CSMS.
+pub fn boot_notification(manager: &mut RegistrationManager, request: &BootNotificationRequest) {
+    manager.reboot((&request.reason).into());
+}
+
+/// Applies a `BootNotificationResponse`, received by the Charging Station or sent by the
+/// CSMS at `now`.
+pub fn boot_notification_response(
+    manager: &mut RegistrationManager,
+    response: &BootNotificationResponse,
+    now: DateTime<Utc>,
+) {
+    manager.registered(
+        (&response.status).into(),
+        Duration::seconds(response.interval.into()),
+        now,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn register_after_reboot() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut station = RegistrationManager::new(BootReason::FirmwareUpdate);
+        let request = boot_notification_request(&station, ChargingStationType::default());
+        assert_eq!(request.reason, BootReasonEnumType::FirmwareUpdate);
+
+        let mut csms = RegistrationManager::new(BootReason::Unknown);
+        boot_notification(&mut csms, &request);
+        assert_eq!(csms.reason(), BootReason::FirmwareUpdate);
+
+        let response = BootNotificationResponse {
+            current_time: now,
+            interval: 0,
+            status: RegistrationStatusEnumType::Rejected,
+            status_info: None,
+        };
+        boot_notification_response(&mut station, &response, now);
+        boot_notification_response(&mut csms, &response, now);
+        assert!(!station.may_respond(now));
+        assert!(!csms.may_initiate("GetVariables"));
+
+        let response = BootNotificationResponse {
+            interval: 300,
+            status: RegistrationStatusEnumType::Accepted,
+            ..response
+        };
+        boot_notification_response(&mut station, &response, now);
+        assert!(station.send("StatusNotification", now));
+        assert_eq!(station.heartbeat_interval(), Some(Duration::minutes(5)));
+    }
+}
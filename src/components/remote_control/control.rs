@@ -0,0 +1,221 @@
+use std::collections::VecDeque;
+
+use crate::components::{
+    availability::{AvailabilityManager, CsmsOperation},
+    registration::RegistrationManager,
+};
+
+use super::{MessageTrigger, RemoteControlError, RemoteStart, TriggerStatus, TriggeredMessage};
+
+/// Remote starts and triggered messages of a Charging Station.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemoteControl {
+    authorize_remote_start: bool,
+    implemented: Option<Vec<MessageTrigger>>,
+    triggered: VecDeque<TriggeredMessage>,
+}
+
+impl RemoteControl {
+    /// Creates the remote control of a station that implements every trigger, authorizing
+    /// the token of a remote start first when `authorize_remote_start`.
+    pub fn new(authorize_remote_start: bool) -> Self {
+        Self {
+            authorize_remote_start,
+            ..Default::default()
+        }
+    }
+
+    /// Implements only the triggers `messages`, answering `NotImplemented` to the others.
+    pub fn with_triggers(mut self, messages: Vec<MessageTrigger>) -> Self {
+        self.implemented = Some(messages);
+        self
+    }
+
+    /// Whether the token of a remote start is authorized first.
+    pub fn authorize_remote_start(&self) -> bool {
+        self.authorize_remote_start
+    }
+
+    /// Changes whether the token of a remote start is authorized first, e.g. when the
+    /// CSMS changes the configuration.
+    pub fn set_authorize_remote_start(&mut self, authorize_remote_start: bool) {
+        self.authorize_remote_start = authorize_remote_start;
+    }
+
+    /// Checks a remote start on connector `connector_id` of EVSE `evse_id`, choosing the
+    /// first connector that allows it when either is `None`.
+    pub fn remote_start(
+        &self,
+        availability: &AvailabilityManager,
+        evse_id: Option<i32>,
+        connector_id: Option<i32>,
+    ) -> Result<RemoteStart, RemoteControlError> {
+        let connectors = |evse_id| {
+            (1..).take_while(move |&connector_id| {
+                availability.status(evse_id, connector_id).is_some()
+            })
+        };
+        let candidates: Vec<(i32, i32)> = match (evse_id, connector_id) {
+            (Some(evse_id), Some(connector_id)) => {
+                if availability.status(evse_id, connector_id).is_none() {
+                    return Err(RemoteControlError::UnknownConnector {
+                        evse_id,
+                        connector_id,
+                    });
+                }
+                vec![(evse_id, connector_id)]
+            }
+            (Some(evse_id), None) => connectors(evse_id).map(|c| (evse_id, c)).collect(),
+            (None, _) => (1..)
+                .take_while(|&evse_id| connectors(evse_id).next().is_some())
+                .flat_map(|evse_id| connectors(evse_id).map(move |c| (evse_id, c)))
+                .collect(),
+        };
+        if let (Some(evse_id), true) = (evse_id, candidates.is_empty()) {
+            return Err(RemoteControlError::UnknownConnector {
+                evse_id,
+                connector_id: connector_id.unwrap_or(1),
+            });
+        }
+        candidates
+            .into_iter()
+            .find(|&(evse_id, connector_id)| {
+                availability.allows(evse_id, connector_id, CsmsOperation::RemoteStart)
+            })
+            .map(|(evse_id, connector_id)| RemoteStart {
+                evse_id,
+                connector_id,
+                authorize: self.authorize_remote_start,
+            })
+            .ok_or(RemoteControlError::Unavailable)
+    }
+
+    /// Handles a `TriggerMessage` for `triggered`, queueing the message when accepted.
+    ///
+    /// A message about an unknown EVSE or connector is rejected, as is a `TransactionEvent`
+    /// for an EVSE without transaction and any message while the registration is
+    /// `Rejected`.
+    pub fn trigger(
+        &mut self,
+        registration: &mut RegistrationManager,
+        availability: &AvailabilityManager,
+        triggered: TriggeredMessage,
+    ) -> TriggerStatus {
+        if self
+            .implemented
+            .as_ref()
+            .is_some_and(|implemented| !implemented.contains(&triggered.message))
+        {
+            return TriggerStatus::NotImplemented;
+        }
+        if let Some(evse_id) = triggered.evse_id {
+            let connector_id = triggered.connector_id.unwrap_or(1);
+            if availability.status(evse_id, connector_id).is_none()
+                || (triggered.message == MessageTrigger::TransactionEvent
+                    && !availability.has_transaction(evse_id))
+            {
+                return TriggerStatus::Rejected;
+            }
+        }
+        if !registration.trigger(triggered.message.action()) {
+            return TriggerStatus::Rejected;
+        }
+        self.triggered.push_back(triggered);
+        TriggerStatus::Accepted
+    }
+
+    /// The next triggered message to send, once the `TriggerMessage` response is sent.
+    pub fn next_triggered(&mut self) -> Option<TriggeredMessage> {
+        self.triggered.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use super::*;
+    use crate::components::{
+        availability::{AvailabilityTarget, OperationalStatus},
+        registration::{BootReason, RegistrationStatus},
+    };
+
+    #[test]
+    fn chooses_a_connector_for_remote_starts() {
+        let mut availability = AvailabilityManager::new(&[1, 2]);
+        let control = RemoteControl::new(true);
+        assert_eq!(
+            control.remote_start(&availability, None, None),
+            Ok(RemoteStart {
+                evse_id: 1,
+                connector_id: 1,
+                authorize: true
+            })
+        );
+
+        availability.transaction_started(1);
+        availability.change_availability(
+            AvailabilityTarget::Connector(2, 1),
+            OperationalStatus::Inoperative,
+        );
+        let start = control.remote_start(&availability, None, None).unwrap();
+        assert_eq!((start.evse_id, start.connector_id), (2, 2));
+        assert_eq!(
+            control.remote_start(&availability, Some(1), None),
+            Err(RemoteControlError::Unavailable)
+        );
+        assert_eq!(
+            control.remote_start(&availability, Some(3), None),
+            Err(RemoteControlError::UnknownConnector {
+                evse_id: 3,
+                connector_id: 1
+            })
+        );
+    }
+
+    #[test]
+    fn queues_triggered_messages() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let availability = AvailabilityManager::new(&[1]);
+        let mut registration = RegistrationManager::new(BootReason::PowerUp);
+        registration.registered(RegistrationStatus::Pending, Duration::seconds(60), now);
+        let mut control =
+            RemoteControl::new(false).with_triggers(vec![MessageTrigger::StatusNotification]);
+
+        let triggered = |message, evse_id| TriggeredMessage {
+            message,
+            evse_id,
+            connector_id: None,
+        };
+        assert_eq!(
+            control.trigger(
+                &mut registration,
+                &availability,
+                triggered(MessageTrigger::Heartbeat, None)
+            ),
+            TriggerStatus::NotImplemented
+        );
+        assert_eq!(
+            control.trigger(
+                &mut registration,
+                &availability,
+                triggered(MessageTrigger::StatusNotification, Some(2))
+            ),
+            TriggerStatus::Rejected
+        );
+        let status_notification = triggered(MessageTrigger::StatusNotification, Some(1));
+        assert_eq!(
+            control.trigger(
+                &mut registration,
+                &availability,
+                status_notification.clone()
+            ),
+            TriggerStatus::Accepted
+        );
+        assert_eq!(control.next_triggered(), Some(status_notification));
+        assert_eq!(control.next_triggered(), None);
+        // may be sent once while pending
+        assert!(registration.send("StatusNotification", now));
+        assert!(!registration.send("StatusNotification", now));
+    }
+}
@@ -0,0 +1,153 @@
+//! # Remote control
+//!
+//! Charging Station side of the requests with which the CSMS controls transactions and
+//! messages: `RemoteStartTransaction` and `RemoteStopTransaction` (1.6),
+//! `RequestStartTransaction` and `RequestStopTransaction` (2.x), and `TriggerMessage`.
+//!
+//! [`RemoteControl`] checks a remote start against the availability of the connector,
+//! choosing a connector when the CSMS did not, and tells whether the token must be
+//! authorized first (`AuthorizeRemoteTxRequests` in 1.6, `AuthCtrlr.AuthorizeRemoteStart`
+//! in 2.x). The version modules also reject a charging profile that is not a `TxProfile`
+//! for the new transaction, and hand an accepted start or stop to the
+//! [`TransactionEngine`](crate::components::transactions::TransactionEngine) in 2.x.
+//!
+//! An accepted `TriggerMessage` is queued, to be sent with [`RemoteControl::next_triggered`]
+//! once the response is sent. While the registration is `Pending` the triggered message is
+//! recorded with the [`RegistrationManager`](crate::components::registration::RegistrationManager)
+//! so that it may be sent.
+
+use std::fmt;
+
+mod control;
+
+#[cfg(feature = "v1_6")]
+pub mod v1_6;
+#[cfg(feature = "v2_0_1")]
+pub mod v2_0_1;
+#[cfg(feature = "v2_1")]
+pub mod v2_1;
+
+pub use control::RemoteControl;
+
+/// Message the CSMS may trigger, `MessageTrigger` in 1.6 and `MessageTriggerEnumType` in
+/// 2.x.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MessageTrigger {
+    /// `BootNotification`.
+    BootNotification,
+    /// `DiagnosticsStatusNotification` (1.6).
+    DiagnosticsStatusNotification,
+    /// `FirmwareStatusNotification`.
+    FirmwareStatusNotification,
+    /// `Heartbeat`.
+    Heartbeat,
+    /// `LogStatusNotification` (2.x).
+    LogStatusNotification,
+    /// `MeterValues`.
+    MeterValues,
+    /// `PublishFirmwareStatusNotification` (2.x).
+    PublishFirmwareStatusNotification,
+    /// `SignCertificate` for the Charging Station certificate (2.x).
+    SignChargingStationCertificate,
+    /// `SignCertificate` for the Charging Station and V2G certificates (2.x).
+    SignCombinedCertificate,
+    /// `SignCertificate` for the V2G certificate (2.x).
+    SignV2GCertificate,
+    /// `SignCertificate` for the ISO 15118-20 V2G certificate (2.1).
+    SignV2G20Certificate,
+    /// `StatusNotification`.
+    StatusNotification,
+    /// `TransactionEvent` (2.x).
+    TransactionEvent,
+    /// A custom trigger of 2.1, by its name.
+    CustomTrigger(String),
+}
+
+impl MessageTrigger {
+    /// Action of the triggered request, such as `SignCertificate`.
+    pub fn action(&self) -> &str {
+        match self {
+            Self::BootNotification => "BootNotification",
+            Self::DiagnosticsStatusNotification => "DiagnosticsStatusNotification",
+            Self::FirmwareStatusNotification => "FirmwareStatusNotification",
+            Self::Heartbeat => "Heartbeat",
+            Self::LogStatusNotification => "LogStatusNotification",
+            Self::MeterValues => "MeterValues",
+            Self::PublishFirmwareStatusNotification => "PublishFirmwareStatusNotification",
+            Self::SignChargingStationCertificate
+            | Self::SignCombinedCertificate
+            | Self::SignV2GCertificate
+            | Self::SignV2G20Certificate => "SignCertificate",
+            Self::StatusNotification => "StatusNotification",
+            Self::TransactionEvent => "TransactionEvent",
+            Self::CustomTrigger(name) => name,
+        }
+    }
+}
+
+/// A message to send because the CSMS triggered it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriggeredMessage {
+    /// The message.
+    pub message: MessageTrigger,
+    /// EVSE, or connector in 1.6, the message is about; `None` for all of them.
+    pub evse_id: Option<i32>,
+    /// Connector of the EVSE the message is about (2.x).
+    pub connector_id: Option<i32>,
+}
+
+/// Answer to a `TriggerMessage`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerStatus {
+    /// The message will be sent.
+    Accepted,
+    /// The message will not be sent.
+    Rejected,
+    /// The Charging Station does not support triggering the message.
+    NotImplemented,
+}
+
+/// A remote start that was accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemoteStart {
+    /// EVSE, or connector in 1.6, to start the transaction on.
+    pub evse_id: i32,
+    /// Connector of the EVSE.
+    pub connector_id: i32,
+    /// Whether the token must be authorized before the transaction starts.
+    pub authorize: bool,
+}
+
+/// Why a remote start or stop is rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteControlError {
+    /// The EVSE or connector does not exist.
+    UnknownConnector {
+        /// The EVSE, the connector in 1.6.
+        evse_id: i32,
+        /// The connector of the EVSE.
+        connector_id: i32,
+    },
+    /// No connector is available for a new transaction.
+    Unavailable,
+    /// The charging profile is not a `TxProfile` for the new transaction.
+    InvalidChargingProfile,
+    /// The transaction to stop is not ongoing.
+    UnknownTransaction,
+}
+
+impl fmt::Display for RemoteControlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownConnector {
+                evse_id,
+                connector_id,
+            } => write!(f, "unknown connector {connector_id} of EVSE {evse_id}"),
+            Self::Unavailable => write!(f, "no connector available"),
+            Self::InvalidChargingProfile => write!(f, "charging profile is not a TxProfile"),
+            Self::UnknownTransaction => write!(f, "unknown transaction"),
+        }
+    }
+}
+
+impl std::error::Error for RemoteControlError {}
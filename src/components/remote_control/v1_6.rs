@@ -0,0 +1,207 @@
+//! OCPP 1.6 messages for the [`RemoteControl`].
+//!
+//! 1.6 has no EVSEs: a connector is addressed as an EVSE with a single connector, and
+//! connector 0 addresses the Charge Point as a whole.
+
+use crate::{
+    components::{availability::AvailabilityManager, registration::RegistrationManager},
+    v1_6::{
+        messages::{
+            remote_start_transaction::{
+                RemoteStartTransactionRequest, RemoteStartTransactionResponse,
+            },
+            remote_stop_transaction::{
+                RemoteStopTransactionRequest, RemoteStopTransactionResponse,
+            },
+            trigger_message::{TriggerMessageRequest, TriggerMessageResponse},
+        },
+        types::{self, ChargingProfilePurposeType, RemoteStartStopStatus, TriggerMessageStatus},
+    },
+};
+
+use super::{
+    MessageTrigger, RemoteControl, RemoteControlError, RemoteStart, TriggerStatus, TriggeredMessage,
+};
+
+impl From<&types::MessageTrigger> for MessageTrigger {
+    fn from(message: &types::MessageTrigger) -> Self {
+        match message {
+            types::MessageTrigger::BootNotification => Self::BootNotification,
+            types::MessageTrigger::DiagnosticsStatusNotification => {
+                Self::DiagnosticsStatusNotification
+            }
+            types::MessageTrigger::FirmwareStatusNotification => Self::FirmwareStatusNotification,
+            types::MessageTrigger::Heartbeat => Self::Heartbeat,
+            types::MessageTrigger::MeterValues => Self::MeterValues,
+            types::MessageTrigger::StatusNotification => Self::StatusNotification,
+        }
+    }
+}
+
+impl From<TriggerStatus> for TriggerMessageStatus {
+    fn from(status: TriggerStatus) -> Self {
+        match status {
+            TriggerStatus::Accepted => Self::Accepted,
+            TriggerStatus::Rejected => Self::Rejected,
+            TriggerStatus::NotImplemented => Self::NotImplemented,
+        }
+    }
+}
+
+fn remote_start(
+    control: &RemoteControl,
+    availability: &AvailabilityManager,
+    request: &RemoteStartTransactionRequest,
+) -> Result<RemoteStart, RemoteControlError> {
+    if let Some(profile) = &request.charging_profile {
+        if profile.charging_profile_purpose != ChargingProfilePurposeType::TxProfile
+            || profile.transaction_id.is_some()
+        {
+            return Err(RemoteControlError::InvalidChargingProfile);
+        }
+    }
+    let connector_id = match request.connector_id.map(i32::try_from) {
+        None => None,
+        Some(Ok(connector_id)) if connector_id > 0 => Some(connector_id),
+        Some(_) => {
+            return Err(RemoteControlError::UnknownConnector {
+                evse_id: 0,
+                connector_id: 1,
+            })
+        }
+    };
+    control.remote_start(availability, connector_id, connector_id.map(|_| 1))
+}
+
+/// Handles a `RemoteStartTransactionRequest`, returning the response and the accepted start.
+///
+/// Unless the start must be authorized first, the Charge Point starts the transaction as
+/// if `idTag` was presented.
+pub fn remote_start_transaction(
+    control: &RemoteControl,
+    availability: &AvailabilityManager,
+    request: &RemoteStartTransactionRequest,
+) -> (RemoteStartTransactionResponse, Option<RemoteStart>) {
+    let start = remote_start(control, availability, request).ok();
+    let status = match start {
+        Some(_) => RemoteStartStopStatus::Accepted,
+        None => RemoteStartStopStatus::Rejected,
+    };
+    (RemoteStartTransactionResponse { status }, start)
+}
+
+/// Handles a `RemoteStopTransactionRequest`, returning the response and the connector of
+/// the transaction to stop. `connector_of` gives the connector of a transaction id.
+pub fn remote_stop_transaction(
+    availability: &AvailabilityManager,
+    request: &RemoteStopTransactionRequest,
+    connector_of: impl FnOnce(i32) -> Option<i32>,
+) -> (RemoteStopTransactionResponse, Option<i32>) {
+    let connector_id = connector_of(request.transaction_id)
+        .filter(|&connector_id| availability.has_transaction(connector_id));
+    let status = match connector_id {
+        Some(_) => RemoteStartStopStatus::Accepted,
+        None => RemoteStartStopStatus::Rejected,
+    };
+    (RemoteStopTransactionResponse { status }, connector_id)
+}
+
+/// Handles a `TriggerMessageRequest`, queueing the triggered message when accepted.
+pub fn trigger_message(
+    control: &mut RemoteControl,
+    registration: &mut RegistrationManager,
+    availability: &AvailabilityManager,
+    request: &TriggerMessageRequest,
+) -> TriggerMessageResponse {
+    let triggered = TriggeredMessage {
+        message: (&request.requested_message).into(),
+        evse_id: request
+            .connector_id
+            .filter(|&connector_id| connector_id > 0)
+            .map(|connector_id| i32::try_from(connector_id).unwrap_or(i32::MAX)),
+        connector_id: None,
+    };
+    TriggerMessageResponse {
+        status: control
+            .trigger(registration, availability, triggered)
+            .into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use super::*;
+    use crate::{
+        components::registration::{BootReason, RegistrationStatus},
+        v1_6::types::ChargingProfile,
+    };
+
+    #[test]
+    fn handles_remote_start_and_stop() {
+        let mut availability = AvailabilityManager::new(&[1, 1]);
+        let control = RemoteControl::new(false);
+        let request = RemoteStartTransactionRequest {
+            connector_id: Some(2),
+            id_tag: "TAG1".to_string(),
+            charging_profile: None,
+        };
+        let (response, start) = remote_start_transaction(&control, &availability, &request);
+        assert_eq!(response.status, RemoteStartStopStatus::Accepted);
+        let start = start.unwrap();
+        assert_eq!((start.evse_id, start.authorize), (2, false));
+
+        let invalid = RemoteStartTransactionRequest {
+            charging_profile: Some(ChargingProfile {
+                charging_profile_purpose: ChargingProfilePurposeType::TxDefaultProfile,
+                ..Default::default()
+            }),
+            ..request.clone()
+        };
+        let (response, _) = remote_start_transaction(&control, &availability, &invalid);
+        assert_eq!(response.status, RemoteStartStopStatus::Rejected);
+        let invalid = RemoteStartTransactionRequest {
+            connector_id: Some(0),
+            ..request
+        };
+        let (response, _) = remote_start_transaction(&control, &availability, &invalid);
+        assert_eq!(response.status, RemoteStartStopStatus::Rejected);
+
+        availability.transaction_started(2);
+        let request = RemoteStopTransactionRequest { transaction_id: 42 };
+        let (response, connector_id) =
+            remote_stop_transaction(&availability, &request, |id| (id == 42).then_some(2));
+        assert_eq!(response.status, RemoteStartStopStatus::Accepted);
+        assert_eq!(connector_id, Some(2));
+        let (response, _) = remote_stop_transaction(&availability, &request, |_| Some(1));
+        assert_eq!(response.status, RemoteStartStopStatus::Rejected);
+    }
+
+    #[test]
+    fn handles_trigger_message() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let availability = AvailabilityManager::new(&[1]);
+        let mut registration = RegistrationManager::new(BootReason::Unknown);
+        registration.registered(RegistrationStatus::Accepted, Duration::seconds(300), now);
+        let mut control = RemoteControl::new(false);
+
+        let request = TriggerMessageRequest {
+            requested_message: types::MessageTrigger::MeterValues,
+            connector_id: Some(1),
+        };
+        let response = trigger_message(&mut control, &mut registration, &availability, &request);
+        assert_eq!(response.status, TriggerMessageStatus::Accepted);
+        let triggered = control.next_triggered().unwrap();
+        assert_eq!(triggered.message, MessageTrigger::MeterValues);
+        assert_eq!(triggered.evse_id, Some(1));
+
+        let request = TriggerMessageRequest {
+            requested_message: types::MessageTrigger::BootNotification,
+            connector_id: Some(0),
+        };
+        let response = trigger_message(&mut control, &mut registration, &availability, &request);
+        assert_eq!(response.status, TriggerMessageStatus::Accepted);
+        assert_eq!(control.next_triggered().unwrap().evse_id, None);
+    }
+}
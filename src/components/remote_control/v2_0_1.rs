@@ -0,0 +1,259 @@
+//! OCPP 2.0.1 messages for the [`RemoteControl`].
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    components::{
+        availability::AvailabilityManager,
+        registration::RegistrationManager,
+        transactions::{SessionEvent, TransactionEngine, TransactionEvent},
+    },
+    v2_0_1::{
+        enumerations::{
+            charging_profile_purpose_enum_type::ChargingProfilePurposeEnumType,
+            message_trigger_enum_type::MessageTriggerEnumType,
+            request_start_stop_status_enum_type::RequestStartStopStatusEnumType,
+            trigger_message_status_enum_type::TriggerMessageStatusEnumType,
+        },
+        messages::{
+            request_start_transaction::{
+                RequestStartTransactionRequest, RequestStartTransactionResponse,
+            },
+            request_stop_transaction::{
+                RequestStopTransactionRequest, RequestStopTransactionResponse,
+            },
+            trigger_message::{TriggerMessageRequest, TriggerMessageResponse},
+        },
+    },
+};
+
+use super::{
+    MessageTrigger, RemoteControl, RemoteControlError, RemoteStart, TriggerStatus, TriggeredMessage,
+};
+
+impl From<&MessageTriggerEnumType> for MessageTrigger {
+    fn from(message: &MessageTriggerEnumType) -> Self {
+        match message {
+            MessageTriggerEnumType::BootNotification => Self::BootNotification,
+            MessageTriggerEnumType::LogStatusNotification => Self::LogStatusNotification,
+            MessageTriggerEnumType::FirmwareStatusNotification => Self::FirmwareStatusNotification,
+            MessageTriggerEnumType::Heartbeat => Self::Heartbeat,
+            MessageTriggerEnumType::MeterValues => Self::MeterValues,
+            MessageTriggerEnumType::SignChargingStationCertificate => {
+                Self::SignChargingStationCertificate
+            }
+            MessageTriggerEnumType::SignV2GCertificate => Self::SignV2GCertificate,
+            MessageTriggerEnumType::StatusNotification => Self::StatusNotification,
+            MessageTriggerEnumType::TransactionEvent => Self::TransactionEvent,
+            MessageTriggerEnumType::SignCombinedCertificate => Self::SignCombinedCertificate,
+            MessageTriggerEnumType::PublishFirmwareStatusNotification => {
+                Self::PublishFirmwareStatusNotification
+            }
+        }
+    }
+}
+
+impl From<TriggerStatus> for TriggerMessageStatusEnumType {
+    fn from(status: TriggerStatus) -> Self {
+        match status {
+            TriggerStatus::Accepted => Self::Accepted,
+            TriggerStatus::Rejected => Self::Rejected,
+            TriggerStatus::NotImplemented => Self::NotImplemented,
+        }
+    }
+}
+
+fn remote_start(
+    control: &RemoteControl,
+    availability: &AvailabilityManager,
+    request: &RequestStartTransactionRequest,
+) -> Result<RemoteStart, RemoteControlError> {
+    if let Some(profile) = &request.charging_profile {
+        if profile.charging_profile_purpose != ChargingProfilePurposeEnumType::TxProfile
+            || profile.transaction_id.is_some()
+        {
+            return Err(RemoteControlError::InvalidChargingProfile);
+        }
+    }
+    control.remote_start(availability, request.evse_id, None)
+}
+
+/// Handles a `RequestStartTransactionRequest`, returning the response, the accepted start
+/// and the transaction event to send.
+///
+/// Unless the token must be authorized first, the start is handed to `engine` right away
+/// and the response carries the id of the transaction when it started. Otherwise the
+/// start is handed to `engine` once the token is authorized.
+pub fn request_start_transaction(
+    control: &RemoteControl,
+    availability: &AvailabilityManager,
+    engine: &mut TransactionEngine,
+    request: &RequestStartTransactionRequest,
+    now: DateTime<Utc>,
+) -> (
+    RequestStartTransactionResponse,
+    Option<RemoteStart>,
+    Option<TransactionEvent>,
+) {
+    let Ok(start) = remote_start(control, availability, request) else {
+        let response = RequestStartTransactionResponse {
+            status: RequestStartStopStatusEnumType::Rejected,
+            ..Default::default()
+        };
+        return (response, None, None);
+    };
+    let event = (!start.authorize)
+        .then(|| {
+            let remote_start_id = request.remote_start_id;
+            engine.handle(
+                start.evse_id,
+                SessionEvent::RemoteStart { remote_start_id },
+                now,
+            )
+        })
+        .flatten();
+    let response = RequestStartTransactionResponse {
+        status: RequestStartStopStatusEnumType::Accepted,
+        transaction_id: engine.transaction_id(start.evse_id).map(str::to_string),
+        ..Default::default()
+    };
+    (response, Some(start), event)
+}
+
+/// Handles a `RequestStopTransactionRequest`, stopping the transaction in `engine` and
+/// returning the response and the transaction event to send.
+pub fn request_stop_transaction(
+    engine: &mut TransactionEngine,
+    request: &RequestStopTransactionRequest,
+    now: DateTime<Utc>,
+) -> (RequestStopTransactionResponse, Option<TransactionEvent>) {
+    let evse_id = engine.evse_of(&request.transaction_id);
+    let status = match evse_id {
+        Some(_) => RequestStartStopStatusEnumType::Accepted,
+        None => RequestStartStopStatusEnumType::Rejected,
+    };
+    let response = RequestStopTransactionResponse {
+        status,
+        ..Default::default()
+    };
+    let event = evse_id.and_then(|evse_id| engine.handle(evse_id, SessionEvent::RemoteStop, now));
+    (response, event)
+}
+
+/// Handles a `TriggerMessageRequest`, queueing the triggered message when accepted. A
+/// `BootNotification` is rejected once the registration is accepted.
+pub fn trigger_message(
+    control: &mut RemoteControl,
+    registration: &mut RegistrationManager,
+    availability: &AvailabilityManager,
+    request: &TriggerMessageRequest,
+) -> TriggerMessageResponse {
+    let triggered = TriggeredMessage {
+        message: (&request.requested_message).into(),
+        evse_id: request
+            .evse
+            .as_ref()
+            .map(|evse| evse.id)
+            .filter(|&id| id > 0),
+        connector_id: request.evse.as_ref().and_then(|evse| evse.connector_id),
+    };
+    let status = match triggered.message {
+        MessageTrigger::BootNotification if registration.is_accepted() => TriggerStatus::Rejected,
+        _ => control.trigger(registration, availability, triggered),
+    };
+    TriggerMessageResponse {
+        status: status.into(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+    use crate::{
+        components::{
+            registration::{BootReason, RegistrationStatus},
+            transactions::{TransactionEventType, TriggerReason, TxPoint},
+        },
+        v2_0_1::datatypes::{charging_profile_type::ChargingProfileType, evse_type::EVSEType},
+    };
+
+    #[test]
+    fn handles_request_start_and_stop() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let availability = AvailabilityManager::new(&[1]);
+        let mut engine =
+            TransactionEngine::new(vec![TxPoint::Authorized], vec![TxPoint::Authorized], &[1]);
+        let control = RemoteControl::new(false);
+        let request = RequestStartTransactionRequest {
+            evse_id: Some(1),
+            remote_start_id: 7,
+            ..Default::default()
+        };
+        let (response, start, event) =
+            request_start_transaction(&control, &availability, &mut engine, &request, now);
+        assert_eq!(response.status, RequestStartStopStatusEnumType::Accepted);
+        assert!(!start.unwrap().authorize);
+        let event = event.unwrap();
+        assert_eq!(event.trigger_reason, TriggerReason::RemoteStart);
+        assert_eq!(event.remote_start_id, Some(7));
+        assert_eq!(response.transaction_id, Some(event.transaction_id.clone()));
+
+        let invalid = RequestStartTransactionRequest {
+            charging_profile: Some(ChargingProfileType {
+                charging_profile_purpose: ChargingProfilePurposeEnumType::TxProfile,
+                transaction_id: Some("other".to_string()),
+                ..Default::default()
+            }),
+            ..request
+        };
+        let (response, start, _) =
+            request_start_transaction(&control, &availability, &mut engine, &invalid, now);
+        assert_eq!(response.status, RequestStartStopStatusEnumType::Rejected);
+        assert_eq!(start, None);
+
+        let request = RequestStopTransactionRequest {
+            transaction_id: event.transaction_id,
+        };
+        let (response, ended) = request_stop_transaction(&mut engine, &request, now);
+        assert_eq!(response.status, RequestStartStopStatusEnumType::Accepted);
+        assert_eq!(ended.unwrap().event_type, TransactionEventType::Ended);
+        let (response, _) = request_stop_transaction(&mut engine, &request, now);
+        assert_eq!(response.status, RequestStartStopStatusEnumType::Rejected);
+    }
+
+    #[test]
+    fn handles_trigger_message() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut availability = AvailabilityManager::new(&[1]);
+        let mut registration = RegistrationManager::new(BootReason::PowerUp);
+        registration.registered(RegistrationStatus::Accepted, Duration::seconds(300), now);
+        let mut control = RemoteControl::new(false);
+
+        let request = TriggerMessageRequest {
+            requested_message: MessageTriggerEnumType::BootNotification,
+            evse: None,
+        };
+        let response = trigger_message(&mut control, &mut registration, &availability, &request);
+        assert_eq!(response.status, TriggerMessageStatusEnumType::Rejected);
+
+        let request = TriggerMessageRequest {
+            requested_message: MessageTriggerEnumType::TransactionEvent,
+            evse: Some(EVSEType {
+                id: 1,
+                connector_id: None,
+            }),
+        };
+        let response = trigger_message(&mut control, &mut registration, &availability, &request);
+        assert_eq!(response.status, TriggerMessageStatusEnumType::Rejected);
+        availability.transaction_started(1);
+        let response = trigger_message(&mut control, &mut registration, &availability, &request);
+        assert_eq!(response.status, TriggerMessageStatusEnumType::Accepted);
+        assert_eq!(
+            control.next_triggered().unwrap().message,
+            MessageTrigger::TransactionEvent
+        );
+    }
+}
@@ -0,0 +1,267 @@
+//! OCPP 2.1 messages for the [`RemoteControl`].
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    components::{
+        availability::AvailabilityManager,
+        registration::RegistrationManager,
+        transactions::{SessionEvent, TransactionEngine, TransactionEvent},
+    },
+    v2_1::{
+        enumerations::{
+            ChargingProfilePurposeEnumType, MessageTriggerEnumType, RequestStartStopStatusEnumType,
+            TriggerMessageStatusEnumType,
+        },
+        messages::{
+            request_start_transaction::{
+                RequestStartTransactionRequest, RequestStartTransactionResponse,
+            },
+            request_stop_transaction::{
+                RequestStopTransactionRequest, RequestStopTransactionResponse,
+            },
+            trigger_message::{TriggerMessageRequest, TriggerMessageResponse},
+        },
+    },
+};
+
+use super::{
+    MessageTrigger, RemoteControl, RemoteControlError, RemoteStart, TriggerStatus, TriggeredMessage,
+};
+
+impl From<&TriggerMessageRequest> for MessageTrigger {
+    fn from(request: &TriggerMessageRequest) -> Self {
+        match request.requested_message {
+            MessageTriggerEnumType::BootNotification => Self::BootNotification,
+            MessageTriggerEnumType::LogStatusNotification => Self::LogStatusNotification,
+            MessageTriggerEnumType::FirmwareStatusNotification => Self::FirmwareStatusNotification,
+            MessageTriggerEnumType::Heartbeat => Self::Heartbeat,
+            MessageTriggerEnumType::MeterValues => Self::MeterValues,
+            MessageTriggerEnumType::SignChargingStationCertificate => {
+                Self::SignChargingStationCertificate
+            }
+            MessageTriggerEnumType::SignV2GCertificate => Self::SignV2GCertificate,
+            MessageTriggerEnumType::SignV2G20Certificate => Self::SignV2G20Certificate,
+            MessageTriggerEnumType::StatusNotification => Self::StatusNotification,
+            MessageTriggerEnumType::TransactionEvent => Self::TransactionEvent,
+            MessageTriggerEnumType::SignCombinedCertificate => Self::SignCombinedCertificate,
+            MessageTriggerEnumType::PublishFirmwareStatusNotification => {
+                Self::PublishFirmwareStatusNotification
+            }
+            MessageTriggerEnumType::CustomTrigger => {
+                Self::CustomTrigger(request.custom_trigger.clone().unwrap_or_default())
+            }
+        }
+    }
+}
+
+impl From<TriggerStatus> for TriggerMessageStatusEnumType {
+    fn from(status: TriggerStatus) -> Self {
+        match status {
+            TriggerStatus::Accepted => Self::Accepted,
+            TriggerStatus::Rejected => Self::Rejected,
+            TriggerStatus::NotImplemented => Self::NotImplemented,
+        }
+    }
+}
+
+fn remote_start(
+    control: &RemoteControl,
+    availability: &AvailabilityManager,
+    request: &RequestStartTransactionRequest,
+) -> Result<RemoteStart, RemoteControlError> {
+    if let Some(profile) = &request.charging_profile {
+        if profile.charging_profile_purpose != ChargingProfilePurposeEnumType::TxProfile
+            || profile.transaction_id.is_some()
+        {
+            return Err(RemoteControlError::InvalidChargingProfile);
+        }
+    }
+    control.remote_start(availability, request.evse_id, None)
+}
+
+/// Handles a `RequestStartTransactionRequest`, returning the response, the accepted start
+/// and the transaction event to send.
+///
+/// Unless the token must be authorized first, the start is handed to `engine` right away
+/// and the response carries the id of the transaction when it started. Otherwise the
+/// start is handed to `engine` once the token is authorized.
+pub fn request_start_transaction(
+    control: &RemoteControl,
+    availability: &AvailabilityManager,
+    engine: &mut TransactionEngine,
+    request: &RequestStartTransactionRequest,
+    now: DateTime<Utc>,
+) -> (
+    RequestStartTransactionResponse,
+    Option<RemoteStart>,
+    Option<TransactionEvent>,
+) {
+    let Ok(start) = remote_start(control, availability, request) else {
+        let response = RequestStartTransactionResponse {
+            status: RequestStartStopStatusEnumType::Rejected,
+            transaction_id: None,
+            status_info: None,
+            custom_data: None,
+        };
+        return (response, None, None);
+    };
+    let event = (!start.authorize)
+        .then(|| {
+            let remote_start_id = request.remote_start_id;
+            engine.handle(
+                start.evse_id,
+                SessionEvent::RemoteStart { remote_start_id },
+                now,
+            )
+        })
+        .flatten();
+    let response = RequestStartTransactionResponse {
+        status: RequestStartStopStatusEnumType::Accepted,
+        transaction_id: engine.transaction_id(start.evse_id).map(str::to_string),
+        status_info: None,
+        custom_data: None,
+    };
+    (response, Some(start), event)
+}
+
+/// Handles a `RequestStopTransactionRequest`, stopping the transaction in `engine` and
+/// returning the response and the transaction event to send.
+pub fn request_stop_transaction(
+    engine: &mut TransactionEngine,
+    request: &RequestStopTransactionRequest,
+    now: DateTime<Utc>,
+) -> (RequestStopTransactionResponse, Option<TransactionEvent>) {
+    let evse_id = engine.evse_of(&request.transaction_id);
+    let status = match evse_id {
+        Some(_) => RequestStartStopStatusEnumType::Accepted,
+        None => RequestStartStopStatusEnumType::Rejected,
+    };
+    let response = RequestStopTransactionResponse {
+        status,
+        status_info: None,
+        custom_data: None,
+    };
+    let event = evse_id.and_then(|evse_id| engine.handle(evse_id, SessionEvent::RemoteStop, now));
+    (response, event)
+}
+
+/// Handles a `TriggerMessageRequest`, queueing the triggered message when accepted. A
+/// `BootNotification` is rejected once the registration is accepted, as is a
+/// `CustomTrigger` without name.
+pub fn trigger_message(
+    control: &mut RemoteControl,
+    registration: &mut RegistrationManager,
+    availability: &AvailabilityManager,
+    request: &TriggerMessageRequest,
+) -> TriggerMessageResponse {
+    let triggered = TriggeredMessage {
+        message: request.into(),
+        evse_id: request
+            .evse
+            .as_ref()
+            .map(|evse| evse.id)
+            .filter(|&id| id > 0),
+        connector_id: request.evse.as_ref().and_then(|evse| evse.connector_id),
+    };
+    let status = match &triggered.message {
+        MessageTrigger::BootNotification if registration.is_accepted() => TriggerStatus::Rejected,
+        MessageTrigger::CustomTrigger(name) if name.is_empty() => TriggerStatus::Rejected,
+        _ => control.trigger(registration, availability, triggered),
+    };
+    TriggerMessageResponse::new(status.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+    use crate::{
+        components::{
+            registration::{BootReason, RegistrationStatus},
+            transactions::{TransactionEventType, TriggerReason, TxPoint},
+        },
+        v2_1::{
+            datatypes::{ChargingProfileType, EVSEType, IdTokenType},
+            enumerations::ChargingProfileKindEnumType,
+        },
+    };
+
+    #[test]
+    fn handles_request_start_and_stop() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let availability = AvailabilityManager::new(&[1]);
+        let mut engine =
+            TransactionEngine::new(vec![TxPoint::Authorized], vec![TxPoint::Authorized], &[1]);
+        let control = RemoteControl::new(false);
+        let request = RequestStartTransactionRequest {
+            custom_data: None,
+            evse_id: None,
+            group_id_token: None,
+            id_token: IdTokenType::new("TOKEN1".to_string(), "ISO14443".to_string()),
+            remote_start_id: 7,
+            charging_profile: None,
+        };
+        let (response, start, event) =
+            request_start_transaction(&control, &availability, &mut engine, &request, now);
+        assert_eq!(response.status, RequestStartStopStatusEnumType::Accepted);
+        assert_eq!(start.unwrap().evse_id, 1);
+        let event = event.unwrap();
+        assert_eq!(event.trigger_reason, TriggerReason::RemoteStart);
+        assert_eq!(response.transaction_id, Some(event.transaction_id.clone()));
+
+        let invalid = RequestStartTransactionRequest {
+            charging_profile: Some(ChargingProfileType::new(
+                1,
+                0,
+                ChargingProfilePurposeEnumType::TxDefaultProfile,
+                ChargingProfileKindEnumType::Absolute,
+                vec![],
+            )),
+            ..request
+        };
+        let (response, start, _) =
+            request_start_transaction(&control, &availability, &mut engine, &invalid, now);
+        assert_eq!(response.status, RequestStartStopStatusEnumType::Rejected);
+        assert_eq!(start, None);
+
+        let request = RequestStopTransactionRequest {
+            custom_data: None,
+            transaction_id: event.transaction_id,
+        };
+        let (response, ended) = request_stop_transaction(&mut engine, &request, now);
+        assert_eq!(response.status, RequestStartStopStatusEnumType::Accepted);
+        assert_eq!(ended.unwrap().event_type, TransactionEventType::Ended);
+        let (response, _) = request_stop_transaction(&mut engine, &request, now);
+        assert_eq!(response.status, RequestStartStopStatusEnumType::Rejected);
+    }
+
+    #[test]
+    fn handles_trigger_message() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let availability = AvailabilityManager::new(&[1]);
+        let mut registration = RegistrationManager::new(BootReason::PowerUp);
+        registration.registered(RegistrationStatus::Accepted, Duration::seconds(300), now);
+        let mut control = RemoteControl::new(false);
+
+        let request = TriggerMessageRequest::new(MessageTriggerEnumType::CustomTrigger)
+            .with_custom_trigger("DataTransfer".to_string())
+            .with_evse(EVSEType::new(1));
+        let response = trigger_message(&mut control, &mut registration, &availability, &request);
+        assert_eq!(response.status, TriggerMessageStatusEnumType::Accepted);
+        let triggered = control.next_triggered().unwrap();
+        assert_eq!(
+            triggered.message,
+            MessageTrigger::CustomTrigger("DataTransfer".to_string())
+        );
+        assert_eq!(triggered.evse_id, Some(1));
+
+        let request = TriggerMessageRequest::new(MessageTriggerEnumType::CustomTrigger);
+        let response = trigger_message(&mut control, &mut registration, &availability, &request);
+        assert_eq!(response.status, TriggerMessageStatusEnumType::Rejected);
+        let request = TriggerMessageRequest::new(MessageTriggerEnumType::BootNotification);
+        let response = trigger_message(&mut control, &mut registration, &availability, &request);
+        assert_eq!(response.status, TriggerMessageStatusEnumType::Rejected);
+    }
+}
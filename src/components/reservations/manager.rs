@@ -0,0 +1,433 @@
+use chrono::{DateTime, Utc};
+
+use super::{Evse, EvseStatus, IdToken, Reservation, ReservationCheck, ReserveStatus};
+
+/// Reservations of a Charging Station and the EVSEs they are made on.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReservationManager {
+    evses: Vec<Evse>,
+    non_evse_specific: bool,
+    reservations: Vec<Reservation>,
+}
+
+impl ReservationManager {
+    /// Creates a manager without reservations.
+    ///
+    /// `non_evse_specific` enables reservations for any EVSE, `ReserveConnectorZeroSupported`
+    /// in 1.6 and `ReservationCtrlr.NonEvseSpecific` in 2.x.
+    pub fn new(evses: Vec<Evse>, non_evse_specific: bool) -> Self {
+        Self {
+            evses,
+            non_evse_specific,
+            reservations: Vec::new(),
+        }
+    }
+
+    /// The EVSEs that can be reserved.
+    pub fn evses(&self) -> &[Evse] {
+        &self.evses
+    }
+
+    /// All reservations, including expired ones not removed yet.
+    pub fn reservations(&self) -> &[Reservation] {
+        &self.reservations
+    }
+
+    /// Reservation `id`.
+    pub fn get(&self, id: i32) -> Option<&Reservation> {
+        self.reservations
+            .iter()
+            .find(|reservation| reservation.id == id)
+    }
+
+    /// Updates the status of EVSE `evse_id`, returning whether the EVSE is known.
+    pub fn set_status(&mut self, evse_id: i32, status: EvseStatus) -> bool {
+        match self.evses.iter_mut().find(|evse| evse.id == evse_id) {
+            Some(evse) => {
+                evse.status = status;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Makes `reservation`, replacing the reservation with the same id.
+    pub fn reserve(&mut self, reservation: Reservation, now: DateTime<Utc>) -> ReserveStatus {
+        let status = self.reserve_status(&reservation, now);
+        if status == ReserveStatus::Accepted {
+            self.reservations.retain(|other| other.id != reservation.id);
+            self.reservations.push(reservation);
+        }
+        status
+    }
+
+    /// Cancels reservation `id`, returning whether it existed.
+    pub fn cancel(&mut self, id: i32) -> bool {
+        self.take(id).is_some()
+    }
+
+    /// Whether `id_token`, member of `group_id`, may use EVSE `evse_id` at `now`.
+    ///
+    /// A reservation for any EVSE matching the id token is only used on an EVSE that does
+    /// not have to be kept free for the reservations of others.
+    pub fn authorize(
+        &self,
+        evse_id: i32,
+        id_token: &IdToken,
+        group_id: Option<&IdToken>,
+        now: DateTime<Utc>,
+    ) -> ReservationCheck {
+        let Some(evse) = self.evses.iter().find(|evse| evse.id == evse_id) else {
+            return ReservationCheck::NotReserved;
+        };
+        let active = self.active(now);
+        if let Some(reservation) = active.iter().find(|r| r.evse_id == Some(evse_id)) {
+            return match reservation.matches(id_token, group_id) {
+                true => ReservationCheck::Reserved(reservation.id),
+                false => ReservationCheck::Blocked,
+            };
+        }
+        let mut own = active.iter().filter(|reservation| {
+            reservation.evse_id.is_none()
+                && evse.supports(reservation.connector_type.as_deref())
+                && reservation.matches(id_token, group_id)
+        });
+        let usable = own.find(|reservation| {
+            let others: Vec<_> = active
+                .iter()
+                .filter(|other| other.id != reservation.id)
+                .copied()
+                .collect();
+            self.can_honour(&others, Some(evse_id))
+        });
+        match usable {
+            Some(reservation) => ReservationCheck::Reserved(reservation.id),
+            None if !self.can_honour(&active, Some(evse_id)) => ReservationCheck::Blocked,
+            None => ReservationCheck::NotReserved,
+        }
+    }
+
+    /// Whether EVSE `evse_id` is to be reported as `Reserved` at `now`: it has a reservation
+    /// of its own, or it must be kept free for reservations for any EVSE.
+    pub fn is_reserved(&self, evse_id: i32, now: DateTime<Utc>) -> bool {
+        let active = self.active(now);
+        active.iter().any(|r| r.evse_id == Some(evse_id))
+            || self
+                .evses
+                .iter()
+                .any(|evse| evse.id == evse_id && evse.status == EvseStatus::Available)
+                && active.iter().any(|r| r.evse_id.is_none())
+                && !self.can_honour(&active, Some(evse_id))
+    }
+
+    /// Removes reservation `id` because it is used by a transaction.
+    pub fn take(&mut self, id: i32) -> Option<Reservation> {
+        let index = self.reservations.iter().position(|r| r.id == id)?;
+        Some(self.reservations.remove(index))
+    }
+
+    /// Removes the reservations expired at `now`.
+    pub fn remove_expired(&mut self, now: DateTime<Utc>) -> Vec<Reservation> {
+        self.remove(|reservation| reservation.is_expired(now))
+    }
+
+    /// Removes the reservations of EVSE `evse_id`, e.g. when it is set unavailable.
+    pub fn remove_for_evse(&mut self, evse_id: i32) -> Vec<Reservation> {
+        self.remove(|reservation| reservation.evse_id == Some(evse_id))
+    }
+
+    fn remove(&mut self, remove: impl Fn(&Reservation) -> bool) -> Vec<Reservation> {
+        let (removed, kept) = std::mem::take(&mut self.reservations)
+            .into_iter()
+            .partition(|reservation| remove(reservation));
+        self.reservations = kept;
+        removed
+    }
+
+    fn active(&self, now: DateTime<Utc>) -> Vec<&Reservation> {
+        self.reservations
+            .iter()
+            .filter(|reservation| !reservation.is_expired(now))
+            .collect()
+    }
+
+    fn reserve_status(&self, reservation: &Reservation, now: DateTime<Utc>) -> ReserveStatus {
+        if reservation.is_expired(now) {
+            return ReserveStatus::Rejected;
+        }
+        let mut others: Vec<_> = self
+            .active(now)
+            .into_iter()
+            .filter(|other| other.id != reservation.id)
+            .collect();
+        let connector_type = reservation.connector_type.as_deref();
+        match reservation.evse_id {
+            Some(evse_id) => {
+                let Some(evse) = self.evses.iter().find(|evse| evse.id == evse_id) else {
+                    return ReserveStatus::Rejected;
+                };
+                if !evse.supports(connector_type) {
+                    return ReserveStatus::Rejected;
+                }
+                match evse.status {
+                    EvseStatus::Faulted => ReserveStatus::Faulted,
+                    EvseStatus::Unavailable => ReserveStatus::Unavailable,
+                    EvseStatus::Occupied => ReserveStatus::Occupied,
+                    EvseStatus::Available
+                        if others.iter().any(|other| other.evse_id == Some(evse_id))
+                            || !self.can_honour(&others, Some(evse_id)) =>
+                    {
+                        ReserveStatus::Occupied
+                    }
+                    EvseStatus::Available => ReserveStatus::Accepted,
+                }
+            }
+            None if !self.non_evse_specific => ReserveStatus::Rejected,
+            None => {
+                let candidates: Vec<_> = self
+                    .evses
+                    .iter()
+                    .filter(|evse| evse.supports(connector_type))
+                    .collect();
+                others.push(reservation);
+                if candidates.is_empty() {
+                    ReserveStatus::Rejected
+                } else if self.can_honour(&others, None) {
+                    ReserveStatus::Accepted
+                } else if candidates
+                    .iter()
+                    .all(|evse| evse.status == EvseStatus::Faulted)
+                {
+                    ReserveStatus::Faulted
+                } else if candidates.iter().all(|evse| {
+                    matches!(evse.status, EvseStatus::Faulted | EvseStatus::Unavailable)
+                }) {
+                    ReserveStatus::Unavailable
+                } else {
+                    ReserveStatus::Occupied
+                }
+            }
+        }
+    }
+
+    /// Whether each of the `reservations` for any EVSE can get an available EVSE of its own,
+    /// one without reservation for a specific EVSE, when EVSE `taken` is used by someone else.
+    fn can_honour(&self, reservations: &[&Reservation], taken: Option<i32>) -> bool {
+        let free: Vec<_> = self
+            .evses
+            .iter()
+            .filter(|evse| {
+                evse.status == EvseStatus::Available
+                    && Some(evse.id) != taken
+                    && !reservations.iter().any(|r| r.evse_id == Some(evse.id))
+            })
+            .collect();
+        let any: Vec<_> = reservations
+            .iter()
+            .filter(|reservation| reservation.evse_id.is_none())
+            .copied()
+            .collect();
+        let mut assigned = vec![None; free.len()];
+        (0..any.len()).all(|index| {
+            let mut visited = vec![false; free.len()];
+            assign(index, &any, &free, &mut visited, &mut assigned)
+        })
+    }
+}
+
+/// Finds a free EVSE for reservation `index`, moving earlier reservations to other EVSEs
+/// when needed (augmenting path of a bipartite matching).
+fn assign(
+    index: usize,
+    reservations: &[&Reservation],
+    free: &[&Evse],
+    visited: &mut [bool],
+    assigned: &mut [Option<usize>],
+) -> bool {
+    let connector_type = reservations[index].connector_type.as_deref();
+    for (evse, candidate) in free.iter().enumerate() {
+        if visited[evse] || !candidate.supports(connector_type) {
+            continue;
+        }
+        visited[evse] = true;
+        if assigned[evse].is_none_or(|other| assign(other, reservations, free, visited, assigned)) {
+            assigned[evse] = Some(index);
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn reservation(id: i32, id_token: &str) -> Reservation {
+        Reservation::new(id, now() + Duration::minutes(15), IdToken::new(id_token))
+    }
+
+    fn manager(count: i32) -> ReservationManager {
+        ReservationManager::new((1..=count).map(Evse::new).collect(), true)
+    }
+
+    #[test]
+    fn reserves_specific_evses() {
+        let mut manager = manager(3);
+        manager.set_status(2, EvseStatus::Faulted);
+        manager.set_status(3, EvseStatus::Unavailable);
+
+        let status = manager.reserve(reservation(1, "A").with_evse_id(1), now());
+        assert_eq!(status, ReserveStatus::Accepted);
+        let status = manager.reserve(reservation(2, "B").with_evse_id(1), now());
+        assert_eq!(status, ReserveStatus::Occupied);
+        let status = manager.reserve(reservation(2, "B").with_evse_id(2), now());
+        assert_eq!(status, ReserveStatus::Faulted);
+        let status = manager.reserve(reservation(2, "B").with_evse_id(3), now());
+        assert_eq!(status, ReserveStatus::Unavailable);
+        let status = manager.reserve(reservation(2, "B").with_evse_id(4), now());
+        assert_eq!(status, ReserveStatus::Rejected);
+        let mut expired = reservation(2, "B").with_evse_id(1);
+        expired.expiry = now();
+        assert_eq!(manager.reserve(expired, now()), ReserveStatus::Rejected);
+
+        // the same id replaces the reservation, moving it to another EVSE
+        manager.set_status(2, EvseStatus::Available);
+        let status = manager.reserve(reservation(1, "A").with_evse_id(2), now());
+        assert_eq!(status, ReserveStatus::Accepted);
+        assert_eq!(manager.reservations().len(), 1);
+        assert!(!manager.is_reserved(1, now()));
+        assert!(manager.is_reserved(2, now()));
+
+        manager.set_status(1, EvseStatus::Occupied);
+        let status = manager.reserve(reservation(2, "B").with_evse_id(1), now());
+        assert_eq!(status, ReserveStatus::Occupied);
+    }
+
+    #[test]
+    fn keeps_an_evse_free_for_reservations_for_any_evse() {
+        let mut manager = manager(2);
+        assert_eq!(
+            manager.reserve(reservation(1, "A"), now()),
+            ReserveStatus::Accepted
+        );
+        assert!(!manager.is_reserved(1, now()));
+
+        // reserving EVSE 1 leaves EVSE 2 for reservation 1
+        let status = manager.reserve(reservation(2, "B").with_evse_id(1), now());
+        assert_eq!(status, ReserveStatus::Accepted);
+        assert!(manager.is_reserved(2, now()));
+        let status = manager.reserve(reservation(3, "C").with_evse_id(2), now());
+        assert_eq!(status, ReserveStatus::Occupied);
+        assert_eq!(
+            manager.reserve(reservation(3, "C"), now()),
+            ReserveStatus::Occupied
+        );
+
+        let stranger = IdToken::new("D");
+        assert_eq!(
+            manager.authorize(2, &stranger, None, now()),
+            ReservationCheck::Blocked
+        );
+        assert_eq!(
+            manager.authorize(2, &IdToken::new("a"), None, now()),
+            ReservationCheck::Reserved(1)
+        );
+
+        manager.set_status(2, EvseStatus::Faulted);
+        manager.cancel(2);
+        assert_eq!(
+            manager.reserve(reservation(3, "C"), now()),
+            ReserveStatus::Occupied
+        );
+        manager.set_status(1, EvseStatus::Unavailable);
+        assert_eq!(
+            manager.reserve(reservation(3, "C"), now()),
+            ReserveStatus::Unavailable
+        );
+        manager.set_status(1, EvseStatus::Faulted);
+        assert_eq!(
+            manager.reserve(reservation(3, "C"), now()),
+            ReserveStatus::Faulted
+        );
+
+        let mut manager = ReservationManager::new(vec![Evse::new(1)], false);
+        assert_eq!(
+            manager.reserve(reservation(1, "A"), now()),
+            ReserveStatus::Rejected
+        );
+    }
+
+    #[test]
+    fn matches_connector_types() {
+        let evses = vec![
+            Evse::new(1).with_connector_types(vec!["cCCS2".to_string(), "sType2".to_string()]),
+            Evse::new(2).with_connector_types(vec!["cCCS2".to_string()]),
+        ];
+        let mut manager = ReservationManager::new(evses, true);
+        let mut type2 = reservation(1, "A");
+        type2.connector_type = Some("sType2".to_string());
+        let mut ccs = reservation(2, "B");
+        ccs.connector_type = Some("cCCS2".to_string());
+        let mut chademo = reservation(3, "C").with_evse_id(2);
+        chademo.connector_type = Some("sChaoJi".to_string());
+
+        // the reservation for any CCS EVSE moves to EVSE 2, leaving EVSE 1 for Type 2
+        assert_eq!(manager.reserve(ccs, now()), ReserveStatus::Accepted);
+        assert_eq!(manager.reserve(type2, now()), ReserveStatus::Accepted);
+        assert_eq!(manager.reserve(chademo, now()), ReserveStatus::Rejected);
+        assert_eq!(
+            manager.authorize(1, &IdToken::new("B"), None, now()),
+            ReservationCheck::Blocked
+        );
+        assert_eq!(
+            manager.authorize(2, &IdToken::new("B"), None, now()),
+            ReservationCheck::Reserved(2)
+        );
+    }
+
+    #[test]
+    fn authorizes_by_group_and_expires() {
+        let mut manager = manager(2);
+        let group = IdToken::new("FLEET");
+        let reserved = reservation(1, "A")
+            .with_evse_id(1)
+            .with_group_id(group.clone());
+        manager.reserve(reserved, now());
+        let mut later = reservation(2, "B").with_evse_id(2);
+        later.expiry = now() + Duration::hours(1);
+        manager.reserve(later, now());
+
+        let member = IdToken::new("M");
+        assert_eq!(
+            manager.authorize(1, &member, Some(&group), now()),
+            ReservationCheck::Reserved(1)
+        );
+        assert_eq!(
+            manager.authorize(1, &member, None, now()),
+            ReservationCheck::Blocked
+        );
+        assert_eq!(
+            manager.authorize(3, &member, None, now()),
+            ReservationCheck::NotReserved
+        );
+
+        let expiry = now() + Duration::minutes(15);
+        assert_eq!(
+            manager.authorize(1, &member, None, expiry),
+            ReservationCheck::NotReserved
+        );
+        let expired = manager.remove_expired(expiry);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].id, 1);
+
+        assert_eq!(manager.take(2).map(|r| r.id), Some(2));
+        assert!(!manager.cancel(2));
+        assert!(manager.reservations().is_empty());
+    }
+}
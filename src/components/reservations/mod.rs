@@ -0,0 +1,174 @@
+//! # Reservations
+//!
+//! Reservations made by `ReserveNow` and cancelled by `CancelReservation`, shared by OCPP 1.6
+//! and 2.x.
+//!
+//! A reservation is either for one EVSE (a connector in 1.6) or, for connector 0 in 1.6 and
+//! without `evseId` in 2.x, for any EVSE of the Charging Station. [`ReservationManager`] only
+//! accepts a reservation when every reservation can still be honoured: a reservation for any
+//! EVSE keeps an available EVSE free until it is used or expires, so a reservation for a
+//! specific EVSE or a transaction of another user that would take that EVSE is refused.
+//!
+//! When an id token is presented, [`ReservationManager::authorize`] tells whether it may use
+//! an EVSE: through one of its reservations, matched on the id token or on its group
+//! (`parentIdTag` in 1.6, `groupIdToken` in 2.x), without reservation, or not at all.
+
+use chrono::{DateTime, Utc};
+
+use super::authorization::IdToken;
+
+mod manager;
+
+#[cfg(feature = "v1_6")]
+pub mod v1_6;
+#[cfg(feature = "v2_0_1")]
+pub mod v2_0_1;
+#[cfg(feature = "v2_1")]
+pub mod v2_1;
+
+pub use manager::ReservationManager;
+
+/// Version independent `ReserveNowRequest`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reservation {
+    /// Identifies the reservation.
+    pub id: i32,
+    /// When the reservation expires.
+    pub expiry: DateTime<Utc>,
+    /// The id token the reservation is for.
+    pub id_token: IdToken,
+    /// The group (parent) id token whose members may also use the reservation.
+    pub group_id: Option<IdToken>,
+    /// The reserved EVSE (connector in 1.6), any EVSE when absent.
+    pub evse_id: Option<i32>,
+    /// Type of connector the reserved EVSE must have, any type when absent (2.x).
+    pub connector_type: Option<String>,
+}
+
+impl Reservation {
+    /// Creates a reservation for any EVSE.
+    pub fn new(id: i32, expiry: DateTime<Utc>, id_token: IdToken) -> Self {
+        Self {
+            id,
+            expiry,
+            id_token,
+            group_id: None,
+            evse_id: None,
+            connector_type: None,
+        }
+    }
+
+    /// Reserves a specific EVSE.
+    pub fn with_evse_id(mut self, evse_id: i32) -> Self {
+        self.evse_id = Some(evse_id);
+        self
+    }
+
+    /// Sets the group (parent) id token.
+    pub fn with_group_id(mut self, group_id: IdToken) -> Self {
+        self.group_id = Some(group_id);
+        self
+    }
+
+    /// Returns `true` when the reservation has expired at `now`.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expiry <= now
+    }
+
+    /// Whether `id_token`, member of `group_id`, may use the reservation.
+    pub fn matches(&self, id_token: &IdToken, group_id: Option<&IdToken>) -> bool {
+        self.id_token == *id_token
+            || self
+                .group_id
+                .as_ref()
+                .is_some_and(|reserved| group_id == Some(reserved))
+    }
+}
+
+/// Status of an EVSE, leaving out reservations which are tracked by the
+/// [`ReservationManager`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EvseStatus {
+    /// Free for a new user.
+    Available,
+    /// In use, e.g. an EV is connected or a transaction is ongoing.
+    Occupied,
+    /// In an error state.
+    Faulted,
+    /// Set unavailable.
+    Unavailable,
+}
+
+/// An EVSE (a connector in 1.6) that can be reserved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Evse {
+    /// Id of the EVSE, > 0.
+    pub id: i32,
+    /// Current status.
+    pub status: EvseStatus,
+    /// Types of its connectors, not checked when empty.
+    pub connector_types: Vec<String>,
+}
+
+impl Evse {
+    /// Creates an available EVSE.
+    pub fn new(id: i32) -> Self {
+        Self {
+            id,
+            status: EvseStatus::Available,
+            connector_types: Vec::new(),
+        }
+    }
+
+    /// Sets the types of the connectors.
+    pub fn with_connector_types(mut self, connector_types: Vec<String>) -> Self {
+        self.connector_types = connector_types;
+        self
+    }
+
+    /// Whether the EVSE has a connector of `connector_type`.
+    pub fn supports(&self, connector_type: Option<&str>) -> bool {
+        connector_type.is_none_or(|connector_type| {
+            self.connector_types.is_empty()
+                || self
+                    .connector_types
+                    .iter()
+                    .any(|kind| kind == connector_type)
+        })
+    }
+}
+
+/// Result of a reservation, `ReservationStatus` in 1.6 and `ReserveNowStatusEnumType` in 2.x.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReserveStatus {
+    /// The reservation has been made.
+    Accepted,
+    /// The EVSE, or every EVSE that could be reserved, is faulted.
+    Faulted,
+    /// The EVSE, or every EVSE that could be reserved, is occupied or reserved.
+    Occupied,
+    /// The reservation is not possible, e.g. an unknown EVSE or an expiry in the past.
+    Rejected,
+    /// The EVSE, or every EVSE that could be reserved, is unavailable.
+    Unavailable,
+}
+
+/// Whether an id token may use an EVSE, see [`ReservationManager::authorize`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReservationCheck {
+    /// The EVSE may be used without a reservation.
+    NotReserved,
+    /// The EVSE may be used through the reservation with this id.
+    Reserved(i32),
+    /// The EVSE is reserved for another id token.
+    Blocked,
+}
+
+/// Why a reservation ended without being used, `ReservationUpdateStatusEnumType` in 2.x.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReservationUpdate {
+    /// The reservation expired.
+    Expired,
+    /// The reservation was removed because its EVSE can no longer be used.
+    Removed,
+}
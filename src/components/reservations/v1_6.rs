@@ -0,0 +1,143 @@
+//! OCPP 1.6 messages for the [`ReservationManager`].
+//!
+//! In OCPP 1.6 connectors are reserved. Connector 0 reserves any connector of the Charge
+//! Point.
+
+use chrono::{DateTime, Utc};
+
+use crate::v1_6::{
+    messages::{
+        cancel_reservation::{CancelReservationRequest, CancelReservationResponse},
+        reserve_now::{ReserveNowRequest, ReserveNowResponse},
+    },
+    types::{CancelReservationStatus, ChargePointStatus, ReservationStatus},
+};
+
+use super::{EvseStatus, IdToken, Reservation, ReservationManager, ReserveStatus};
+
+impl From<&ReserveNowRequest> for Reservation {
+    fn from(request: &ReserveNowRequest) -> Self {
+        Self {
+            id: request.reservation_id,
+            expiry: request.expiry_date,
+            id_token: IdToken::new(request.id_tag.as_str()),
+            group_id: request.parent_id_tag.as_deref().map(IdToken::new),
+            evse_id: match request.connector_id {
+                0 => None,
+                // out of range connectors are rejected as unknown
+                connector_id => Some(i32::try_from(connector_id).unwrap_or(i32::MAX)),
+            },
+            connector_type: None,
+        }
+    }
+}
+
+impl From<ReserveStatus> for ReservationStatus {
+    fn from(status: ReserveStatus) -> Self {
+        match status {
+            ReserveStatus::Accepted => Self::Accepted,
+            ReserveStatus::Faulted => Self::Faulted,
+            ReserveStatus::Occupied => Self::Occupied,
+            ReserveStatus::Rejected => Self::Rejected,
+            ReserveStatus::Unavailable => Self::Unavailable,
+        }
+    }
+}
+
+impl From<&ChargePointStatus> for EvseStatus {
+    /// `Reserved` connectors are available, their reservations are kept by the manager.
+    fn from(status: &ChargePointStatus) -> Self {
+        match status {
+            ChargePointStatus::Available | ChargePointStatus::Reserved => Self::Available,
+            ChargePointStatus::Preparing
+            | ChargePointStatus::Charging
+            | ChargePointStatus::SuspendedEVSE
+            | ChargePointStatus::SuspendedEV
+            | ChargePointStatus::Finishing => Self::Occupied,
+            ChargePointStatus::Unavailable => Self::Unavailable,
+            ChargePointStatus::Faulted => Self::Faulted,
+        }
+    }
+}
+
+/// Handles a `ReserveNowRequest`.
+pub fn reserve_now(
+    manager: &mut ReservationManager,
+    request: &ReserveNowRequest,
+    now: DateTime<Utc>,
+) -> ReserveNowResponse {
+    ReserveNowResponse {
+        status: manager.reserve(request.into(), now).into(),
+    }
+}
+
+/// Handles a `CancelReservationRequest`.
+pub fn cancel_reservation(
+    manager: &mut ReservationManager,
+    request: &CancelReservationRequest,
+) -> CancelReservationResponse {
+    CancelReservationResponse {
+        status: match manager.cancel(request.reservation_id) {
+            true => CancelReservationStatus::Accepted,
+            false => CancelReservationStatus::Rejected,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+    use crate::components::reservations::{Evse, ReservationCheck};
+
+    #[test]
+    fn reserve_and_cancel() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut manager = ReservationManager::new(vec![Evse::new(1), Evse::new(2)], true);
+        manager.set_status(2, (&ChargePointStatus::SuspendedEV).into());
+
+        let request = ReserveNowRequest {
+            connector_id: 0,
+            expiry_date: now + Duration::minutes(30),
+            id_tag: "TAG1".to_string(),
+            parent_id_tag: Some("PARENT".to_string()),
+            reservation_id: 11,
+        };
+        let response = reserve_now(&mut manager, &request, now);
+        assert_eq!(response.status, ReservationStatus::Accepted);
+
+        let request = ReserveNowRequest {
+            connector_id: 1,
+            reservation_id: 12,
+            ..request
+        };
+        let response = reserve_now(&mut manager, &request, now);
+        assert_eq!(response.status, ReservationStatus::Occupied);
+        let request = ReserveNowRequest {
+            connector_id: 2,
+            ..request
+        };
+        let response = reserve_now(&mut manager, &request, now);
+        assert_eq!(response.status, ReservationStatus::Occupied);
+        let request = ReserveNowRequest {
+            connector_id: 3,
+            ..request
+        };
+        let response = reserve_now(&mut manager, &request, now);
+        assert_eq!(response.status, ReservationStatus::Rejected);
+
+        let other = IdToken::new("TAG2");
+        let parent = IdToken::new("parent");
+        assert_eq!(
+            manager.authorize(1, &other, Some(&parent), now),
+            ReservationCheck::Reserved(11)
+        );
+
+        let request = CancelReservationRequest { reservation_id: 11 };
+        let response = cancel_reservation(&mut manager, &request);
+        assert_eq!(response.status, CancelReservationStatus::Accepted);
+        let response = cancel_reservation(&mut manager, &request);
+        assert_eq!(response.status, CancelReservationStatus::Rejected);
+    }
+}
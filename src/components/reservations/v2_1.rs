@@ -0,0 +1,209 @@
+//! OCPP 2.1 messages for the [`ReservationManager`].
+
+use chrono::{DateTime, Utc};
+
+use crate::v2_1::{
+    enumerations::{
+        CancelReservationStatusEnumType, ConnectorEnumType, ConnectorStatusEnumType,
+        ReserveNowStatusEnumType,
+    },
+    messages::{
+        cancel_reservation::{CancelReservationRequest, CancelReservationResponse},
+        reservation_status_update::{
+            ReservationStatusUpdateRequest, ReservationUpdateStatusEnumType,
+        },
+        reserve_now::{ReserveNowRequest, ReserveNowResponse},
+    },
+};
+
+use super::{EvseStatus, Reservation, ReservationManager, ReservationUpdate, ReserveStatus};
+
+/// Name of `connector_type` in OCPP, e.g. `cCCS2`.
+fn connector_type_name(connector_type: &ConnectorEnumType) -> String {
+    serde_json::to_value(connector_type)
+        .ok()
+        .and_then(|name| name.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+impl From<&ReserveNowRequest> for Reservation {
+    fn from(request: &ReserveNowRequest) -> Self {
+        Self {
+            id: request.id,
+            expiry: request.expiry_date_time,
+            id_token: (&request.id_token).into(),
+            group_id: request.group_id_token.as_ref().map(Into::into),
+            evse_id: request.evse_id,
+            connector_type: request.connector_type.as_ref().map(connector_type_name),
+        }
+    }
+}
+
+impl From<ReserveStatus> for ReserveNowStatusEnumType {
+    fn from(status: ReserveStatus) -> Self {
+        match status {
+            ReserveStatus::Accepted => Self::Accepted,
+            ReserveStatus::Faulted => Self::Faulted,
+            ReserveStatus::Occupied => Self::Occupied,
+            ReserveStatus::Rejected => Self::Rejected,
+            ReserveStatus::Unavailable => Self::Unavailable,
+        }
+    }
+}
+
+impl From<ReservationUpdate> for ReservationUpdateStatusEnumType {
+    fn from(update: ReservationUpdate) -> Self {
+        match update {
+            ReservationUpdate::Expired => Self::Expired,
+            ReservationUpdate::Removed => Self::Removed,
+        }
+    }
+}
+
+impl From<&ConnectorStatusEnumType> for EvseStatus {
+    /// `Reserved` EVSEs are available, their reservations are kept by the manager.
+    fn from(status: &ConnectorStatusEnumType) -> Self {
+        match status {
+            ConnectorStatusEnumType::Available | ConnectorStatusEnumType::Reserved => {
+                Self::Available
+            }
+            ConnectorStatusEnumType::Occupied => Self::Occupied,
+            ConnectorStatusEnumType::Unavailable => Self::Unavailable,
+            ConnectorStatusEnumType::Faulted => Self::Faulted,
+        }
+    }
+}
+
+/// Handles a `ReserveNowRequest`.
+pub fn reserve_now(
+    manager: &mut ReservationManager,
+    request: &ReserveNowRequest,
+    now: DateTime<Utc>,
+) -> ReserveNowResponse {
+    ReserveNowResponse {
+        custom_data: None,
+        status: manager.reserve(request.into(), now).into(),
+        status_info: None,
+    }
+}
+
+/// Handles a `CancelReservationRequest`.
+pub fn cancel_reservation(
+    manager: &mut ReservationManager,
+    request: &CancelReservationRequest,
+) -> CancelReservationResponse {
+    CancelReservationResponse {
+        custom_data: None,
+        status: match manager.cancel(request.reservation_id) {
+            true => CancelReservationStatusEnumType::Accepted,
+            false => CancelReservationStatusEnumType::Rejected,
+        },
+        status_info: None,
+    }
+}
+
+/// Removes the reservations expired at `now`, returning a `ReservationStatusUpdateRequest`
+/// for each.
+pub fn expire_reservations(
+    manager: &mut ReservationManager,
+    now: DateTime<Utc>,
+) -> Vec<ReservationStatusUpdateRequest> {
+    status_updates(manager.remove_expired(now), ReservationUpdate::Expired)
+}
+
+/// Removes the reservations of EVSE `evse_id`, returning a `ReservationStatusUpdateRequest`
+/// for each.
+pub fn remove_reservations(
+    manager: &mut ReservationManager,
+    evse_id: i32,
+) -> Vec<ReservationStatusUpdateRequest> {
+    status_updates(manager.remove_for_evse(evse_id), ReservationUpdate::Removed)
+}
+
+fn status_updates(
+    reservations: Vec<Reservation>,
+    update: ReservationUpdate,
+) -> Vec<ReservationStatusUpdateRequest> {
+    reservations
+        .into_iter()
+        .map(|reservation| ReservationStatusUpdateRequest {
+            custom_data: None,
+            reservation_id: reservation.id,
+            reservation_update_status: update.into(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+    use crate::{
+        components::{
+            authorization::IdToken,
+            reservations::{Evse, ReservationCheck},
+        },
+        v2_1::{datatypes::IdTokenType, enumerations::connector::StandardConnectorEnumType},
+    };
+
+    fn id_token(id: &str) -> IdTokenType {
+        IdTokenType::new(id.to_string(), "ISO14443".to_string())
+    }
+
+    #[test]
+    fn reserve_expire_and_cancel() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let evses = vec![
+            Evse::new(1).with_connector_types(vec!["cCCS2".to_string()]),
+            Evse::new(2).with_connector_types(vec!["cCCS2".to_string()]),
+        ];
+        let mut manager = ReservationManager::new(evses, true);
+        manager.set_status(1, (&ConnectorStatusEnumType::Faulted).into());
+
+        let request = ReserveNowRequest {
+            custom_data: None,
+            id: 1,
+            expiry_date_time: now + Duration::minutes(10),
+            connector_type: Some(ConnectorEnumType::Standard(
+                StandardConnectorEnumType::CCCS2,
+            )),
+            id_token: id_token("AABBCC"),
+            evse_id: None,
+            group_id_token: None,
+        };
+        let response = reserve_now(&mut manager, &request, now);
+        assert_eq!(response.status, ReserveNowStatusEnumType::Accepted);
+        assert_eq!(
+            manager.get(1).unwrap().connector_type.as_deref(),
+            Some("cCCS2")
+        );
+
+        // EVSE 2 is the only EVSE left for reservation 1
+        let request = ReserveNowRequest {
+            id: 2,
+            id_token: id_token("DDEEFF"),
+            ..request
+        };
+        let response = reserve_now(&mut manager, &request, now);
+        assert_eq!(response.status, ReserveNowStatusEnumType::Occupied);
+        let stranger = IdToken::with_kind("DDEEFF", "ISO14443");
+        assert_eq!(
+            manager.authorize(2, &stranger, None, now),
+            ReservationCheck::Blocked
+        );
+        assert!(manager.is_reserved(2, now));
+
+        let updates = expire_reservations(&mut manager, now + Duration::minutes(10));
+        assert_eq!(updates.len(), 1);
+        assert_eq!(
+            updates[0].reservation_update_status,
+            ReservationUpdateStatusEnumType::Expired
+        );
+        assert!(remove_reservations(&mut manager, 2).is_empty());
+
+        let request = CancelReservationRequest::new(1);
+        let response = cancel_reservation(&mut manager, &request);
+        assert_eq!(response.status, CancelReservationStatusEnumType::Rejected);
+    }
+}
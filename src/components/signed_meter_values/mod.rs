@@ -0,0 +1,430 @@
+//! # Signed meter values
+//!
+//! Decoding and verification of the `signedMeterData` of a `SignedMeterValueType` in the
+//! Open Charge Metering Format (OCMF), available with the `ocmf` feature.
+//!
+//! German calibration law (Eichrecht) requires every charge detail record to be backed by
+//! meter readings signed by the meter. An OCMF document `OCMF|{payload}|{signature}` holds
+//! the readings and context of the meter as JSON and an ECDSA signature over the payload.
+//! [`SignedMeterData::parse`] decodes the document into an [`OcmfPayload`] with typed
+//! readings and [`SignedMeterData::verify`] checks the signature against the public key
+//! of the meter. [`charged_energy`] takes the energy of a transaction from its begin and
+//! end readings.
+//!
+//! Signatures with the `secp256r1` and `secp384r1` curves are supported. Other encoding
+//! methods, such as the binary EDL format of some Eichrecht data loggers, are rejected with
+//! [`SignedMeterValueError::UnsupportedEncoding`].
+
+use std::fmt;
+
+use base64ct::{Base64, Encoding};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use x509_cert::{
+    der::{asn1::ObjectIdentifier, oid::db::rfc5912, Decode, DecodePem},
+    spki::SubjectPublicKeyInfoOwned,
+};
+
+use crate::components::certificates::{verify_prehash, CertificateError, HashAlgorithm};
+
+mod payload;
+
+#[cfg(feature = "v2_0_1")]
+pub mod v2_0_1;
+#[cfg(feature = "v2_1")]
+pub mod v2_1;
+
+pub use payload::{
+    LossCompensation, MeterStatus, OcmfPayload, Pagination, PaginationKind, Reading, ReadingTime,
+    TimeStatus, TransactionEvent,
+};
+
+/// Encoding method of OCMF documents in a `SignedMeterValueType`.
+pub const OCMF: &str = "OCMF";
+
+/// Reason why signed meter data cannot be decoded or verified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignedMeterValueError {
+    /// The encoding method is not OCMF.
+    UnsupportedEncoding(String),
+    /// The document is malformed.
+    Malformed(String),
+    /// The signature algorithm or signature encoding is not supported.
+    UnsupportedAlgorithm(String),
+    /// No public key to verify the signature with.
+    MissingPublicKey,
+    /// The public key is malformed or does not fit the signature algorithm.
+    InvalidPublicKey,
+    /// The signature does not match the payload.
+    InvalidSignature,
+    /// Two documents do not belong to the same meter or transaction.
+    Inconsistent(String),
+}
+
+impl fmt::Display for SignedMeterValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedEncoding(encoding) => {
+                write!(f, "unsupported encoding method {encoding:?}")
+            }
+            Self::Malformed(error) => write!(f, "malformed signed meter data: {error}"),
+            Self::UnsupportedAlgorithm(algorithm) => {
+                write!(f, "unsupported signature algorithm {algorithm:?}")
+            }
+            Self::MissingPublicKey => write!(f, "missing public key"),
+            Self::InvalidPublicKey => write!(f, "invalid public key"),
+            Self::InvalidSignature => write!(f, "invalid signature"),
+            Self::Inconsistent(error) => write!(f, "inconsistent signed meter data: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for SignedMeterValueError {}
+
+/// Signature section of an OCMF document.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct OcmfSignature {
+    /// Signature algorithm, `SA`, `ECDSA-secp256r1-SHA256` when absent.
+    #[serde(rename = "SA", default = "default_algorithm")]
+    pub algorithm: String,
+    /// Encoding of the signature data, `SE`, `hex` or `base64`.
+    #[serde(rename = "SE", default = "default_encoding")]
+    pub encoding: String,
+    /// MIME type of the signature data, `SM`.
+    #[serde(rename = "SM", default = "default_mime_type")]
+    pub mime_type: String,
+    /// Signature data, `SD`.
+    #[serde(rename = "SD")]
+    pub data: String,
+}
+
+fn default_algorithm() -> String {
+    "ECDSA-secp256r1-SHA256".to_string()
+}
+
+fn default_encoding() -> String {
+    "hex".to_string()
+}
+
+fn default_mime_type() -> String {
+    "application/x-der".to_string()
+}
+
+/// Decoded OCMF document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedMeterData {
+    /// Readings and context of the meter.
+    pub payload: OcmfPayload,
+    /// Signature over the payload.
+    pub signature: OcmfSignature,
+    signed_payload: String,
+}
+
+impl SignedMeterData {
+    /// Decodes an OCMF document, as is or Base64 encoded.
+    pub fn parse(data: &str) -> Result<Self, SignedMeterValueError> {
+        let data = data.trim();
+        let decoded;
+        let document = match data.strip_prefix("OCMF|") {
+            Some(document) => document,
+            None => {
+                decoded = Base64::decode_vec(data)
+                    .ok()
+                    .and_then(|bytes| String::from_utf8(bytes).ok())
+                    .ok_or_else(|| malformed("missing OCMF header"))?;
+                decoded
+                    .strip_prefix("OCMF|")
+                    .ok_or_else(|| malformed("missing OCMF header"))?
+            }
+        };
+        let (payload, signature) = document
+            .rsplit_once('|')
+            .ok_or_else(|| malformed("missing signature section"))?;
+        Ok(Self {
+            payload: serde_json::from_str(payload).map_err(malformed)?,
+            signature: serde_json::from_str(signature).map_err(malformed)?,
+            signed_payload: payload.to_string(),
+        })
+    }
+
+    /// Payload section exactly as signed.
+    pub fn signed_payload(&self) -> &str {
+        &self.signed_payload
+    }
+
+    /// Verifies the signature over the payload with the `public_key` of the meter, a DER
+    /// encoded `SubjectPublicKeyInfo` in hex or Base64, or in PEM.
+    pub fn verify(&self, public_key: &str) -> Result<(), SignedMeterValueError> {
+        let unsupported =
+            || SignedMeterValueError::UnsupportedAlgorithm(self.signature.algorithm.clone());
+        let curve = match self.signature.algorithm.as_str() {
+            "ECDSA-secp256r1-SHA256" => rfc5912::SECP_256_R_1,
+            "ECDSA-secp384r1-SHA256" => rfc5912::SECP_384_R_1,
+            _ => return Err(unsupported()),
+        };
+        if self.signature.mime_type != "application/x-der" {
+            return Err(SignedMeterValueError::UnsupportedAlgorithm(
+                self.signature.mime_type.clone(),
+            ));
+        }
+        let signature = match self.signature.encoding.as_str() {
+            "hex" => decode_hex(&self.signature.data),
+            "base64" => Base64::decode_vec(&self.signature.data).ok(),
+            encoding => {
+                return Err(SignedMeterValueError::UnsupportedAlgorithm(
+                    encoding.to_string(),
+                ))
+            }
+        }
+        .ok_or_else(|| malformed("invalid signature data"))?;
+
+        let public_key = decode_public_key(public_key)?;
+        let key_curve = public_key
+            .algorithm
+            .parameters
+            .as_ref()
+            .and_then(|parameters| parameters.decode_as::<ObjectIdentifier>().ok());
+        if public_key.algorithm.oid != rfc5912::ID_EC_PUBLIC_KEY || key_curve != Some(curve) {
+            return Err(SignedMeterValueError::InvalidPublicKey);
+        }
+        let prehash = Sha256::digest(self.signed_payload.as_bytes());
+        verify_prehash(&public_key, HashAlgorithm::Sha256, &prehash, &signature).map_err(|error| {
+            match error {
+                CertificateError::UnsupportedAlgorithm => unsupported(),
+                _ => SignedMeterValueError::InvalidSignature,
+            }
+        })
+    }
+}
+
+/// Decodes the `signed_meter_data` of a signed meter value with the given
+/// `encoding_method` and verifies its signature with `public_key`.
+///
+/// A public key sent along with the meter value only proves that the data was not altered
+/// in transit. For calibration law compliance it must match the key registered for the
+/// meter.
+pub fn verify_signed_meter_data(
+    signed_meter_data: &str,
+    encoding_method: &str,
+    public_key: Option<&str>,
+) -> Result<SignedMeterData, SignedMeterValueError> {
+    if !encoding_method.eq_ignore_ascii_case(OCMF) {
+        return Err(SignedMeterValueError::UnsupportedEncoding(
+            encoding_method.to_string(),
+        ));
+    }
+    let public_key = public_key
+        .filter(|public_key| !public_key.trim().is_empty())
+        .ok_or(SignedMeterValueError::MissingPublicKey)?;
+    let data = SignedMeterData::parse(signed_meter_data)?;
+    data.verify(public_key)?;
+    Ok(data)
+}
+
+/// Energy in Wh charged between the first energy reading of `begin` and the last energy
+/// reading of `end`, which may be the same document.
+///
+/// Both documents must come from the same meter, in order, and the readings must be valid
+/// readings of the same register.
+pub fn charged_energy(
+    begin: &OcmfPayload,
+    end: &OcmfPayload,
+) -> Result<Decimal, SignedMeterValueError> {
+    let inconsistent = |error: &str| SignedMeterValueError::Inconsistent(error.to_string());
+    if begin.meter_serial != end.meter_serial {
+        return Err(inconsistent("different meters"));
+    }
+    if begin.pagination.kind == end.pagination.kind
+        && begin.pagination.number > end.pagination.number
+    {
+        return Err(inconsistent("pagination out of order"));
+    }
+    let (first, start) = begin
+        .energy_readings()
+        .next()
+        .ok_or_else(|| inconsistent("no energy reading"))?;
+    let (last, stop) = end
+        .energy_readings()
+        .last()
+        .ok_or_else(|| inconsistent("no energy reading"))?;
+    if !first.is_valid() || !last.is_valid() {
+        return Err(inconsistent("meter error"));
+    }
+    if first.identifier != last.identifier {
+        return Err(inconsistent("different registers"));
+    }
+    if last.time.time < first.time.time || stop < start {
+        return Err(inconsistent("readings out of order"));
+    }
+    Ok(stop - start)
+}
+
+fn decode_public_key(public_key: &str) -> Result<SubjectPublicKeyInfoOwned, SignedMeterValueError> {
+    let public_key = public_key.trim();
+    if public_key.starts_with("-----BEGIN") {
+        return SubjectPublicKeyInfoOwned::from_pem(public_key)
+            .map_err(|_| SignedMeterValueError::InvalidPublicKey);
+    }
+    let der = decode_hex(public_key)
+        .or_else(|| Base64::decode_vec(public_key).ok())
+        .ok_or(SignedMeterValueError::InvalidPublicKey)?;
+    SubjectPublicKeyInfoOwned::from_der(&der).map_err(|_| SignedMeterValueError::InvalidPublicKey)
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn malformed(error: impl fmt::Display) -> SignedMeterValueError {
+    SignedMeterValueError::Malformed(error.to_string())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use chrono::{TimeZone, Utc};
+    use p256::{
+        ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey},
+        pkcs8::EncodePublicKey,
+    };
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    pub(crate) const BEGIN: &str = r#"{"FV":"1.0","GI":"ABL SBC-301","GS":"808829900001","GV":"1.4p3","PG":"T12","MV":"Phoenix Contact","MM":"EEM-350-D-MCB","MS":"BQ27400330016","MF":"1.0","IS":true,"IL":"VERIFIED","IF":["RFID_PLAIN","OCPP_RS_TLS"],"IT":"ISO14443","ID":"1F2D3A4F5506C7","RD":[{"TM":"2024-07-24T13:22:04,000+0200 S","TX":"B","RV":2935.6,"RI":"1-b:1.8.0","RU":"kWh","RT":"AC","EF":"","ST":"G"}]}"#;
+    pub(crate) const END: &str = r#"{"FV":"1.0","GI":"ABL SBC-301","GS":"808829900001","GV":"1.4p3","PG":"T13","MV":"Phoenix Contact","MM":"EEM-350-D-MCB","MS":"BQ27400330016","MF":"1.0","IS":true,"IL":"VERIFIED","IF":["RFID_PLAIN","OCPP_RS_TLS"],"IT":"ISO14443","ID":"1F2D3A4F5506C7","RD":[{"TM":"2024-07-24T15:09:36,000+0200 S","TX":"E","RV":2947.115,"RI":"1-b:1.8.0","RU":"kWh","RT":"AC","EF":"","ST":"G"}]}"#;
+
+    pub(crate) fn signing_key() -> SigningKey {
+        SigningKey::from_slice(&[7; 32]).unwrap()
+    }
+
+    /// Public key of [`signing_key`] as hex encoded DER.
+    pub(crate) fn public_key() -> String {
+        let der = signing_key().verifying_key().to_public_key_der().unwrap();
+        der.as_bytes()
+            .iter()
+            .map(|byte| format!("{byte:02X}"))
+            .collect()
+    }
+
+    /// OCMF document of `payload` signed with [`signing_key`].
+    pub(crate) fn sign(payload: &str) -> String {
+        let signature: Signature = signing_key()
+            .sign_prehash(&Sha256::digest(payload.as_bytes()))
+            .unwrap();
+        let data: String = signature
+            .to_der()
+            .as_bytes()
+            .iter()
+            .map(|byte| format!("{byte:02X}"))
+            .collect();
+        format!(r#"OCMF|{payload}|{{"SA":"ECDSA-secp256r1-SHA256","SD":"{data}"}}"#)
+    }
+
+    #[test]
+    fn parses_ocmf_document() {
+        let data = SignedMeterData::parse(&sign(BEGIN)).unwrap();
+        assert_eq!(data.signed_payload(), BEGIN);
+        assert_eq!(data.signature.encoding, "hex");
+        let payload = &data.payload;
+        assert_eq!(
+            payload.pagination,
+            Pagination {
+                kind: PaginationKind::Transaction,
+                number: 12
+            }
+        );
+        assert_eq!(payload.meter_serial, "BQ27400330016");
+        assert_eq!(payload.identification_flags.len(), 2);
+        let reading = &payload.readings[0];
+        assert_eq!(
+            reading.time.time,
+            Utc.with_ymd_and_hms(2024, 7, 24, 11, 22, 4).unwrap()
+        );
+        assert_eq!(reading.time.status, TimeStatus::Synchronized);
+        assert_eq!(reading.transaction, Some(TransactionEvent::Begin));
+        assert_eq!(reading.energy_wh(), Some(dec!(2935600)));
+        assert!(reading.is_valid());
+
+        let encoded = Base64::encode_string(sign(BEGIN).as_bytes());
+        assert_eq!(SignedMeterData::parse(&encoded).unwrap(), data);
+        assert!(matches!(
+            SignedMeterData::parse("OCMF|{}"),
+            Err(SignedMeterValueError::Malformed(_))
+        ));
+        let invalid_time = BEGIN.replace("+0200 S", "+0200 Q");
+        assert!(matches!(
+            SignedMeterData::parse(&sign(&invalid_time)),
+            Err(SignedMeterValueError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn verifies_signature() {
+        let data = SignedMeterData::parse(&sign(BEGIN)).unwrap();
+        data.verify(&public_key()).unwrap();
+        let der = decode_hex(&public_key()).unwrap();
+        data.verify(&Base64::encode_string(&der)).unwrap();
+
+        let mut tampered = data.clone();
+        tampered.signed_payload = BEGIN.replace("2935.6", "2925.6");
+        assert_eq!(
+            tampered.verify(&public_key()),
+            Err(SignedMeterValueError::InvalidSignature)
+        );
+        let other_key = SigningKey::from_slice(&[8; 32]).unwrap();
+        let other_key = other_key.verifying_key().to_public_key_der().unwrap();
+        assert_eq!(
+            data.verify(&Base64::encode_string(other_key.as_bytes())),
+            Err(SignedMeterValueError::InvalidSignature)
+        );
+        let mut brainpool = data.clone();
+        brainpool.signature.algorithm = "ECDSA-brainpool256r1-SHA256".to_string();
+        assert!(matches!(
+            brainpool.verify(&public_key()),
+            Err(SignedMeterValueError::UnsupportedAlgorithm(_))
+        ));
+        let mut p384 = data;
+        p384.signature.algorithm = "ECDSA-secp384r1-SHA256".to_string();
+        assert_eq!(
+            p384.verify(&public_key()),
+            Err(SignedMeterValueError::InvalidPublicKey)
+        );
+
+        assert!(verify_signed_meter_data(&sign(BEGIN), "OCMF", Some(&public_key())).is_ok());
+        assert_eq!(
+            verify_signed_meter_data(&sign(BEGIN), "EDL", Some(&public_key())),
+            Err(SignedMeterValueError::UnsupportedEncoding(
+                "EDL".to_string()
+            ))
+        );
+        assert_eq!(
+            verify_signed_meter_data(&sign(BEGIN), "OCMF", Some("")),
+            Err(SignedMeterValueError::MissingPublicKey)
+        );
+    }
+
+    #[test]
+    fn computes_charged_energy() {
+        let begin = SignedMeterData::parse(&sign(BEGIN)).unwrap().payload;
+        let end = SignedMeterData::parse(&sign(END)).unwrap().payload;
+        assert_eq!(charged_energy(&begin, &end), Ok(dec!(11515)));
+        assert!(matches!(
+            charged_energy(&end, &begin),
+            Err(SignedMeterValueError::Inconsistent(_))
+        ));
+
+        let mut other_meter = end.clone();
+        other_meter.meter_serial = "BQ27400330017".to_string();
+        assert!(charged_energy(&begin, &other_meter).is_err());
+        let mut tampered = end;
+        tampered.readings[0].status = MeterStatus::Tampered;
+        assert!(charged_energy(&begin, &tampered).is_err());
+    }
+}
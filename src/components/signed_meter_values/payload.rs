@@ -0,0 +1,282 @@
+use chrono::{DateTime, FixedOffset};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+/// Payload section of an OCMF document, with the metering readings and the context they
+/// were taken in.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OcmfPayload {
+    /// Version of the format, `FV`.
+    #[serde(rename = "FV", default)]
+    pub format_version: Option<String>,
+    /// Identification of the gateway, `GI`.
+    #[serde(rename = "GI", default)]
+    pub gateway_identification: Option<String>,
+    /// Serial number of the gateway, `GS`.
+    #[serde(rename = "GS", default)]
+    pub gateway_serial: Option<String>,
+    /// Firmware version of the gateway, `GV`.
+    #[serde(rename = "GV", default)]
+    pub gateway_version: Option<String>,
+    /// Pagination of the document, `PG`.
+    #[serde(rename = "PG")]
+    pub pagination: Pagination,
+    /// Manufacturer of the meter, `MV`.
+    #[serde(rename = "MV", default)]
+    pub meter_vendor: Option<String>,
+    /// Model of the meter, `MM`.
+    #[serde(rename = "MM", default)]
+    pub meter_model: Option<String>,
+    /// Serial number of the meter, `MS`.
+    #[serde(rename = "MS")]
+    pub meter_serial: String,
+    /// Firmware version of the meter, `MF`.
+    #[serde(rename = "MF", default)]
+    pub meter_firmware: Option<String>,
+    /// Whether the user was identified, `IS`.
+    #[serde(rename = "IS", default)]
+    pub identification_status: bool,
+    /// Level of the identification, such as `VERIFIED`, `IL`.
+    #[serde(rename = "IL", default)]
+    pub identification_level: Option<String>,
+    /// Flags of the identification, such as `RFID_PLAIN`, `IF`.
+    #[serde(rename = "IF", default)]
+    pub identification_flags: Vec<String>,
+    /// Type of the identification, such as `ISO14443`, `IT`.
+    #[serde(rename = "IT", default)]
+    pub identification_type: Option<String>,
+    /// Identification data, such as the UID of an RFID card, `ID`.
+    #[serde(rename = "ID", default)]
+    pub identification_data: Option<String>,
+    /// Tariff text, `TT`.
+    #[serde(rename = "TT", default)]
+    pub tariff_text: Option<String>,
+    /// Type of the charge point identification, such as `EVSEID`, `CT`.
+    #[serde(rename = "CT", default)]
+    pub charge_point_identification_type: Option<String>,
+    /// Identification of the charge point, `CI`.
+    #[serde(rename = "CI", default)]
+    pub charge_point_identification: Option<String>,
+    /// Cable loss compensation applied to the readings, `LC`.
+    #[serde(rename = "LC", default)]
+    pub loss_compensation: Option<LossCompensation>,
+    /// Metering readings, `RD`.
+    #[serde(rename = "RD")]
+    pub readings: Vec<Reading>,
+}
+
+impl OcmfPayload {
+    /// Energy readings in Wh, in the order of the document.
+    pub fn energy_readings(&self) -> impl Iterator<Item = (&Reading, Decimal)> {
+        self.readings
+            .iter()
+            .filter_map(|reading| Some((reading, reading.energy_wh()?)))
+    }
+}
+
+/// Pagination of an OCMF document, `PG`, counting transaction and fiscal documents
+/// separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Pagination {
+    /// Counter the document belongs to.
+    pub kind: PaginationKind,
+    /// Number of the document.
+    pub number: u64,
+}
+
+/// Counter of an OCMF document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaginationKind {
+    /// Transaction document, `T`.
+    Transaction,
+    /// Fiscal document, `F`.
+    Fiscal,
+}
+
+impl TryFrom<String> for Pagination {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("invalid pagination {value:?}");
+        let kind = match value.chars().next() {
+            Some('T') => PaginationKind::Transaction,
+            Some('F') => PaginationKind::Fiscal,
+            _ => return Err(invalid()),
+        };
+        let number = value[1..].parse().map_err(|_| invalid())?;
+        Ok(Self { kind, number })
+    }
+}
+
+/// Cable loss compensation of an OCMF document, `LC`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LossCompensation {
+    /// Naming of the compensation method, `LN`.
+    #[serde(rename = "LN", default)]
+    pub name: Option<String>,
+    /// Identification of the compensation parameters, `LI`.
+    #[serde(rename = "LI", default)]
+    pub identification: Option<u64>,
+    /// Cable resistance, `LR`.
+    #[serde(rename = "LR")]
+    pub resistance: Decimal,
+    /// Unit of the cable resistance, `mOhm` or `uOhm`, `LU`.
+    #[serde(rename = "LU")]
+    pub unit: String,
+}
+
+/// One metering reading of an OCMF document.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Reading {
+    /// Time of the reading, `TM`.
+    #[serde(rename = "TM")]
+    pub time: ReadingTime,
+    /// Transaction event the reading was taken at, `TX`.
+    #[serde(rename = "TX", default)]
+    pub transaction: Option<TransactionEvent>,
+    /// Value of the reading, `RV`.
+    #[serde(rename = "RV")]
+    pub value: Decimal,
+    /// OBIS code of the reading, such as `1-b:1.8.0` for imported energy, `RI`.
+    #[serde(rename = "RI", default)]
+    pub identifier: Option<String>,
+    /// Unit of the reading, such as `kWh`, `RU`.
+    #[serde(rename = "RU")]
+    pub unit: String,
+    /// Type of current, `AC` or `DC`, `RT`.
+    #[serde(rename = "RT", default)]
+    pub current_type: Option<String>,
+    /// Cumulated cable loss in kWh, `CL`.
+    #[serde(rename = "CL", default)]
+    pub cumulated_loss: Option<Decimal>,
+    /// Error flags of the meter, `E` for energy and `t` for time errors, `EF`.
+    #[serde(rename = "EF", default)]
+    pub error_flags: Option<String>,
+    /// Status of the meter, `ST`.
+    #[serde(rename = "ST")]
+    pub status: MeterStatus,
+}
+
+impl Reading {
+    /// Value in Wh of a reading in Wh or kWh.
+    pub fn energy_wh(&self) -> Option<Decimal> {
+        match self.unit.as_str() {
+            "Wh" => Some(self.value),
+            "kWh" => Some(self.value * Decimal::ONE_THOUSAND),
+            _ => None,
+        }
+    }
+
+    /// Whether the meter reported no errors and a good status.
+    pub fn is_valid(&self) -> bool {
+        self.status == MeterStatus::Good && self.error_flags.as_deref().is_none_or(str::is_empty)
+    }
+}
+
+/// Time of a reading, `TM`, such as `2018-07-24T13:22:04,000+0200 S`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct ReadingTime {
+    /// Local time of the meter.
+    pub time: DateTime<FixedOffset>,
+    /// Synchronization status of the clock of the meter.
+    pub status: TimeStatus,
+}
+
+impl TryFrom<String> for ReadingTime {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("invalid reading time {value:?}");
+        let (time, status) = value.split_once(' ').ok_or_else(invalid)?;
+        let status = match status {
+            "U" => TimeStatus::Unknown,
+            "I" => TimeStatus::Informative,
+            "S" => TimeStatus::Synchronized,
+            "R" => TimeStatus::Relative,
+            _ => return Err(invalid()),
+        };
+        let time = DateTime::parse_from_str(&time.replace(',', "."), "%Y-%m-%dT%H:%M:%S%.f%z")
+            .map_err(|_| invalid())?;
+        Ok(Self { time, status })
+    }
+}
+
+/// Synchronization status of the clock of a meter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeStatus {
+    /// Unknown or unsynchronized, `U`.
+    Unknown,
+    /// Informative, not legally relevant, `I`.
+    Informative,
+    /// Synchronized, `S`.
+    Synchronized,
+    /// Relative time measured by the meter, `R`.
+    Relative,
+}
+
+/// Transaction event a reading was taken at, `TX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum TransactionEvent {
+    /// Start of the transaction, `B`.
+    #[serde(rename = "B")]
+    Begin,
+    /// During the transaction, `C`.
+    #[serde(rename = "C")]
+    Charging,
+    /// Exception during the transaction, `X`.
+    #[serde(rename = "X")]
+    Exception,
+    /// End of the transaction, `E`.
+    #[serde(rename = "E")]
+    End,
+    /// End of the transaction, terminated locally, `L`.
+    #[serde(rename = "L")]
+    EndLocal,
+    /// End of the transaction, terminated remotely, `R`.
+    #[serde(rename = "R")]
+    EndRemote,
+    /// End of the transaction, aborted by an error, `A`.
+    #[serde(rename = "A")]
+    EndAbort,
+    /// End of the transaction, aborted by a power failure, `P`.
+    #[serde(rename = "P")]
+    EndPowerFailure,
+    /// Transaction suspended, `S`.
+    #[serde(rename = "S")]
+    Suspended,
+    /// Tariff change during the transaction, `T`.
+    #[serde(rename = "T")]
+    TariffChange,
+}
+
+impl TransactionEvent {
+    /// Whether the event ends the transaction.
+    pub fn is_end(self) -> bool {
+        matches!(
+            self,
+            Self::End | Self::EndLocal | Self::EndRemote | Self::EndAbort | Self::EndPowerFailure
+        )
+    }
+}
+
+/// Status of a meter at a reading, `ST`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum MeterStatus {
+    /// Meter not present, `N`.
+    #[serde(rename = "N")]
+    NotPresent,
+    /// Meter working correctly, `G`.
+    #[serde(rename = "G")]
+    Good,
+    /// Meter tampered, `T`.
+    #[serde(rename = "T")]
+    Tampered,
+    /// Meter not working, `D`.
+    #[serde(rename = "D")]
+    Dead,
+    /// Energy flow reversed, `R`.
+    #[serde(rename = "R")]
+    Reverse,
+}
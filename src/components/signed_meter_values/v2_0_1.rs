@@ -0,0 +1,98 @@
+//! OCPP 2.0.1 signed meter values.
+
+use crate::v2_0_1::datatypes::{
+    meter_value_type::MeterValueType, signed_meter_value_type::SignedMeterValueType,
+};
+
+use super::{verify_signed_meter_data, SignedMeterData, SignedMeterValueError};
+
+/// Decodes and verifies a signed meter value with the registered `public_key` of the meter,
+/// or else with the public key sent along with the value.
+pub fn verify_signed_meter_value(
+    value: &SignedMeterValueType,
+    public_key: Option<&str>,
+) -> Result<SignedMeterData, SignedMeterValueError> {
+    verify_signed_meter_data(
+        &value.signed_meter_data,
+        &value.encoding_method,
+        public_key.or(Some(&value.public_key)),
+    )
+}
+
+/// Decodes and verifies the signed meter values of `meter_values`, such as those of a
+/// transaction, in order.
+///
+/// Without registered `public_key`, a value without public key is verified with the last
+/// key sent before it, as when `PublicKeyWithSignedMeterValue` is `OncePerTransaction`.
+pub fn verify_meter_values(
+    meter_values: &[MeterValueType],
+    public_key: Option<&str>,
+) -> Result<Vec<SignedMeterData>, SignedMeterValueError> {
+    let mut last_key = None;
+    meter_values
+        .iter()
+        .flat_map(|meter_value| &meter_value.sampled_value)
+        .filter_map(|sampled_value| sampled_value.signed_meter_value.as_ref())
+        .map(|value| {
+            if !value.public_key.trim().is_empty() {
+                last_key = Some(value.public_key.as_str());
+            }
+            verify_signed_meter_data(
+                &value.signed_meter_data,
+                &value.encoding_method,
+                public_key.or(last_key),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::{
+        components::signed_meter_values::{
+            charged_energy,
+            tests::{public_key, sign, BEGIN, END},
+        },
+        v2_0_1::datatypes::sampled_value_type::SampledValueType,
+    };
+
+    fn meter_value(payload: &str, public_key: &str) -> MeterValueType {
+        MeterValueType {
+            timestamp: Utc.with_ymd_and_hms(2024, 7, 24, 11, 22, 4).unwrap(),
+            sampled_value: vec![SampledValueType {
+                value: dec!(2935.6),
+                signed_meter_value: Some(SignedMeterValueType {
+                    signed_meter_data: sign(payload),
+                    signing_method: String::new(),
+                    encoding_method: "OCMF".to_string(),
+                    public_key: public_key.to_string(),
+                }),
+                ..Default::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn verifies_signed_meter_values() {
+        let meter_values = [meter_value(BEGIN, &public_key()), meter_value(END, "")];
+        let data = verify_meter_values(&meter_values, None).unwrap();
+        assert_eq!(
+            charged_energy(&data[0].payload, &data[1].payload),
+            Ok(dec!(11515))
+        );
+
+        let value = meter_values[1].sampled_value[0]
+            .signed_meter_value
+            .as_ref()
+            .unwrap();
+        assert_eq!(
+            verify_signed_meter_value(value, None),
+            Err(SignedMeterValueError::MissingPublicKey)
+        );
+        assert!(verify_signed_meter_value(value, Some(&public_key())).is_ok());
+    }
+}
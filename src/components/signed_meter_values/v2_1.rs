@@ -0,0 +1,97 @@
+//! OCPP 2.1 signed meter values.
+
+use crate::v2_1::datatypes::{MeterValueType, SignedMeterValueType};
+
+use super::{verify_signed_meter_data, SignedMeterData, SignedMeterValueError};
+
+/// Decodes and verifies a signed meter value with the registered `public_key` of the meter,
+/// or else with the public key sent along with the value.
+pub fn verify_signed_meter_value(
+    value: &SignedMeterValueType,
+    public_key: Option<&str>,
+) -> Result<SignedMeterData, SignedMeterValueError> {
+    verify_signed_meter_data(
+        &value.signed_meter_data,
+        &value.encoding_method,
+        public_key.or(value.public_key.as_deref()),
+    )
+}
+
+/// Decodes and verifies the signed meter values of `meter_values`, such as those of a
+/// transaction, in order.
+///
+/// Without registered `public_key`, a value without public key is verified with the last
+/// key sent before it, as when `PublicKeyWithSignedMeterValue` is `OncePerTransaction`.
+pub fn verify_meter_values(
+    meter_values: &[MeterValueType],
+    public_key: Option<&str>,
+) -> Result<Vec<SignedMeterData>, SignedMeterValueError> {
+    let mut last_key = None;
+    meter_values
+        .iter()
+        .flat_map(|meter_value| &meter_value.sampled_value)
+        .filter_map(|sampled_value| sampled_value.signed_meter_value.as_ref())
+        .map(|value| {
+            if let Some(key) = value
+                .public_key
+                .as_deref()
+                .filter(|key| !key.trim().is_empty())
+            {
+                last_key = Some(key);
+            }
+            verify_signed_meter_data(
+                &value.signed_meter_data,
+                &value.encoding_method,
+                public_key.or(last_key),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::{
+        components::signed_meter_values::{
+            charged_energy,
+            tests::{public_key, sign, BEGIN, END},
+        },
+        v2_1::datatypes::SampledValueType,
+    };
+
+    fn meter_value(payload: &str, public_key: Option<String>) -> MeterValueType {
+        let mut signed = SignedMeterValueType::new(sign(payload), "OCMF".to_string());
+        signed.public_key = public_key;
+        let sampled_value = SampledValueType::new(2935.6).with_signed_meter_value(signed);
+        MeterValueType::new(
+            Utc.with_ymd_and_hms(2024, 7, 24, 11, 22, 4).unwrap(),
+            vec![sampled_value],
+        )
+    }
+
+    #[test]
+    fn verifies_signed_meter_values() {
+        let meter_values = [
+            meter_value(BEGIN, Some(public_key())),
+            meter_value(END, None),
+        ];
+        let data = verify_meter_values(&meter_values, None).unwrap();
+        assert_eq!(
+            charged_energy(&data[0].payload, &data[1].payload),
+            Ok(dec!(11515))
+        );
+
+        let value = meter_values[1].sampled_value[0]
+            .signed_meter_value
+            .as_ref()
+            .unwrap();
+        assert_eq!(
+            verify_signed_meter_value(value, None),
+            Err(SignedMeterValueError::MissingPublicKey)
+        );
+        assert!(verify_signed_meter_value(value, Some(&public_key())).is_ok());
+    }
+}
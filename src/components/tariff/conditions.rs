@@ -0,0 +1,273 @@
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+use rust_decimal::Decimal;
+
+use crate::v2_1::{
+    datatypes::{TariffConditionsFixedType, TariffConditionsType},
+    enumerations::DayOfWeekEnumType,
+};
+
+use super::{TariffContext, TariffState};
+
+/// Evaluates the conditions of an energy or time price element in `state`.
+///
+/// All conditions must hold for the element to apply. Minimum values are inclusive and
+/// maximum values exclusive. Times of day, days of week and dates are evaluated in the
+/// local time of `context`. A condition on a value that is unknown, such as power when no
+/// power has been measured, does not hold.
+///
+/// Returns `None` when a time of day or date is malformed.
+pub fn applies(
+    conditions: &TariffConditionsType,
+    context: &TariffContext,
+    state: &TariffState,
+) -> Option<bool> {
+    let local = context.local_time(state.timestamp);
+    let applies = time_of_day(
+        conditions.start_time_of_day.as_deref(),
+        conditions.end_time_of_day.as_deref(),
+        local,
+    )? && valid_date(
+        conditions.valid_from_date.as_deref(),
+        conditions.valid_to_date.as_deref(),
+        local,
+    )? && day_of_week(conditions.day_of_week.as_deref(), local)
+        && conditions
+            .evse_kind
+            .as_ref()
+            .is_none_or(|kind| context.evse_kind.as_ref() == Some(kind))
+        && within(
+            conditions.min_energy,
+            conditions.max_energy,
+            Some(state.energy),
+        )
+        && within(
+            conditions.min_current,
+            conditions.max_current,
+            state.current,
+        )
+        && within(conditions.min_power, conditions.max_power, state.power)
+        && within_seconds(conditions.min_time, conditions.max_time, state.time)
+        && within_seconds(
+            conditions.min_charging_time,
+            conditions.max_charging_time,
+            state.charging_time,
+        )
+        && within_seconds(
+            conditions.min_idle_time,
+            conditions.max_idle_time,
+            state.idle_time,
+        );
+    Some(applies)
+}
+
+/// Evaluates the conditions of a fixed price element at `timestamp`.
+///
+/// Besides the time and EVSE conditions of [`applies`], the payment brand and payment
+/// recognition of `context` must match when set, ignoring ASCII case.
+///
+/// Returns `None` when a time of day or date is malformed.
+pub fn applies_fixed(
+    conditions: &TariffConditionsFixedType,
+    context: &TariffContext,
+    timestamp: DateTime<Utc>,
+) -> Option<bool> {
+    let local = context.local_time(timestamp);
+    let applies = time_of_day(
+        conditions.start_time_of_day.as_deref(),
+        conditions.end_time_of_day.as_deref(),
+        local,
+    )? && valid_date(
+        conditions.valid_from_date.as_deref(),
+        conditions.valid_to_date.as_deref(),
+        local,
+    )? && day_of_week(conditions.day_of_week.as_deref(), local)
+        && conditions
+            .evse_kind
+            .as_ref()
+            .is_none_or(|kind| context.evse_kind.as_ref() == Some(kind))
+        && matches(
+            conditions.payment_brand.as_deref(),
+            context.payment_brand.as_deref(),
+        )
+        && matches(
+            conditions.payment_recognition.as_deref(),
+            context.payment_recognition.as_deref(),
+        );
+    Some(applies)
+}
+
+/// Whether `local` lies in the window from `start` (inclusive) to `end` (exclusive). A
+/// window whose end is not after its start wraps around midnight.
+fn time_of_day(start: Option<&str>, end: Option<&str>, local: NaiveDateTime) -> Option<bool> {
+    let parse = |time: &str| NaiveTime::parse_from_str(time, "%H:%M").ok();
+    let time = local.time();
+    let applies = match (start.map(parse), end.map(parse)) {
+        (None, None) => true,
+        (Some(start), None) => time >= start?,
+        (None, Some(end)) => {
+            let end = end?;
+            end == NaiveTime::MIN || time < end
+        }
+        (Some(start), Some(end)) => {
+            let (start, end) = (start?, end?);
+            match start < end {
+                true => start <= time && time < end,
+                false => time >= start || time < end,
+            }
+        }
+    };
+    Some(applies)
+}
+
+/// Whether the date of `local` lies from `from` (inclusive) until `to` (exclusive).
+fn valid_date(from: Option<&str>, to: Option<&str>, local: NaiveDateTime) -> Option<bool> {
+    let parse = |date: &str| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok();
+    let date = local.date();
+    let after_from = match from {
+        Some(from) => date >= parse(from)?,
+        None => true,
+    };
+    let before_to = match to {
+        Some(to) => date < parse(to)?,
+        None => true,
+    };
+    Some(after_from && before_to)
+}
+
+fn day_of_week(days: Option<&[DayOfWeekEnumType]>, local: NaiveDateTime) -> bool {
+    days.is_none_or(|days| {
+        days.iter()
+            .any(|day| weekday(day) == local.date().weekday())
+    })
+}
+
+fn weekday(day: &DayOfWeekEnumType) -> Weekday {
+    match day {
+        DayOfWeekEnumType::Monday => Weekday::Mon,
+        DayOfWeekEnumType::Tuesday => Weekday::Tue,
+        DayOfWeekEnumType::Wednesday => Weekday::Wed,
+        DayOfWeekEnumType::Thursday => Weekday::Thu,
+        DayOfWeekEnumType::Friday => Weekday::Fri,
+        DayOfWeekEnumType::Saturday => Weekday::Sat,
+        DayOfWeekEnumType::Sunday => Weekday::Sun,
+    }
+}
+
+fn within(min: Option<Decimal>, max: Option<Decimal>, value: Option<Decimal>) -> bool {
+    if min.is_none() && max.is_none() {
+        return true;
+    }
+    value.is_some_and(|value| {
+        min.is_none_or(|min| value >= min) && max.is_none_or(|max| value < max)
+    })
+}
+
+fn within_seconds(min: Option<i32>, max: Option<i32>, value: i64) -> bool {
+    min.is_none_or(|min| value >= i64::from(min)) && max.is_none_or(|max| value < i64::from(max))
+}
+
+fn matches(condition: Option<&str>, value: Option<&str>) -> bool {
+    condition
+        .is_none_or(|condition| value.is_some_and(|value| value.eq_ignore_ascii_case(condition)))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset, TimeZone};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::v2_1::enumerations::EvseKindEnumType;
+
+    /// Saturday 1 March 2025, 21:30 UTC, 22:30 in UTC+1.
+    fn state() -> TariffState {
+        TariffState {
+            timestamp: Utc.with_ymd_and_hms(2025, 3, 1, 21, 30, 0).unwrap(),
+            energy: dec!(15000),
+            power: Some(dec!(11000)),
+            current: None,
+            time: 3600,
+            charging_time: 3000,
+            idle_time: 600,
+        }
+    }
+
+    fn context() -> TariffContext {
+        TariffContext::new()
+            .with_utc_offset(FixedOffset::east_opt(3600).unwrap())
+            .with_evse_kind(EvseKindEnumType::AC)
+    }
+
+    #[test]
+    fn time_of_day_in_local_time() {
+        let night = TariffConditionsType::new()
+            .with_start_time_of_day("22:00".to_string())
+            .with_end_time_of_day("06:00".to_string());
+        assert_eq!(applies(&night, &context(), &state()), Some(true));
+        assert_eq!(
+            applies(&night, &TariffContext::new(), &state()),
+            Some(false)
+        );
+
+        let evening = TariffConditionsType::new()
+            .with_start_time_of_day("18:00".to_string())
+            .with_end_time_of_day("00:00".to_string());
+        assert_eq!(applies(&evening, &context(), &state()), Some(true));
+
+        let malformed = TariffConditionsType::new().with_start_time_of_day("22h".to_string());
+        assert_eq!(applies(&malformed, &context(), &state()), None);
+    }
+
+    #[test]
+    fn days_and_dates() {
+        let weekend = TariffConditionsType::new()
+            .with_day_of_week(vec![DayOfWeekEnumType::Saturday, DayOfWeekEnumType::Sunday]);
+        assert_eq!(applies(&weekend, &context(), &state()), Some(true));
+
+        let march = TariffConditionsType::new()
+            .with_valid_from_date("2025-03-01".to_string())
+            .with_valid_to_date("2025-04-01".to_string());
+        assert_eq!(applies(&march, &context(), &state()), Some(true));
+
+        let february = TariffConditionsType::new().with_valid_to_date("2025-03-01".to_string());
+        assert_eq!(applies(&february, &context(), &state()), Some(false));
+    }
+
+    #[test]
+    fn measured_values_and_durations() {
+        let conditions = TariffConditionsType::new()
+            .with_min_energy(dec!(10000))
+            .with_max_energy(dec!(15000));
+        assert_eq!(applies(&conditions, &context(), &state()), Some(false));
+
+        let conditions = TariffConditionsType::new()
+            .with_min_power(dec!(11000))
+            .with_evse_kind(EvseKindEnumType::AC);
+        assert_eq!(applies(&conditions, &context(), &state()), Some(true));
+
+        // no current has been measured
+        let conditions = TariffConditionsType::new().with_max_current(dec!(32));
+        assert_eq!(applies(&conditions, &context(), &state()), Some(false));
+
+        let conditions = TariffConditionsType::new()
+            .with_min_time(3600)
+            .with_max_charging_time(3001)
+            .with_min_idle_time(600);
+        assert_eq!(applies(&conditions, &context(), &state()), Some(true));
+    }
+
+    #[test]
+    fn fixed_conditions_match_payment() {
+        let conditions = TariffConditionsFixedType::new()
+            .with_payment_brand("Visa".to_string())
+            .with_day_of_week(vec![DayOfWeekEnumType::Saturday]);
+        let timestamp = state().timestamp;
+        assert_eq!(
+            applies_fixed(&conditions, &context(), timestamp),
+            Some(false)
+        );
+
+        let context = context().with_payment("VISA".to_string(), "Debit".to_string());
+        assert_eq!(applies_fixed(&conditions, &context, timestamp), Some(true));
+    }
+}
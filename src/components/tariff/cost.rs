@@ -0,0 +1,740 @@
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use rust_decimal::Decimal;
+
+use crate::v2_1::{
+    datatypes::{
+        ChargingPeriodType, CostDetailsType, CostDimensionType, PriceType, TariffConditionsType,
+        TariffFixedType, TariffTimeType, TariffType, TaxRateType, TotalCostType, TotalPriceType,
+        TotalUsageType,
+    },
+    enumerations::{CostDimensionEnumType, TariffCostEnumType},
+};
+
+use super::{applies, applies_fixed, TariffContext, TariffSession, TariffState};
+
+const FAILURE_REASON_MAX_LENGTH: usize = 500;
+
+const OUT_OF_RANGE: &str = "cost out of range";
+
+/// Calculates the cost of `session` with `tariff`.
+///
+/// An ongoing session is calculated up to `now`. The session is evaluated in steps that end
+/// at every whole minute, meter reading, charging state change and transaction duration a
+/// condition depends on. Each step is priced with the first energy, charging time and idle
+/// time price elements whose conditions [apply](super::applies) at its start. A new charging
+/// period starts whenever a different price element applies.
+///
+/// Energy is priced per kWh, charging time while the EV is charging and idle time while it
+/// is not. Fixed fees are applied once at the start of the transaction and reservation
+/// costs at the start of the reservation. Taxes are added per tariff dimension and the
+/// total is limited to the minimum and maximum cost of the tariff.
+///
+/// When part of the cost cannot be calculated, the remaining cost is still reported with
+/// `failure_to_calculate` set and the reasons in `failure_reason`.
+pub fn cost_details(
+    tariff: &TariffType,
+    session: &TariffSession,
+    now: DateTime<Utc>,
+) -> CostDetailsType {
+    let mut calculation = Calculation {
+        tariff,
+        context: session.context(),
+        failures: Vec::new(),
+    };
+    calculation.run(session, now)
+}
+
+#[derive(Debug, Default)]
+struct Period {
+    start: DateTime<Utc>,
+    elements: (Option<usize>, Option<usize>, Option<usize>),
+    energy: Decimal,
+    charging_time: i64,
+    idle_time: i64,
+    power: Option<(Decimal, Decimal)>,
+    current: Option<(Decimal, Decimal)>,
+}
+
+impl Period {
+    fn dimensions(&self) -> Vec<CostDimensionType> {
+        let mut dimensions = vec![
+            dimension(CostDimensionEnumType::Energy, self.energy),
+            dimension(
+                CostDimensionEnumType::ChargingTime,
+                Decimal::from(self.charging_time / 1000),
+            ),
+            dimension(
+                CostDimensionEnumType::IdleTime,
+                Decimal::from(self.idle_time / 1000),
+            ),
+        ];
+        if let Some((min, max)) = self.power {
+            dimensions.push(dimension(CostDimensionEnumType::MinPower, min));
+            dimensions.push(dimension(CostDimensionEnumType::MaxPower, max));
+        }
+        if let Some((min, max)) = self.current {
+            dimensions.push(dimension(CostDimensionEnumType::MinCurrent, min));
+            dimensions.push(dimension(CostDimensionEnumType::MaxCurrent, max));
+        }
+        dimensions
+    }
+}
+
+struct Calculation<'a> {
+    tariff: &'a TariffType,
+    context: &'a TariffContext,
+    failures: Vec<&'static str>,
+}
+
+impl Calculation<'_> {
+    fn run(&mut self, session: &TariffSession, now: DateTime<Utc>) -> CostDetailsType {
+        let tariff = self.tariff;
+        let start = session.start();
+        let end = session.end().unwrap_or(now).max(start);
+
+        if tariff
+            .valid_from
+            .is_some_and(|valid_from| valid_from > start)
+        {
+            self.fail("tariff is not valid at the start of the transaction");
+        }
+        if tariff.energy.is_some() && end > start && !session.has_energy() {
+            self.fail("no energy meter values");
+        }
+        if session.energy_decreases() {
+            self.fail("energy register decreased");
+        }
+
+        let mut energy_cost = Decimal::ZERO;
+        let mut charging_cost = Decimal::ZERO;
+        let mut idle_cost = Decimal::ZERO;
+        let mut energy = Decimal::ZERO;
+        let mut charging_time = 0;
+        let mut idle_time = 0;
+        let mut periods: Vec<Period> = Vec::new();
+
+        let mut from = start;
+        for to in steps(tariff, session, start, end) {
+            let milliseconds = (to - from).num_milliseconds();
+            let minutes = Decimal::from(milliseconds) / Decimal::from(60_000);
+            let step_energy = session
+                .energy_at(to)
+                .zip(session.energy_at(from))
+                .and_then(|(to, from)| to.checked_sub(from));
+            let step_energy = step_energy.unwrap_or_else(|| {
+                self.fail(OUT_OF_RANGE);
+                Decimal::ZERO
+            });
+            let charging = session.charging_at(from).unwrap_or(!step_energy.is_zero());
+            let (power, current) = session.power_and_current_at(from);
+            let state = TariffState {
+                timestamp: from,
+                energy,
+                power: power.or_else(|| {
+                    let energy = step_energy.checked_mul(Decimal::from(3_600_000))?;
+                    (milliseconds > 0).then(|| energy / Decimal::from(milliseconds))
+                }),
+                current,
+                time: (from - start).num_seconds(),
+                charging_time: charging_time / 1000,
+                idle_time: idle_time / 1000,
+            };
+
+            let energy_element = tariff.energy.as_ref().and_then(|energy| {
+                let conditions = energy.prices.iter().map(|price| price.conditions.as_ref());
+                self.select(conditions, &state)
+            });
+            let charging_element = tariff
+                .charging_time
+                .as_ref()
+                .and_then(|time| self.select_time(time, &state));
+            let idle_element = tariff
+                .idle_time
+                .as_ref()
+                .and_then(|time| self.select_time(time, &state));
+
+            if let (Some(index), Some(prices)) = (energy_element, &tariff.energy) {
+                let cost = prices.prices[index].price_kwh.checked_mul(step_energy);
+                energy_cost = self.add(energy_cost, cost.map(|cost| cost / Decimal::ONE_THOUSAND));
+            }
+            if charging {
+                charging_time += milliseconds;
+                if let (Some(index), Some(time)) = (charging_element, &tariff.charging_time) {
+                    let cost = time.prices[index].price_minute.checked_mul(minutes);
+                    charging_cost = self.add(charging_cost, cost);
+                }
+            } else {
+                idle_time += milliseconds;
+                if let (Some(index), Some(time)) = (idle_element, &tariff.idle_time) {
+                    let cost = time.prices[index].price_minute.checked_mul(minutes);
+                    idle_cost = self.add(idle_cost, cost);
+                }
+            }
+            energy = self.add(energy, Some(step_energy));
+
+            let elements = (energy_element, charging_element, idle_element);
+            if periods
+                .last()
+                .is_none_or(|period| period.elements != elements)
+            {
+                periods.push(Period {
+                    start: from,
+                    elements,
+                    ..Default::default()
+                });
+            }
+            if let Some(period) = periods.last_mut() {
+                period.energy = self.add(period.energy, Some(step_energy));
+                match charging {
+                    true => period.charging_time += milliseconds,
+                    false => period.idle_time += milliseconds,
+                }
+                period.power = widen(period.power, power);
+                period.current = widen(period.current, current);
+            }
+            from = to;
+        }
+
+        let fixed = tariff
+            .fixed_fee
+            .as_ref()
+            .map(|fee| self.fixed_price(fee, start));
+        let (reservation_time, reservation_fixed, reservation_seconds) = match session.reservation()
+        {
+            Some((reserved, released)) => {
+                let reservation_time = tariff
+                    .reservation_time
+                    .as_ref()
+                    .map(|time| self.reservation_price(time, reserved, released));
+                let reservation_fixed = tariff
+                    .reservation_fixed
+                    .as_ref()
+                    .map(|fee| self.fixed_price(fee, reserved));
+                let seconds = (released - reserved).num_seconds().max(0);
+                (reservation_time, reservation_fixed, Some(seconds))
+            }
+            None => (None, None, None),
+        };
+
+        let energy_price = tariff
+            .energy
+            .as_ref()
+            .map(|prices| self.price(energy_cost, prices.tax_rates.as_deref()));
+        let charging_price = tariff
+            .charging_time
+            .as_ref()
+            .map(|time| self.price(charging_cost, time.tax_rates.as_deref()));
+        let idle_price = tariff
+            .idle_time
+            .as_ref()
+            .map(|time| self.price(idle_cost, time.tax_rates.as_deref()));
+
+        let parts = [
+            &fixed,
+            &energy_price,
+            &charging_price,
+            &idle_price,
+            &reservation_time,
+            &reservation_fixed,
+        ];
+        let mut sum = |price: fn(&PriceType) -> Option<Decimal>| {
+            let sum = parts
+                .iter()
+                .flat_map(|part| part.iter())
+                .flat_map(price)
+                .try_fold(Decimal::ZERO, Decimal::checked_add);
+            if sum.is_none() {
+                self.fail(OUT_OF_RANGE);
+            }
+            sum
+        };
+        let total = TotalPriceType {
+            excl_tax: sum(|price| price.excl_tax),
+            incl_tax: sum(|price| price.incl_tax),
+            custom_data: None,
+        };
+        let (total, type_of_cost) = limit(total, tariff);
+
+        let charging_periods = periods
+            .iter()
+            .map(|period| ChargingPeriodType {
+                start_period: period.start,
+                dimensions: Some(period.dimensions()),
+                tariff_id: Some(tariff.tariff_id.clone()),
+                custom_data: None,
+            })
+            .collect::<Vec<_>>();
+
+        let failure_reason = (!self.failures.is_empty()).then(|| {
+            let mut reason = self.failures.join("; ");
+            reason.truncate(FAILURE_REASON_MAX_LENGTH);
+            reason
+        });
+
+        CostDetailsType {
+            charging_periods: (!charging_periods.is_empty()).then_some(charging_periods),
+            total_cost: TotalCostType {
+                currency: tariff.currency.clone(),
+                type_of_cost,
+                fixed,
+                energy: energy_price,
+                charging_time: charging_price,
+                idle_time: idle_price,
+                reservation_time,
+                reservation_fixed,
+                total,
+                custom_data: None,
+            },
+            total_usage: TotalUsageType {
+                energy: energy / Decimal::ONE_THOUSAND,
+                charging_time: seconds((charging_time + idle_time) / 1000),
+                idle_time: seconds(idle_time / 1000),
+                reservation_time: reservation_seconds.map(seconds),
+                custom_data: None,
+            },
+            failure_to_calculate: failure_reason.is_some().then_some(true),
+            failure_reason,
+            custom_data: None,
+        }
+    }
+
+    fn fail(&mut self, reason: &'static str) {
+        if !self.failures.contains(&reason) {
+            self.failures.push(reason);
+        }
+    }
+
+    /// `sum` plus `value`, or `sum` with a failure when `value` or the sum is out of range.
+    fn add(&mut self, sum: Decimal, value: Option<Decimal>) -> Decimal {
+        match value.and_then(|value| sum.checked_add(value)) {
+            Some(sum) => sum,
+            None => {
+                self.fail(OUT_OF_RANGE);
+                sum
+            }
+        }
+    }
+
+    /// Price of `excl_tax` with `tax_rates`, without the price including tax when it is out
+    /// of range.
+    fn price(&mut self, excl_tax: Decimal, tax_rates: Option<&[TaxRateType]>) -> PriceType {
+        let incl_tax = incl_tax(excl_tax, tax_rates.unwrap_or_default());
+        if incl_tax.is_none() {
+            self.fail(OUT_OF_RANGE);
+        }
+        PriceType {
+            excl_tax: Some(excl_tax),
+            incl_tax,
+            tax_rates: tax_rates.map(<[TaxRateType]>::to_vec),
+            custom_data: None,
+        }
+    }
+
+    /// Index of the first price element whose conditions apply in `state`.
+    fn select<'c>(
+        &mut self,
+        conditions: impl Iterator<Item = Option<&'c TariffConditionsType>>,
+        state: &TariffState,
+    ) -> Option<usize> {
+        for (index, conditions) in conditions.enumerate() {
+            let applies = conditions.map_or(Some(true), |conditions| {
+                applies(conditions, self.context, state)
+            });
+            match applies {
+                Some(true) => return Some(index),
+                Some(false) => {}
+                None => self.fail("invalid tariff conditions"),
+            }
+        }
+        None
+    }
+
+    fn select_time(&mut self, time: &TariffTimeType, state: &TariffState) -> Option<usize> {
+        let conditions = time.prices.iter().map(|price| price.conditions.as_ref());
+        self.select(conditions, state)
+    }
+
+    fn fixed_price(&mut self, fee: &TariffFixedType, timestamp: DateTime<Utc>) -> PriceType {
+        let mut cost = Decimal::ZERO;
+        for element in &fee.prices {
+            let applies = element
+                .conditions
+                .as_ref()
+                .map_or(Some(true), |conditions| {
+                    applies_fixed(conditions, self.context, timestamp)
+                });
+            match applies {
+                Some(true) => {
+                    cost = element.price_fixed;
+                    break;
+                }
+                Some(false) => {}
+                None => self.fail("invalid tariff conditions"),
+            }
+        }
+        self.price(cost, fee.tax_rates.as_deref())
+    }
+
+    fn reservation_price(
+        &mut self,
+        time: &TariffTimeType,
+        reserved: DateTime<Utc>,
+        released: DateTime<Utc>,
+    ) -> PriceType {
+        let state = TariffState {
+            timestamp: reserved,
+            energy: Decimal::ZERO,
+            power: None,
+            current: None,
+            time: 0,
+            charging_time: 0,
+            idle_time: 0,
+        };
+        let minutes =
+            Decimal::from((released - reserved).num_milliseconds().max(0)) / Decimal::from(60_000);
+        let cost = match self.select_time(time, &state) {
+            Some(index) => {
+                let cost = time.prices[index].price_minute.checked_mul(minutes);
+                self.add(Decimal::ZERO, cost)
+            }
+            None => Decimal::ZERO,
+        };
+        self.price(cost, time.tax_rates.as_deref())
+    }
+}
+
+/// Ends of the evaluation steps between `start` and `end`: every whole minute, every change
+/// recorded in the session and every transaction duration on which a condition of the
+/// tariff depends.
+fn steps(
+    tariff: &TariffType,
+    session: &TariffSession,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> BTreeSet<DateTime<Utc>> {
+    let time_prices = [&tariff.charging_time, &tariff.idle_time]
+        .into_iter()
+        .flatten()
+        .flat_map(|time| time.prices.iter().map(|price| price.conditions.as_ref()));
+    let energy_prices = tariff
+        .energy
+        .iter()
+        .flat_map(|energy| energy.prices.iter().map(|price| price.conditions.as_ref()));
+    let durations = time_prices
+        .chain(energy_prices)
+        .flatten()
+        .flat_map(|conditions| [conditions.min_time, conditions.max_time])
+        .flatten()
+        .filter_map(|seconds| start.checked_add_signed(Duration::seconds(seconds.into())));
+
+    let mut steps: BTreeSet<_> = session
+        .change_points()
+        .chain(durations)
+        .filter(|time| *time > start && *time < end)
+        .collect();
+    if end > start {
+        let mut minute =
+            start.duration_trunc(Duration::minutes(1)).unwrap_or(start) + Duration::minutes(1);
+        while minute < end {
+            steps.insert(minute);
+            minute += Duration::minutes(1);
+        }
+        steps.insert(end);
+    }
+    steps
+}
+
+fn dimension(type_: CostDimensionEnumType, volume: Decimal) -> CostDimensionType {
+    CostDimensionType {
+        type_,
+        volume,
+        custom_data: None,
+    }
+}
+
+fn widen(range: Option<(Decimal, Decimal)>, value: Option<Decimal>) -> Option<(Decimal, Decimal)> {
+    match (range, value) {
+        (Some((min, max)), Some(value)) => Some((min.min(value), max.max(value))),
+        (None, Some(value)) => Some((value, value)),
+        (range, None) => range,
+    }
+}
+
+fn seconds(seconds: i64) -> i32 {
+    i32::try_from(seconds).unwrap_or(i32::MAX)
+}
+
+/// Cost `excl_tax` with the taxes of `tax_rates` added.
+///
+/// Taxes of stack 0 are calculated on the net price, taxes of a higher stack on the price
+/// including the taxes of the lower stacks. Returns `None` when out of range.
+pub(crate) fn incl_tax(excl_tax: Decimal, tax_rates: &[TaxRateType]) -> Option<Decimal> {
+    let stacks: BTreeSet<i32> = tax_rates
+        .iter()
+        .map(|tax_rate| tax_rate.stack.unwrap_or(0))
+        .collect();
+    stacks.into_iter().try_fold(excl_tax, |base, stack| {
+        tax_rates
+            .iter()
+            .filter(|tax_rate| tax_rate.stack.unwrap_or(0) == stack)
+            .map(|tax_rate| Some(base.checked_mul(tax_rate.tax)? / Decimal::ONE_HUNDRED))
+            .try_fold(base, |total, tax| total.checked_add(tax?))
+    })
+}
+
+/// Limits `total` to the minimum and maximum cost of `tariff`.
+///
+/// The limits are compared excluding tax when the limit has a price excluding tax, and
+/// including tax otherwise.
+fn limit(total: TotalPriceType, tariff: &TariffType) -> (TotalPriceType, TariffCostEnumType) {
+    let compare = |bound: &PriceType| match (bound.excl_tax, bound.incl_tax) {
+        (Some(excl_tax), _) => total.excl_tax.map(|total| total.cmp(&excl_tax)),
+        (None, Some(incl_tax)) => total.incl_tax.map(|total| total.cmp(&incl_tax)),
+        (None, None) => None,
+    };
+    let bound = |bound: &PriceType| TotalPriceType {
+        excl_tax: bound.excl_tax,
+        incl_tax: bound.incl_tax.or_else(|| {
+            bound.excl_tax.and_then(|excl_tax| {
+                incl_tax(excl_tax, bound.tax_rates.as_deref().unwrap_or_default())
+            })
+        }),
+        custom_data: None,
+    };
+
+    if let Some(min_cost) = &tariff.min_cost {
+        if compare(min_cost).is_some_and(|ordering| ordering.is_lt()) {
+            return (bound(min_cost), TariffCostEnumType::MinCost);
+        }
+    }
+    if let Some(max_cost) = &tariff.max_cost {
+        if compare(max_cost).is_some_and(|ordering| ordering.is_gt()) {
+            return (bound(max_cost), TariffCostEnumType::MaxCost);
+        }
+    }
+    (total, TariffCostEnumType::NormalCost)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::{
+        components::tariff::MeterReading,
+        v2_1::datatypes::{
+            TariffEnergyPriceType, TariffEnergyType, TariffFixedPriceType, TariffTimePriceType,
+        },
+    };
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn tariff() -> TariffType {
+        TariffType::new("T1".to_string(), "EUR".to_string())
+            .with_energy(
+                TariffEnergyType::new(vec![TariffEnergyPriceType::new(dec!(0.30))])
+                    .with_tax_rates(vec![TaxRateType::new(dec!(20), "VAT".to_string())]),
+            )
+            .with_charging_time(TariffTimeType::new(vec![TariffTimePriceType::new(dec!(
+                0.02
+            ))]))
+            .with_idle_time(TariffTimeType::new(vec![TariffTimePriceType::new(dec!(
+                0.10
+            ))]))
+            .with_fixed_fee(TariffFixedType::new(vec![TariffFixedPriceType::new(dec!(
+                1.00
+            ))]))
+    }
+
+    /// Charges 10 kWh in one hour, then stays connected for half an hour.
+    fn session() -> TariffSession {
+        let mut session = TariffSession::new(start());
+        session.add_reading(MeterReading::new(start()).with_energy(dec!(50000)));
+        session
+            .add_reading(MeterReading::new(start() + Duration::hours(1)).with_energy(dec!(60000)));
+        session.set_charging(start(), true);
+        session.set_charging(start() + Duration::hours(1), false);
+        session
+    }
+
+    fn finished_session() -> TariffSession {
+        let mut session = session();
+        session.stop(start() + Duration::minutes(90));
+        session
+    }
+
+    #[test]
+    fn session_cost_per_dimension() {
+        let details = cost_details(&tariff(), &finished_session(), start());
+        let total_cost = &details.total_cost;
+
+        assert_eq!(details.failure_to_calculate, None);
+        assert_eq!(total_cost.type_of_cost, TariffCostEnumType::NormalCost);
+        let energy = total_cost.energy.as_ref().unwrap();
+        assert_eq!(energy.excl_tax, Some(dec!(3.00)));
+        assert_eq!(energy.incl_tax, Some(dec!(3.60)));
+        assert_eq!(
+            total_cost.charging_time.as_ref().unwrap().excl_tax,
+            Some(dec!(1.20))
+        );
+        assert_eq!(
+            total_cost.idle_time.as_ref().unwrap().excl_tax,
+            Some(dec!(3.00))
+        );
+        assert_eq!(
+            total_cost.fixed.as_ref().unwrap().excl_tax,
+            Some(dec!(1.00))
+        );
+        assert_eq!(total_cost.reservation_time, None);
+        assert_eq!(total_cost.total.excl_tax, Some(dec!(8.20)));
+        assert_eq!(total_cost.total.incl_tax, Some(dec!(8.80)));
+
+        let usage = &details.total_usage;
+        assert_eq!(usage.energy, dec!(10));
+        assert_eq!(usage.charging_time, 5400);
+        assert_eq!(usage.idle_time, 1800);
+
+        let periods = details.charging_periods.unwrap();
+        assert_eq!(periods.len(), 1);
+        assert_eq!(periods[0].start_period, start());
+        assert_eq!(
+            periods[0].dimensions.as_ref().unwrap()[..3],
+            [
+                dimension(CostDimensionEnumType::Energy, dec!(10000)),
+                dimension(CostDimensionEnumType::ChargingTime, dec!(3600)),
+                dimension(CostDimensionEnumType::IdleTime, dec!(1800)),
+            ]
+        );
+    }
+
+    #[test]
+    fn running_cost_is_calculated_up_to_now() {
+        let details = cost_details(&tariff(), &session(), start() + Duration::minutes(30));
+
+        assert_eq!(details.total_usage.energy, dec!(5));
+        assert_eq!(
+            details.total_cost.charging_time.unwrap().excl_tax,
+            Some(dec!(0.60))
+        );
+        assert_eq!(details.total_cost.total.excl_tax, Some(dec!(3.10)));
+    }
+
+    #[test]
+    fn stacked_taxes() {
+        let tax_rates = [
+            TaxRateType::new(dec!(10), "Federal".to_string()),
+            TaxRateType::new(dec!(5), "State".to_string()).with_stack(1),
+        ];
+        assert_eq!(incl_tax(dec!(100), &tax_rates), Some(dec!(115.5)));
+        assert_eq!(incl_tax(dec!(100), &[]), Some(dec!(100)));
+        assert_eq!(incl_tax(Decimal::MAX, &tax_rates), None);
+    }
+
+    #[test]
+    fn total_is_limited_to_min_and_max_cost() {
+        let tariff = tariff().with_min_cost(PriceType::new(dec!(10), false));
+        let details = cost_details(&tariff, &finished_session(), start());
+        assert_eq!(details.total_cost.type_of_cost, TariffCostEnumType::MinCost);
+        assert_eq!(details.total_cost.total.excl_tax, Some(dec!(10)));
+
+        let tariff = tariff.with_max_cost(PriceType::new(dec!(5), true));
+        let mut session = session();
+        session.stop(start() + Duration::hours(5));
+        let details = cost_details(&tariff, &session, start());
+        assert_eq!(details.total_cost.type_of_cost, TariffCostEnumType::MaxCost);
+        assert_eq!(details.total_cost.total.incl_tax, Some(dec!(5)));
+    }
+
+    #[test]
+    fn reservation_is_charged() {
+        let tariff = tariff()
+            .with_reservation_time(TariffTimeType::new(vec![TariffTimePriceType::new(dec!(
+                0.05
+            ))]))
+            .with_reservation_fixed(TariffFixedType::new(vec![TariffFixedPriceType::new(dec!(
+                2
+            ))]));
+        let session = finished_session().with_reservation(start() - Duration::minutes(30), start());
+        let details = cost_details(&tariff, &session, start());
+
+        let total_cost = &details.total_cost;
+        assert_eq!(
+            total_cost.reservation_time.as_ref().unwrap().excl_tax,
+            Some(dec!(1.5))
+        );
+        assert_eq!(
+            total_cost.reservation_fixed.as_ref().unwrap().excl_tax,
+            Some(dec!(2))
+        );
+        assert_eq!(total_cost.total.excl_tax, Some(dec!(11.70)));
+        assert_eq!(details.total_usage.reservation_time, Some(1800));
+    }
+
+    #[test]
+    fn failures_are_reported() {
+        let mut session = TariffSession::new(start());
+        session.stop(start() + Duration::minutes(10));
+        let tariff = tariff().with_valid_from(start() + Duration::days(1));
+        let details = cost_details(&tariff, &session, start());
+
+        assert_eq!(details.failure_to_calculate, Some(true));
+        assert_eq!(
+            details.failure_reason.as_deref(),
+            Some("tariff is not valid at the start of the transaction; no energy meter values")
+        );
+        // the time based cost is still calculated
+        assert_eq!(details.total_cost.total.excl_tax, Some(dec!(2.00)));
+    }
+
+    #[test]
+    fn oversized_prices_are_reported() {
+        let tariff = tariff().with_energy(TariffEnergyType::new(vec![TariffEnergyPriceType::new(
+            Decimal::MAX,
+        )]));
+        let details = cost_details(&tariff, &finished_session(), start());
+
+        assert_eq!(details.failure_to_calculate, Some(true));
+        assert_eq!(details.failure_reason.as_deref(), Some("cost out of range"));
+        assert_eq!(details.total_usage.energy, dec!(10));
+    }
+
+    #[test]
+    fn conditional_elements_start_new_periods() {
+        let tariff = tariff().with_energy(TariffEnergyType::new(vec![
+            TariffEnergyPriceType::new(dec!(0.40))
+                .with_conditions(TariffConditionsType::new().with_max_time(1800)),
+            TariffEnergyPriceType::new(dec!(0.30)),
+        ]));
+        let details = cost_details(&tariff, &finished_session(), start());
+
+        // 5 kWh at 0.40 during the first half hour and 5 kWh at 0.30 after
+        let energy = details.total_cost.energy.unwrap();
+        assert_eq!(energy.excl_tax, Some(dec!(3.50)));
+        let periods = details.charging_periods.unwrap();
+        assert_eq!(periods.len(), 2);
+        assert_eq!(periods[1].start_period, start() + Duration::minutes(30));
+    }
+
+    #[test]
+    fn invalid_conditions_are_reported() {
+        let tariff =
+            tariff().with_idle_time(TariffTimeType::new(vec![TariffTimePriceType::new(dec!(1))
+                .with_conditions(
+                    TariffConditionsType::new().with_start_time_of_day("noon".to_string()),
+                )]));
+        let details = cost_details(&tariff, &finished_session(), start());
+        assert_eq!(
+            details.failure_reason.as_deref(),
+            Some("invalid tariff conditions")
+        );
+        assert_eq!(
+            details.total_cost.idle_time.unwrap().excl_tax,
+            Some(dec!(0))
+        );
+    }
+}
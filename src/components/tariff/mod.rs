@@ -0,0 +1,101 @@
+//! # Tariff
+//!
+//! Local cost calculation of an OCPP 2.1 `TariffType`.
+//!
+//! A [`TariffSession`] records what happened during a transaction: the meter readings, the
+//! changes of the charging state and an optional preceding reservation. [`cost_details`]
+//! applies a tariff to such a session and produces the `CostDetailsType` to report in the
+//! final `TransactionEventRequest`, with the cost split into charging periods, the total
+//! cost per tariff dimension and the total usage.
+//!
+//! Which price element of a tariff applies at an instant is decided by [`applies`] and
+//! [`applies_fixed`], from the [`TariffState`] of the transaction at that instant and the
+//! [`TariffContext`] of the session.
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::v2_1::enumerations::EvseKindEnumType;
+
+mod conditions;
+mod cost;
+mod session;
+
+pub use conditions::{applies, applies_fixed};
+pub use cost::cost_details;
+pub use session::{MeterReading, TariffSession};
+
+/// State of a transaction at an instant, against which tariff conditions are evaluated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TariffState {
+    /// The instant.
+    pub timestamp: DateTime<Utc>,
+    /// Energy in Wh transferred since the start of the transaction.
+    pub energy: Decimal,
+    /// Power in W, when known.
+    pub power: Option<Decimal>,
+    /// Sum of the current over all phases in A, when known.
+    pub current: Option<Decimal>,
+    /// Seconds since the start of the transaction.
+    pub time: i64,
+    /// Seconds the EV has been charging since the start of the transaction.
+    pub charging_time: i64,
+    /// Seconds the EV has not been charging since the start of the transaction.
+    pub idle_time: i64,
+}
+
+/// Properties of a session that tariff conditions depend on and that do not change during
+/// the session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TariffContext {
+    /// Offset of the local time of the Charging Station, in which times of day, days of week
+    /// and dates of conditions are evaluated.
+    pub utc_offset: FixedOffset,
+    /// Type of the EVSE of the transaction.
+    pub evse_kind: Option<EvseKindEnumType>,
+    /// Payment brand used for the transaction, e.g. "Visa".
+    pub payment_brand: Option<String>,
+    /// Way the payment was recognized, e.g. "Debit".
+    pub payment_recognition: Option<String>,
+}
+
+impl TariffContext {
+    /// Creates a context in UTC without EVSE kind or payment details.
+    pub fn new() -> Self {
+        Self {
+            utc_offset: FixedOffset::east_opt(0).expect("zero offset is valid"),
+            evse_kind: None,
+            payment_brand: None,
+            payment_recognition: None,
+        }
+    }
+
+    /// Sets the offset of the local time.
+    pub fn with_utc_offset(mut self, utc_offset: FixedOffset) -> Self {
+        self.utc_offset = utc_offset;
+        self
+    }
+
+    /// Sets the type of the EVSE.
+    pub fn with_evse_kind(mut self, evse_kind: EvseKindEnumType) -> Self {
+        self.evse_kind = Some(evse_kind);
+        self
+    }
+
+    /// Sets the payment brand and the way the payment was recognized.
+    pub fn with_payment(mut self, brand: String, recognition: String) -> Self {
+        self.payment_brand = Some(brand);
+        self.payment_recognition = Some(recognition);
+        self
+    }
+
+    pub(crate) fn local_time(&self, timestamp: DateTime<Utc>) -> NaiveDateTime {
+        timestamp.with_timezone(&self.utc_offset).naive_local()
+    }
+}
+
+impl Default for TariffContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
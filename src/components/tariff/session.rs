@@ -0,0 +1,370 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+
+use crate::v2_1::{
+    datatypes::SampledValueType,
+    enumerations::{
+        ChargingStateEnumType, LocationEnumType, MeasurandEnumType, TransactionEventEnumType,
+    },
+    messages::transaction_event::TransactionEventRequest,
+};
+
+use super::TariffContext;
+
+/// Meter values sampled at one instant, in Wh, W and A.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeterReading {
+    /// When the values were sampled.
+    pub timestamp: DateTime<Utc>,
+    /// Value of the `Energy.Active.Import.Register` in Wh.
+    pub energy: Option<Decimal>,
+    /// `Power.Active.Import` in W.
+    pub power: Option<Decimal>,
+    /// `Current.Import` in A, the value sent without phase. Values per phase are ignored.
+    pub current: Option<Decimal>,
+}
+
+impl MeterReading {
+    /// Creates a reading without values.
+    pub fn new(timestamp: DateTime<Utc>) -> Self {
+        Self {
+            timestamp,
+            energy: None,
+            power: None,
+            current: None,
+        }
+    }
+
+    /// Sets the energy register in Wh.
+    pub fn with_energy(mut self, energy: Decimal) -> Self {
+        self.energy = Some(energy);
+        self
+    }
+
+    /// Sets the power in W.
+    pub fn with_power(mut self, power: Decimal) -> Self {
+        self.power = Some(power);
+        self
+    }
+
+    /// Sets the current in A.
+    pub fn with_current(mut self, current: Decimal) -> Self {
+        self.current = Some(current);
+        self
+    }
+
+    fn is_empty(&self) -> bool {
+        self.energy.is_none() && self.power.is_none() && self.current.is_none()
+    }
+}
+
+/// Meter readings and charging state changes of a transaction.
+///
+/// The energy transferred is derived from the energy register relative to its first reading
+/// and interpolated linearly between readings. When no charging state has been recorded yet,
+/// the EV is considered to be charging while energy is transferred.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TariffSession {
+    start: DateTime<Utc>,
+    end: Option<DateTime<Utc>>,
+    readings: Vec<MeterReading>,
+    charging: Vec<(DateTime<Utc>, bool)>,
+    reservation: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    context: TariffContext,
+}
+
+impl TariffSession {
+    /// Creates a session for a transaction started at `start`.
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            start,
+            end: None,
+            readings: Vec::new(),
+            charging: Vec::new(),
+            reservation: None,
+            context: TariffContext::new(),
+        }
+    }
+
+    /// Sets the context in which tariff conditions are evaluated.
+    pub fn with_context(mut self, context: TariffContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Sets the reservation that preceded the transaction.
+    pub fn with_reservation(mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        self.reservation = Some((start, end));
+        self
+    }
+
+    /// Start of the transaction.
+    pub fn start(&self) -> DateTime<Utc> {
+        self.start
+    }
+
+    /// End of the transaction, `None` while it is ongoing.
+    pub fn end(&self) -> Option<DateTime<Utc>> {
+        self.end
+    }
+
+    /// Start and end of the reservation that preceded the transaction.
+    pub fn reservation(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        self.reservation
+    }
+
+    /// Context in which tariff conditions are evaluated.
+    pub fn context(&self) -> &TariffContext {
+        &self.context
+    }
+
+    /// Meter readings ordered by time.
+    pub fn readings(&self) -> &[MeterReading] {
+        &self.readings
+    }
+
+    /// Adds a meter reading. Values of a reading at the same instant as an earlier reading
+    /// are merged into it.
+    pub fn add_reading(&mut self, reading: MeterReading) {
+        let index = self
+            .readings
+            .partition_point(|existing| existing.timestamp < reading.timestamp);
+        match self.readings.get_mut(index) {
+            Some(existing) if existing.timestamp == reading.timestamp => {
+                existing.energy = reading.energy.or(existing.energy);
+                existing.power = reading.power.or(existing.power);
+                existing.current = reading.current.or(existing.current);
+            }
+            _ => self.readings.insert(index, reading),
+        }
+    }
+
+    /// Records whether the EV is charging from `timestamp` on.
+    pub fn set_charging(&mut self, timestamp: DateTime<Utc>, charging: bool) {
+        let index = self
+            .charging
+            .partition_point(|(existing, _)| *existing <= timestamp);
+        self.charging.insert(index, (timestamp, charging));
+    }
+
+    /// Ends the transaction.
+    pub fn stop(&mut self, timestamp: DateTime<Utc>) {
+        self.end = Some(timestamp);
+    }
+
+    /// Records the meter values and charging state of a `TransactionEventRequest`, and ends
+    /// the session on an `Ended` event.
+    ///
+    /// Only values of the `Outlet` location without phase are used. Energy, power and
+    /// current are converted to Wh, W and A; values in other units are ignored.
+    pub fn record_transaction_event(&mut self, event: &TransactionEventRequest) {
+        for meter_value in &event.meter_value {
+            let mut reading = MeterReading::new(meter_value.timestamp);
+            for sampled_value in &meter_value.sampled_value {
+                record_sampled_value(&mut reading, sampled_value);
+            }
+            if !reading.is_empty() {
+                self.add_reading(reading);
+            }
+        }
+        if let Some(state) = &event.transaction_info.charging_state {
+            self.set_charging(event.timestamp, *state == ChargingStateEnumType::Charging);
+        }
+        if event.event_type == TransactionEventEnumType::Ended {
+            self.stop(event.timestamp);
+        }
+    }
+
+    /// Returns `true` when the energy register decreased between two readings.
+    pub(crate) fn energy_decreases(&self) -> bool {
+        let mut energies = self.readings.iter().filter_map(|reading| reading.energy);
+        let Some(mut previous) = energies.next() else {
+            return false;
+        };
+        energies.any(|energy| {
+            let decreased = energy < previous;
+            previous = energy;
+            decreased
+        })
+    }
+
+    /// Returns `true` when at least one energy register value has been recorded.
+    pub(crate) fn has_energy(&self) -> bool {
+        self.readings.iter().any(|reading| reading.energy.is_some())
+    }
+
+    /// Energy in Wh transferred between the first energy reading and `timestamp`, `None`
+    /// when out of range.
+    pub(crate) fn energy_at(&self, timestamp: DateTime<Utc>) -> Option<Decimal> {
+        let mut energies = self
+            .readings
+            .iter()
+            .filter_map(|reading| reading.energy.map(|energy| (reading.timestamp, energy)));
+        let Some((first_time, baseline)) = energies.next() else {
+            return Some(Decimal::ZERO);
+        };
+        if timestamp <= first_time {
+            return Some(Decimal::ZERO);
+        }
+        let mut previous = (first_time, baseline);
+        for (time, energy) in energies {
+            if timestamp < time {
+                let elapsed = (timestamp - previous.0).num_milliseconds();
+                let span = (time - previous.0).num_milliseconds();
+                let delta = energy
+                    .checked_sub(previous.1)?
+                    .checked_mul(Decimal::from(elapsed))?
+                    / Decimal::from(span);
+                return previous.1.checked_add(delta)?.checked_sub(baseline);
+            }
+            previous = (time, energy);
+        }
+        previous.1.checked_sub(baseline)
+    }
+
+    /// Recorded charging state at `timestamp`, if any.
+    pub(crate) fn charging_at(&self, timestamp: DateTime<Utc>) -> Option<bool> {
+        self.charging
+            .iter()
+            .take_while(|(time, _)| *time <= timestamp)
+            .last()
+            .map(|(_, charging)| *charging)
+    }
+
+    /// Latest power and current sampled at or before `timestamp`.
+    pub(crate) fn power_and_current_at(
+        &self,
+        timestamp: DateTime<Utc>,
+    ) -> (Option<Decimal>, Option<Decimal>) {
+        let earlier = self
+            .readings
+            .iter()
+            .take_while(|reading| reading.timestamp <= timestamp);
+        let (mut power, mut current) = (None, None);
+        for reading in earlier {
+            power = reading.power.or(power);
+            current = reading.current.or(current);
+        }
+        (power, current)
+    }
+
+    /// Instants at which a recorded value or state changes.
+    pub(crate) fn change_points(&self) -> impl Iterator<Item = DateTime<Utc>> + '_ {
+        self.readings
+            .iter()
+            .map(|reading| reading.timestamp)
+            .chain(self.charging.iter().map(|(time, _)| *time))
+    }
+}
+
+fn record_sampled_value(reading: &mut MeterReading, sampled_value: &SampledValueType) {
+    let outlet = sampled_value
+        .location
+        .as_ref()
+        .is_none_or(|location| *location == LocationEnumType::Outlet);
+    if !outlet || sampled_value.phase.is_some() {
+        return;
+    }
+    let Some(value) = Decimal::from_f64(sampled_value.value) else {
+        return;
+    };
+    let (unit, multiplier) = sampled_value
+        .unit_of_measure
+        .as_ref()
+        .map_or((None, 0), |unit| {
+            (Some(unit.unit.as_str()), unit.multiplier)
+        });
+    let power = match multiplier {
+        0.. => 10_i64
+            .checked_pow(multiplier.unsigned_abs())
+            .map(Decimal::from),
+        _ => Decimal::try_new(1, multiplier.unsigned_abs()).ok(),
+    };
+    let measurand = sampled_value
+        .measurand
+        .clone()
+        .unwrap_or(MeasurandEnumType::EnergyActiveImportRegister);
+    let (field, scale) = match (measurand, unit) {
+        (MeasurandEnumType::EnergyActiveImportRegister, None | Some("Wh")) => {
+            (&mut reading.energy, Decimal::ONE)
+        }
+        (MeasurandEnumType::EnergyActiveImportRegister, Some("kWh")) => {
+            (&mut reading.energy, Decimal::ONE_THOUSAND)
+        }
+        (MeasurandEnumType::PowerActiveImport, None | Some("W")) => {
+            (&mut reading.power, Decimal::ONE)
+        }
+        (MeasurandEnumType::PowerActiveImport, Some("kW")) => {
+            (&mut reading.power, Decimal::ONE_THOUSAND)
+        }
+        (MeasurandEnumType::CurrentImport, None | Some("A")) => {
+            (&mut reading.current, Decimal::ONE)
+        }
+        _ => return,
+    };
+    if let Some(value) = power
+        .and_then(|power| value.checked_mul(power))
+        .and_then(|value| value.checked_mul(scale))
+    {
+        *field = Some(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::v2_1::{datatypes::UnitOfMeasureType, enumerations::PhaseEnumType};
+
+    #[test]
+    fn energy_is_interpolated_between_readings() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let mut session = TariffSession::new(start);
+        session.add_reading(MeterReading::new(start).with_energy(dec!(1000)));
+        session
+            .add_reading(MeterReading::new(start + Duration::minutes(10)).with_energy(dec!(3000)));
+
+        assert_eq!(
+            session.energy_at(start - Duration::minutes(1)),
+            Some(dec!(0))
+        );
+        assert_eq!(
+            session.energy_at(start + Duration::minutes(5)),
+            Some(dec!(1000))
+        );
+        assert_eq!(
+            session.energy_at(start + Duration::minutes(20)),
+            Some(dec!(2000))
+        );
+        assert!(!session.energy_decreases());
+    }
+
+    #[test]
+    fn transaction_event_values_are_converted() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let mut energy = SampledValueType::new(1.5);
+        energy.unit_of_measure = Some(UnitOfMeasureType::new_with_unit("kWh".to_string()));
+        let power = SampledValueType::new(7.2)
+            .with_measurand(MeasurandEnumType::PowerActiveImport)
+            .with_unit_of_measure(UnitOfMeasureType::new_with_unit("kW".to_string()));
+        let phase_current = SampledValueType::new(16.0)
+            .with_measurand(MeasurandEnumType::CurrentImport)
+            .with_phase(PhaseEnumType::L1);
+
+        let mut reading = MeterReading::new(start);
+        for sampled_value in [&energy, &power, &phase_current] {
+            record_sampled_value(&mut reading, sampled_value);
+        }
+        assert_eq!(reading.energy, Some(dec!(1500)));
+        assert_eq!(reading.power, Some(dec!(7200)));
+        assert_eq!(reading.current, None);
+
+        let large = SampledValueType::new(1e20).with_unit_of_measure(
+            UnitOfMeasureType::new_with_unit("kWh".to_string()).with_multiplier(9),
+        );
+        record_sampled_value(&mut reading, &large);
+        assert_eq!(reading.energy, Some(dec!(1500)));
+    }
+}
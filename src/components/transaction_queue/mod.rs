@@ -0,0 +1,112 @@
+//! # Transaction queue
+//!
+//! Queue of the transaction-related messages of a Charging Station, shared by OCPP 1.6
+//! (`StartTransaction`, `StopTransaction` and `MeterValues` of a transaction) and 2.x
+//! (`TransactionEvent`), which must reach the CSMS in order even when the connection is lost.
+//!
+//! Every message goes through the [`TransactionQueue`] and is kept in a [`QueueStore`],
+//! a file by default, until the CSMS answers it, so queued messages survive a reboot. The
+//! oldest message is sent first and the next one only once it has been answered; after a
+//! reconnect the queue simply resumes with its oldest message. A message the CSMS answers
+//! with an error is retried with the [`RetryPolicy`] set by `TransactionMessageAttempts` and
+//! `TransactionMessageRetryInterval` (`MessageAttempts` and `MessageAttemptInterval` of
+//! the `OCPPCommCtrlr` in 2.x), waiting twice as long after each failed attempt, and
+//! dropped after the last attempt.
+//!
+//! A 1.6 transaction started while offline has no `transactionId` until its
+//! `StartTransaction` is answered. The [`v1_6`] module gives it a provisional negative id to
+//! use in its `StopTransaction` and `MeterValues`, replaced in the queue by the id of the
+//! `StartTransactionResponse`. In 2.x, `TransactionEvent`s queued while offline are marked
+//! `offline`.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+mod queue;
+mod store;
+
+#[cfg(feature = "v1_6")]
+pub mod v1_6;
+#[cfg(feature = "v2_0_1")]
+pub mod v2_0_1;
+#[cfg(feature = "v2_1")]
+pub mod v2_1;
+
+pub use queue::TransactionQueue;
+pub use store::{FileStore, MemoryStore, QueueStore};
+
+/// A message waiting in the [`TransactionQueue`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedMessage {
+    /// Identifies the message in the queue, increasing in queue order.
+    pub id: u64,
+    /// OCPP action, e.g. `StartTransaction`.
+    pub action: String,
+    /// The request as JSON.
+    pub payload: serde_json::Value,
+    /// Number of failed attempts.
+    pub attempts: u32,
+    /// Earliest time of the next attempt, after a failed one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+/// Attempts of a transaction-related message the CSMS answers with an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of attempts, including the first one.
+    pub attempts: u32,
+    /// Wait after the first failed attempt, doubled after each following one.
+    pub interval: Duration,
+}
+
+impl RetryPolicy {
+    /// Creates the policy of the `TransactionMessageAttempts` and
+    /// `TransactionMessageRetryInterval` (seconds) configuration values. A message is
+    /// attempted at least once, negative intervals count as zero.
+    pub fn new(attempts: i32, retry_interval: i32) -> Self {
+        Self {
+            attempts: attempts.max(1) as u32,
+            interval: Duration::seconds(retry_interval.max(0).into()),
+        }
+    }
+
+    /// Wait after `attempts` failed attempts, `None` when no attempt is left.
+    pub fn delay(&self, attempts: u32) -> Option<Duration> {
+        match attempts {
+            0 => Some(Duration::zero()),
+            attempts if attempts >= self.attempts => None,
+            attempts => Some(
+                self.interval
+                    .checked_mul(2_i32.saturating_pow(attempts - 1))
+                    .unwrap_or(Duration::MAX),
+            ),
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Three attempts, 60 seconds apart at first.
+    fn default() -> Self {
+        Self::new(3, 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delays_double() {
+        let policy = RetryPolicy::new(4, 10);
+        assert_eq!(policy.delay(0), Some(Duration::zero()));
+        assert_eq!(policy.delay(1), Some(Duration::seconds(10)));
+        assert_eq!(policy.delay(2), Some(Duration::seconds(20)));
+        assert_eq!(policy.delay(3), Some(Duration::seconds(40)));
+        assert_eq!(policy.delay(4), None);
+
+        assert_eq!(RetryPolicy::new(0, -5).delay(1), None);
+        assert_eq!(RetryPolicy::new(0, -5).interval, Duration::zero());
+    }
+}
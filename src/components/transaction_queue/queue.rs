@@ -0,0 +1,245 @@
+use std::{collections::VecDeque, io};
+
+use chrono::{DateTime, Utc};
+
+use super::{FileStore, QueueStore, QueuedMessage, RetryPolicy};
+
+/// Ordered, persistent queue of transaction-related messages.
+///
+/// Every change is saved to the store before it returns.
+#[derive(Debug)]
+pub struct TransactionQueue<S: QueueStore = FileStore> {
+    store: S,
+    policy: RetryPolicy,
+    messages: VecDeque<QueuedMessage>,
+    next_id: u64,
+}
+
+impl<S: QueueStore> TransactionQueue<S> {
+    /// Opens the queue kept in `store`, with the messages left from before.
+    pub fn open(mut store: S, policy: RetryPolicy) -> io::Result<Self> {
+        let messages: VecDeque<_> = store.load()?.into();
+        let next_id = messages.iter().map(|message| message.id + 1).max();
+        Ok(Self {
+            store,
+            policy,
+            messages,
+            next_id: next_id.unwrap_or(1),
+        })
+    }
+
+    /// The retry policy.
+    pub fn policy(&self) -> RetryPolicy {
+        self.policy
+    }
+
+    /// Changes the retry policy, e.g. when the CSMS changes its configuration.
+    pub fn set_policy(&mut self, policy: RetryPolicy) {
+        self.policy = policy;
+    }
+
+    /// The queued messages, oldest first.
+    pub fn messages(&self) -> impl Iterator<Item = &QueuedMessage> {
+        self.messages.iter()
+    }
+
+    /// Number of queued messages.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns `true` when no message is queued.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Queues a request for `action`, returning its id.
+    pub fn push(&mut self, action: &str, payload: serde_json::Value) -> io::Result<u64> {
+        let id = self.next_id;
+        self.messages.push_back(QueuedMessage {
+            id,
+            action: action.to_string(),
+            payload,
+            attempts: 0,
+            retry_at: None,
+        });
+        if let Err(error) = self.save() {
+            self.messages.pop_back();
+            return Err(error);
+        }
+        self.next_id += 1;
+        Ok(id)
+    }
+
+    /// The message to send at `now`: the oldest one, unless it waits for a retry.
+    pub fn next(&self, now: DateTime<Utc>) -> Option<&QueuedMessage> {
+        self.messages
+            .front()
+            .filter(|message| message.retry_at.is_none_or(|retry_at| retry_at <= now))
+    }
+
+    /// Removes message `id` once the CSMS answered it.
+    pub fn acknowledge(&mut self, id: u64) -> io::Result<Option<QueuedMessage>> {
+        let message = self.remove(id);
+        if message.is_some() {
+            self.save()?;
+        }
+        Ok(message)
+    }
+
+    /// Removes message `id` once the CSMS answered it and replaces the `transactionId` `from`
+    /// by `to` in the remaining requests, saving both changes at once. Used when the
+    /// `StartTransaction` of a 1.6 transaction with a provisional id is answered.
+    pub fn acknowledge_with_transaction_id(
+        &mut self,
+        id: u64,
+        from: i32,
+        to: i32,
+    ) -> io::Result<Option<QueuedMessage>> {
+        let Some(message) = self.remove(id) else {
+            return Ok(None);
+        };
+        self.replace_ids(from, to);
+        self.save()?;
+        Ok(Some(message))
+    }
+
+    /// Records that the CSMS answered message `id` with an error at `now`, returning when
+    /// to attempt it again. The message is dropped when no attempt is left.
+    ///
+    /// A message that got no answer because the connection was lost is not a failed
+    /// attempt: it stays first in the queue to be sent again after the reconnect.
+    pub fn failed(&mut self, id: u64, now: DateTime<Utc>) -> io::Result<Option<DateTime<Utc>>> {
+        self.failed_with_transaction_id(id, None, now)
+    }
+
+    /// Records a failed attempt of message `id` like [`Self::failed`]. When the message is
+    /// dropped, the requests with `transactionId` `transaction_id` are dropped with it, in the
+    /// same save. Used for the `StartTransaction` of a 1.6 transaction with a provisional id.
+    pub fn failed_dropping_transaction(
+        &mut self,
+        id: u64,
+        transaction_id: i32,
+        now: DateTime<Utc>,
+    ) -> io::Result<Option<DateTime<Utc>>> {
+        self.failed_with_transaction_id(id, Some(transaction_id), now)
+    }
+
+    /// Replaces the `transactionId` `from` by `to` in the queued requests, returning the
+    /// number of requests changed. Used for the provisional ids of 1.6 transactions.
+    pub fn replace_transaction_id(&mut self, from: i32, to: i32) -> io::Result<usize> {
+        let replaced = self.replace_ids(from, to);
+        if replaced > 0 {
+            self.save()?;
+        }
+        Ok(replaced)
+    }
+
+    fn failed_with_transaction_id(
+        &mut self,
+        id: u64,
+        transaction_id: Option<i32>,
+        now: DateTime<Utc>,
+    ) -> io::Result<Option<DateTime<Utc>>> {
+        let Some(message) = self.messages.iter_mut().find(|message| message.id == id) else {
+            return Ok(None);
+        };
+        message.attempts += 1;
+        message.retry_at = self.policy.delay(message.attempts).map(|delay| {
+            now.checked_add_signed(delay)
+                .unwrap_or(DateTime::<Utc>::MAX_UTC)
+        });
+        let retry_at = message.retry_at;
+        if retry_at.is_none() {
+            self.remove(id);
+            if let Some(transaction_id) = transaction_id {
+                self.messages.retain(|message| {
+                    message
+                        .payload
+                        .get("transactionId")
+                        .is_none_or(|id| *id != transaction_id)
+                });
+            }
+        }
+        self.save()?;
+        Ok(retry_at)
+    }
+
+    fn remove(&mut self, id: u64) -> Option<QueuedMessage> {
+        let index = self.messages.iter().position(|message| message.id == id)?;
+        self.messages.remove(index)
+    }
+
+    fn replace_ids(&mut self, from: i32, to: i32) -> usize {
+        let mut replaced = 0;
+        for message in &mut self.messages {
+            if let Some(transaction_id) = message.payload.get_mut("transactionId") {
+                if *transaction_id == from {
+                    *transaction_id = to.into();
+                    replaced += 1;
+                }
+            }
+        }
+        replaced
+    }
+
+    fn save(&mut self) -> io::Result<()> {
+        self.store.save(self.messages.make_contiguous())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use serde_json::json;
+
+    use super::*;
+    use crate::components::transaction_queue::MemoryStore;
+
+    #[test]
+    fn replays_in_order_with_retries() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut queue =
+            TransactionQueue::open(MemoryStore::default(), RetryPolicy::new(2, 30)).unwrap();
+        let first = queue
+            .push("TransactionEvent", json!({ "seqNo": 0 }))
+            .unwrap();
+        let second = queue
+            .push("TransactionEvent", json!({ "seqNo": 1 }))
+            .unwrap();
+        assert_eq!(queue.next(now).unwrap().id, first);
+
+        assert_eq!(
+            queue.failed(first, now).unwrap(),
+            Some(now + Duration::seconds(30))
+        );
+        assert!(queue.next(now).is_none());
+        let later = now + Duration::seconds(30);
+        assert_eq!(queue.next(later).unwrap().id, first);
+
+        // the last attempt failed, the message is dropped
+        assert_eq!(queue.failed(first, later).unwrap(), None);
+        assert_eq!(queue.next(later).unwrap().id, second);
+        assert_eq!(queue.acknowledge(second).unwrap().unwrap().id, second);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn survives_a_reopen() {
+        let mut queue =
+            TransactionQueue::open(MemoryStore::default(), RetryPolicy::default()).unwrap();
+        queue
+            .push("StartTransaction", json!({ "connectorId": 1 }))
+            .unwrap();
+        let stop = queue
+            .push("StopTransaction", json!({ "transactionId": -1 }))
+            .unwrap();
+        assert_eq!(queue.replace_transaction_id(-1, 42).unwrap(), 1);
+
+        let mut queue = TransactionQueue::open(queue.store, RetryPolicy::default()).unwrap();
+        assert_eq!(queue.len(), 2);
+        let payloads: Vec<_> = queue.messages().map(|message| &message.payload).collect();
+        assert_eq!(payloads[1], &json!({ "transactionId": 42 }));
+        assert_eq!(queue.push("MeterValues", json!({})).unwrap(), stop + 1);
+    }
+}
@@ -0,0 +1,115 @@
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use super::QueuedMessage;
+
+/// Persistent storage of a [`TransactionQueue`](super::TransactionQueue).
+pub trait QueueStore {
+    /// Loads the queued messages, in queue order.
+    fn load(&mut self) -> io::Result<Vec<QueuedMessage>>;
+
+    /// Replaces the stored messages by `messages`.
+    fn save(&mut self, messages: &[QueuedMessage]) -> io::Result<()>;
+}
+
+/// Stores the queue as JSON in a file, written to a temporary file and synced to disk first
+/// so that a crash or power loss while saving keeps the previous content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStore {
+    path: PathBuf,
+}
+
+impl FileStore {
+    /// Creates a store in the file at `path`, which does not need to exist yet.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl QueueStore for FileStore {
+    fn load(&mut self) -> io::Result<Vec<QueuedMessage>> {
+        match fs::read(&self.path) {
+            Ok(content) => Ok(serde_json::from_slice(&content)?),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn save(&mut self, messages: &[QueuedMessage]) -> io::Result<()> {
+        let temporary = self.path.with_extension("tmp");
+        let mut file = File::create(&temporary)?;
+        file.write_all(&serde_json::to_vec(messages)?)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(temporary, &self.path)?;
+        sync_directory(&self.path)
+    }
+}
+
+/// Syncs the directory of `path` so that a rename into it survives a power loss.
+#[cfg(unix)]
+fn sync_directory(path: &Path) -> io::Result<()> {
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    File::open(directory)?.sync_all()
+}
+
+/// Directories cannot be opened to be synced on this platform.
+#[cfg(not(unix))]
+fn sync_directory(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Keeps the queue in memory only, e.g. for tests.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryStore {
+    messages: Vec<QueuedMessage>,
+}
+
+impl QueueStore for MemoryStore {
+    fn load(&mut self) -> io::Result<Vec<QueuedMessage>> {
+        Ok(self.messages.clone())
+    }
+
+    fn save(&mut self, messages: &[QueuedMessage]) -> io::Result<()> {
+        self.messages = messages.to_vec();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_store_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "rust-ocpp-transaction-queue-{}.json",
+            uuid::Uuid::new_v4()
+        ));
+        let mut store = FileStore::new(&path);
+        assert_eq!(store.load().unwrap(), Vec::new());
+
+        let messages = vec![QueuedMessage {
+            id: 1,
+            action: "StopTransaction".to_string(),
+            payload: serde_json::json!({ "transactionId": 7, "meterStop": 1200 }),
+            attempts: 1,
+            retry_at: None,
+        }];
+        store.save(&messages).unwrap();
+        assert_eq!(FileStore::new(&path).load().unwrap(), messages);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
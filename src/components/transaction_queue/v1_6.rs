@@ -0,0 +1,170 @@
+//! OCPP 1.6 messages for the [`TransactionQueue`].
+//!
+//! A queued `StartTransaction` gets a provisional negative transaction id, derived from its
+//! id in the queue, to use in the `StopTransaction` and `MeterValues` of the transaction
+//! until [`start_transaction_response`] replaces it by the id given by the Central System.
+//! A failed attempt of a `StartTransaction` is recorded with [`start_transaction_failed`],
+//! which drops the requests of the transaction along with it after the last attempt, as
+//! their provisional id would never be replaced.
+
+use std::io;
+
+use chrono::{DateTime, Utc};
+
+use crate::v1_6::messages::{
+    meter_values::MeterValuesRequest,
+    start_transaction::{StartTransactionRequest, StartTransactionResponse},
+    stop_transaction::StopTransactionRequest,
+};
+
+use super::{QueueStore, TransactionQueue};
+
+/// Provisional transaction id of the `StartTransaction` with id `message_id` in the queue.
+fn provisional_transaction_id(message_id: u64) -> i32 {
+    i32::try_from(message_id).map_or(i32::MIN, |id| -id)
+}
+
+/// Queues a `StartTransactionRequest`, returning the provisional id of the transaction.
+pub fn start_transaction<S: QueueStore>(
+    queue: &mut TransactionQueue<S>,
+    request: &StartTransactionRequest,
+) -> io::Result<i32> {
+    let id = queue.push("StartTransaction", serde_json::to_value(request)?)?;
+    Ok(provisional_transaction_id(id))
+}
+
+/// Handles the `StartTransactionResponse` to the queued message `message_id`, replacing the
+/// provisional transaction id in the queue.
+pub fn start_transaction_response<S: QueueStore>(
+    queue: &mut TransactionQueue<S>,
+    message_id: u64,
+    response: &StartTransactionResponse,
+) -> io::Result<()> {
+    queue.acknowledge_with_transaction_id(
+        message_id,
+        provisional_transaction_id(message_id),
+        response.transaction_id,
+    )?;
+    Ok(())
+}
+
+/// Records that the Central System answered the queued `StartTransaction` `message_id` with
+/// an error at `now`, returning when to attempt it again. After the last attempt the
+/// `StopTransaction` and `MeterValues` of the transaction are dropped with it.
+pub fn start_transaction_failed<S: QueueStore>(
+    queue: &mut TransactionQueue<S>,
+    message_id: u64,
+    now: DateTime<Utc>,
+) -> io::Result<Option<DateTime<Utc>>> {
+    queue.failed_dropping_transaction(message_id, provisional_transaction_id(message_id), now)
+}
+
+/// Queues a `StopTransactionRequest`, returning its id in the queue.
+pub fn stop_transaction<S: QueueStore>(
+    queue: &mut TransactionQueue<S>,
+    request: &StopTransactionRequest,
+) -> io::Result<u64> {
+    queue.push("StopTransaction", serde_json::to_value(request)?)
+}
+
+/// Queues the `MeterValuesRequest` of a transaction, returning its id in the queue.
+pub fn meter_values<S: QueueStore>(
+    queue: &mut TransactionQueue<S>,
+    request: &MeterValuesRequest,
+) -> io::Result<u64> {
+    queue.push("MeterValues", serde_json::to_value(request)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::{
+        components::transaction_queue::{MemoryStore, RetryPolicy},
+        v1_6::types::{AuthorizationStatus, IdTagInfo},
+    };
+
+    #[test]
+    fn replaces_provisional_transaction_ids() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut queue =
+            TransactionQueue::open(MemoryStore::default(), RetryPolicy::default()).unwrap();
+        let request = StartTransactionRequest {
+            connector_id: 1,
+            id_tag: "TAG1".to_string(),
+            meter_start: 100,
+            reservation_id: None,
+            timestamp: now,
+        };
+        let transaction_id = start_transaction(&mut queue, &request).unwrap();
+        assert_eq!(transaction_id, -1);
+
+        let request = MeterValuesRequest {
+            connector_id: 1,
+            transaction_id: Some(transaction_id),
+            meter_value: Vec::new(),
+        };
+        meter_values(&mut queue, &request).unwrap();
+        let request = StopTransactionRequest {
+            meter_stop: 900,
+            timestamp: now,
+            transaction_id,
+            ..Default::default()
+        };
+        stop_transaction(&mut queue, &request).unwrap();
+
+        // replayed after a reconnect
+        let start = queue.next(now).unwrap().clone();
+        assert_eq!(start.action, "StartTransaction");
+        let response = StartTransactionResponse {
+            id_tag_info: IdTagInfo {
+                status: AuthorizationStatus::Accepted,
+                ..Default::default()
+            },
+            transaction_id: 42,
+        };
+        start_transaction_response(&mut queue, start.id, &response).unwrap();
+
+        let ids: Vec<_> = queue
+            .messages()
+            .map(|message| message.payload["transactionId"].clone())
+            .collect();
+        assert_eq!(ids, vec![42, 42]);
+    }
+
+    #[test]
+    fn drops_transaction_of_failed_start() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut queue =
+            TransactionQueue::open(MemoryStore::default(), RetryPolicy::new(1, 60)).unwrap();
+        let request = StartTransactionRequest {
+            connector_id: 1,
+            id_tag: "TAG1".to_string(),
+            meter_start: 100,
+            reservation_id: None,
+            timestamp: now,
+        };
+        let transaction_id = start_transaction(&mut queue, &request).unwrap();
+        let request = StopTransactionRequest {
+            meter_stop: 900,
+            timestamp: now,
+            transaction_id,
+            ..Default::default()
+        };
+        stop_transaction(&mut queue, &request).unwrap();
+        let other = StopTransactionRequest {
+            transaction_id: 42,
+            ..request
+        };
+        let other = stop_transaction(&mut queue, &other).unwrap();
+
+        let start = queue.next(now).unwrap().id;
+        assert_eq!(
+            start_transaction_failed(&mut queue, start, now).unwrap(),
+            None
+        );
+        let ids: Vec<_> = queue.messages().map(|message| message.id).collect();
+        assert_eq!(ids, vec![other]);
+    }
+}
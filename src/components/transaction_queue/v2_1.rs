@@ -0,0 +1,53 @@
+//! OCPP 2.1 messages for the [`TransactionQueue`].
+
+use std::io;
+
+use crate::v2_1::messages::transaction_event::TransactionEventRequest;
+
+use super::{QueueStore, TransactionQueue};
+
+/// Queues a `TransactionEventRequest`, marked `offline` when it occurred while the
+/// connection to the CSMS was lost, returning its id in the queue.
+pub fn transaction_event<S: QueueStore>(
+    queue: &mut TransactionQueue<S>,
+    request: &TransactionEventRequest,
+    offline: bool,
+) -> io::Result<u64> {
+    let request = TransactionEventRequest {
+        offline: offline.then_some(true).or(request.offline),
+        ..request.clone()
+    };
+    queue.push("TransactionEvent", serde_json::to_value(request)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::{
+        components::transaction_queue::{MemoryStore, RetryPolicy},
+        v2_1::messages::{Transaction, TransactionEventEnum, TriggerReasonEnum},
+    };
+
+    #[test]
+    fn marks_offline_events() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut queue =
+            TransactionQueue::open(MemoryStore::default(), RetryPolicy::default()).unwrap();
+        let request = TransactionEventRequest::new(
+            TransactionEventEnum::Started,
+            Vec::new(),
+            now,
+            TriggerReasonEnum::Authorized,
+            0,
+            Transaction::new("TX1".to_string()),
+        );
+        let id = transaction_event(&mut queue, &request, true).unwrap();
+
+        let message = queue.next(now).unwrap();
+        assert_eq!(message.id, id);
+        assert_eq!(message.action, "TransactionEvent");
+        assert_eq!(message.payload["offline"], true);
+    }
+}
@@ -0,0 +1,463 @@
+use std::{collections::BTreeMap, mem};
+
+use chrono::{DateTime, Duration, Utc};
+
+use super::{
+    ChargingState, SessionEvent, StopReason, TransactionEvent, TransactionEventType, TriggerReason,
+    TxPoint,
+};
+
+const POINTS: [TxPoint; 6] = [
+    TxPoint::ParkingBayOccupancy,
+    TxPoint::EVConnected,
+    TxPoint::Authorized,
+    TxPoint::DataSigned,
+    TxPoint::PowerPathClosed,
+    TxPoint::EnergyTransfer,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Transaction {
+    id: String,
+    seq_no: i32,
+    charging_state: ChargingState,
+    stopped_reason: Option<StopReason>,
+    charging_since: Option<DateTime<Utc>>,
+    time_spent_charging: Duration,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Session {
+    ev_detected: bool,
+    ev_connected: bool,
+    authorized: bool,
+    data_signed: bool,
+    energy_transfer: bool,
+    suspended_by_ev: Option<bool>,
+    id_token: bool,
+    remote_start_id: Option<i32>,
+    transaction: Option<Transaction>,
+}
+
+impl Session {
+    fn met(&self, point: TxPoint) -> bool {
+        match point {
+            TxPoint::ParkingBayOccupancy => self.ev_detected,
+            TxPoint::EVConnected => self.ev_connected,
+            TxPoint::Authorized => self.authorized,
+            TxPoint::DataSigned => self.data_signed,
+            TxPoint::PowerPathClosed => self.ev_connected && self.authorized,
+            TxPoint::EnergyTransfer => self.energy_transfer,
+        }
+    }
+
+    fn charging_state(&self) -> ChargingState {
+        match self.suspended_by_ev {
+            _ if self.energy_transfer => ChargingState::Charging,
+            Some(true) => ChargingState::SuspendedEV,
+            Some(false) => ChargingState::SuspendedEVSE,
+            None if self.ev_connected => ChargingState::EVConnected,
+            None => ChargingState::Idle,
+        }
+    }
+
+    fn stop_energy_transfer(&mut self) {
+        self.energy_transfer = false;
+        self.suspended_by_ev = None;
+    }
+
+    /// Applies `event`, returning its trigger reason.
+    fn apply(&mut self, event: SessionEvent) -> TriggerReason {
+        match event {
+            SessionEvent::EVDetected => {
+                self.ev_detected = true;
+                TriggerReason::EVDetected
+            }
+            SessionEvent::EVDeparted => {
+                self.ev_detected = false;
+                TriggerReason::EVDeparted
+            }
+            SessionEvent::CablePluggedIn => {
+                self.ev_connected = true;
+                TriggerReason::CablePluggedIn
+            }
+            SessionEvent::CableUnplugged => {
+                self.ev_connected = false;
+                self.stop_energy_transfer();
+                TriggerReason::EVCommunicationLost
+            }
+            SessionEvent::Authorized => {
+                self.authorized = true;
+                self.id_token = true;
+                TriggerReason::Authorized
+            }
+            SessionEvent::RemoteStart { remote_start_id } => {
+                self.authorized = true;
+                self.id_token = true;
+                self.remote_start_id = Some(remote_start_id);
+                TriggerReason::RemoteStart
+            }
+            SessionEvent::Deauthorized
+            | SessionEvent::StopAuthorized
+            | SessionEvent::RemoteStop => {
+                self.authorized = false;
+                self.stop_energy_transfer();
+                match event {
+                    SessionEvent::Deauthorized => TriggerReason::Deauthorized,
+                    SessionEvent::StopAuthorized => TriggerReason::StopAuthorized,
+                    _ => TriggerReason::RemoteStop,
+                }
+            }
+            SessionEvent::DataSigned => {
+                self.data_signed = true;
+                TriggerReason::SignedDataReceived
+            }
+            SessionEvent::EnergyTransferStarted => {
+                self.energy_transfer = true;
+                self.suspended_by_ev = None;
+                TriggerReason::ChargingStateChanged
+            }
+            SessionEvent::EnergyTransferSuspended { by_ev } => {
+                self.energy_transfer = false;
+                self.suspended_by_ev = Some(by_ev);
+                TriggerReason::ChargingStateChanged
+            }
+            SessionEvent::Stopped(reason) => {
+                self.authorized = false;
+                self.stop_energy_transfer();
+                stop_trigger_reason(reason)
+            }
+        }
+    }
+
+    /// Forgets the authorization and its data once the transaction ended.
+    fn reset(&mut self) {
+        self.authorized = false;
+        self.data_signed = false;
+        self.id_token = false;
+        self.remote_start_id = None;
+        self.stop_energy_transfer();
+    }
+}
+
+/// Trigger reason of a transaction stopped by the Charging Station for `reason`.
+fn stop_trigger_reason(reason: StopReason) -> TriggerReason {
+    match reason {
+        StopReason::DeAuthorized => TriggerReason::Deauthorized,
+        StopReason::EnergyLimitReached | StopReason::SOCLimitReached => {
+            TriggerReason::EnergyLimitReached
+        }
+        StopReason::EVDisconnected => TriggerReason::EVCommunicationLost,
+        StopReason::ImmediateReset | StopReason::Reboot => TriggerReason::ResetCommand,
+        StopReason::Local | StopReason::MasterPass => TriggerReason::StopAuthorized,
+        StopReason::Remote => TriggerReason::RemoteStop,
+        StopReason::StoppedByEV => TriggerReason::ChargingStateChanged,
+        StopReason::TimeLimitReached => TriggerReason::TimeLimitReached,
+        StopReason::Timeout => TriggerReason::EVConnectTimeout,
+        _ => TriggerReason::AbnormalCondition,
+    }
+}
+
+/// Stopped reason of a transaction ended by `event`.
+fn stopped_reason(event: SessionEvent) -> StopReason {
+    match event {
+        SessionEvent::EVDeparted | SessionEvent::CableUnplugged => StopReason::EVDisconnected,
+        SessionEvent::Deauthorized => StopReason::DeAuthorized,
+        SessionEvent::StopAuthorized => StopReason::Local,
+        SessionEvent::RemoteStop => StopReason::Remote,
+        SessionEvent::EnergyTransferSuspended { by_ev: true } => StopReason::StoppedByEV,
+        SessionEvent::Stopped(reason) => reason,
+        _ => StopReason::Other,
+    }
+}
+
+/// Starts and ends the transactions of the EVSEs of a Charging Station.
+///
+/// `DataSigned` as a stop point ends the transaction when signed meter data is received
+/// after the start of the transaction. A [`SessionEvent::Stopped`] ends the transaction
+/// whatever the stop points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionEngine {
+    start_points: Vec<TxPoint>,
+    stop_points: Vec<TxPoint>,
+    sessions: BTreeMap<i32, Session>,
+}
+
+impl TransactionEngine {
+    /// Creates the engine of the EVSEs `evse_ids`, starting and ending transactions at
+    /// `start_points` and `stop_points`. OCPP recommends `PowerPathClosed` and
+    /// `EVConnected,Authorized`.
+    pub fn new(start_points: Vec<TxPoint>, stop_points: Vec<TxPoint>, evse_ids: &[i32]) -> Self {
+        Self {
+            start_points,
+            stop_points,
+            sessions: evse_ids
+                .iter()
+                .map(|&evse_id| (evse_id, Session::default()))
+                .collect(),
+        }
+    }
+
+    /// Changes the start and stop points, e.g. when the CSMS changes `TxStartPoint` or
+    /// `TxStopPoint`. Ongoing transactions end at the new stop points.
+    pub fn set_points(&mut self, start_points: Vec<TxPoint>, stop_points: Vec<TxPoint>) {
+        self.start_points = start_points;
+        self.stop_points = stop_points;
+    }
+
+    /// Id of the ongoing transaction at `evse_id`.
+    pub fn transaction_id(&self, evse_id: i32) -> Option<&str> {
+        let transaction = self.sessions.get(&evse_id)?.transaction.as_ref()?;
+        Some(&transaction.id)
+    }
+
+    /// EVSE of the ongoing transaction `transaction_id`.
+    pub fn evse_of(&self, transaction_id: &str) -> Option<i32> {
+        self.sessions.iter().find_map(|(&evse_id, session)| {
+            session
+                .transaction
+                .as_ref()
+                .filter(|transaction| transaction.id == transaction_id)
+                .map(|_| evse_id)
+        })
+    }
+
+    /// Charging state at `evse_id`.
+    pub fn charging_state(&self, evse_id: i32) -> Option<ChargingState> {
+        self.sessions.get(&evse_id).map(Session::charging_state)
+    }
+
+    /// Handles `event` at `evse_id` at `now`, returning the transaction event to send, if
+    /// any.
+    pub fn handle(
+        &mut self,
+        evse_id: i32,
+        event: SessionEvent,
+        now: DateTime<Utc>,
+    ) -> Option<TransactionEvent> {
+        let session = self.sessions.get_mut(&evse_id)?;
+        let before: Vec<_> = POINTS
+            .into_iter()
+            .filter(|&point| session.met(point))
+            .collect();
+        let trigger_reason = session.apply(event);
+        let charging_state = session.charging_state();
+        let reached = |point| !before.contains(&point) && session.met(point);
+        let lost = |point| before.contains(&point) && !session.met(point);
+        let starts = self.start_points.iter().any(|&point| reached(point));
+        let ends = matches!(event, SessionEvent::Stopped(_))
+            || self.stop_points.iter().any(|&point| match point {
+                TxPoint::DataSigned => reached(point),
+                _ => lost(point),
+            });
+
+        let Some(transaction) = &mut session.transaction else {
+            if matches!(event, SessionEvent::Stopped(_)) {
+                session.reset();
+            }
+            if !starts {
+                return None;
+            }
+            let transaction = Transaction {
+                id: uuid::Uuid::new_v4().to_string(),
+                seq_no: 1,
+                charging_state,
+                stopped_reason: None,
+                charging_since: session.energy_transfer.then_some(now),
+                time_spent_charging: Duration::zero(),
+            };
+            let started = TransactionEvent {
+                event_type: TransactionEventType::Started,
+                timestamp: now,
+                trigger_reason,
+                seq_no: 0,
+                evse_id,
+                transaction_id: transaction.id.clone(),
+                charging_state: Some(charging_state),
+                time_spent_charging: None,
+                stopped_reason: None,
+                remote_start_id: session.remote_start_id.take(),
+                id_token: mem::take(&mut session.id_token),
+            };
+            session.transaction = Some(transaction);
+            return Some(started);
+        };
+
+        if matches!(
+            event,
+            SessionEvent::Deauthorized
+                | SessionEvent::StopAuthorized
+                | SessionEvent::RemoteStop
+                | SessionEvent::Stopped(_)
+        ) {
+            transaction.stopped_reason = transaction.stopped_reason.or(Some(stopped_reason(event)));
+        }
+        match (transaction.charging_since, session.energy_transfer) {
+            (Some(since), false) => {
+                transaction.time_spent_charging += now - since;
+                transaction.charging_since = None;
+            }
+            (None, true) => transaction.charging_since = Some(now),
+            _ => {}
+        }
+        let state_changed = charging_state != transaction.charging_state;
+        transaction.charging_state = charging_state;
+
+        if !ends && trigger_reason == TriggerReason::ChargingStateChanged && !state_changed {
+            return None;
+        }
+
+        let seq_no = transaction.seq_no;
+        transaction.seq_no += 1;
+        let mut transaction_event = TransactionEvent {
+            event_type: TransactionEventType::Updated,
+            timestamp: now,
+            trigger_reason,
+            seq_no,
+            evse_id,
+            transaction_id: transaction.id.clone(),
+            charging_state: state_changed.then_some(charging_state),
+            time_spent_charging: None,
+            stopped_reason: None,
+            remote_start_id: session.remote_start_id.take(),
+            id_token: mem::take(&mut session.id_token),
+        };
+        if ends {
+            let time_spent = transaction.time_spent_charging.num_seconds();
+            transaction_event.event_type = TransactionEventType::Ended;
+            transaction_event.time_spent_charging =
+                Some(i32::try_from(time_spent).unwrap_or(i32::MAX));
+            transaction_event.stopped_reason =
+                transaction.stopped_reason.or(Some(stopped_reason(event)));
+            session.transaction = None;
+            session.reset();
+        }
+        Some(transaction_event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn starts_at_power_path_closed() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut engine = TransactionEngine::new(
+            vec![TxPoint::PowerPathClosed],
+            vec![TxPoint::EVConnected, TxPoint::Authorized],
+            &[1],
+        );
+        assert_eq!(engine.handle(1, SessionEvent::CablePluggedIn, now), None);
+        assert_eq!(engine.charging_state(1), Some(ChargingState::EVConnected));
+
+        let started = engine.handle(1, SessionEvent::Authorized, now).unwrap();
+        assert_eq!(started.event_type, TransactionEventType::Started);
+        assert_eq!(started.trigger_reason, TriggerReason::Authorized);
+        assert_eq!(started.charging_state, Some(ChargingState::EVConnected));
+        assert!(started.id_token);
+        assert_eq!(
+            engine.transaction_id(1),
+            Some(started.transaction_id.as_str())
+        );
+
+        let charging = engine
+            .handle(1, SessionEvent::EnergyTransferStarted, now)
+            .unwrap();
+        assert_eq!(
+            (
+                charging.seq_no,
+                charging.trigger_reason,
+                charging.charging_state
+            ),
+            (
+                1,
+                TriggerReason::ChargingStateChanged,
+                Some(ChargingState::Charging)
+            )
+        );
+        assert!(!charging.id_token);
+        assert_eq!(
+            engine.handle(1, SessionEvent::EnergyTransferStarted, now),
+            None
+        );
+        let later = now + Duration::minutes(30);
+        let suspended = engine
+            .handle(
+                1,
+                SessionEvent::EnergyTransferSuspended { by_ev: true },
+                later,
+            )
+            .unwrap();
+        assert_eq!(suspended.charging_state, Some(ChargingState::SuspendedEV));
+
+        let ended = engine
+            .handle(1, SessionEvent::StopAuthorized, later)
+            .unwrap();
+        assert_eq!(ended.event_type, TransactionEventType::Ended);
+        assert_eq!(ended.trigger_reason, TriggerReason::StopAuthorized);
+        assert_eq!(ended.stopped_reason, Some(StopReason::Local));
+        assert_eq!(ended.charging_state, Some(ChargingState::EVConnected));
+        assert_eq!(ended.time_spent_charging, Some(1800));
+        assert_eq!(engine.transaction_id(1), None);
+        assert_eq!(engine.handle(1, SessionEvent::CableUnplugged, later), None);
+    }
+
+    #[test]
+    fn keeps_the_first_stop_reason() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut engine =
+            TransactionEngine::new(vec![TxPoint::EVConnected], vec![TxPoint::EVConnected], &[1]);
+        let started = engine.handle(1, SessionEvent::CablePluggedIn, now).unwrap();
+        assert_eq!(started.trigger_reason, TriggerReason::CablePluggedIn);
+        assert!(!started.id_token);
+
+        let authorized = engine
+            .handle(1, SessionEvent::RemoteStart { remote_start_id: 7 }, now)
+            .unwrap();
+        assert_eq!(authorized.event_type, TransactionEventType::Updated);
+        assert_eq!(authorized.remote_start_id, Some(7));
+        assert!(authorized.id_token);
+        engine.handle(1, SessionEvent::EnergyTransferStarted, now);
+
+        let stop = engine.handle(1, SessionEvent::RemoteStop, now).unwrap();
+        assert_eq!(stop.event_type, TransactionEventType::Updated);
+        assert_eq!(stop.charging_state, Some(ChargingState::EVConnected));
+
+        let ended = engine.handle(1, SessionEvent::CableUnplugged, now).unwrap();
+        assert_eq!(ended.seq_no, 4);
+        assert_eq!(ended.trigger_reason, TriggerReason::EVCommunicationLost);
+        assert_eq!(ended.stopped_reason, Some(StopReason::Remote));
+        assert_eq!(ended.charging_state, Some(ChargingState::Idle));
+
+        let restarted = engine.handle(1, SessionEvent::CablePluggedIn, now).unwrap();
+        assert_eq!(restarted.seq_no, 0);
+        assert_ne!(restarted.transaction_id, started.transaction_id);
+        assert_eq!(restarted.remote_start_id, None);
+    }
+
+    #[test]
+    fn stops_on_request_of_the_station() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut engine = TransactionEngine::new(
+            vec![TxPoint::Authorized],
+            vec![TxPoint::EVConnected],
+            &[1, 2],
+        );
+        engine.handle(1, SessionEvent::Authorized, now).unwrap();
+        let ended = engine
+            .handle(1, SessionEvent::Stopped(StopReason::Timeout), now)
+            .unwrap();
+        assert_eq!(ended.trigger_reason, TriggerReason::EVConnectTimeout);
+        assert_eq!(ended.stopped_reason, Some(StopReason::Timeout));
+
+        engine.set_points(vec![TxPoint::EVConnected], vec![TxPoint::DataSigned]);
+        engine.handle(2, SessionEvent::CablePluggedIn, now).unwrap();
+        let ended = engine.handle(2, SessionEvent::DataSigned, now).unwrap();
+        assert_eq!(ended.event_type, TransactionEventType::Ended);
+        assert_eq!(ended.trigger_reason, TriggerReason::SignedDataReceived);
+        assert_eq!(engine.handle(3, SessionEvent::CablePluggedIn, now), None);
+    }
+}
@@ -0,0 +1,258 @@
+//! # Transactions
+//!
+//! Start and end of OCPP 2.x transactions, following the `TxCtrlr.TxStartPoint` and
+//! `TxCtrlr.TxStopPoint` variables.
+//!
+//! The [`TransactionEngine`] turns what happens at an EVSE, a [`SessionEvent`] such as the
+//! cable being plugged in, a token being authorized or energy starting to flow, into the
+//! [`TransactionEvent`]s to send as `TransactionEventRequest`s. A transaction starts at the
+//! first of its start points that is reached and ends at the first of its stop points that
+//! is no longer met, e.g. with `TxStopPoint` `EVConnected,Authorized` when the cable is
+//! unplugged or the driver stops the transaction with their token, whichever comes first.
+//!
+//! Every event carries its trigger reason and the charging state when it changed. The
+//! `Ended` event carries the stopped reason: the reason of the first request to stop, such
+//! as `Local` when the driver presented their token, even when the transaction only ends
+//! later when the cable is unplugged.
+
+use chrono::{DateTime, Utc};
+
+mod engine;
+
+#[cfg(feature = "v2_0_1")]
+pub mod v2_0_1;
+#[cfg(feature = "v2_1")]
+pub mod v2_1;
+
+pub use engine::TransactionEngine;
+
+/// Point in a charging session where a transaction starts or ends, the values of
+/// `TxStartPoint` and `TxStopPoint`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TxPoint {
+    /// An EV is detected in the parking bay.
+    ParkingBayOccupancy,
+    /// The cable is plugged into the EVSE and the EV.
+    EVConnected,
+    /// The driver is authorized, locally or by the CSMS.
+    Authorized,
+    /// Signed meter data is received from the meter.
+    DataSigned,
+    /// The EV is connected and the driver authorized, energy may flow.
+    PowerPathClosed,
+    /// Energy flows to or from the EV.
+    EnergyTransfer,
+}
+
+impl TxPoint {
+    /// Point of its OCPP name, such as `EVConnected`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ParkingBayOccupancy" => Some(Self::ParkingBayOccupancy),
+            "EVConnected" => Some(Self::EVConnected),
+            "Authorized" => Some(Self::Authorized),
+            "DataSigned" => Some(Self::DataSigned),
+            "PowerPathClosed" => Some(Self::PowerPathClosed),
+            "EnergyTransfer" => Some(Self::EnergyTransfer),
+            _ => None,
+        }
+    }
+}
+
+/// Parses the `TxStartPoint` or `TxStopPoint` variable, a comma separated list of points,
+/// skipping invalid entries.
+pub fn parse_tx_points(value: &str) -> Vec<TxPoint> {
+    value
+        .split(',')
+        .filter_map(|point| TxPoint::from_name(point.trim()))
+        .collect()
+}
+
+/// Something that happened at an EVSE.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionEvent {
+    /// An EV entered the parking bay.
+    EVDetected,
+    /// The EV left the parking bay.
+    EVDeparted,
+    /// The cable was plugged in at both ends.
+    CablePluggedIn,
+    /// The cable was unplugged, or communication with the EV was lost.
+    CableUnplugged,
+    /// A token was authorized, locally or by the CSMS.
+    Authorized,
+    /// The CSMS started a transaction with `RequestStartTransaction`.
+    RemoteStart {
+        /// `remoteStartId` of the request.
+        remote_start_id: i32,
+    },
+    /// The authorization was withdrawn, e.g. the CSMS did not accept the token in a
+    /// `TransactionEventResponse`.
+    Deauthorized,
+    /// The driver presented a token to stop.
+    StopAuthorized,
+    /// The CSMS stopped the transaction with `RequestStopTransaction`.
+    RemoteStop,
+    /// Signed meter data was received.
+    DataSigned,
+    /// Energy started to flow.
+    EnergyTransferStarted,
+    /// Energy stopped flowing, because of the EV or of the EVSE.
+    EnergyTransferSuspended {
+        /// Whether the EV stopped taking energy.
+        by_ev: bool,
+    },
+    /// The Charging Station ended the transaction, e.g. because of a fault, a reset or a
+    /// limit being reached.
+    Stopped(StopReason),
+}
+
+/// Type of a [`TransactionEvent`], `TransactionEventEnumType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionEventType {
+    /// First event of a transaction.
+    Started,
+    /// Event during a transaction.
+    Updated,
+    /// Last event of a transaction.
+    Ended,
+}
+
+/// Reason of a [`TransactionEvent`], `TriggerReasonEnumType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerReason {
+    /// A token was authorized.
+    Authorized,
+    /// The cable was plugged in.
+    CablePluggedIn,
+    /// The charging state changed.
+    ChargingStateChanged,
+    /// The authorization was withdrawn.
+    Deauthorized,
+    /// An energy limit was reached.
+    EnergyLimitReached,
+    /// Communication with the EV was lost, e.g. the cable was unplugged.
+    EVCommunicationLost,
+    /// The EV was not connected within `EVConnectionTimeOut`.
+    EVConnectTimeout,
+    /// A time limit was reached.
+    TimeLimitReached,
+    /// The driver presented a token to stop.
+    StopAuthorized,
+    /// The EV left the parking bay.
+    EVDeparted,
+    /// An EV entered the parking bay.
+    EVDetected,
+    /// `RequestStopTransaction` of the CSMS.
+    RemoteStop,
+    /// `RequestStartTransaction` of the CSMS.
+    RemoteStart,
+    /// An abnormal condition, such as a fault.
+    AbnormalCondition,
+    /// Signed meter data was received.
+    SignedDataReceived,
+    /// A reset of the Charging Station.
+    ResetCommand,
+}
+
+/// Charging state of a transaction, `ChargingStateEnumType`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChargingState {
+    /// Energy flows.
+    Charging,
+    /// The EV is connected, no energy is offered.
+    EVConnected,
+    /// The EV does not take the energy offered.
+    SuspendedEV,
+    /// The EVSE does not offer energy.
+    SuspendedEVSE,
+    /// No EV is connected.
+    #[default]
+    Idle,
+}
+
+/// Why a transaction ended, `ReasonEnumType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// The authorization was withdrawn.
+    DeAuthorized,
+    /// The emergency stop button was pressed.
+    EmergencyStop,
+    /// An energy limit was reached.
+    EnergyLimitReached,
+    /// The EV was disconnected.
+    EVDisconnected,
+    /// A ground fault.
+    GroundFault,
+    /// A reset with `Immediate` type.
+    ImmediateReset,
+    /// Stopped by the driver at the Charging Station.
+    Local,
+    /// The driver ran out of credit.
+    LocalOutOfCredit,
+    /// Stopped with a master pass.
+    MasterPass,
+    /// Any other reason.
+    Other,
+    /// An overcurrent fault.
+    OvercurrentFault,
+    /// Power loss.
+    PowerLoss,
+    /// Bad power quality.
+    PowerQuality,
+    /// A reboot of the Charging Station.
+    Reboot,
+    /// Stopped by the CSMS.
+    Remote,
+    /// A state of charge limit was reached.
+    SOCLimitReached,
+    /// Stopped by the EV.
+    StoppedByEV,
+    /// A time limit was reached.
+    TimeLimitReached,
+    /// The EV was not connected in time.
+    Timeout,
+}
+
+/// A transaction event to send in a `TransactionEventRequest`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionEvent {
+    /// Started, updated or ended.
+    pub event_type: TransactionEventType,
+    /// When the event happened.
+    pub timestamp: DateTime<Utc>,
+    /// Why the event is sent.
+    pub trigger_reason: TriggerReason,
+    /// Sequence number of the event in its transaction, from 0.
+    pub seq_no: i32,
+    /// EVSE of the transaction.
+    pub evse_id: i32,
+    /// Id of the transaction.
+    pub transaction_id: String,
+    /// Charging state, when it changed or the transaction started.
+    pub charging_state: Option<ChargingState>,
+    /// Seconds spent charging, in the `Ended` event.
+    pub time_spent_charging: Option<i32>,
+    /// Why the transaction ended, in the `Ended` event.
+    pub stopped_reason: Option<StopReason>,
+    /// `remoteStartId` of the `RequestStartTransaction` that authorized the transaction,
+    /// in the first event after it.
+    pub remote_start_id: Option<i32>,
+    /// Whether the event carries the token that authorized the transaction, which is sent
+    /// once, in the first event after the authorization.
+    pub id_token: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tx_points() {
+        assert_eq!(
+            parse_tx_points("EVConnected, Authorized,Unknown"),
+            vec![TxPoint::EVConnected, TxPoint::Authorized]
+        );
+        assert!(parse_tx_points("").is_empty());
+    }
+}
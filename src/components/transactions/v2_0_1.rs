@@ -0,0 +1,198 @@
+//! OCPP 2.0.1 messages for the [`TransactionEngine`].
+
+use chrono::{DateTime, Utc};
+
+use crate::v2_0_1::{
+    datatypes::{
+        evse_type::EVSEType, id_token_type::IdTokenType, meter_value_type::MeterValueType,
+        transaction_type::TransactionType,
+    },
+    enumerations::{
+        authorization_status_enum_type::AuthorizationStatusEnumType,
+        charging_state_enum_type::ChargingStateEnumType, reason_enum_type::ReasonEnumType,
+        transaction_event_enum_type::TransactionEventEnumType,
+        trigger_reason_enum_type::TriggerReasonEnumType,
+    },
+    messages::transaction_event::{TransactionEventRequest, TransactionEventResponse},
+};
+
+use super::{
+    ChargingState, SessionEvent, StopReason, TransactionEngine, TransactionEvent,
+    TransactionEventType, TriggerReason,
+};
+
+impl From<TransactionEventType> for TransactionEventEnumType {
+    fn from(event_type: TransactionEventType) -> Self {
+        match event_type {
+            TransactionEventType::Started => Self::Started,
+            TransactionEventType::Updated => Self::Updated,
+            TransactionEventType::Ended => Self::Ended,
+        }
+    }
+}
+
+impl From<TriggerReason> for TriggerReasonEnumType {
+    fn from(reason: TriggerReason) -> Self {
+        match reason {
+            TriggerReason::Authorized => Self::Authorized,
+            TriggerReason::CablePluggedIn => Self::CablePluggedIn,
+            TriggerReason::ChargingStateChanged => Self::ChargingStateChanged,
+            TriggerReason::Deauthorized => Self::Deauthorized,
+            TriggerReason::EnergyLimitReached => Self::EnergyLimitReached,
+            TriggerReason::EVCommunicationLost => Self::EVCommunicationLost,
+            TriggerReason::EVConnectTimeout => Self::EVConnectTimeout,
+            TriggerReason::TimeLimitReached => Self::TimeLimitReached,
+            TriggerReason::StopAuthorized => Self::StopAuthorized,
+            TriggerReason::EVDeparted => Self::EVDeparted,
+            TriggerReason::EVDetected => Self::EVDetected,
+            TriggerReason::RemoteStop => Self::RemoteStop,
+            TriggerReason::RemoteStart => Self::RemoteStart,
+            TriggerReason::AbnormalCondition => Self::AbnormalCondition,
+            TriggerReason::SignedDataReceived => Self::SignedDataReceived,
+            TriggerReason::ResetCommand => Self::ResetCommand,
+        }
+    }
+}
+
+impl From<ChargingState> for ChargingStateEnumType {
+    fn from(state: ChargingState) -> Self {
+        match state {
+            ChargingState::Charging => Self::Charging,
+            ChargingState::EVConnected => Self::EVConnected,
+            ChargingState::SuspendedEV => Self::SuspendedEV,
+            ChargingState::SuspendedEVSE => Self::SuspendedEVSE,
+            ChargingState::Idle => Self::Idle,
+        }
+    }
+}
+
+impl From<StopReason> for ReasonEnumType {
+    fn from(reason: StopReason) -> Self {
+        match reason {
+            StopReason::DeAuthorized => Self::DeAuthorized,
+            StopReason::EmergencyStop => Self::EmergencyStop,
+            StopReason::EnergyLimitReached => Self::EnergyLimitReached,
+            StopReason::EVDisconnected => Self::EVDisconnected,
+            StopReason::GroundFault => Self::GroundFault,
+            StopReason::ImmediateReset => Self::ImmediateReset,
+            StopReason::Local => Self::Local,
+            StopReason::LocalOutOfCredit => Self::LocalOutOfCredit,
+            StopReason::MasterPass => Self::MasterPass,
+            StopReason::Other => Self::Other,
+            StopReason::OvercurrentFault => Self::OvercurrentFault,
+            StopReason::PowerLoss => Self::PowerLoss,
+            StopReason::PowerQuality => Self::PowerQuality,
+            StopReason::Reboot => Self::Reboot,
+            StopReason::Remote => Self::Remote,
+            StopReason::SOCLimitReached => Self::SOCLimitReached,
+            StopReason::StoppedByEV => Self::StoppedByEV,
+            StopReason::TimeLimitReached => Self::TimeLimitReached,
+            StopReason::Timeout => Self::Timeout,
+        }
+    }
+}
+
+/// The `TransactionEventRequest` of `event`, with the `meter_value`s sampled for it.
+///
+/// `id_token` is the token that authorized the transaction, sent only when the event
+/// carries it. The EVSE is sent in the `Started` event.
+pub fn transaction_event_request(
+    event: &TransactionEvent,
+    id_token: Option<IdTokenType>,
+    meter_value: Vec<MeterValueType>,
+) -> TransactionEventRequest {
+    TransactionEventRequest {
+        event_type: event.event_type.into(),
+        timestamp: event.timestamp,
+        trigger_reason: event.trigger_reason.into(),
+        seq_no: event.seq_no,
+        transaction_info: TransactionType {
+            transaction_id: event.transaction_id.clone(),
+            charging_state: event.charging_state.map(Into::into),
+            time_spent_charging: event.time_spent_charging,
+            stopped_reason: event.stopped_reason.map(Into::into),
+            remote_start_id: event.remote_start_id,
+        },
+        id_token: id_token.filter(|_| event.id_token),
+        evse: (event.event_type == TransactionEventType::Started).then_some(EVSEType {
+            id: event.evse_id,
+            connector_id: None,
+        }),
+        meter_value: (!meter_value.is_empty()).then_some(meter_value),
+        ..Default::default()
+    }
+}
+
+/// Handles the `TransactionEventResponse` to an event of the transaction at `evse_id`,
+/// deauthorizing the transaction when the CSMS did not accept its token.
+pub fn transaction_event_response(
+    engine: &mut TransactionEngine,
+    evse_id: i32,
+    response: &TransactionEventResponse,
+    now: DateTime<Utc>,
+) -> Option<TransactionEvent> {
+    let status = &response.id_token_info.as_ref()?.status;
+    if *status == AuthorizationStatusEnumType::Accepted {
+        return None;
+    }
+    engine.handle(evse_id, SessionEvent::Deauthorized, now)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::{
+        components::transactions::TxPoint,
+        v2_0_1::{
+            datatypes::id_token_info_type::IdTokenInfoType,
+            enumerations::id_token_enum_type::IdTokenEnumType,
+        },
+    };
+
+    #[test]
+    fn builds_transaction_events() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut engine = TransactionEngine::new(
+            vec![TxPoint::PowerPathClosed],
+            vec![TxPoint::EVConnected, TxPoint::Authorized],
+            &[1],
+        );
+        let token = IdTokenType {
+            id_token: "TAG1".to_string(),
+            kind: IdTokenEnumType::ISO14443,
+            ..Default::default()
+        };
+        engine.handle(1, SessionEvent::CablePluggedIn, now);
+        let started = engine.handle(1, SessionEvent::Authorized, now).unwrap();
+        let request = transaction_event_request(&started, Some(token.clone()), Vec::new());
+        assert_eq!(request.event_type, TransactionEventEnumType::Started);
+        assert_eq!(request.trigger_reason, TriggerReasonEnumType::Authorized);
+        assert_eq!(request.id_token, Some(token.clone()));
+        assert_eq!(request.evse.unwrap().id, 1);
+        assert_eq!(request.meter_value, None);
+        assert_eq!(
+            request.transaction_info.charging_state,
+            Some(ChargingStateEnumType::EVConnected)
+        );
+
+        let response = TransactionEventResponse {
+            id_token_info: Some(IdTokenInfoType {
+                status: AuthorizationStatusEnumType::Invalid,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let ended = transaction_event_response(&mut engine, 1, &response, now).unwrap();
+        let request = transaction_event_request(&ended, Some(token), Vec::new());
+        assert_eq!(request.event_type, TransactionEventEnumType::Ended);
+        assert_eq!(request.trigger_reason, TriggerReasonEnumType::Deauthorized);
+        assert_eq!(
+            request.transaction_info.stopped_reason,
+            Some(ReasonEnumType::DeAuthorized)
+        );
+        assert_eq!(request.id_token, None);
+        assert_eq!(request.evse, None);
+    }
+}
@@ -12,6 +12,12 @@
 //!
 //! # v2_0_1
 //! The [v2_0_1](v2_0_1) module contains the `ocpp 2.0.1` implementation
+//!
+//! # components
+//! The [components](components) module contains reusable behaviour built on top of the
+//! message types, such as local authorization, shared by all enabled versions
+#[cfg(any(feature = "v1_6", feature = "v2_0_1", feature = "v2_1"))]
+pub mod components;
 /// tests
 #[cfg(test)]
 pub mod tests;
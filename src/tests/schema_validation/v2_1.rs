@@ -578,3 +578,588 @@ fn validate_cancel_reservation_response() -> Result<(), Box<dyn std::error::Erro
 }
 
 // We recommend installing an extension to run rust tests.
+
+#[test]
+fn validate_notify_event_request() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::v2_1::datatypes::{ComponentType, VariableType};
+    use crate::v2_1::enumerations::{EventNotificationEnumType, EventTriggerEnumType};
+    use crate::v2_1::messages::notify_event::{EventDataType, NotifyEventRequest};
+
+    let test = NotifyEventRequest {
+        event_data: vec![EventDataType {
+            actual_value: "81".to_string(),
+            event_id: 2,
+            timestamp: chrono::Utc::now(),
+            trigger: EventTriggerEnumType::Alerting,
+            cause: Some(1),
+            tech_code: None,
+            tech_info: None,
+            cleared: Some(true),
+            transaction_id: None,
+            component: ComponentType::new("EVSE".to_string()),
+            variable_monitoring_id: Some(3),
+            event_notification_type: EventNotificationEnumType::CustomMonitor,
+            variable: VariableType::new("Power".to_string()),
+            severity: Some(4),
+            custom_data: None,
+        }],
+        tbc: None,
+        seq_no: 0,
+        generated_at: chrono::Utc::now(),
+        custom_data: None,
+    };
+
+    let instance = serde_json::to_value(test)?;
+    assert!(validate_schema_instance(
+        "NotifyEventRequest.json",
+        instance
+    )?);
+    Ok(())
+}
+
+#[test]
+fn validate_notify_periodic_event_stream() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::v2_1::datatypes::StreamDataElementType;
+    use crate::v2_1::messages::notify_periodic_event_stream::NotifyPeriodicEventStreamRequest;
+
+    let test = NotifyPeriodicEventStreamRequest {
+        basetime: chrono::Utc::now(),
+        data: vec![
+            StreamDataElementType::new(rust_decimal::Decimal::ZERO, "230.1".to_string()),
+            StreamDataElementType::new(rust_decimal::Decimal::new(1500, 3), "230.4".to_string()),
+        ],
+        id: 1,
+        pending: 0,
+        custom_data: None,
+    };
+
+    let instance = serde_json::to_value(test)?;
+    assert!(validate_schema_instance(
+        "NotifyPeriodicEventStream.json",
+        instance
+    )?);
+    Ok(())
+}
+
+/// Lists message types by name, failing to compile when one doesn't exist.
+macro_rules! message_types {
+    ($($module:ident::$name:ident),* $(,)?) => {{
+        $(let _: Option<crate::v2_1::messages::$module::$name> = None;)*
+        [$(stringify!($name)),*]
+    }};
+}
+
+#[test]
+fn every_schema_has_a_message_type() -> Result<(), Box<dyn std::error::Error>> {
+    let types = message_types![
+        afrr_signal::AFRRSignalRequest,
+        afrr_signal::AFRRSignalResponse,
+        adjust_periodic_event_stream::AdjustPeriodicEventStreamRequest,
+        adjust_periodic_event_stream::AdjustPeriodicEventStreamResponse,
+        authorize::AuthorizeRequest,
+        authorize::AuthorizeResponse,
+        battery_swap::BatterySwapRequest,
+        battery_swap::BatterySwapResponse,
+        boot_notification::BootNotificationRequest,
+        boot_notification::BootNotificationResponse,
+        cancel_reservation::CancelReservationRequest,
+        cancel_reservation::CancelReservationResponse,
+        certificate_signed::CertificateSignedRequest,
+        certificate_signed::CertificateSignedResponse,
+        change_availability::ChangeAvailabilityRequest,
+        change_availability::ChangeAvailabilityResponse,
+        change_transaction_tariff::ChangeTransactionTariffRequest,
+        change_transaction_tariff::ChangeTransactionTariffResponse,
+        clear_cache::ClearCacheRequest,
+        clear_cache::ClearCacheResponse,
+        clear_charging_profile::ClearChargingProfileRequest,
+        clear_charging_profile::ClearChargingProfileResponse,
+        clear_der_control::ClearDERControlRequest,
+        clear_der_control::ClearDERControlResponse,
+        clear_display_message::ClearDisplayMessageRequest,
+        clear_display_message::ClearDisplayMessageResponse,
+        clear_tariffs::ClearTariffsRequest,
+        clear_tariffs::ClearTariffsResponse,
+        clear_variable_monitoring::ClearVariableMonitoringRequest,
+        clear_variable_monitoring::ClearVariableMonitoringResponse,
+        cleared_charging_limit::ClearedChargingLimitRequest,
+        cleared_charging_limit::ClearedChargingLimitResponse,
+        close_periodic_event_stream::ClosePeriodicEventStreamRequest,
+        close_periodic_event_stream::ClosePeriodicEventStreamResponse,
+        cost_updated::CostUpdatedRequest,
+        cost_updated::CostUpdatedResponse,
+        customer_information::CustomerInformationRequest,
+        customer_information::CustomerInformationResponse,
+        data_transfer::DataTransferRequest,
+        data_transfer::DataTransferResponse,
+        delete_certificate::DeleteCertificateRequest,
+        delete_certificate::DeleteCertificateResponse,
+        firmware_status_notification::FirmwareStatusNotificationRequest,
+        firmware_status_notification::FirmwareStatusNotificationResponse,
+        get_15118ev_certificate::Get15118EVCertificateRequest,
+        get_15118ev_certificate::Get15118EVCertificateResponse,
+        get_base_report::GetBaseReportRequest,
+        get_base_report::GetBaseReportResponse,
+        get_certificate_chain_status::GetCertificateChainStatusRequest,
+        get_certificate_chain_status::GetCertificateChainStatusResponse,
+        get_certificate_status::GetCertificateStatusRequest,
+        get_certificate_status::GetCertificateStatusResponse,
+        get_charging_profiles::GetChargingProfilesRequest,
+        get_charging_profiles::GetChargingProfilesResponse,
+        get_composite_schedule::GetCompositeScheduleRequest,
+        get_composite_schedule::GetCompositeScheduleResponse,
+        get_der_control::GetDERControlRequest,
+        get_der_control::GetDERControlResponse,
+        get_display_messages::GetDisplayMessagesRequest,
+        get_display_messages::GetDisplayMessagesResponse,
+        get_installed_certificate_ids::GetInstalledCertificateIdsRequest,
+        get_installed_certificate_ids::GetInstalledCertificateIdsResponse,
+        get_local_list_version::GetLocalListVersionRequest,
+        get_local_list_version::GetLocalListVersionResponse,
+        get_log::GetLogRequest,
+        get_log::GetLogResponse,
+        get_monitoring_report::GetMonitoringReportRequest,
+        get_monitoring_report::GetMonitoringReportResponse,
+        get_periodic_event_stream::GetPeriodicEventStreamRequest,
+        get_periodic_event_stream::GetPeriodicEventStreamResponse,
+        get_report::GetReportRequest,
+        get_report::GetReportResponse,
+        get_tariffs::GetTariffsRequest,
+        get_tariffs::GetTariffsResponse,
+        get_transaction_status::GetTransactionStatusRequest,
+        get_transaction_status::GetTransactionStatusResponse,
+        get_variables::GetVariablesRequest,
+        get_variables::GetVariablesResponse,
+        heartbeat::HeartbeatRequest,
+        heartbeat::HeartbeatResponse,
+        install_certificate::InstallCertificateRequest,
+        install_certificate::InstallCertificateResponse,
+        log_status_notification::LogStatusNotificationRequest,
+        log_status_notification::LogStatusNotificationResponse,
+        meter_values::MeterValuesRequest,
+        meter_values::MeterValuesResponse,
+        notify_allowed_energy_transfer::NotifyAllowedEnergyTransferRequest,
+        notify_allowed_energy_transfer::NotifyAllowedEnergyTransferResponse,
+        notify_charging_limit::NotifyChargingLimitRequest,
+        notify_charging_limit::NotifyChargingLimitResponse,
+        notify_customer_information::NotifyCustomerInformationRequest,
+        notify_customer_information::NotifyCustomerInformationResponse,
+        notify_der_alarm::NotifyDERAlarmRequest,
+        notify_der_alarm::NotifyDERAlarmResponse,
+        notify_der_start_stop::NotifyDERStartStopRequest,
+        notify_der_start_stop::NotifyDERStartStopResponse,
+        notify_display_messages::NotifyDisplayMessagesRequest,
+        notify_display_messages::NotifyDisplayMessagesResponse,
+        notify_ev_charging_needs::NotifyEVChargingNeedsRequest,
+        notify_ev_charging_needs::NotifyEVChargingNeedsResponse,
+        notify_ev_charging_schedule::NotifyEVChargingScheduleRequest,
+        notify_ev_charging_schedule::NotifyEVChargingScheduleResponse,
+        notify_event::NotifyEventRequest,
+        notify_event::NotifyEventResponse,
+        notify_monitoring_report::NotifyMonitoringReportRequest,
+        notify_monitoring_report::NotifyMonitoringReportResponse,
+        notify_periodic_event_stream::NotifyPeriodicEventStreamRequest,
+        notify_priority_charging::NotifyPriorityChargingRequest,
+        notify_priority_charging::NotifyPriorityChargingResponse,
+        notify_report::NotifyReportRequest,
+        notify_report::NotifyReportResponse,
+        notify_settlement::NotifySettlementRequest,
+        notify_settlement::NotifySettlementResponse,
+        notify_web_payment_started::NotifyWebPaymentStartedRequest,
+        notify_web_payment_started::NotifyWebPaymentStartedResponse,
+        open_periodic_event_stream::OpenPeriodicEventStreamRequest,
+        open_periodic_event_stream::OpenPeriodicEventStreamResponse,
+        publish_firmware::PublishFirmwareRequest,
+        publish_firmware::PublishFirmwareResponse,
+        publish_firmware_status_notification::PublishFirmwareStatusNotificationRequest,
+        publish_firmware_status_notification::PublishFirmwareStatusNotificationResponse,
+        pull_dynamic_schedule_update::PullDynamicScheduleUpdateRequest,
+        pull_dynamic_schedule_update::PullDynamicScheduleUpdateResponse,
+        report_charging_profiles::ReportChargingProfilesRequest,
+        report_charging_profiles::ReportChargingProfilesResponse,
+        report_der_control::ReportDERControlRequest,
+        report_der_control::ReportDERControlResponse,
+        request_battery_swap::RequestBatterySwapRequest,
+        request_battery_swap::RequestBatterySwapResponse,
+        request_start_transaction::RequestStartTransactionRequest,
+        request_start_transaction::RequestStartTransactionResponse,
+        request_stop_transaction::RequestStopTransactionRequest,
+        request_stop_transaction::RequestStopTransactionResponse,
+        reservation_status_update::ReservationStatusUpdateRequest,
+        reservation_status_update::ReservationStatusUpdateResponse,
+        reserve_now::ReserveNowRequest,
+        reserve_now::ReserveNowResponse,
+        reset::ResetRequest,
+        reset::ResetResponse,
+        security_event_notification::SecurityEventNotificationRequest,
+        security_event_notification::SecurityEventNotificationResponse,
+        send_local_list::SendLocalListRequest,
+        send_local_list::SendLocalListResponse,
+        set_charging_profile::SetChargingProfileRequest,
+        set_charging_profile::SetChargingProfileResponse,
+        set_der_control::SetDERControlRequest,
+        set_der_control::SetDERControlResponse,
+        set_default_tariff::SetDefaultTariffRequest,
+        set_default_tariff::SetDefaultTariffResponse,
+        set_display_message::SetDisplayMessageRequest,
+        set_display_message::SetDisplayMessageResponse,
+        set_monitoring_base::SetMonitoringBaseRequest,
+        set_monitoring_base::SetMonitoringBaseResponse,
+        set_monitoring_level::SetMonitoringLevelRequest,
+        set_monitoring_level::SetMonitoringLevelResponse,
+        set_network_profile::SetNetworkProfileRequest,
+        set_network_profile::SetNetworkProfileResponse,
+        set_variable_monitoring::SetVariableMonitoringRequest,
+        set_variable_monitoring::SetVariableMonitoringResponse,
+        set_variables::SetVariablesRequest,
+        set_variables::SetVariablesResponse,
+        sign_certificate::SignCertificateRequest,
+        sign_certificate::SignCertificateResponse,
+        status_notification::StatusNotificationRequest,
+        status_notification::StatusNotificationResponse,
+        transaction_event::TransactionEventRequest,
+        transaction_event::TransactionEventResponse,
+        trigger_message::TriggerMessageRequest,
+        trigger_message::TriggerMessageResponse,
+        unlock_connector::UnlockConnectorRequest,
+        unlock_connector::UnlockConnectorResponse,
+        unpublish_firmware::UnpublishFirmwareRequest,
+        unpublish_firmware::UnpublishFirmwareResponse,
+        update_dynamic_schedule::UpdateDynamicScheduleRequest,
+        update_dynamic_schedule::UpdateDynamicScheduleResponse,
+        update_firmware::UpdateFirmwareRequest,
+        update_firmware::UpdateFirmwareResponse,
+        use_priority_charging::UsePriorityChargingRequest,
+        use_priority_charging::UsePriorityChargingResponse,
+        vat_number_validation::VatNumberValidationRequest,
+        vat_number_validation::VatNumberValidationResponse,
+    ];
+
+    for entry in std::fs::read_dir(SCHEMA_DIR)? {
+        let file_name = entry?.file_name().into_string().unwrap();
+        let name = file_name.trim_end_matches(".json");
+        // schemas of requests without response have no suffix
+        let type_name = match name.ends_with("Request") || name.ends_with("Response") {
+            true => name.to_string(),
+            false => format!("{name}Request"),
+        };
+        assert!(
+            types.contains(&type_name.as_str()),
+            "no message type for schema {file_name}"
+        );
+    }
+    Ok(())
+}
+
+fn round_trip<T>(schema_name: &str, message: T) -> Result<(), Box<dyn std::error::Error>>
+where
+    T: serde::Serialize
+        + serde::de::DeserializeOwned
+        + validator::Validate
+        + PartialEq
+        + std::fmt::Debug,
+{
+    assert!(message.validate().is_ok());
+    let instance = serde_json::to_value(&message)?;
+    assert!(validate_schema_instance(schema_name, instance.clone())?);
+    assert_eq!(serde_json::from_value::<T>(instance)?, message);
+    Ok(())
+}
+
+#[test]
+fn validate_set_der_control() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::v2_1::datatypes::{DERCurvePointsType, DERCurveType, FixedPFType, HysteresisType};
+    use crate::v2_1::enumerations::{
+        DERControlEnumType, DERControlStatusEnumType, DERUnitEnumType,
+    };
+    use crate::v2_1::messages::set_der_control::{SetDERControlRequest, SetDERControlResponse};
+    use rust_decimal_macros::dec;
+
+    let curve = DERCurveType::new(
+        vec![
+            DERCurvePointsType::new(dec!(92), dec!(44)),
+            DERCurvePointsType::new(dec!(108.5), dec!(-44)),
+        ],
+        1,
+        DERUnitEnumType::PctMaxVar,
+    )
+    .with_hysteresis(HysteresisType::new().with_hysteresis_high(dec!(105)))
+    .with_response_time(dec!(1.5))
+    .with_start_time(chrono::Utc::now())
+    .with_duration(dec!(3600));
+    let request = SetDERControlRequest::new(false, "vv-1".to_string(), DERControlEnumType::VoltVar)
+        .with_curve(curve);
+    round_trip("SetDERControlRequest.json", request)?;
+
+    let request =
+        SetDERControlRequest::new(true, "pf-1".to_string(), DERControlEnumType::FixedPFAbsorb)
+            .with_fixed_pf_absorb(FixedPFType::new(0, 0.95, true));
+    let json = serde_json::to_value(&request)?;
+    assert!(json.get("fixedPFAbsorb").is_some());
+    round_trip("SetDERControlRequest.json", request)?;
+
+    let response = SetDERControlResponse::new(DERControlStatusEnumType::Accepted)
+        .with_superseded_ids(vec!["vv-0".to_string()]);
+    round_trip("SetDERControlResponse.json", response)?;
+
+    let response = SetDERControlResponse::new(DERControlStatusEnumType::NotSupported)
+        .with_status_info(StatusInfoType::new("UnsupportedRequest".to_string()));
+    round_trip("SetDERControlResponse.json", response)?;
+
+    let mut invalid = serde_json::to_value(SetDERControlRequest::new(
+        false,
+        "x".repeat(37),
+        DERControlEnumType::VoltVar,
+    ))?;
+    assert!(!validate_schema_instance(
+        "SetDERControlRequest.json",
+        invalid.clone()
+    )?);
+    invalid["controlId"] = "vv-1".into();
+    invalid["controlType"] = "PowerTarget".into();
+    assert!(!validate_schema_instance(
+        "SetDERControlRequest.json",
+        invalid
+    )?);
+    Ok(())
+}
+
+#[test]
+fn validate_get_der_control() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::v2_1::enumerations::{DERControlEnumType, DERControlStatusEnumType};
+    use crate::v2_1::messages::get_der_control::{GetDERControlRequest, GetDERControlResponse};
+
+    round_trip("GetDERControlRequest.json", GetDERControlRequest::new(1))?;
+    let request = GetDERControlRequest::new(2)
+        .with_is_default(true)
+        .with_control_type(DERControlEnumType::FreqDroop)
+        .with_control_id("droop-1".to_string());
+    round_trip("GetDERControlRequest.json", request)?;
+
+    let response = GetDERControlResponse::new(DERControlStatusEnumType::NotFound)
+        .with_status_info(StatusInfoType::new("NotFound".to_string()));
+    round_trip("GetDERControlResponse.json", response)?;
+
+    let invalid = serde_json::json!({ "isDefault": true });
+    assert!(!validate_schema_instance(
+        "GetDERControlRequest.json",
+        invalid
+    )?);
+    Ok(())
+}
+
+#[test]
+fn validate_report_der_control_fixed_pf() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::v2_1::datatypes::{FixedPFGetType, FixedPFType};
+    use crate::v2_1::messages::report_der_control::ReportDERControlRequest;
+
+    let request = ReportDERControlRequest {
+        custom_data: None,
+        request_id: 1,
+        tbc: None,
+        curve: None,
+        enter_service: None,
+        fixed_pf_absorb: Some(vec![FixedPFGetType::new(
+            FixedPFType::new(0, 0.9, true),
+            "pf-1".to_string(),
+            false,
+            true,
+        )]),
+        fixed_pf_inject: None,
+        fixed_var: None,
+        freq_droop: None,
+        gradient: None,
+        limit_max_discharge: None,
+    };
+
+    let instance = serde_json::to_value(request)?;
+    assert!(validate_schema_instance(
+        "ReportDERControlRequest.json",
+        instance
+    )?);
+    Ok(())
+}
+
+#[test]
+fn validate_set_display_message() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::v2_1::datatypes::MessageContentType;
+    use crate::v2_1::enumerations::{
+        DisplayMessageStatusEnumType, MessageFormatEnumType, MessagePriorityEnumType,
+        MessageStateEnumType,
+    };
+    use crate::v2_1::messages::notify_display_messages::MessageInfoType;
+    use crate::v2_1::messages::set_display_message::{
+        SetDisplayMessageRequest, SetDisplayMessageResponse,
+    };
+
+    let content = |language: &str, content: &str| MessageContentType {
+        format: MessageFormatEnumType::UTF8,
+        language: language.to_string(),
+        content: content.to_string(),
+        custom_data: None,
+    };
+    let message = MessageInfoType {
+        display: None,
+        id: 7,
+        priority: MessagePriorityEnumType::InFront,
+        state: Some(MessageStateEnumType::Charging),
+        start_date_time: Some(chrono::Utc::now()),
+        end_date_time: None,
+        transaction_id: Some("tx-1".to_string()),
+        message: content("en", "Charging at reduced power"),
+        message_extra: Some(vec![content("nl", "Laden met beperkt vermogen")]),
+        custom_data: None,
+    };
+    round_trip(
+        "SetDisplayMessageRequest.json",
+        SetDisplayMessageRequest::new(message.clone()),
+    )?;
+
+    let response = SetDisplayMessageResponse::new(DisplayMessageStatusEnumType::UnknownTransaction);
+    round_trip("SetDisplayMessageResponse.json", response)?;
+
+    let mut too_many = message;
+    too_many.message_extra = Some(vec![content("nl", "-"); 5]);
+    let request = SetDisplayMessageRequest::new(too_many);
+    assert!(validator::Validate::validate(&request).is_err());
+    let instance = serde_json::to_value(request)?;
+    assert!(!validate_schema_instance(
+        "SetDisplayMessageRequest.json",
+        instance
+    )?);
+    Ok(())
+}
+
+#[test]
+fn validate_trigger_message() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::v2_1::datatypes::EVSEType;
+    use crate::v2_1::enumerations::{MessageTriggerEnumType, TriggerMessageStatusEnumType};
+    use crate::v2_1::messages::trigger_message::{TriggerMessageRequest, TriggerMessageResponse};
+
+    let request = TriggerMessageRequest::new(MessageTriggerEnumType::StatusNotification)
+        .with_evse(EVSEType::new(1).with_connector_id(2));
+    round_trip("TriggerMessageRequest.json", request)?;
+    let request = TriggerMessageRequest::new(MessageTriggerEnumType::CustomTrigger)
+        .with_custom_trigger("DiagnosticsStatusNotification".to_string());
+    round_trip("TriggerMessageRequest.json", request)?;
+
+    let response = TriggerMessageResponse::new(TriggerMessageStatusEnumType::NotImplemented)
+        .with_status_info(StatusInfoType::new("UnsupportedRequest".to_string()));
+    round_trip("TriggerMessageResponse.json", response)?;
+
+    let request = TriggerMessageRequest::new(MessageTriggerEnumType::CustomTrigger)
+        .with_custom_trigger("x".repeat(51));
+    assert!(validator::Validate::validate(&request).is_err());
+    let instance = serde_json::to_value(request)?;
+    assert!(!validate_schema_instance(
+        "TriggerMessageRequest.json",
+        instance
+    )?);
+    Ok(())
+}
+
+#[test]
+fn validate_update_dynamic_schedule() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::v2_1::datatypes::ChargingScheduleUpdateType;
+    use crate::v2_1::enumerations::ChargingProfileStatusEnumType;
+    use crate::v2_1::messages::update_dynamic_schedule::{
+        UpdateDynamicScheduleRequest, UpdateDynamicScheduleResponse,
+    };
+
+    let schedule_update: ChargingScheduleUpdateType = serde_json::from_value(serde_json::json!({
+        "limit": 11000.0,
+        "dischargeLimit": -7400,
+        "setpoint": 3000.0
+    }))?;
+    round_trip(
+        "UpdateDynamicScheduleRequest.json",
+        UpdateDynamicScheduleRequest::new(3, schedule_update),
+    )?;
+
+    let response = UpdateDynamicScheduleResponse::new(ChargingProfileStatusEnumType::Rejected);
+    round_trip("UpdateDynamicScheduleResponse.json", response)?;
+
+    let invalid = serde_json::json!({ "chargingProfileId": 3 });
+    assert!(!validate_schema_instance(
+        "UpdateDynamicScheduleRequest.json",
+        invalid
+    )?);
+    Ok(())
+}
+
+#[test]
+fn validate_notify_report() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::v2_1::datatypes::{
+        ComponentType, ReportDataType, VariableAttributeType, VariableType,
+    };
+    use crate::v2_1::enumerations::{AttributeEnumType, MutabilityEnumType};
+    use crate::v2_1::messages::notify_report::{NotifyReportRequest, NotifyReportResponse};
+
+    let report_data = ReportDataType::new(
+        ComponentType::new("OCPPCommCtrlr".to_string()),
+        VariableType::new("HeartbeatInterval".to_string()),
+        vec![VariableAttributeType::new_with_value(
+            AttributeEnumType::Actual,
+            "300".to_string(),
+            MutabilityEnumType::ReadWrite,
+        )],
+    );
+    let request = NotifyReportRequest::new(1, chrono::Utc::now(), 0)
+        .with_report_data(vec![report_data])
+        .with_tbc(true);
+    round_trip("NotifyReportRequest.json", request)?;
+    round_trip("NotifyReportResponse.json", NotifyReportResponse::default())?;
+
+    let invalid = serde_json::to_value(NotifyReportRequest::new(1, chrono::Utc::now(), -1))?;
+    assert!(!validate_schema_instance(
+        "NotifyReportRequest.json",
+        invalid
+    )?);
+    Ok(())
+}
+
+#[test]
+fn validate_reservation_status_update() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::v2_1::messages::reservation_status_update::{
+        ReservationStatusUpdateRequest, ReservationUpdateStatusEnumType,
+    };
+
+    for status in [
+        ReservationUpdateStatusEnumType::Expired,
+        ReservationUpdateStatusEnumType::Removed,
+        ReservationUpdateStatusEnumType::NoTransaction,
+    ] {
+        let request = ReservationStatusUpdateRequest {
+            custom_data: None,
+            reservation_id: 7,
+            reservation_update_status: status,
+        };
+        round_trip("ReservationStatusUpdateRequest.json", request)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn validate_status_notification() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::v2_1::messages::status_notification::{
+        ConnectorStatusEnumType, StatusNotificationRequest,
+    };
+
+    for status in [
+        ConnectorStatusEnumType::Available,
+        ConnectorStatusEnumType::Occupied,
+        ConnectorStatusEnumType::Reserved,
+        ConnectorStatusEnumType::Unavailable,
+        ConnectorStatusEnumType::Faulted,
+    ] {
+        let request = StatusNotificationRequest {
+            custom_data: None,
+            timestamp: chrono::Utc::now(),
+            connector_status: status,
+            evse_id: 1,
+            connector_id: 1,
+        };
+        round_trip("StatusNotificationRequest.json", request)?;
+    }
+    Ok(())
+}
@@ -10,6 +10,7 @@ use super::{custom_data::CustomDataType, fixed_pf::FixedPFType};
 #[serde(rename_all = "camelCase")]
 pub struct FixedPFGetType {
     /// The fixed power factor settings.
+    #[serde(rename = "fixedPF")]
     #[validate(nested)]
     pub fixed_pf: FixedPFType,
 
@@ -417,7 +418,7 @@ mod tests {
     fn test_serialization_deserialization() {
         // Create a JSON string directly
         let json_str = r#"{
-            "fixedPf": {
+            "fixedPF": {
                 "priority": 1,
                 "displacement": 0.95,
                 "excitation": true,
@@ -469,7 +470,7 @@ mod tests {
 
         // Check JSON structure
         assert!(json_value.is_object());
-        assert!(json_value.get("fixedPf").is_some());
+        assert!(json_value.get("fixedPF").is_some());
         assert!(json_value.get("id").is_some());
         assert!(json_value.get("isSuperseded").is_some());
         assert!(json_value.get("isDefault").is_some());
@@ -479,9 +480,9 @@ mod tests {
         assert_eq!(json_value["id"], "setting1");
         assert_eq!(json_value["isSuperseded"], false);
         assert_eq!(json_value["isDefault"], true);
-        assert_eq!(json_value["fixedPf"]["priority"], 1);
-        assert_eq!(json_value["fixedPf"]["displacement"], 0.95);
-        assert_eq!(json_value["fixedPf"]["excitation"], true);
+        assert_eq!(json_value["fixedPF"]["priority"], 1);
+        assert_eq!(json_value["fixedPF"]["displacement"], 0.95);
+        assert_eq!(json_value["fixedPF"]["excitation"], true);
         assert_eq!(json_value["customData"]["vendorId"], "VendorX");
         assert_eq!(json_value["customData"]["version"], "1.0");
     }
@@ -490,7 +491,7 @@ mod tests {
     fn test_deserialization_from_json() {
         // Create a JSON string representing a FixedPFGetType
         let json_str = r#"{
-            "fixedPf": {
+            "fixedPF": {
                 "priority": 1,
                 "displacement": 0.95,
                 "excitation": true,
@@ -532,7 +533,7 @@ mod tests {
     fn test_partial_json() {
         // Test with only required fields
         let json_str = r#"{
-            "fixedPf": {
+            "fixedPF": {
                 "priority": 1,
                 "displacement": 0.95,
                 "excitation": true,
@@ -1,4 +1,4 @@
-use serde::{Serialize, Deserialize};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DataEnumType {
@@ -24,4 +24,4 @@ impl Default for DataEnumType {
     fn default() -> Self {
         DataEnumType::String
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+pub use super::der_control_status::DERControlStatusEnumType;
+
 /// Type of DER curve
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum DERControlEnumType {
@@ -54,11 +56,3 @@ pub enum DERControlEnumType {
     CurrentTarget,
     LoadPriority,
 }
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub enum DERControlStatusEnumType {
-    Accepted,
-    Rejected,
-    NotSupported,
-}
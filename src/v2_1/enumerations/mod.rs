@@ -33,6 +33,7 @@ pub mod data_transfer_status;
 pub mod day_of_week;
 pub mod delete_certificate_status;
 pub mod der_control;
+pub mod der_control_status;
 pub mod der_unit;
 pub mod display_message_status;
 pub mod energy_transfer_mode;
@@ -102,6 +103,7 @@ pub mod tariff_get_status;
 pub mod tariff_kind;
 pub mod tariff_set_status;
 pub mod transaction_event;
+pub mod trigger_message_status;
 pub mod trigger_reason;
 pub mod unlock_status;
 pub mod unpublish_firmware_status;
@@ -144,6 +146,7 @@ pub use data_transfer_status::DataTransferStatusEnumType;
 pub use day_of_week::DayOfWeekEnumType;
 pub use delete_certificate_status::DeleteCertificateStatusEnumType;
 pub use der_control::DERControlEnumType;
+pub use der_control_status::DERControlStatusEnumType;
 pub use der_unit::DERUnitEnumType;
 pub use display_message_status::DisplayMessageStatusEnumType;
 pub use energy_transfer_mode::EnergyTransferModeEnumType;
@@ -213,6 +216,7 @@ pub use tariff_get_status::TariffGetStatusEnumType;
 pub use tariff_kind::TariffKindEnumType;
 pub use tariff_set_status::TariffSetStatusEnumType;
 pub use transaction_event::TransactionEventEnumType;
+pub use trigger_message_status::TriggerMessageStatusEnumType;
 pub use trigger_reason::TriggerReasonEnumType;
 pub use unlock_status::UnlockStatusEnumType;
 pub use unpublish_firmware_status::UnpublishFirmwareStatusEnumType;
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::v2_1::{
+    datatypes::{CustomDataType, StatusInfoType},
+    enumerations::der_control::{DERControlEnumType, DERControlStatusEnumType},
+};
+
+/// Request to get DER control settings, which the Charging Station reports in
+/// ReportDERControlRequests.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDERControlRequest {
+    /// Required. RequestId to be used in ReportDERControlRequest.
+    pub request_id: i32,
+
+    /// Optional. True: get a default DER control. False: get a scheduled control.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_default: Option<bool>,
+
+    /// Optional. Type of control settings to retrieve. Not used when control_id is provided.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub control_type: Option<DERControlEnumType>,
+
+    /// Optional. Id of setting to get. When omitted all settings for control_type are retrieved.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(length(max = 36))]
+    pub control_id: Option<String>,
+
+    /// Optional. Custom data from the CSMS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_data: Option<CustomDataType>,
+}
+
+/// Response to a GetDERControlRequest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDERControlResponse {
+    /// Required. Result of operation.
+    pub status: DERControlStatusEnumType,
+
+    /// Optional. Detailed status information.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_info: Option<StatusInfoType>,
+
+    /// Optional. Custom data from the Charging Station.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_data: Option<CustomDataType>,
+}
+
+impl GetDERControlRequest {
+    /// Creates a new `GetDERControlRequest` for all scheduled controls.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` - RequestId to be used in ReportDERControlRequest
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `GetDERControlRequest` with optional fields set to `None`
+    pub fn new(request_id: i32) -> Self {
+        Self {
+            request_id,
+            is_default: None,
+            control_type: None,
+            control_id: None,
+            custom_data: None,
+        }
+    }
+
+    /// Sets whether default or scheduled controls are requested.
+    ///
+    /// # Arguments
+    ///
+    /// * `is_default` - True to get default controls
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_is_default(mut self, is_default: bool) -> Self {
+        self.is_default = Some(is_default);
+        self
+    }
+
+    /// Sets the type of the requested controls.
+    ///
+    /// # Arguments
+    ///
+    /// * `control_type` - Type of control settings to retrieve
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_control_type(mut self, control_type: DERControlEnumType) -> Self {
+        self.control_type = Some(control_type);
+        self
+    }
+
+    /// Sets the id of the requested control.
+    ///
+    /// # Arguments
+    ///
+    /// * `control_id` - Id of setting to get
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_control_id(mut self, control_id: String) -> Self {
+        self.control_id = Some(control_id);
+        self
+    }
+
+    /// Sets the custom data.
+    ///
+    /// # Arguments
+    ///
+    /// * `custom_data` - Custom data for this request
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_custom_data(mut self, custom_data: CustomDataType) -> Self {
+        self.custom_data = Some(custom_data);
+        self
+    }
+}
+
+impl GetDERControlResponse {
+    /// Creates a new `GetDERControlResponse` with required fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - Result of operation
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `GetDERControlResponse` with optional fields set to `None`
+    pub fn new(status: DERControlStatusEnumType) -> Self {
+        Self {
+            status,
+            status_info: None,
+            custom_data: None,
+        }
+    }
+
+    /// Sets the status information.
+    ///
+    /// # Arguments
+    ///
+    /// * `status_info` - Detailed status information
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_status_info(mut self, status_info: StatusInfoType) -> Self {
+        self.status_info = Some(status_info);
+        self
+    }
+
+    /// Sets the custom data.
+    ///
+    /// # Arguments
+    ///
+    /// * `custom_data` - Custom data for this response
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_custom_data(mut self, custom_data: CustomDataType) -> Self {
+        self.custom_data = Some(custom_data);
+        self
+    }
+}
@@ -26,6 +26,7 @@ pub mod get_certificate_chain_status;
 pub mod get_certificate_status;
 pub mod get_charging_profiles;
 pub mod get_composite_schedule;
+pub mod get_der_control;
 pub mod get_display_messages;
 pub mod get_installed_certificate_ids;
 pub mod get_local_list_version;
@@ -71,6 +72,8 @@ pub mod security_event_notification;
 pub mod send_local_list;
 pub mod set_charging_profile;
 pub mod set_default_tariff;
+pub mod set_der_control;
+pub mod set_display_message;
 pub mod set_monitoring_base;
 pub mod set_monitoring_level;
 pub mod set_network_profile;
@@ -79,8 +82,10 @@ pub mod set_variables;
 pub mod sign_certificate;
 pub mod status_notification;
 pub mod transaction_event;
+pub mod trigger_message;
 pub mod unlock_connector;
 pub mod unpublish_firmware;
+pub mod update_dynamic_schedule;
 pub mod update_firmware;
 pub mod use_priority_charging;
 pub mod vat_number_validation;
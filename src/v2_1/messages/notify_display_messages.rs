@@ -41,8 +41,15 @@ pub struct MessageInfoType {
     pub transaction_id: Option<String>,
 
     /// Required. Contains message details.
+    #[validate(nested)]
     pub message: MessageContentType,
 
+    /// Optional. Contains message details for extra languages to be displayed on a
+    /// Charging Station.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1, max = 4), nested)]
+    pub message_extra: Option<Vec<MessageContentType>>,
+
     /// Optional. Custom data specific to this class.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub custom_data: Option<CustomDataType>,
@@ -61,7 +68,7 @@ pub struct NotifyDisplayMessagesRequest {
     pub tbc: Option<bool>,
 
     /// Required. Array of message info objects.
-    #[validate(length(min = 1))]
+    #[validate(length(min = 1), nested)]
     pub message_info: Vec<MessageInfoType>,
 
     /// Optional. Custom data specific to this class.
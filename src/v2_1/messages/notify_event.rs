@@ -23,6 +23,25 @@ pub struct EventDataType {
     /// Required. Trigger type of the event.
     pub trigger: EventTriggerEnumType,
 
+    /// Optional. Refers to the Id of an event that is considered to be the cause for this event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 0))]
+    pub cause: Option<i32>,
+
+    /// Optional. Technical (error) code as reported by component.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(length(max = 50))]
+    pub tech_code: Option<String>,
+
+    /// Optional. Technical detail information as reported by component.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(length(max = 500))]
+    pub tech_info: Option<String>,
+
+    /// Optional. Cleared is set to true to report the clearing of a monitored situation, i.e. a 'return to normal'.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cleared: Option<bool>,
+
     /// Optional. If an event notification is linked to a specific transaction, this field can be used to specify its transactionId.
     #[serde(skip_serializing_if = "Option::is_none")]
     #[validate(length(max = 36))]
@@ -35,12 +54,17 @@ pub struct EventDataType {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub variable_monitoring_id: Option<i32>,
 
-    /// Required. Type of notification of the event.
-    pub event_notification: EventNotificationEnumType,
+    /// Required. Specifies the event notification type of the message.
+    pub event_notification_type: EventNotificationEnumType,
 
     /// Required. The variable for which this event applies.
     pub variable: VariableType,
 
+    /// Optional. Severity associated with the monitor in variableMonitoringId or with the hardwired notification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 0))]
+    pub severity: Option<i32>,
+
     /// Optional. Custom data specific to this class.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub custom_data: Option<CustomDataType>,
@@ -1,24 +1,11 @@
+//! NotifyPeriodicEventStream is a SEND message: the Charging Station sends it without
+//! expecting a response, so there is no response type.
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-use crate::v2_1::datatypes::CustomDataType;
-
-/// Contains stream data element information.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
-#[serde(rename_all = "camelCase")]
-pub struct StreamDataElementType {
-    /// Required. Offset relative to basetime of this message. basetime + t is timestamp of recorded value.
-    pub t: f64,
-
-    /// Required. Value of the monitored variable.
-    #[validate(length(max = 2500))]
-    pub v: String,
-
-    /// Optional. Custom data specific to this class.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub custom_data: Option<CustomDataType>,
-}
+use crate::v2_1::datatypes::{CustomDataType, StreamDataElementType};
 
 /// Request to notify the CSMS about periodic event stream data.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
@@ -28,7 +15,7 @@ pub struct NotifyPeriodicEventStreamRequest {
     pub basetime: DateTime<Utc>,
 
     /// Required. Array of stream data elements.
-    #[validate(length(min = 1))]
+    #[validate(length(min = 1), nested)]
     pub data: Vec<StreamDataElementType>,
 
     /// Required. Id of stream.
@@ -43,12 +30,3 @@ pub struct NotifyPeriodicEventStreamRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub custom_data: Option<CustomDataType>,
 }
-
-/// Response to a NotifyPeriodicEventStreamRequest. This message has no fields.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
-#[serde(rename_all = "camelCase")]
-pub struct NotifyPeriodicEventStreamResponse {
-    /// Optional. Custom data specific to this class.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub custom_data: Option<CustomDataType>,
-}
@@ -1 +1,109 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
 
+use crate::v2_1::datatypes::{CustomDataType, ReportDataType};
+
+/// Request to send a part of a report, requested by GetReport or GetBaseReport, to the CSMS.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyReportRequest {
+    /// Required. The id of the GetReportRequest or GetBaseReportRequest that requested this report.
+    pub request_id: i32,
+
+    /// Required. Timestamp of the moment this message was generated at the Charging Station.
+    pub generated_at: DateTime<Utc>,
+
+    /// Optional. Components and variables of this part of the report.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1), nested)]
+    pub report_data: Option<Vec<ReportDataType>>,
+
+    /// Optional. "to be continued" indicator. Indicates whether another part of the report
+    /// follows in an upcoming NotifyReportRequest message. Default value when omitted is false.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tbc: Option<bool>,
+
+    /// Required. Sequence number of this message. First message starts at 0.
+    #[validate(range(min = 0))]
+    pub seq_no: i32,
+
+    /// Optional. Custom data from the Charging Station.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_data: Option<CustomDataType>,
+}
+
+/// Response to a NotifyReportRequest. This message has no fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyReportResponse {
+    /// Optional. Custom data from the CSMS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_data: Option<CustomDataType>,
+}
+
+impl NotifyReportRequest {
+    /// Creates a new `NotifyReportRequest` with required fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` - The id of the request that requested this report
+    /// * `generated_at` - Timestamp of the moment this message was generated
+    /// * `seq_no` - Sequence number of this message
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `NotifyReportRequest` with optional fields set to `None`
+    pub fn new(request_id: i32, generated_at: DateTime<Utc>, seq_no: i32) -> Self {
+        Self {
+            request_id,
+            generated_at,
+            report_data: None,
+            tbc: None,
+            seq_no,
+            custom_data: None,
+        }
+    }
+
+    /// Sets the report data.
+    ///
+    /// # Arguments
+    ///
+    /// * `report_data` - Components and variables of this part of the report
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_report_data(mut self, report_data: Vec<ReportDataType>) -> Self {
+        self.report_data = Some(report_data);
+        self
+    }
+
+    /// Sets the "to be continued" indicator.
+    ///
+    /// # Arguments
+    ///
+    /// * `tbc` - Whether another part of the report follows
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_tbc(mut self, tbc: bool) -> Self {
+        self.tbc = Some(tbc);
+        self
+    }
+
+    /// Sets the custom data.
+    ///
+    /// # Arguments
+    ///
+    /// * `custom_data` - Custom data for this request
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_custom_data(mut self, custom_data: CustomDataType) -> Self {
+        self.custom_data = Some(custom_data);
+        self
+    }
+}
@@ -32,12 +32,12 @@ pub struct ReportDERControlRequest {
     pub enter_service: Option<Vec<EnterServiceGetType>>,
 
     /// Optional. Array of fixed power factor settings for absorbing reactive power.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "fixedPFAbsorb", skip_serializing_if = "Option::is_none")]
     #[validate(length(min = 1, max = 24))]
     pub fixed_pf_absorb: Option<Vec<FixedPFGetType>>,
 
     /// Optional. Array of fixed power factor settings for injecting reactive power.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "fixedPFInject", skip_serializing_if = "Option::is_none")]
     #[validate(length(min = 1, max = 24))]
     pub fixed_pf_inject: Option<Vec<FixedPFGetType>>,
 
@@ -5,7 +5,6 @@ use crate::v2_1::datatypes::CustomDataType;
 
 /// The updated reservation status enumeration type.
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
 pub enum ReservationUpdateStatusEnumType {
     Expired,
     Removed,
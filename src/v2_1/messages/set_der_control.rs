@@ -0,0 +1,308 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::v2_1::{
+    datatypes::{
+        CustomDataType, DERCurveType, EnterServiceType, FixedPFType, FixedVarType, FreqDroopType,
+        GradientType, LimitMaxDischargeType, StatusInfoType,
+    },
+    enumerations::der_control::{DERControlEnumType, DERControlStatusEnumType},
+};
+
+/// Request to set a default or scheduled DER control setting.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDERControlRequest {
+    /// Required. True if this is a default DER control.
+    pub is_default: bool,
+
+    /// Required. Unique id of this control, e.g. UUID.
+    #[validate(length(max = 36))]
+    pub control_id: String,
+
+    /// Required. Type of control. Determines which setting field below is used.
+    pub control_type: DERControlEnumType,
+
+    /// Optional. Curve parameters, for all curve-based control types.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub curve: Option<DERCurveType>,
+
+    /// Optional. Enter service parameters, for control type EnterService.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub enter_service: Option<EnterServiceType>,
+
+    /// Optional. Fixed power factor setpoint, for control type FixedPFAbsorb.
+    #[serde(rename = "fixedPFAbsorb", skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub fixed_pf_absorb: Option<FixedPFType>,
+
+    /// Optional. Fixed power factor setpoint, for control type FixedPFInject.
+    #[serde(rename = "fixedPFInject", skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub fixed_pf_inject: Option<FixedPFType>,
+
+    /// Optional. Fixed reactive power setpoint, for control type FixedVar.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub fixed_var: Option<FixedVarType>,
+
+    /// Optional. Frequency droop parameters, for control type FreqDroop.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub freq_droop: Option<FreqDroopType>,
+
+    /// Optional. Gradient parameters, for control type Gradients.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub gradient: Option<GradientType>,
+
+    /// Optional. Maximum discharge power limit, for control type LimitMaxDischarge.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub limit_max_discharge: Option<LimitMaxDischargeType>,
+
+    /// Optional. Custom data from the CSMS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_data: Option<CustomDataType>,
+}
+
+/// Response to a SetDERControlRequest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDERControlResponse {
+    /// Required. Result of operation.
+    pub status: DERControlStatusEnumType,
+
+    /// Optional. Detailed status information.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_info: Option<StatusInfoType>,
+
+    /// Optional. List of control ids that are superseded as a result of setting this control.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1, max = 24))]
+    pub superseded_ids: Option<Vec<String>>,
+
+    /// Optional. Custom data from the Charging Station.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_data: Option<CustomDataType>,
+}
+
+impl SetDERControlRequest {
+    /// Creates a new `SetDERControlRequest` without a setting.
+    ///
+    /// # Arguments
+    ///
+    /// * `is_default` - True if this is a default DER control
+    /// * `control_id` - Unique id of this control
+    /// * `control_type` - Type of control
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `SetDERControlRequest` with optional fields set to `None`
+    pub fn new(is_default: bool, control_id: String, control_type: DERControlEnumType) -> Self {
+        Self {
+            is_default,
+            control_id,
+            control_type,
+            curve: None,
+            enter_service: None,
+            fixed_pf_absorb: None,
+            fixed_pf_inject: None,
+            fixed_var: None,
+            freq_droop: None,
+            gradient: None,
+            limit_max_discharge: None,
+            custom_data: None,
+        }
+    }
+
+    /// Sets the curve parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `curve` - Curve parameters of a curve-based control
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_curve(mut self, curve: DERCurveType) -> Self {
+        self.curve = Some(curve);
+        self
+    }
+
+    /// Sets the enter service parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `enter_service` - Enter service parameters
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_enter_service(mut self, enter_service: EnterServiceType) -> Self {
+        self.enter_service = Some(enter_service);
+        self
+    }
+
+    /// Sets the fixed power factor setpoint when absorbing reactive power.
+    ///
+    /// # Arguments
+    ///
+    /// * `fixed_pf_absorb` - Fixed power factor setpoint
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_fixed_pf_absorb(mut self, fixed_pf_absorb: FixedPFType) -> Self {
+        self.fixed_pf_absorb = Some(fixed_pf_absorb);
+        self
+    }
+
+    /// Sets the fixed power factor setpoint when injecting reactive power.
+    ///
+    /// # Arguments
+    ///
+    /// * `fixed_pf_inject` - Fixed power factor setpoint
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_fixed_pf_inject(mut self, fixed_pf_inject: FixedPFType) -> Self {
+        self.fixed_pf_inject = Some(fixed_pf_inject);
+        self
+    }
+
+    /// Sets the fixed reactive power setpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `fixed_var` - Fixed reactive power setpoint
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_fixed_var(mut self, fixed_var: FixedVarType) -> Self {
+        self.fixed_var = Some(fixed_var);
+        self
+    }
+
+    /// Sets the frequency droop parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `freq_droop` - Frequency droop parameters
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_freq_droop(mut self, freq_droop: FreqDroopType) -> Self {
+        self.freq_droop = Some(freq_droop);
+        self
+    }
+
+    /// Sets the gradient parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `gradient` - Gradient parameters
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_gradient(mut self, gradient: GradientType) -> Self {
+        self.gradient = Some(gradient);
+        self
+    }
+
+    /// Sets the maximum discharge power limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit_max_discharge` - Maximum discharge power limit
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_limit_max_discharge(mut self, limit_max_discharge: LimitMaxDischargeType) -> Self {
+        self.limit_max_discharge = Some(limit_max_discharge);
+        self
+    }
+
+    /// Sets the custom data.
+    ///
+    /// # Arguments
+    ///
+    /// * `custom_data` - Custom data for this request
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_custom_data(mut self, custom_data: CustomDataType) -> Self {
+        self.custom_data = Some(custom_data);
+        self
+    }
+}
+
+impl SetDERControlResponse {
+    /// Creates a new `SetDERControlResponse` with required fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - Result of operation
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `SetDERControlResponse` with optional fields set to `None`
+    pub fn new(status: DERControlStatusEnumType) -> Self {
+        Self {
+            status,
+            status_info: None,
+            superseded_ids: None,
+            custom_data: None,
+        }
+    }
+
+    /// Sets the status information.
+    ///
+    /// # Arguments
+    ///
+    /// * `status_info` - Detailed status information
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_status_info(mut self, status_info: StatusInfoType) -> Self {
+        self.status_info = Some(status_info);
+        self
+    }
+
+    /// Sets the ids of the controls superseded by the new control.
+    ///
+    /// # Arguments
+    ///
+    /// * `superseded_ids` - Ids of superseded controls
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_superseded_ids(mut self, superseded_ids: Vec<String>) -> Self {
+        self.superseded_ids = Some(superseded_ids);
+        self
+    }
+
+    /// Sets the custom data.
+    ///
+    /// # Arguments
+    ///
+    /// * `custom_data` - Custom data for this response
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_custom_data(mut self, custom_data: CustomDataType) -> Self {
+        self.custom_data = Some(custom_data);
+        self
+    }
+}
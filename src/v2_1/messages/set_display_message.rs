@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::notify_display_messages::MessageInfoType;
+use crate::v2_1::{
+    datatypes::{CustomDataType, StatusInfoType},
+    enumerations::DisplayMessageStatusEnumType,
+};
+
+/// Request to configure a new display message that the Charging Station will display.
+/// A message with the id of an existing message replaces it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDisplayMessageRequest {
+    /// Required. Message to display.
+    #[validate(nested)]
+    pub message: MessageInfoType,
+
+    /// Optional. Custom data from the CSMS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_data: Option<CustomDataType>,
+}
+
+/// Response to a SetDisplayMessageRequest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDisplayMessageResponse {
+    /// Required. Whether the Charging Station is able to display the message.
+    pub status: DisplayMessageStatusEnumType,
+
+    /// Optional. Detailed status information.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_info: Option<StatusInfoType>,
+
+    /// Optional. Custom data from the Charging Station.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_data: Option<CustomDataType>,
+}
+
+impl SetDisplayMessageRequest {
+    /// Creates a new `SetDisplayMessageRequest` with required fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Message to display
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `SetDisplayMessageRequest` with optional fields set to `None`
+    pub fn new(message: MessageInfoType) -> Self {
+        Self {
+            message,
+            custom_data: None,
+        }
+    }
+
+    /// Sets the custom data.
+    ///
+    /// # Arguments
+    ///
+    /// * `custom_data` - Custom data for this request
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_custom_data(mut self, custom_data: CustomDataType) -> Self {
+        self.custom_data = Some(custom_data);
+        self
+    }
+}
+
+impl SetDisplayMessageResponse {
+    /// Creates a new `SetDisplayMessageResponse` with required fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - Whether the Charging Station is able to display the message
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `SetDisplayMessageResponse` with optional fields set to `None`
+    pub fn new(status: DisplayMessageStatusEnumType) -> Self {
+        Self {
+            status,
+            status_info: None,
+            custom_data: None,
+        }
+    }
+
+    /// Sets the status information.
+    ///
+    /// # Arguments
+    ///
+    /// * `status_info` - Detailed status information
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_status_info(mut self, status_info: StatusInfoType) -> Self {
+        self.status_info = Some(status_info);
+        self
+    }
+
+    /// Sets the custom data.
+    ///
+    /// # Arguments
+    ///
+    /// * `custom_data` - Custom data for this response
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_custom_data(mut self, custom_data: CustomDataType) -> Self {
+        self.custom_data = Some(custom_data);
+        self
+    }
+}
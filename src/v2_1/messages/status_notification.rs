@@ -3,17 +3,7 @@ use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 use crate::v2_1::datatypes::CustomDataType;
-
-/// This contains the current status of the Connector.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub enum ConnectorStatusEnumType {
-    Available,
-    Occupied,
-    Reserved,
-    Unavailable,
-    Faulted,
-}
+pub use crate::v2_1::enumerations::ConnectorStatusEnumType;
 
 /// Request to notify the CSMS about a status change of a connector.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
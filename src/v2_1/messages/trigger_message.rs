@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::v2_1::{
+    datatypes::{CustomDataType, EVSEType, StatusInfoType},
+    enumerations::{MessageTriggerEnumType, TriggerMessageStatusEnumType},
+};
+
+/// Request to make the Charging Station send a message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerMessageRequest {
+    /// Required. Type of message to be triggered.
+    pub requested_message: MessageTriggerEnumType,
+
+    /// Optional. EVSE, and connector, for which the message is requested. When absent, the
+    /// message is requested for the Charging Station as a whole.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub evse: Option<EVSEType>,
+
+    /// Optional. When requested_message is CustomTrigger, the name of the message to send,
+    /// if supported by the Charging Station.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(length(max = 50))]
+    pub custom_trigger: Option<String>,
+
+    /// Optional. Custom data from the CSMS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_data: Option<CustomDataType>,
+}
+
+/// Response to a TriggerMessageRequest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerMessageResponse {
+    /// Required. Whether the Charging Station will send the requested message.
+    pub status: TriggerMessageStatusEnumType,
+
+    /// Optional. Detailed status information.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_info: Option<StatusInfoType>,
+
+    /// Optional. Custom data from the Charging Station.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_data: Option<CustomDataType>,
+}
+
+impl TriggerMessageRequest {
+    /// Creates a new `TriggerMessageRequest` with required fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `requested_message` - Type of message to be triggered
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `TriggerMessageRequest` with optional fields set to `None`
+    pub fn new(requested_message: MessageTriggerEnumType) -> Self {
+        Self {
+            requested_message,
+            evse: None,
+            custom_trigger: None,
+            custom_data: None,
+        }
+    }
+
+    /// Sets the EVSE for which the message is requested.
+    ///
+    /// # Arguments
+    ///
+    /// * `evse` - EVSE, and connector, for which the message is requested
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_evse(mut self, evse: EVSEType) -> Self {
+        self.evse = Some(evse);
+        self
+    }
+
+    /// Sets the name of the custom message to trigger.
+    ///
+    /// # Arguments
+    ///
+    /// * `custom_trigger` - Name of the message to send for CustomTrigger
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_custom_trigger(mut self, custom_trigger: String) -> Self {
+        self.custom_trigger = Some(custom_trigger);
+        self
+    }
+
+    /// Sets the custom data.
+    ///
+    /// # Arguments
+    ///
+    /// * `custom_data` - Custom data for this request
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_custom_data(mut self, custom_data: CustomDataType) -> Self {
+        self.custom_data = Some(custom_data);
+        self
+    }
+}
+
+impl TriggerMessageResponse {
+    /// Creates a new `TriggerMessageResponse` with required fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - Whether the Charging Station will send the requested message
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `TriggerMessageResponse` with optional fields set to `None`
+    pub fn new(status: TriggerMessageStatusEnumType) -> Self {
+        Self {
+            status,
+            status_info: None,
+            custom_data: None,
+        }
+    }
+
+    /// Sets the status information.
+    ///
+    /// # Arguments
+    ///
+    /// * `status_info` - Detailed status information
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_status_info(mut self, status_info: StatusInfoType) -> Self {
+        self.status_info = Some(status_info);
+        self
+    }
+
+    /// Sets the custom data.
+    ///
+    /// # Arguments
+    ///
+    /// * `custom_data` - Custom data for this response
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_custom_data(mut self, custom_data: CustomDataType) -> Self {
+        self.custom_data = Some(custom_data);
+        self
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::v2_1::{
+    datatypes::{ChargingScheduleUpdateType, CustomDataType, StatusInfoType},
+    enumerations::ChargingProfileStatusEnumType,
+};
+
+/// Request to update the dynamic charging schedule of a charging profile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateDynamicScheduleRequest {
+    /// Required. Id of charging profile to update.
+    pub charging_profile_id: i32,
+
+    /// Required. Updated limits and setpoints of the schedule.
+    #[validate(nested)]
+    pub schedule_update: ChargingScheduleUpdateType,
+
+    /// Optional. Custom data from the CSMS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_data: Option<CustomDataType>,
+}
+
+/// Response to an UpdateDynamicScheduleRequest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateDynamicScheduleResponse {
+    /// Required. Whether the Charging Station accepted the update.
+    pub status: ChargingProfileStatusEnumType,
+
+    /// Optional. Detailed status information.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_info: Option<StatusInfoType>,
+
+    /// Optional. Custom data from the Charging Station.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_data: Option<CustomDataType>,
+}
+
+impl UpdateDynamicScheduleRequest {
+    /// Creates a new `UpdateDynamicScheduleRequest` with required fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `charging_profile_id` - Id of charging profile to update
+    /// * `schedule_update` - Updated limits and setpoints of the schedule
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `UpdateDynamicScheduleRequest` with optional fields set to `None`
+    pub fn new(charging_profile_id: i32, schedule_update: ChargingScheduleUpdateType) -> Self {
+        Self {
+            charging_profile_id,
+            schedule_update,
+            custom_data: None,
+        }
+    }
+
+    /// Sets the custom data.
+    ///
+    /// # Arguments
+    ///
+    /// * `custom_data` - Custom data for this request
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_custom_data(mut self, custom_data: CustomDataType) -> Self {
+        self.custom_data = Some(custom_data);
+        self
+    }
+}
+
+impl UpdateDynamicScheduleResponse {
+    /// Creates a new `UpdateDynamicScheduleResponse` with required fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - Whether the Charging Station accepted the update
+    ///
+    /// # Returns
+    ///
+    /// A new instance of `UpdateDynamicScheduleResponse` with optional fields set to `None`
+    pub fn new(status: ChargingProfileStatusEnumType) -> Self {
+        Self {
+            status,
+            status_info: None,
+            custom_data: None,
+        }
+    }
+
+    /// Sets the status information.
+    ///
+    /// # Arguments
+    ///
+    /// * `status_info` - Detailed status information
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_status_info(mut self, status_info: StatusInfoType) -> Self {
+        self.status_info = Some(status_info);
+        self
+    }
+
+    /// Sets the custom data.
+    ///
+    /// # Arguments
+    ///
+    /// * `custom_data` - Custom data for this response
+    ///
+    /// # Returns
+    ///
+    /// Self reference for method chaining
+    pub fn with_custom_data(mut self, custom_data: CustomDataType) -> Self {
+        self.custom_data = Some(custom_data);
+        self
+    }
+}